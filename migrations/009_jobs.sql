@@ -0,0 +1,28 @@
+-- Durable provisioning job queue.
+-- Workers claim rows with FOR UPDATE SKIP LOCKED; a `running` row whose
+-- `locked_until` has passed belongs to a dead worker and is reclaimed.
+
+CREATE TABLE IF NOT EXISTS jobs (
+    id UUID PRIMARY KEY,
+    kind VARCHAR(50) NOT NULL,
+    bot_id UUID NOT NULL REFERENCES bots(id) ON DELETE CASCADE,
+    status VARCHAR(50) NOT NULL DEFAULT 'queued',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    max_attempts INTEGER NOT NULL DEFAULT 5,
+    run_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    locked_until TIMESTAMPTZ,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_jobs_status_run_at ON jobs (status, run_at);
+CREATE INDEX IF NOT EXISTS idx_jobs_bot_id ON jobs (bot_id);
+
+-- At most one unfinished job per bot, so spawn/redeploy/destroy never race.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_jobs_one_active_per_bot
+    ON jobs (bot_id) WHERE status IN ('queued', 'running');
+
+CREATE TRIGGER update_jobs_updated_at BEFORE UPDATE ON jobs
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
@@ -0,0 +1,11 @@
+-- Persist why a bot is in its current status, so failures are visible outside the logs.
+-- status_reason holds a domain::TransitionReason (snake_case); NULL until the first transition.
+
+ALTER TABLE bots ADD COLUMN IF NOT EXISTS status_reason VARCHAR(50);
+ALTER TABLE bots ADD COLUMN IF NOT EXISTS status_detail TEXT;
+ALTER TABLE bots ADD COLUMN IF NOT EXISTS status_changed_at TIMESTAMPTZ;
+
+UPDATE bots SET status_changed_at = updated_at WHERE status_changed_at IS NULL;
+
+ALTER TABLE bots ALTER COLUMN status_changed_at SET DEFAULT NOW();
+ALTER TABLE bots ALTER COLUMN status_changed_at SET NOT NULL;
@@ -0,0 +1,12 @@
+-- Track when the current droplet registered, so bots stuck in provisioning can be timed out.
+-- registration_timeouts counts consecutive deadline misses and bounds automatic redeploys.
+
+ALTER TABLE bots ADD COLUMN IF NOT EXISTS registered_at TIMESTAMPTZ;
+ALTER TABLE bots ADD COLUMN IF NOT EXISTS registered_ip VARCHAR(45);
+ALTER TABLE bots ADD COLUMN IF NOT EXISTS agent_version VARCHAR(64);
+ALTER TABLE bots ADD COLUMN IF NOT EXISTS registration_timeouts INTEGER NOT NULL DEFAULT 0;
+
+-- Supports the registration watchdog scan.
+CREATE INDEX IF NOT EXISTS idx_bots_unregistered_provisioning
+    ON bots (status_changed_at)
+    WHERE status = 'provisioning' AND registered_at IS NULL;
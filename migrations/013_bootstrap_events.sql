@@ -0,0 +1,13 @@
+-- Progress reports from openclaw-bootstrap.sh, one row per finished (or failed) phase.
+
+CREATE TABLE IF NOT EXISTS bootstrap_events (
+    id UUID PRIMARY KEY,
+    bot_id UUID NOT NULL REFERENCES bots(id) ON DELETE CASCADE,
+    phase VARCHAR(50) NOT NULL,
+    exit_code INTEGER NOT NULL,
+    message TEXT,
+    reported_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_bootstrap_events_bot_reported_at
+    ON bootstrap_events (bot_id, reported_at);
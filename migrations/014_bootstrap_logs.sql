@@ -0,0 +1,14 @@
+-- Log tails uploaded by droplets whose bootstrap failed, redacted before storage.
+-- droplet_id identifies the provisioning attempt the logs belong to.
+
+CREATE TABLE IF NOT EXISTS bootstrap_logs (
+    id UUID PRIMARY KEY,
+    bot_id UUID NOT NULL REFERENCES bots(id) ON DELETE CASCADE,
+    droplet_id BIGINT,
+    source VARCHAR(50) NOT NULL,
+    content TEXT NOT NULL,
+    uploaded_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_bootstrap_logs_bot_uploaded_at
+    ON bootstrap_logs (bot_id, uploaded_at);
@@ -0,0 +1,6 @@
+-- Workspace customizer (janebot-cli) settings: per-account overrides, and the
+-- resolved settings each bot was created with so redeploys reproduce its workspace.
+-- Bots created before this migration have NULL and use the service defaults.
+
+ALTER TABLE accounts ADD COLUMN IF NOT EXISTS customizer JSONB NOT NULL DEFAULT '{}'::jsonb;
+ALTER TABLE bots ADD COLUMN IF NOT EXISTS customizer JSONB;
@@ -0,0 +1,6 @@
+-- Named toolchain profiles: the profile each bot selected at creation, and the
+-- profile each droplet's user data was rendered with.
+-- Rows created before this migration have NULL and use the default profile.
+
+ALTER TABLE bots ADD COLUMN IF NOT EXISTS toolchain_profile VARCHAR(64);
+ALTER TABLE droplets ADD COLUMN IF NOT EXISTS toolchain_profile VARCHAR(64);
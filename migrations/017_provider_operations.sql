@@ -0,0 +1,28 @@
+-- Outbox of DigitalOcean calls, written in the same transaction as the state
+-- change that needs them. A `pending` row whose `locked_until` has passed was
+-- abandoned by its writer and is picked up by the outbox executor.
+-- No foreign key on bot_id: a destroy must outlive a hard-deleted bot.
+
+CREATE TABLE IF NOT EXISTS provider_operations (
+    id UUID PRIMARY KEY,
+    kind VARCHAR(50) NOT NULL,
+    bot_id UUID NOT NULL,
+    droplet_id BIGINT,
+    status VARCHAR(50) NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    locked_until TIMESTAMPTZ NOT NULL,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_provider_operations_pending
+    ON provider_operations (locked_until) WHERE status = 'pending';
+
+-- At most one pending create per bot, so two writers never both create a droplet.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_provider_operations_one_create_per_bot
+    ON provider_operations (bot_id) WHERE status = 'pending' AND kind = 'create_droplet';
+
+CREATE TRIGGER update_provider_operations_updated_at BEFORE UPDATE ON provider_operations
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
@@ -0,0 +1,5 @@
+-- Hibernation: a bot's choice of snapshotting its droplet before hibernating
+-- (NULL follows the account tier), and the snapshot its next droplet boots from.
+
+ALTER TABLE bots ADD COLUMN IF NOT EXISTS snapshot_on_hibernate BOOLEAN;
+ALTER TABLE bots ADD COLUMN IF NOT EXISTS hibernation_snapshot_id BIGINT;
@@ -0,0 +1,27 @@
+-- The OUT columns of increment_bot_counter shadowed the table's own columns,
+-- so every call failed with "column reference is ambiguous". Qualify them.
+
+CREATE OR REPLACE FUNCTION increment_bot_counter(p_account_id UUID)
+RETURNS TABLE (
+    success BOOLEAN,
+    current_count INTEGER,
+    max_count INTEGER
+) AS $$
+BEGIN
+    RETURN QUERY
+    UPDATE account_bot_counters c
+    SET
+        current_count = c.current_count + 1,
+        updated_at = NOW()
+    WHERE c.account_id = p_account_id
+      AND c.current_count < c.max_count
+    RETURNING TRUE, c.current_count, c.max_count;
+
+    IF NOT FOUND THEN
+        RETURN QUERY
+        SELECT FALSE, c.current_count, c.max_count
+        FROM account_bot_counters c
+        WHERE c.account_id = p_account_id;
+    END IF;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,8 @@
+-- Each reservation of an Idempotency-Key gets its own token, so a request
+-- whose key was taken over as abandoned can't store its response over, or
+-- release, the retry that took it. Rows reserved before this migration get
+-- the nil token, which no request holds.
+
+ALTER TABLE idempotency_keys
+    ADD COLUMN IF NOT EXISTS reservation_id UUID NOT NULL
+    DEFAULT '00000000-0000-0000-0000-000000000000';
@@ -0,0 +1,5 @@
+-- Digest of the user data each droplet was created with, so an in-place
+-- rebuild, which re-runs that user data, can be refused once it is stale.
+-- Droplets created before this migration have NULL and must be recreated.
+
+ALTER TABLE droplets ADD COLUMN IF NOT EXISTS user_data_digest VARCHAR(71);
@@ -0,0 +1,155 @@
+-- SQLite schema, equivalent to the Postgres migrations in the parent directory.
+--
+-- Differences from Postgres:
+-- - UUIDs are 16-byte BLOBs, timestamps RFC 3339 TEXT in UTC (both as sqlx
+--   encodes them), and JSONB columns JSON TEXT.
+-- - The repositories set every timestamp, including updated_at, themselves.
+-- - increment_bot_counter and get_next_config_version_atomic are plain SQL in
+--   the repositories; SQLite runs one writer at a time, so no advisory lock is
+--   needed. Counters are still created and resized by triggers.
+
+CREATE TABLE IF NOT EXISTS accounts (
+    id BLOB PRIMARY KEY,
+    external_id TEXT NOT NULL UNIQUE,
+    subscription_tier TEXT NOT NULL DEFAULT 'free',
+    max_bots INTEGER NOT NULL DEFAULT 0,
+    customizer TEXT NOT NULL DEFAULT '{}',
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS account_bot_counters (
+    account_id BLOB PRIMARY KEY REFERENCES accounts(id) ON DELETE CASCADE,
+    current_count INTEGER NOT NULL DEFAULT 0,
+    max_count INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TRIGGER IF NOT EXISTS init_account_counter_trigger
+    AFTER INSERT ON accounts
+    FOR EACH ROW
+BEGIN
+    INSERT INTO account_bot_counters (account_id, current_count, max_count)
+    VALUES (NEW.id, 0, NEW.max_bots);
+END;
+
+CREATE TRIGGER IF NOT EXISTS update_account_counter_max_trigger
+    AFTER UPDATE OF max_bots ON accounts
+    FOR EACH ROW
+BEGIN
+    UPDATE account_bot_counters SET max_count = NEW.max_bots WHERE account_id = NEW.id;
+END;
+
+CREATE TABLE IF NOT EXISTS bots (
+    id BLOB PRIMARY KEY,
+    account_id BLOB NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    persona TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    droplet_id INTEGER,
+    desired_config_version_id BLOB,
+    applied_config_version_id BLOB,
+    registration_token TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    last_heartbeat_at TEXT,
+    status_reason TEXT,
+    status_detail TEXT,
+    status_changed_at TEXT NOT NULL,
+    registered_at TEXT,
+    registered_ip TEXT,
+    agent_version TEXT,
+    registration_timeouts INTEGER NOT NULL DEFAULT 0,
+    customizer TEXT,
+    toolchain_profile TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_bots_account_created_at ON bots (account_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_bots_status_last_heartbeat_at ON bots (status, last_heartbeat_at);
+CREATE INDEX IF NOT EXISTS idx_bots_droplet_id ON bots (droplet_id);
+CREATE INDEX IF NOT EXISTS idx_bots_unregistered_provisioning
+    ON bots (status_changed_at)
+    WHERE status = 'provisioning' AND registered_at IS NULL;
+
+CREATE TABLE IF NOT EXISTS bot_configs (
+    id BLOB PRIMARY KEY,
+    bot_id BLOB NOT NULL REFERENCES bots(id) ON DELETE CASCADE,
+    version INTEGER NOT NULL,
+    trading_config TEXT NOT NULL,
+    risk_config TEXT NOT NULL,
+    secrets_encrypted BLOB NOT NULL,
+    llm_provider TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    UNIQUE (bot_id, version)
+);
+
+CREATE TABLE IF NOT EXISTS droplets (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    region TEXT NOT NULL,
+    size TEXT NOT NULL,
+    image TEXT NOT NULL,
+    status TEXT NOT NULL,
+    ip_address TEXT,
+    bot_id BLOB REFERENCES bots(id) ON DELETE SET NULL,
+    created_at TEXT NOT NULL,
+    destroyed_at TEXT,
+    toolchain_profile TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_droplets_bot_id ON droplets (bot_id);
+
+CREATE TABLE IF NOT EXISTS jobs (
+    id BLOB PRIMARY KEY,
+    kind TEXT NOT NULL,
+    bot_id BLOB NOT NULL REFERENCES bots(id) ON DELETE CASCADE,
+    status TEXT NOT NULL DEFAULT 'queued',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    max_attempts INTEGER NOT NULL DEFAULT 5,
+    run_at TEXT NOT NULL,
+    locked_until TEXT,
+    last_error TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    completed_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_jobs_status_run_at ON jobs (status, run_at);
+
+-- At most one unfinished job per bot, so spawn/redeploy/destroy never race.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_jobs_one_active_per_bot
+    ON jobs (bot_id) WHERE status IN ('queued', 'running');
+
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    key TEXT PRIMARY KEY,
+    request_fingerprint TEXT NOT NULL,
+    response_status INTEGER,
+    response_body BLOB,
+    created_at TEXT NOT NULL,
+    completed_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_keys_created_at ON idempotency_keys (created_at);
+
+CREATE TABLE IF NOT EXISTS bootstrap_events (
+    id BLOB PRIMARY KEY,
+    bot_id BLOB NOT NULL REFERENCES bots(id) ON DELETE CASCADE,
+    phase TEXT NOT NULL,
+    exit_code INTEGER NOT NULL,
+    message TEXT,
+    reported_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_bootstrap_events_bot_reported_at
+    ON bootstrap_events (bot_id, reported_at);
+
+CREATE TABLE IF NOT EXISTS bootstrap_logs (
+    id BLOB PRIMARY KEY,
+    bot_id BLOB NOT NULL REFERENCES bots(id) ON DELETE CASCADE,
+    droplet_id INTEGER,
+    source TEXT NOT NULL,
+    content TEXT NOT NULL,
+    uploaded_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_bootstrap_logs_bot_uploaded_at
+    ON bootstrap_logs (bot_id, uploaded_at);
@@ -0,0 +1,22 @@
+-- Outbox of DigitalOcean calls; see migrations/017_provider_operations.sql.
+
+CREATE TABLE IF NOT EXISTS provider_operations (
+    id BLOB PRIMARY KEY,
+    kind TEXT NOT NULL,
+    bot_id BLOB NOT NULL,
+    droplet_id INTEGER,
+    status TEXT NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    locked_until TEXT NOT NULL,
+    last_error TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    completed_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_provider_operations_pending
+    ON provider_operations (locked_until) WHERE status = 'pending';
+
+-- At most one pending create per bot, so two writers never both create a droplet.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_provider_operations_one_create_per_bot
+    ON provider_operations (bot_id) WHERE status = 'pending' AND kind = 'create_droplet';
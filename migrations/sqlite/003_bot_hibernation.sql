@@ -0,0 +1,4 @@
+-- Bot hibernation; see migrations/018_bot_hibernation.sql.
+
+ALTER TABLE bots ADD COLUMN snapshot_on_hibernate INTEGER;
+ALTER TABLE bots ADD COLUMN hibernation_snapshot_id INTEGER;
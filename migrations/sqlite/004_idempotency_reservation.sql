@@ -0,0 +1,4 @@
+-- Idempotency-Key reservation tokens; see migrations/020_idempotency_reservation.sql.
+
+ALTER TABLE idempotency_keys
+    ADD COLUMN reservation_id BLOB NOT NULL DEFAULT X'00000000000000000000000000000000';
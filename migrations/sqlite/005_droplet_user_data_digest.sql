@@ -0,0 +1,3 @@
+-- Droplet user data digests; see migrations/021_droplet_user_data_digest.sql.
+
+ALTER TABLE droplets ADD COLUMN user_data_digest TEXT;
@@ -16,6 +16,8 @@ CONTROL_PLANE_URL="${CONTROL_PLANE_URL:-https://api.cedros.io}"
 BOT_CONFIG='${BOT_CONFIG}'
 CURL_CONNECT_TIMEOUT_SECONDS=10
 CURL_MAX_TIME_SECONDS=30
+# Reported at registration; bump when this script's behaviour changes.
+AGENT_VERSION="openclaw-bootstrap/1"
 
 # Workspace/customization (janebot-cli)
 CUSTOMIZER_REPO_URL="${CUSTOMIZER_REPO_URL:-https://github.com/janebot2026/janebot-cli.git}"
@@ -38,11 +40,83 @@ TOOLCHAIN_EXTRA_APT_PACKAGES="${TOOLCHAIN_EXTRA_APT_PACKAGES:-}"
 TOOLCHAIN_GLOBAL_NPM_PACKAGES="${TOOLCHAIN_GLOBAL_NPM_PACKAGES:-}"
 TOOLCHAIN_CARGO_CRATES="${TOOLCHAIN_CARGO_CRATES:-}"
 
+# `cloud_config` when cloud-init has already installed the base packages and
+# written the bot runner, its systemd unit and root-only env files.
+USER_DATA_FORMAT="${USER_DATA_FORMAT:-script}"
+
+# Phase progress reporting (POST /bot/:id/bootstrap_progress). Best-effort: a
+# control plane that is unreachable must never fail the bootstrap itself.
+CURRENT_PHASE="apt_update"
+
+report_progress() {
+    local phase="$1"
+    local exit_code="$2"
+    local message="${3:-}"
+    # Keep the hand-built JSON valid.
+    message="${message//[\"\\]/}"
+    curl -s -o /dev/null \
+        --connect-timeout "$CURL_CONNECT_TIMEOUT_SECONDS" \
+        --max-time "$CURL_MAX_TIME_SECONDS" \
+        -X POST \
+        -H "Content-Type: application/json" \
+        -H "Authorization: Bearer $REGISTRATION_TOKEN" \
+        -d "{\"phase\": \"$phase\", \"exit_code\": $exit_code, \"message\": \"$message\"}" \
+        "$CONTROL_PLANE_URL/bot/$BOT_ID/bootstrap_progress" >/dev/null 2>&1 || true
+}
+
+# With `set -e` any failing step exits the script; report which phase it was in.
+on_exit() {
+    local status=$?
+    if [ "$status" -ne 0 ]; then
+        report_progress "$CURRENT_PHASE" "$status" "Bootstrap aborted"
+    fi
+}
+trap on_exit EXIT
+
+# Log tails uploaded on failure (POST /bot/:id/bootstrap_logs); the control plane
+# rejects uploads over 64 KiB and redacts secrets before storing them.
+BOOTSTRAP_LOG="/var/log/cloud-init-output.log"
+CUSTOMIZER_LOG="/var/log/openclaw-bot.log"
+LOG_UPLOAD_MAX_BYTES=65536
+LOGS_UPLOADED=false
+
+upload_log() {
+    local source="$1"
+    local file="$2"
+    [ -f "$file" ] || return 0
+    tail -n 500 "$file" | tail -c "$LOG_UPLOAD_MAX_BYTES" | curl -s -o /dev/null \
+        --connect-timeout "$CURL_CONNECT_TIMEOUT_SECONDS" \
+        --max-time "$CURL_MAX_TIME_SECONDS" \
+        -X POST \
+        -H "Content-Type: text/plain" \
+        -H "Authorization: Bearer $REGISTRATION_TOKEN" \
+        --data-binary @- \
+        "$CONTROL_PLANE_URL/bot/$BOT_ID/bootstrap_logs?source=$source" >/dev/null 2>&1 || true
+}
+
+on_error() {
+    # Only fatal errors; failures under `set +e` (the best-effort customizer) are not.
+    case "$-" in
+        *e*) ;;
+        *) return 0 ;;
+    esac
+    if [ "$LOGS_UPLOADED" = "true" ]; then
+        return 0
+    fi
+    LOGS_UPLOADED=true
+    upload_log bootstrap "$BOOTSTRAP_LOG"
+    upload_log customizer "$CUSTOMIZER_LOG"
+}
+trap on_error ERR
+
 echo "=== OpenClaw Bot Setup Starting ==="
 echo "Bot ID: $BOT_ID"
 echo "Control Plane: $CONTROL_PLANE_URL"
 echo "Date: $(date)"
 
+if [ "$USER_DATA_FORMAT" = "cloud_config" ]; then
+    echo "=== System packages installed by cloud-init ==="
+else
 # Update system
 echo "=== Updating System ==="
 apt-get update
@@ -60,8 +134,11 @@ apt-get install -y \
     software-properties-common \
     apt-transport-https \
     jq
+fi
+report_progress apt_update 0
 
 # Install Node.js (janebot-cli requires node >=18; default is 20 LTS)
+CURRENT_PHASE="node"
 echo "=== Installing Node.js (for janebot-cli) ==="
 if command -v node >/dev/null 2>&1; then
     NODE_MAJOR=$(node -v 2>/dev/null | sed 's/^v\([0-9]*\).*/\1/')
@@ -97,8 +174,10 @@ if [ -n "$TOOLCHAIN_EXTRA_APT_PACKAGES" ]; then
     # shellcheck disable=SC2086
     apt-get install -y $TOOLCHAIN_EXTRA_APT_PACKAGES
 fi
+report_progress node 0
 
 # Install Docker
+CURRENT_PHASE="docker"
 echo "=== Installing Docker ==="
 install -m 0755 -d /etc/apt/keyrings
 curl -fsSL https://download.docker.com/linux/ubuntu/gpg | gpg --dearmor -o /etc/apt/keyrings/docker.gpg
@@ -112,8 +191,10 @@ apt-get install -y docker-ce docker-ce-cli containerd.io docker-compose-plugin
 # Start Docker
 systemctl enable docker
 systemctl start docker
+report_progress docker 0
 
-# Create bot user
+# Create bot user and install the remaining toolchain
+CURRENT_PHASE="rust"
 echo "=== Creating Bot User ==="
 useradd -m -s /bin/bash -U openclaw || true
 usermod -aG docker openclaw
@@ -136,6 +217,7 @@ if [ -n "$TOOLCHAIN_GLOBAL_NPM_PACKAGES" ]; then
     # shellcheck disable=SC2086
     npm install -g $TOOLCHAIN_GLOBAL_NPM_PACKAGES
 fi
+report_progress rust 0
 
 # Prepare log file early so bootstrap steps are captured
 touch /var/log/openclaw-bot.log
@@ -146,13 +228,14 @@ mkdir -p /opt/openclaw
 cd /opt/openclaw
 
 # Bootstrap customized workspace layout (best-effort)
+CURRENT_PHASE="customizer"
 echo "=== Bootstrapping Clawdbot Workspace (janebot-cli) ==="
-CUSTOMIZER_LOG="/var/log/openclaw-bot.log"
 CUSTOMIZER_MARKER="/opt/openclaw/.customizer_ran"
 CUSTOMIZER_STATUS_FILE="/opt/openclaw/customizer_status.txt"
 
 if [ -f "$CUSTOMIZER_MARKER" ]; then
     echo "Customizer already ran; skipping workspace bootstrap" | tee -a "$CUSTOMIZER_LOG"
+    report_progress customizer 0 "Already ran; skipped"
 else
 CUSTOMIZER_STATUS=0
 set +e
@@ -212,6 +295,10 @@ set -e
 if [ $CUSTOMIZER_STATUS -ne 0 ]; then
     echo "WARN: janebot-cli customization failed (status=$CUSTOMIZER_STATUS) at $(date); continuing bootstrap" \
         | tee -a "$CUSTOMIZER_LOG"
+    report_progress customizer "$CUSTOMIZER_STATUS" "janebot-cli customization failed; see $CUSTOMIZER_LOG"
+    upload_log customizer "$CUSTOMIZER_LOG"
+else
+    report_progress customizer 0
 fi
 
 # Mark as attempted so reboots don't re-run customization.
@@ -230,6 +317,7 @@ chown openclaw:openclaw "$CUSTOMIZER_MARKER" "$CUSTOMIZER_STATUS_FILE" 2>/dev/nu
 fi
 
 # Create the bot configuration file
+CURRENT_PHASE="register"
 echo "=== Creating Bot Configuration ==="
 if [ -n "$BOT_CONFIG" ] && [ "$BOT_CONFIG" != "\${BOT_CONFIG}" ]; then
     cat > config.json << EOFCFG
@@ -244,6 +332,9 @@ fi
 echo "=== Registering with Control Plane ==="
 MAX_RETRIES=5
 RETRY_COUNT=0
+# Public IPv4 from the DigitalOcean metadata service; empty if unavailable.
+DROPLET_IP=$(curl -s --connect-timeout 2 --max-time 5 \
+    http://169.254.169.254/metadata/v1/interfaces/public/0/ipv4/address 2>/dev/null || true)
 
 while [ $RETRY_COUNT -lt $MAX_RETRIES ]; do
     HTTP_CODE=$(curl -s -o /tmp/register_response.json -w "%{http_code}" \
@@ -252,7 +343,7 @@ while [ $RETRY_COUNT -lt $MAX_RETRIES ]; do
         -X POST \
         -H "Content-Type: application/json" \
         -H "Authorization: Bearer $REGISTRATION_TOKEN" \
-        -d "{\"bot_id\": \"$BOT_ID\"}" \
+        -d "{\"bot_id\": \"$BOT_ID\", \"ip_address\": \"$DROPLET_IP\", \"agent_version\": \"$AGENT_VERSION\"}" \
         "$CONTROL_PLANE_URL/bot/register" 2>/dev/null || echo "000")
     
     if [ "$HTTP_CODE" = "200" ] || [ "$HTTP_CODE" = "201" ]; then
@@ -268,9 +359,16 @@ done
 if [ $RETRY_COUNT -eq $MAX_RETRIES ]; then
     echo "ERROR: Failed to register with control plane after $MAX_RETRIES attempts"
     # Continue anyway - bot can retry registration later
+    report_progress register 1 "Registration failed after $MAX_RETRIES attempts (last HTTP $HTTP_CODE)"
+else
+    report_progress register 0
 fi
 
 # Create the main bot runner script
+CURRENT_PHASE="service_start"
+if [ "$USER_DATA_FORMAT" = "cloud_config" ]; then
+    echo "=== Bot Runner and Systemd Service written by cloud-init ==="
+else
 echo "=== Creating Bot Runner ==="
 cat > /opt/openclaw/run.sh << 'EOFSCRIPT'
 #!/bin/bash
@@ -388,6 +486,7 @@ StandardError=append:/var/log/openclaw-bot.log
 [Install]
 WantedBy=multi-user.target
 EOFSERVICE
+fi
 
 # Set proper ownership for /opt/openclaw and log file
 echo "=== Setting Permissions ==="
@@ -411,6 +510,7 @@ echo "=== Starting Bot Service ==="
 systemctl daemon-reload
 systemctl enable openclaw-bot.service
 systemctl start openclaw-bot.service
+report_progress service_start 0
 
 # Create a simple health check endpoint
 cat > /opt/openclaw/health.sh << 'EOFHEALTH'
@@ -0,0 +1,120 @@
+//! Bootstrap progress reported by droplets while `openclaw-bootstrap.sh` runs.
+//!
+//! Each phase of the script reports its exit status, so a failed customizer or
+//! toolchain step shows up on `GET /bots/:id/bootstrap` without SSH access. When
+//! the script fails it also uploads the tail of its logs, redacted before storage.
+
+use crate::application::LifecycleError;
+use crate::domain::{
+    redact_secrets, BootstrapEvent, BootstrapLog, BootstrapLogSource, BootstrapPhase,
+};
+use crate::infrastructure::{BootstrapEventRepository, BootstrapLogRepository, BotRepository};
+use std::sync::Arc;
+use tracing::{info, instrument, warn};
+use uuid::Uuid;
+
+/// Most events returned for one bot; older ones are left out of the timeline.
+pub const BOOTSTRAP_TIMELINE_LIMIT: i64 = 200;
+
+/// Most log uploads kept for one bot; each upload deletes those older than these.
+pub const BOOTSTRAP_LOGS_LIMIT: i64 = 20;
+
+pub struct BootstrapService<B, E, L>
+where
+    B: BotRepository + ?Sized,
+    E: BootstrapEventRepository + ?Sized,
+    L: BootstrapLogRepository + ?Sized,
+{
+    bot_repo: Arc<B>,
+    event_repo: Arc<E>,
+    log_repo: Arc<L>,
+}
+
+impl<B, E, L> BootstrapService<B, E, L>
+where
+    B: BotRepository + ?Sized,
+    E: BootstrapEventRepository + ?Sized,
+    L: BootstrapLogRepository + ?Sized,
+{
+    pub fn new(bot_repo: Arc<B>, event_repo: Arc<E>, log_repo: Arc<L>) -> Self {
+        Self {
+            bot_repo,
+            event_repo,
+            log_repo,
+        }
+    }
+
+    /// Authenticates a droplet by its registration token and stores one progress report.
+    #[instrument(skip(self, token, message), fields(bot_id = %bot_id, phase = %phase))]
+    pub async fn record_progress(
+        &self,
+        bot_id: Uuid,
+        token: &str,
+        phase: BootstrapPhase,
+        exit_code: i32,
+        message: Option<String>,
+    ) -> Result<BootstrapEvent, LifecycleError> {
+        self.bot_repo.get_by_id_with_token(bot_id, token).await?;
+
+        let event = BootstrapEvent::new(bot_id, phase, exit_code, message);
+        self.event_repo.record(&event).await?;
+
+        if event.succeeded() {
+            info!("Bot {} finished bootstrap phase {}", bot_id, phase);
+        } else {
+            warn!(
+                "Bot {} bootstrap phase {} failed with exit code {}",
+                bot_id, phase, exit_code
+            );
+        }
+        Ok(event)
+    }
+
+    /// The bot's bootstrap timeline, oldest report first.
+    pub async fn timeline(&self, bot_id: Uuid) -> Result<Vec<BootstrapEvent>, LifecycleError> {
+        self.bot_repo.get_by_id(bot_id).await?;
+        Ok(self
+            .event_repo
+            .list_for_bot(bot_id, BOOTSTRAP_TIMELINE_LIMIT)
+            .await?)
+    }
+
+    /// Authenticates a droplet by its registration token and stores a log tail,
+    /// with the token and other secret-looking values redacted. The upload is
+    /// filed under the bot's current droplet, i.e. the provisioning attempt.
+    #[instrument(skip(self, token, content), fields(bot_id = %bot_id, source = %source))]
+    pub async fn upload_log(
+        &self,
+        bot_id: Uuid,
+        token: &str,
+        source: BootstrapLogSource,
+        content: &str,
+    ) -> Result<BootstrapLog, LifecycleError> {
+        let bot = self.bot_repo.get_by_id_with_token(bot_id, token).await?;
+
+        let log = BootstrapLog::new(
+            bot_id,
+            bot.droplet_id,
+            source,
+            redact_secrets(content, &[token]),
+        );
+        self.log_repo.record(&log, BOOTSTRAP_LOGS_LIMIT).await?;
+
+        warn!(
+            "Bot {} uploaded its {} log ({} bytes)",
+            bot_id,
+            source,
+            log.content.len()
+        );
+        Ok(log)
+    }
+
+    /// The bot's uploaded logs, newest first.
+    pub async fn logs(&self, bot_id: Uuid) -> Result<Vec<BootstrapLog>, LifecycleError> {
+        self.bot_repo.get_by_id(bot_id).await?;
+        Ok(self
+            .log_repo
+            .list_for_bot(bot_id, BOOTSTRAP_LOGS_LIMIT)
+            .await?)
+    }
+}
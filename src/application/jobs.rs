@@ -0,0 +1,500 @@
+//! Durable job queue for provisioning work.
+//!
+//! HTTP handlers enqueue a [`Job`] and return immediately; [`JobWorker`]s claim jobs
+//! from the queue, run them through a [`JobHandler`] and record the outcome. Jobs
+//! live in the database, so work queued or in flight when the process stops is
+//! picked up again once the worker lease expires.
+
+use crate::application::{ProvisioningError, ProvisioningService};
+use crate::domain::{Job, JobKind};
+use crate::infrastructure::{
+    AccountRepository, AppConfig, BotRepository, ConfigRepository, DropletRepository,
+    JobRepository, RepositoryError,
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, instrument, warn};
+use uuid::Uuid;
+
+/// Executes the work described by a job. Implementations must tolerate being
+/// re-run after a partial attempt.
+#[async_trait]
+pub trait JobHandler: Send + Sync {
+    async fn handle(&self, job: &Job) -> Result<(), ProvisioningError>;
+}
+
+#[async_trait]
+impl<A, B, C, D> JobHandler for ProvisioningService<A, B, C, D>
+where
+    A: AccountRepository + ?Sized,
+    B: BotRepository + ?Sized,
+    C: ConfigRepository + ?Sized,
+    D: DropletRepository + ?Sized,
+{
+    async fn handle(&self, job: &Job) -> Result<(), ProvisioningError> {
+        match job.kind {
+            JobKind::SpawnBot => self.provision_bot(job.bot_id).await,
+            JobKind::DestroyBot => self.destroy_bot(job.bot_id).await,
+            JobKind::RedeployBot => self.redeploy_bot(job.bot_id).await,
+            JobKind::RebuildBot => self.rebuild_bot(job.bot_id).await,
+            JobKind::HibernateBot => self.hibernate_bot(job.bot_id).await,
+            JobKind::WakeBot => self.wake_bot(job.bot_id).await,
+            JobKind::PauseBot => self.pause_bot(job.bot_id).await,
+            JobKind::ResumeBot => self.resume_bot(job.bot_id).await,
+        }
+    }
+}
+
+/// Enqueue and inspect jobs.
+pub struct JobService<J>
+where
+    J: JobRepository + ?Sized,
+{
+    job_repo: Arc<J>,
+    max_attempts: i32,
+}
+
+impl<J> JobService<J>
+where
+    J: JobRepository + ?Sized,
+{
+    pub fn new(job_repo: Arc<J>, max_attempts: i32) -> Self {
+        Self {
+            job_repo,
+            max_attempts,
+        }
+    }
+
+    /// Fails with `RepositoryError::Conflict` if the bot already has a job in progress.
+    #[instrument(skip(self), fields(bot_id = %bot_id))]
+    pub async fn enqueue(&self, kind: JobKind, bot_id: Uuid) -> Result<Job, RepositoryError> {
+        let job = Job::new(kind, bot_id, self.max_attempts);
+        self.job_repo.enqueue(&job).await?;
+        info!(job_id = %job.id, kind = %kind, "Enqueued job");
+        Ok(job)
+    }
+
+    pub async fn get_job(&self, job_id: Uuid) -> Result<Job, RepositoryError> {
+        self.job_repo.get_by_id(job_id).await
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct JobWorkerSettings {
+    /// Sleep between polls when the queue is empty.
+    pub poll_interval: Duration,
+    /// How long a claimed job is held before another worker may reclaim it.
+    /// Must comfortably exceed the slowest job (DO retries included).
+    pub lease: Duration,
+    /// Delay before the first retry; doubles per attempt up to `retry_max_delay`.
+    pub retry_base_delay: Duration,
+    pub retry_max_delay: Duration,
+}
+
+impl Default for JobWorkerSettings {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_secs(1),
+            lease: Duration::from_secs(900),
+            retry_base_delay: Duration::from_secs(5),
+            retry_max_delay: Duration::from_secs(300),
+        }
+    }
+}
+
+impl JobWorkerSettings {
+    /// The defaults, with the lease from `job_lease_secs`.
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            lease: Duration::from_secs(config.job_lease_secs),
+            ..Self::default()
+        }
+    }
+
+    /// Backoff before retrying a job that has been attempted `attempts` times.
+    pub fn retry_delay(&self, attempts: i32) -> Duration {
+        let exponent = attempts.saturating_sub(1).clamp(0, 16) as u32;
+        self.retry_base_delay
+            .saturating_mul(2_u32.pow(exponent))
+            .min(self.retry_max_delay)
+    }
+}
+
+/// Claims and runs jobs. Run several concurrently for parallelism; the queue's
+/// row locking guarantees each job is held by one worker at a time.
+pub struct JobWorker<J, H>
+where
+    J: JobRepository + ?Sized,
+    H: JobHandler,
+{
+    job_repo: Arc<J>,
+    handler: Arc<H>,
+    settings: JobWorkerSettings,
+}
+
+impl<J, H> JobWorker<J, H>
+where
+    J: JobRepository + ?Sized,
+    H: JobHandler,
+{
+    pub fn new(job_repo: Arc<J>, handler: Arc<H>, settings: JobWorkerSettings) -> Self {
+        Self {
+            job_repo,
+            handler,
+            settings,
+        }
+    }
+
+    /// Poll the queue until `shutdown` is cancelled. A job already running when
+    /// shutdown is requested is finished first, so its outcome is recorded.
+    pub async fn run(&self, shutdown: CancellationToken) {
+        while !shutdown.is_cancelled() {
+            let idle = match self.run_once().await {
+                Ok(claimed) => !claimed,
+                Err(e) => {
+                    error!(error = %e, "Failed to claim job");
+                    true
+                }
+            };
+            if idle {
+                tokio::select! {
+                    _ = sleep(self.settings.poll_interval) => {}
+                    _ = shutdown.cancelled() => {}
+                }
+            }
+        }
+        info!("Job worker stopped");
+    }
+
+    /// Claim and run at most one job. Returns `false` when nothing was due.
+    pub async fn run_once(&self) -> Result<bool, RepositoryError> {
+        let lease =
+            chrono::Duration::from_std(self.settings.lease).unwrap_or(chrono::Duration::MAX);
+        match self.job_repo.claim_next(lease).await? {
+            Some(job) => {
+                self.execute(&job).await;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    #[instrument(
+        skip_all,
+        fields(job_id = %job.id, job_kind = %job.kind, bot_id = %job.bot_id, attempt = job.attempts)
+    )]
+    async fn execute(&self, job: &Job) {
+        // A reclaimed job whose final attempt died with its worker.
+        if job.attempts > job.max_attempts {
+            self.record(
+                job,
+                self.job_repo
+                    .mark_failed(job, "Exceeded max attempts")
+                    .await,
+            );
+            return;
+        }
+
+        let outcome = match self.handler.handle(job).await {
+            Ok(()) => {
+                info!("Job succeeded");
+                self.job_repo.mark_succeeded(job).await
+            }
+            Err(e) if e.is_retryable() && !job.attempts_exhausted() => {
+                let delay = self.settings.retry_delay(job.attempts);
+                warn!(error = %e, retry_in_secs = delay.as_secs(), "Job failed, will retry");
+                let run_at =
+                    Utc::now() + chrono::Duration::from_std(delay).unwrap_or(chrono::Duration::MAX);
+                self.job_repo
+                    .schedule_retry(job, &e.to_string(), run_at)
+                    .await
+            }
+            Err(e) => {
+                error!(error = %e, "Job failed permanently");
+                self.job_repo.mark_failed(job, &e.to_string()).await
+            }
+        };
+        self.record(job, outcome);
+    }
+
+    fn record(&self, job: &Job, outcome: Result<(), RepositoryError>) {
+        match outcome {
+            Ok(()) => {}
+            Err(RepositoryError::Conflict(_)) => {
+                warn!(job_id = %job.id, "Job lease lost to another worker; outcome discarded");
+            }
+            Err(e) => {
+                // The lease will expire and the job will be retried.
+                error!(job_id = %job.id, error = %e, "Failed to record job outcome");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::JobStatus;
+    use crate::domain::{Account, Bot, Persona, SubscriptionTier};
+    use crate::infrastructure::{AccountRepository, DigitalOceanError, MemoryStore};
+    use std::sync::Mutex;
+
+    /// Fails with the queued errors in order, then succeeds.
+    struct ScriptedHandler {
+        failures: Mutex<Vec<ProvisioningError>>,
+    }
+
+    impl ScriptedHandler {
+        fn failing_with(mut failures: Vec<ProvisioningError>) -> Self {
+            failures.reverse();
+            Self {
+                failures: Mutex::new(failures),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl JobHandler for ScriptedHandler {
+        async fn handle(&self, _job: &Job) -> Result<(), ProvisioningError> {
+            match self.failures.lock().unwrap().pop() {
+                Some(e) => Err(e),
+                None => Ok(()),
+            }
+        }
+    }
+
+    /// Simulates a shutdown signal arriving while a job is running.
+    struct CancelDuringJob(CancellationToken);
+
+    #[async_trait]
+    impl JobHandler for CancelDuringJob {
+        async fn handle(&self, _job: &Job) -> Result<(), ProvisioningError> {
+            self.0.cancel();
+            Ok(())
+        }
+    }
+
+    fn immediate_retries() -> JobWorkerSettings {
+        JobWorkerSettings {
+            retry_base_delay: Duration::ZERO,
+            retry_max_delay: Duration::ZERO,
+            ..JobWorkerSettings::default()
+        }
+    }
+
+    /// A bot for jobs to be queued against.
+    async fn bot(store: &MemoryStore) -> Uuid {
+        let account = Account::new(Uuid::new_v4().to_string(), SubscriptionTier::Basic);
+        store.accounts().create(&account).await.unwrap();
+        let bot = Bot::new(account.id, "queued".to_string(), Persona::Beginner);
+        store.bots().create(&bot).await.unwrap();
+        bot.id
+    }
+
+    fn rate_limited() -> ProvisioningError {
+        ProvisioningError::DigitalOcean(DigitalOceanError::RateLimited)
+    }
+
+    #[tokio::test]
+    async fn enqueue_rejects_second_active_job_for_same_bot() {
+        let store = MemoryStore::new();
+        let service = JobService::new(Arc::new(store.jobs()), 3);
+        let bot_id = bot(&store).await;
+
+        let job = service.enqueue(JobKind::SpawnBot, bot_id).await.unwrap();
+        assert_eq!(job.status, JobStatus::Queued);
+        assert_eq!(job.max_attempts, 3);
+
+        let err = service
+            .enqueue(JobKind::DestroyBot, bot_id)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RepositoryError::Conflict(_)));
+    }
+
+    #[tokio::test]
+    async fn worker_retries_transient_failures_then_succeeds() {
+        let store = MemoryStore::new();
+        let repo = Arc::new(store.jobs());
+        let queued = Job::new(JobKind::SpawnBot, bot(&store).await, 3);
+        repo.enqueue(&queued).await.unwrap();
+        let worker = JobWorker::new(
+            repo.clone(),
+            Arc::new(ScriptedHandler::failing_with(vec![rate_limited()])),
+            immediate_retries(),
+        );
+
+        assert!(worker.run_once().await.unwrap());
+        let job = repo.get_by_id(queued.id).await.unwrap();
+        assert_eq!(job.status, JobStatus::Queued);
+        assert_eq!(
+            job.last_error.as_deref(),
+            Some("DigitalOcean error: Rate limited")
+        );
+
+        assert!(worker.run_once().await.unwrap());
+        let job = repo.get_by_id(queued.id).await.unwrap();
+        assert_eq!(job.status, JobStatus::Succeeded);
+        assert_eq!(job.attempts, 2);
+
+        assert!(!worker.run_once().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn worker_fails_permanent_errors_without_retry() {
+        let store = MemoryStore::new();
+        let repo = Arc::new(store.jobs());
+        let queued = Job::new(JobKind::RedeployBot, bot(&store).await, 5);
+        repo.enqueue(&queued).await.unwrap();
+        let worker = JobWorker::new(
+            repo.clone(),
+            Arc::new(ScriptedHandler::failing_with(vec![
+                ProvisioningError::InvalidConfig("No config found".to_string()),
+            ])),
+            immediate_retries(),
+        );
+
+        worker.run_once().await.unwrap();
+        let job = repo.get_by_id(queued.id).await.unwrap();
+        assert_eq!(job.status, JobStatus::Failed);
+        assert_eq!(job.attempts, 1);
+        assert!(job.completed_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn worker_gives_up_after_max_attempts() {
+        let store = MemoryStore::new();
+        let repo = Arc::new(store.jobs());
+        let queued = Job::new(JobKind::DestroyBot, bot(&store).await, 2);
+        repo.enqueue(&queued).await.unwrap();
+        let worker = JobWorker::new(
+            repo.clone(),
+            Arc::new(ScriptedHandler::failing_with(vec![
+                rate_limited(),
+                rate_limited(),
+                rate_limited(),
+            ])),
+            immediate_retries(),
+        );
+
+        worker.run_once().await.unwrap();
+        worker.run_once().await.unwrap();
+        let job = repo.get_by_id(queued.id).await.unwrap();
+        assert_eq!(job.status, JobStatus::Failed);
+        assert_eq!(job.attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn expired_lease_is_reclaimed_and_stale_outcome_discarded() {
+        let store = MemoryStore::new();
+        let repo = Arc::new(store.jobs());
+        let queued = Job::new(JobKind::SpawnBot, bot(&store).await, 3);
+        repo.enqueue(&queued).await.unwrap();
+
+        // First worker claims with an already-expired lease, then "dies".
+        let abandoned = repo
+            .claim_next(chrono::Duration::seconds(-1))
+            .await
+            .unwrap()
+            .unwrap();
+
+        let worker = JobWorker::new(
+            repo.clone(),
+            Arc::new(ScriptedHandler::failing_with(vec![])),
+            immediate_retries(),
+        );
+        assert!(worker.run_once().await.unwrap());
+        let job = repo.get_by_id(queued.id).await.unwrap();
+        assert_eq!(job.status, JobStatus::Succeeded);
+        assert_eq!(job.attempts, 2);
+
+        let err = repo.mark_failed(&abandoned, "late").await.unwrap_err();
+        assert!(matches!(err, RepositoryError::Conflict(_)));
+        let job = repo.get_by_id(queued.id).await.unwrap();
+        assert_eq!(job.status, JobStatus::Succeeded);
+    }
+
+    #[tokio::test]
+    async fn worker_stops_promptly_when_idle_and_shut_down() {
+        let shutdown = CancellationToken::new();
+        let worker = JobWorker::new(
+            Arc::new(MemoryStore::new().jobs()),
+            Arc::new(ScriptedHandler::failing_with(vec![])),
+            JobWorkerSettings {
+                poll_interval: Duration::from_secs(3600),
+                ..JobWorkerSettings::default()
+            },
+        );
+
+        let run = tokio::spawn({
+            let shutdown = shutdown.clone();
+            async move { worker.run(shutdown).await }
+        });
+        shutdown.cancel();
+        tokio::time::timeout(Duration::from_secs(5), run)
+            .await
+            .expect("worker exits on shutdown")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn worker_finishes_in_flight_job_before_stopping() {
+        let store = MemoryStore::new();
+        let repo = Arc::new(store.jobs());
+        let mut queued = Vec::new();
+        for _ in 0..2 {
+            let job = Job::new(JobKind::SpawnBot, bot(&store).await, 3);
+            repo.enqueue(&job).await.unwrap();
+            queued.push(job);
+        }
+        let shutdown = CancellationToken::new();
+        let worker = JobWorker::new(
+            repo.clone(),
+            Arc::new(CancelDuringJob(shutdown.clone())),
+            immediate_retries(),
+        );
+
+        worker.run(shutdown).await;
+
+        let first = repo.get_by_id(queued[0].id).await.unwrap();
+        assert_eq!(first.status, JobStatus::Succeeded);
+        // Shutdown was requested mid-job, so the next job is left for another process.
+        let second = repo.get_by_id(queued[1].id).await.unwrap();
+        assert_eq!(second.status, JobStatus::Queued);
+    }
+
+    #[test]
+    fn retry_delay_doubles_and_caps() {
+        let settings = JobWorkerSettings {
+            retry_base_delay: Duration::from_secs(5),
+            retry_max_delay: Duration::from_secs(60),
+            ..JobWorkerSettings::default()
+        };
+        assert_eq!(settings.retry_delay(1), Duration::from_secs(5));
+        assert_eq!(settings.retry_delay(2), Duration::from_secs(10));
+        assert_eq!(settings.retry_delay(4), Duration::from_secs(40));
+        assert_eq!(settings.retry_delay(5), Duration::from_secs(60));
+        assert_eq!(settings.retry_delay(100), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn only_transient_provisioning_errors_are_retryable() {
+        assert!(rate_limited().is_retryable());
+        assert!(
+            ProvisioningError::DigitalOcean(DigitalOceanError::RequestFailed(
+                "timeout".to_string()
+            ))
+            .is_retryable()
+        );
+        assert!(!ProvisioningError::InvalidConfig("bad".to_string()).is_retryable());
+        assert!(
+            !ProvisioningError::Repository(RepositoryError::NotFound("bot".to_string()))
+                .is_retryable()
+        );
+        assert!(!ProvisioningError::AccountLimitReached(1).is_retryable());
+    }
+}
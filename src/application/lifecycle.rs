@@ -1,9 +1,9 @@
-use crate::domain::{Bot, BotStatus, StoredBotConfig};
-use crate::infrastructure::{BotRepository, ConfigRepository, RepositoryError};
+use crate::domain::{Bot, BotStatus, InvalidTransition, StoredBotConfig, TransitionReason};
+use crate::infrastructure::{BotFilter, BotRepository, ConfigRepository, RepositoryError};
 use chrono::{Duration, Utc};
 use std::sync::Arc;
 use thiserror::Error;
-use tracing::{info, warn};
+use tracing::{info, instrument, warn};
 use uuid::Uuid;
 
 #[derive(Error, Debug)]
@@ -19,12 +19,14 @@ pub enum LifecycleError {
         acknowledged: Uuid,
         desired: Option<Uuid>,
     },
+    #[error(transparent)]
+    InvalidTransition(#[from] InvalidTransition),
 }
 
 pub struct BotLifecycleService<B, C>
 where
-    B: BotRepository,
-    C: ConfigRepository,
+    B: BotRepository + ?Sized,
+    C: ConfigRepository + ?Sized,
 {
     bot_repo: Arc<B>,
     config_repo: Arc<C>,
@@ -32,8 +34,8 @@ where
 
 impl<B, C> BotLifecycleService<B, C>
 where
-    B: BotRepository,
-    C: ConfigRepository,
+    B: BotRepository + ?Sized,
+    C: ConfigRepository + ?Sized,
 {
     pub fn new(bot_repo: Arc<B>, config_repo: Arc<C>) -> Self {
         Self {
@@ -54,6 +56,22 @@ where
         Ok(self.bot_repo.get_by_id_with_token(bot_id, token).await?)
     }
 
+    /// Authenticates a droplet's `POST /bot/register` and records the registration.
+    #[instrument(skip(self, token), fields(bot_id = %bot_id))]
+    pub async fn register_bot(
+        &self,
+        bot_id: Uuid,
+        token: &str,
+        ip: Option<&str>,
+        agent_version: Option<&str>,
+    ) -> Result<Bot, LifecycleError> {
+        let bot = self.bot_repo.get_by_id_with_token(bot_id, token).await?;
+        self.bot_repo
+            .record_registration(bot_id, ip, agent_version)
+            .await?;
+        Ok(bot)
+    }
+
     /// PERF-002: List bots with pagination support
     /// - limit: Maximum number of bots to return
     /// - offset: Number of bots to skip
@@ -69,6 +87,17 @@ where
             .await?)
     }
 
+    /// Bots across all accounts matching `filter`, newest first.
+    pub async fn list_bots(
+        &self,
+        filter: &BotFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Bot>, LifecycleError> {
+        Ok(self.bot_repo.list(filter, limit, offset).await?)
+    }
+
+    #[instrument(skip(self, config), fields(bot_id = %bot_id))]
     pub async fn create_bot_config(
         &self,
         bot_id: Uuid,
@@ -108,6 +137,7 @@ where
         Ok(config_with_version)
     }
 
+    #[instrument(skip(self), fields(bot_id = %bot_id))]
     pub async fn acknowledge_config(
         &self,
         bot_id: Uuid,
@@ -128,13 +158,26 @@ where
             });
         }
 
+        // An acknowledgement brings a provisioning (or recovered) bot online;
+        // for bots that are online or paused it only records the applied config.
+        let brings_online = matches!(bot.status, BotStatus::Provisioning | BotStatus::Error);
+        if brings_online {
+            bot.check_transition(BotStatus::Online, TransitionReason::ConfigAcknowledged)?;
+        }
+
         self.bot_repo
             .update_config_version(bot_id, Some(config_id), Some(config_id))
             .await?;
 
-        if bot.status == BotStatus::Provisioning || bot.status == BotStatus::Pending {
+        if brings_online {
             self.bot_repo
-                .update_status(bot_id, BotStatus::Online)
+                .transition_status(
+                    bot_id,
+                    bot.status,
+                    BotStatus::Online,
+                    TransitionReason::ConfigAcknowledged,
+                    None,
+                )
                 .await?;
         }
 
@@ -159,27 +202,48 @@ where
         }
     }
 
+    #[instrument(skip(self), fields(bot_id = %bot_id))]
     pub async fn record_heartbeat(&self, bot_id: Uuid) -> Result<(), LifecycleError> {
         self.bot_repo.update_heartbeat(bot_id).await?;
         Ok(())
     }
 
     /// Check for bots with stale heartbeats and mark them as Error (HIGH-001)
+    #[instrument(skip(self))]
     pub async fn check_stale_bots(
         &self,
         heartbeat_timeout: Duration,
     ) -> Result<Vec<Bot>, LifecycleError> {
         let threshold = Utc::now() - heartbeat_timeout;
-        let stale_bots = self.bot_repo.list_stale_bots(threshold).await?;
+        let mut stale_bots = Vec::new();
 
-        for bot in &stale_bots {
+        for bot in self.bot_repo.list_stale_bots(threshold).await? {
             warn!(
                 "Bot {} heartbeat timeout (last: {:?}), marking as Error",
                 bot.id, bot.last_heartbeat_at
             );
-            self.bot_repo
-                .update_status(bot.id, BotStatus::Error)
-                .await?;
+            let detail = match bot.last_heartbeat_at {
+                Some(at) => format!("No heartbeat since {}", at.to_rfc3339()),
+                None => "No heartbeat received since coming online".to_string(),
+            };
+            match self
+                .bot_repo
+                .transition_status(
+                    bot.id,
+                    BotStatus::Online,
+                    BotStatus::Error,
+                    TransitionReason::HeartbeatTimeout,
+                    Some(&detail),
+                )
+                .await
+            {
+                Ok(()) => stale_bots.push(bot),
+                // Paused or destroyed since it was listed; no longer ours to mark.
+                Err(RepositoryError::InvalidTransition(e)) => {
+                    info!("Skipping bot {}: {}", bot.id, e);
+                }
+                Err(e) => return Err(e.into()),
+            }
         }
 
         if !stale_bots.is_empty() {
@@ -191,4 +255,63 @@ where
 
         Ok(stale_bots)
     }
+
+    /// Mark bots whose droplet has not registered within `deadline` of entering
+    /// `Provisioning` as Error. Returned bots carry their updated
+    /// `registration_timeouts` count.
+    #[instrument(skip(self))]
+    pub async fn check_registration_timeouts(
+        &self,
+        deadline: Duration,
+    ) -> Result<Vec<Bot>, LifecycleError> {
+        let threshold = Utc::now() - deadline;
+        let mut timed_out = Vec::new();
+
+        for mut bot in self.bot_repo.list_registration_overdue(threshold).await? {
+            warn!(
+                "Bot {} has not registered since {}, marking as Error",
+                bot.id, bot.status_changed_at
+            );
+            let detail = format!(
+                "Droplet did not register within {} minutes of provisioning",
+                deadline.num_minutes()
+            );
+            match self
+                .bot_repo
+                .transition_status(
+                    bot.id,
+                    BotStatus::Provisioning,
+                    BotStatus::Error,
+                    TransitionReason::RegistrationTimeout,
+                    Some(&detail),
+                )
+                .await
+            {
+                Ok(()) => {
+                    bot.status = BotStatus::Error;
+                    bot.status_reason = Some(TransitionReason::RegistrationTimeout);
+                    bot.status_detail = Some(detail);
+                    bot.registration_timeouts = self
+                        .bot_repo
+                        .increment_registration_timeouts(bot.id)
+                        .await?;
+                    timed_out.push(bot);
+                }
+                // Registered, failed or destroyed since it was listed.
+                Err(RepositoryError::InvalidTransition(e)) => {
+                    info!("Skipping bot {}: {}", bot.id, e);
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        if !timed_out.is_empty() {
+            info!(
+                "Marked {} bot(s) as Error due to registration timeout",
+                timed_out.len()
+            );
+        }
+
+        Ok(timed_out)
+    }
 }
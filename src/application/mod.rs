@@ -1,5 +1,17 @@
+pub mod bootstrap_progress;
+pub mod jobs;
 pub mod lifecycle;
+pub mod outbox;
 pub mod provisioning;
+pub mod provisioning_settings;
+pub mod user_data;
+pub mod watchdog;
 
+pub use bootstrap_progress::*;
+pub use jobs::*;
 pub use lifecycle::*;
+pub use outbox::*;
 pub use provisioning::*;
+pub use provisioning_settings::*;
+pub use user_data::*;
+pub use watchdog::*;
@@ -0,0 +1,263 @@
+//! Executor for the `provider_operations` outbox.
+//!
+//! Droplet creates and destroys are recorded in the same transaction as the
+//! state change that needs them, and the writer runs them right after it
+//! commits. [`OutboxExecutor`] finishes operations whose writer crashed or
+//! failed before completing them, once their lease expires.
+
+use crate::application::{ProvisioningError, ProvisioningService};
+use crate::domain::ProviderOperation;
+use crate::infrastructure::{
+    AccountRepository, BotRepository, ConfigRepository, DropletRepository,
+    ProviderOperationRepository, RepositoryError,
+};
+use async_trait::async_trait;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, instrument, warn};
+
+/// Runs a claimed provider operation to completion. Implementations must
+/// complete or fail the operation themselves, and tolerate it having been
+/// partly carried out by an earlier attempt.
+#[async_trait]
+pub trait ProviderOperationHandler: Send + Sync {
+    async fn execute(&self, operation: &ProviderOperation) -> Result<(), ProvisioningError>;
+}
+
+#[async_trait]
+impl<A, B, C, D> ProviderOperationHandler for ProvisioningService<A, B, C, D>
+where
+    A: AccountRepository + ?Sized,
+    B: BotRepository + ?Sized,
+    C: ConfigRepository + ?Sized,
+    D: DropletRepository + ?Sized,
+{
+    async fn execute(&self, operation: &ProviderOperation) -> Result<(), ProvisioningError> {
+        self.execute_provider_operation(operation).await
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OutboxSettings {
+    /// Time between scans for abandoned operations.
+    pub interval: Duration,
+    /// How long a claimed operation is held before it may be claimed again.
+    /// Must comfortably exceed a droplet create, DO retries included.
+    pub lease: Duration,
+    /// Attempts before an operation is marked failed and left alone.
+    pub max_attempts: i32,
+}
+
+impl Default for OutboxSettings {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(30),
+            lease: Duration::from_secs(600),
+            max_attempts: 10,
+        }
+    }
+}
+
+pub struct OutboxExecutor<P, H>
+where
+    P: ProviderOperationRepository + ?Sized,
+    H: ProviderOperationHandler,
+{
+    operations: Arc<P>,
+    handler: Arc<H>,
+    settings: OutboxSettings,
+}
+
+impl<P, H> OutboxExecutor<P, H>
+where
+    P: ProviderOperationRepository + ?Sized,
+    H: ProviderOperationHandler,
+{
+    pub fn new(operations: Arc<P>, handler: Arc<H>, settings: OutboxSettings) -> Self {
+        Self {
+            operations,
+            handler,
+            settings,
+        }
+    }
+
+    /// Scan every `interval` until `shutdown` is cancelled.
+    pub async fn run(&self, shutdown: CancellationToken) {
+        while !shutdown.is_cancelled() {
+            if let Err(e) = self.run_once().await {
+                error!(error = %e, "Failed to claim provider operation");
+            }
+            tokio::select! {
+                _ = sleep(self.settings.interval) => {}
+                _ = shutdown.cancelled() => {}
+            }
+        }
+        info!("Outbox executor stopped");
+    }
+
+    /// Claim and run every operation whose lease has expired. Returns how many
+    /// were attempted; an operation that fails is not retried within the scan.
+    pub async fn run_once(&self) -> Result<usize, RepositoryError> {
+        let lease =
+            chrono::Duration::from_std(self.settings.lease).unwrap_or(chrono::Duration::MAX);
+        let mut attempted = HashSet::new();
+        while let Some(operation) = self.operations.claim_expired(lease).await? {
+            if !attempted.insert(operation.id) {
+                break;
+            }
+            self.execute(&operation).await;
+        }
+        Ok(attempted.len())
+    }
+
+    #[instrument(
+        skip_all,
+        fields(operation_id = %operation.id, kind = %operation.kind, bot_id = %operation.bot_id, attempt = operation.attempts)
+    )]
+    async fn execute(&self, operation: &ProviderOperation) {
+        // A reclaimed operation whose final attempt died with its executor.
+        if operation.attempts > self.settings.max_attempts {
+            self.record(
+                self.operations
+                    .mark_failed(operation, "Exceeded max attempts")
+                    .await,
+            );
+            return;
+        }
+
+        let Err(e) = self.handler.execute(operation).await else {
+            info!("Provider operation completed");
+            return;
+        };
+        let outcome = if operation.attempts >= self.settings.max_attempts {
+            error!(error = %e, "Provider operation failed permanently");
+            self.operations.mark_failed(operation, &e.to_string()).await
+        } else {
+            // Left pending; it is claimed again once the lease expires.
+            warn!(error = %e, "Provider operation failed, will retry");
+            self.operations
+                .record_error(operation, &e.to_string())
+                .await
+        };
+        self.record(outcome);
+    }
+
+    fn record(&self, outcome: Result<(), RepositoryError>) {
+        match outcome {
+            Ok(()) => {}
+            Err(RepositoryError::Conflict(_)) => {
+                warn!("Provider operation finished or reclaimed elsewhere; outcome discarded");
+            }
+            Err(e) => error!(error = %e, "Failed to record provider operation outcome"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::{DigitalOceanError, MemoryStore};
+    use std::sync::Mutex;
+    use uuid::Uuid;
+
+    /// Fails with the queued errors in order, then completes the operation.
+    struct ScriptedHandler<P: ProviderOperationRepository> {
+        operations: Arc<P>,
+        failures: Mutex<Vec<ProvisioningError>>,
+    }
+
+    #[async_trait]
+    impl<P: ProviderOperationRepository> ProviderOperationHandler for ScriptedHandler<P> {
+        async fn execute(&self, operation: &ProviderOperation) -> Result<(), ProvisioningError> {
+            let failure = self.failures.lock().unwrap().pop();
+            match failure {
+                Some(e) => Err(e),
+                None => Ok(self.operations.mark_completed(operation).await?),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn executor_retries_abandoned_operation_until_it_completes() {
+        let operations = Arc::new(MemoryStore::new().provider_operations());
+        // Written with an already-expired lease, as if its writer crashed.
+        let abandoned =
+            ProviderOperation::destroy_droplet(Uuid::new_v4(), 42, chrono::Duration::seconds(-1));
+        operations.create(&abandoned).await.unwrap();
+
+        let executor = OutboxExecutor::new(
+            operations.clone(),
+            Arc::new(ScriptedHandler {
+                operations: operations.clone(),
+                failures: Mutex::new(vec![ProvisioningError::DigitalOcean(
+                    DigitalOceanError::RateLimited,
+                )]),
+            }),
+            OutboxSettings {
+                lease: Duration::ZERO,
+                ..OutboxSettings::default()
+            },
+        );
+
+        // The failure leaves it pending but isn't retried within the same scan.
+        assert_eq!(executor.run_once().await.unwrap(), 1);
+        sleep(Duration::from_millis(5)).await;
+        assert_eq!(executor.run_once().await.unwrap(), 1);
+        assert_eq!(executor.run_once().await.unwrap(), 0);
+
+        // The writer's late outcome loses to the executor's.
+        let err = operations.mark_completed(&abandoned).await.unwrap_err();
+        assert!(matches!(err, RepositoryError::Conflict(_)));
+    }
+
+    #[tokio::test]
+    async fn executor_fails_operations_after_max_attempts() {
+        let operations = Arc::new(MemoryStore::new().provider_operations());
+        let abandoned =
+            ProviderOperation::destroy_droplet(Uuid::new_v4(), 42, chrono::Duration::seconds(-1));
+        operations.create(&abandoned).await.unwrap();
+        let rate_limited = || ProvisioningError::DigitalOcean(DigitalOceanError::RateLimited);
+        let handler = Arc::new(ScriptedHandler {
+            operations: operations.clone(),
+            failures: Mutex::new(vec![rate_limited(), rate_limited(), rate_limited()]),
+        });
+        let executor = OutboxExecutor::new(
+            operations.clone(),
+            handler.clone(),
+            OutboxSettings {
+                lease: Duration::from_millis(20),
+                max_attempts: 2,
+                ..OutboxSettings::default()
+            },
+        );
+
+        assert_eq!(executor.run_once().await.unwrap(), 1);
+        sleep(Duration::from_millis(30)).await;
+        assert_eq!(executor.run_once().await.unwrap(), 1);
+        // The second failure was final; the operation is no longer claimed.
+        sleep(Duration::from_millis(30)).await;
+        assert_eq!(executor.run_once().await.unwrap(), 0);
+        assert_eq!(handler.failures.lock().unwrap().len(), 1);
+
+        // One reclaimed past the limit is failed without running it.
+        let reclaimed =
+            ProviderOperation::destroy_droplet(Uuid::new_v4(), 43, chrono::Duration::seconds(-1));
+        operations.create(&reclaimed).await.unwrap();
+        let executor = OutboxExecutor::new(
+            operations.clone(),
+            handler.clone(),
+            OutboxSettings {
+                lease: Duration::ZERO,
+                max_attempts: 0,
+                ..OutboxSettings::default()
+            },
+        );
+        assert_eq!(executor.run_once().await.unwrap(), 1);
+        sleep(Duration::from_millis(30)).await;
+        assert_eq!(executor.run_once().await.unwrap(), 0);
+        assert_eq!(handler.failures.lock().unwrap().len(), 1);
+    }
+}
@@ -1,15 +1,23 @@
+use crate::application::{
+    ProvisioningSettings, UserDataError, UserDataFormat, UserDataOverlays, UserDataRenderer,
+    UserDataValue, UserDataVars,
+};
 use crate::domain::{
-    Bot, BotConfig, BotStatus, DropletCreateRequest, EncryptedBotSecrets, Persona, StoredBotConfig,
+    Bot, BotConfig, BotStatus, CustomizerError, CustomizerOverrides, CustomizerSettings, Droplet,
+    DropletCreateRequest, DropletStatus, EncryptedBotSecrets, InvalidTransition, Job, JobKind,
+    Persona, ProviderOperation, ProviderOperationKind, StoredBotConfig, SubscriptionTier,
+    ToolchainError, ToolchainProfile, TransitionReason,
 };
 use crate::infrastructure::{
     AccountRepository, BotRepository, ConfigRepository, DigitalOceanClient, DigitalOceanError,
-    DropletRepository, RepositoryError, SecretsEncryption,
+    DropletRepository, RepositoryError, RepositoryTransaction, SecretsEncryption, UnitOfWork,
 };
 use rand::RngCore;
+use sha2::{Digest, Sha256};
 use std::sync::Arc;
 use thiserror::Error;
 use tokio::time::{sleep, Duration};
-use tracing::{error, info, warn, Span};
+use tracing::{error, field::Empty, info, instrument, warn, Span};
 use uuid::Uuid;
 
 /// MED-005: Maximum length for sanitized bot names
@@ -19,6 +27,16 @@ const MAX_BOT_NAME_LENGTH: usize = 64;
 const RETRY_ATTEMPTS: usize = 3;
 const RETRY_DELAYS_MS: [u64; RETRY_ATTEMPTS - 1] = [100, 200];
 
+/// Stands in for the registration token in [`ProvisioningService::preview_user_data`].
+/// Rendered as a secret, so it keeps to the characters a real token uses.
+const PREVIEW_REGISTRATION_TOKEN: &str = "registration-token-placeholder";
+
+/// Digest of user data rendered with [`PREVIEW_REGISTRATION_TOKEN`], recorded
+/// on the droplet so a rebuild can tell whether it would still boot the same.
+fn user_data_digest(user_data: &str) -> String {
+    format!("sha256:{:x}", Sha256::digest(user_data.as_bytes()))
+}
+
 /// REL-001: Retry an async operation with exponential backoff
 /// Logs each retry attempt with structured context
 async fn retry_with_backoff<F, Fut, T, E>(operation_name: &str, bot_id: Uuid, f: F) -> Result<T, E>
@@ -86,11 +104,6 @@ fn sanitize_bot_name(name: &str) -> String {
     }
 }
 
-fn shell_escape(value: &str) -> String {
-    let escaped = value.replace('\'', "'\"'\"'");
-    format!("'{escaped}'")
-}
-
 #[derive(Error, Debug)]
 pub enum ProvisioningError {
     #[error("DigitalOcean error: {0}")]
@@ -103,425 +116,244 @@ pub enum ProvisioningError {
     InvalidConfig(String),
     #[error("Encryption error: {0}")]
     Encryption(String),
+    #[error(transparent)]
+    InvalidTransition(#[from] InvalidTransition),
+    #[error("User data error: {0}")]
+    UserData(#[from] UserDataError),
+    #[error(transparent)]
+    Customizer(#[from] CustomizerError),
+    #[error(transparent)]
+    Toolchain(#[from] ToolchainError),
+}
+
+impl ProvisioningError {
+    /// Whether a queued job that failed with this error is worth retrying.
+    /// Validation and not-found errors will fail the same way on every attempt.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            ProvisioningError::DigitalOcean(
+                DigitalOceanError::RateLimited | DigitalOceanError::RequestFailed(_)
+            ) | ProvisioningError::Repository(RepositoryError::DatabaseError(_))
+        )
+    }
 }
 
 pub struct ProvisioningService<A, B, C, D>
 where
-    A: AccountRepository,
-    B: BotRepository,
-    C: ConfigRepository,
-    D: DropletRepository,
+    A: AccountRepository + ?Sized,
+    B: BotRepository + ?Sized,
+    C: ConfigRepository + ?Sized,
+    D: DropletRepository + ?Sized,
 {
     do_client: Arc<DigitalOceanClient>,
     account_repo: Arc<A>,
     bot_repo: Arc<B>,
     config_repo: Arc<C>,
     droplet_repo: Arc<D>,
+    unit_of_work: Arc<dyn UnitOfWork>,
     encryption: Arc<SecretsEncryption>,
-    openclaw_image: String,
-    control_plane_url: String,
-
-    // janebot-cli customization
-    customizer_repo_url: String,
-    customizer_ref: String,
-    customizer_workspace_dir: String,
-    customizer_agent_name: String,
-    customizer_owner_name: String,
-    customizer_skip_qmd: bool,
-    customizer_skip_cron: bool,
-    customizer_skip_git: bool,
-    customizer_skip_heartbeat: bool,
-
-    // Droplet toolchain/bootstrap customization
-    toolchain_node_major: u8,
-    toolchain_install_pnpm: bool,
-    toolchain_pnpm_version: String,
-    toolchain_install_rust: bool,
-    toolchain_rust_toolchain: String,
-    toolchain_extra_apt_packages: String,
-    toolchain_global_npm_packages: String,
-    toolchain_cargo_crates: String,
+    settings: ProvisioningSettings,
+
+    user_data: UserDataRenderer,
 }
 
 #[cfg(test)]
 #[allow(clippy::items_after_test_module)]
 mod tests {
     use super::*;
+    use crate::application::ToolchainSettings;
+    use crate::domain::{toolchain_tokens, Account, CustomizerAllowList};
+    use crate::infrastructure::digital_ocean_stub::DigitalOceanStub;
+    use crate::infrastructure::{
+        InMemoryAccountRepository, InMemoryBotRepository, InMemoryConfigRepository,
+        InMemoryDropletRepository, JobRepository, MemoryStore, ProviderOperationRepository,
+    };
     use async_trait::async_trait;
     use chrono::Utc;
-    use std::collections::HashSet;
-    use std::sync::Mutex;
-    use std::sync::atomic::{AtomicUsize, Ordering};
-
-    #[derive(Default)]
-    struct NoopAccountRepo;
-    #[async_trait]
-    impl AccountRepository for NoopAccountRepo {
-        async fn create(&self, _account: &crate::domain::Account) -> Result<(), RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn get_by_id(&self, _id: Uuid) -> Result<crate::domain::Account, RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn get_by_external_id(
-            &self,
-            _external_id: &str,
-        ) -> Result<crate::domain::Account, RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn update_subscription(
-            &self,
-            _id: Uuid,
-            _tier: crate::domain::SubscriptionTier,
-        ) -> Result<(), RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-    }
-
-    #[derive(Default)]
-    struct NoopBotRepo;
-    #[async_trait]
-    impl BotRepository for NoopBotRepo {
-        async fn create(&self, _bot: &Bot) -> Result<(), RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn get_by_id(&self, _id: Uuid) -> Result<Bot, RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn get_by_id_with_token(
-            &self,
-            _id: Uuid,
-            _token: &str,
-        ) -> Result<Bot, RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn list_by_account(&self, _account_id: Uuid) -> Result<Vec<Bot>, RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn list_by_account_paginated(
-            &self,
-            _account_id: Uuid,
-            _limit: i64,
-            _offset: i64,
-        ) -> Result<Vec<Bot>, RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn count_by_account(&self, _account_id: Uuid) -> Result<i64, RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn update_status(
-            &self,
-            _id: Uuid,
-            _status: BotStatus,
-        ) -> Result<(), RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn update_droplet(
-            &self,
-            _bot_id: Uuid,
-            _droplet_id: Option<i64>,
-        ) -> Result<(), RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn update_config_version(
-            &self,
-            _bot_id: Uuid,
-            _desired: Option<Uuid>,
-            _applied: Option<Uuid>,
-        ) -> Result<(), RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn update_heartbeat(&self, _bot_id: Uuid) -> Result<(), RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn update_registration_token(
-            &self,
-            _bot_id: Uuid,
-            _token: &str,
-        ) -> Result<(), RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn delete(&self, _id: Uuid) -> Result<(), RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn hard_delete(&self, _id: Uuid) -> Result<(), RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn increment_bot_counter(
-            &self,
-            _account_id: Uuid,
-        ) -> Result<(bool, i32, i32), RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn decrement_bot_counter(&self, _account_id: Uuid) -> Result<(), RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn list_stale_bots(
-            &self,
-            _threshold: chrono::DateTime<chrono::Utc>,
-        ) -> Result<Vec<Bot>, RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-    }
-
-    #[derive(Default)]
-    struct NoopConfigRepo;
+    use std::collections::BTreeMap;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    type TestService = ProvisioningService<
+        InMemoryAccountRepository,
+        InMemoryBotRepository,
+        InMemoryConfigRepository,
+        InMemoryDropletRepository,
+    >;
+
+    /// Fails every config insert, to check that `create_bot` leaves nothing behind.
+    struct FailingConfigCreates;
     #[async_trait]
-    impl ConfigRepository for NoopConfigRepo {
+    impl ConfigRepository for FailingConfigCreates {
         async fn create(&self, _config: &StoredBotConfig) -> Result<(), RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
+            Err(RepositoryError::InvalidData(
+                "forced config create failure".to_string(),
+            ))
         }
-        async fn get_by_id(&self, _id: Uuid) -> Result<StoredBotConfig, RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
+        async fn get_by_id(&self, id: Uuid) -> Result<StoredBotConfig, RepositoryError> {
+            Err(RepositoryError::NotFound(format!("Config {}", id)))
         }
         async fn get_latest_for_bot(
             &self,
             _bot_id: Uuid,
         ) -> Result<Option<StoredBotConfig>, RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
+            Ok(None)
         }
         async fn list_by_bot(
             &self,
             _bot_id: Uuid,
         ) -> Result<Vec<StoredBotConfig>, RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
+            Ok(Vec::new())
         }
         async fn get_next_version_atomic(&self, _bot_id: Uuid) -> Result<i32, RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
+            Ok(1)
         }
     }
 
-    #[derive(Default)]
-    struct NoopDropletRepo;
+    /// Memory store transactions with [`FailingConfigCreates`] in place of their configs.
+    struct ConfigCreateFailingUnitOfWork(MemoryStore);
     #[async_trait]
-    impl DropletRepository for NoopDropletRepo {
-        async fn create(&self, _droplet: &crate::domain::Droplet) -> Result<(), RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn get_by_id(&self, _id: i64) -> Result<crate::domain::Droplet, RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn update_bot_assignment(
-            &self,
-            _droplet_id: i64,
-            _bot_id: Option<Uuid>,
-        ) -> Result<(), RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn update_status(
-            &self,
-            _droplet_id: i64,
-            _status: &str,
-        ) -> Result<(), RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn update_ip(
-            &self,
-            _droplet_id: i64,
-            _ip: Option<String>,
-        ) -> Result<(), RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn mark_destroyed(&self, _droplet_id: i64) -> Result<(), RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
+    impl UnitOfWork for ConfigCreateFailingUnitOfWork {
+        async fn begin(&self) -> Result<Box<dyn RepositoryTransaction>, RepositoryError> {
+            Ok(Box::new(ConfigCreateFailingTransaction(
+                self.0.begin().await?,
+            )))
         }
     }
 
-    #[derive(Default)]
-    struct HappyAccountRepo;
+    struct ConfigCreateFailingTransaction(Box<dyn RepositoryTransaction>);
     #[async_trait]
-    impl AccountRepository for HappyAccountRepo {
-        async fn create(&self, _account: &crate::domain::Account) -> Result<(), RepositoryError> {
-            Ok(())
+    impl RepositoryTransaction for ConfigCreateFailingTransaction {
+        fn bots(&self) -> &dyn BotRepository {
+            self.0.bots()
         }
-        async fn get_by_id(&self, id: Uuid) -> Result<crate::domain::Account, RepositoryError> {
-            let now = Utc::now();
-            Ok(crate::domain::Account {
-                id,
-                external_id: "test-account".to_string(),
-                subscription_tier: crate::domain::SubscriptionTier::Basic,
-                max_bots: 2,
-                created_at: now,
-                updated_at: now,
-            })
+        fn configs(&self) -> &dyn ConfigRepository {
+            &FailingConfigCreates
         }
-        async fn get_by_external_id(
-            &self,
-            _external_id: &str,
-        ) -> Result<crate::domain::Account, RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
+        fn droplets(&self) -> &dyn DropletRepository {
+            self.0.droplets()
         }
-        async fn update_subscription(
-            &self,
-            _id: Uuid,
-            _tier: crate::domain::SubscriptionTier,
-        ) -> Result<(), RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
+        fn jobs(&self) -> &dyn JobRepository {
+            self.0.jobs()
+        }
+        fn provider_operations(&self) -> &dyn ProviderOperationRepository {
+            self.0.provider_operations()
+        }
+        async fn commit(self: Box<Self>) -> Result<(), RepositoryError> {
+            self.0.commit().await
         }
     }
 
-    #[derive(Default)]
-    struct RollbackTrackingBotRepo {
-        created: Mutex<HashSet<Uuid>>,
-        hard_deleted: AtomicUsize,
-        decremented: AtomicUsize,
+    /// Destroys `bot_id` behind the service's back on the first `begin`, as a
+    /// destroy racing the one under test would.
+    struct RacingDestroy {
+        store: MemoryStore,
+        bot_id: Uuid,
+        raced: AtomicBool,
     }
     #[async_trait]
-    impl BotRepository for RollbackTrackingBotRepo {
-        async fn create(&self, bot: &Bot) -> Result<(), RepositoryError> {
-            self.created.lock().expect("lock").insert(bot.id);
-            Ok(())
-        }
-        async fn get_by_id(&self, _id: Uuid) -> Result<Bot, RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn get_by_id_with_token(
-            &self,
-            _id: Uuid,
-            _token: &str,
-        ) -> Result<Bot, RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn list_by_account(&self, _account_id: Uuid) -> Result<Vec<Bot>, RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn list_by_account_paginated(
-            &self,
-            _account_id: Uuid,
-            _limit: i64,
-            _offset: i64,
-        ) -> Result<Vec<Bot>, RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn count_by_account(&self, _account_id: Uuid) -> Result<i64, RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn update_status(
-            &self,
-            _id: Uuid,
-            _status: BotStatus,
-        ) -> Result<(), RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn update_droplet(
-            &self,
-            _bot_id: Uuid,
-            _droplet_id: Option<i64>,
-        ) -> Result<(), RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn update_config_version(
-            &self,
-            _bot_id: Uuid,
-            _desired: Option<Uuid>,
-            _applied: Option<Uuid>,
-        ) -> Result<(), RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn update_heartbeat(&self, _bot_id: Uuid) -> Result<(), RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn update_registration_token(
-            &self,
-            _bot_id: Uuid,
-            _token: &str,
-        ) -> Result<(), RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn delete(&self, _id: Uuid) -> Result<(), RepositoryError> {
-            Ok(())
-        }
-        async fn hard_delete(&self, id: Uuid) -> Result<(), RepositoryError> {
-            let _ = self.created.lock().expect("lock").remove(&id);
-            self.hard_deleted.fetch_add(1, Ordering::SeqCst);
-            Ok(())
-        }
-        async fn increment_bot_counter(
-            &self,
-            _account_id: Uuid,
-        ) -> Result<(bool, i32, i32), RepositoryError> {
-            Ok((true, 1, 2))
-        }
-        async fn decrement_bot_counter(&self, _account_id: Uuid) -> Result<(), RepositoryError> {
-            self.decremented.fetch_add(1, Ordering::SeqCst);
-            Ok(())
-        }
-        async fn list_stale_bots(
-            &self,
-            _threshold: chrono::DateTime<chrono::Utc>,
-        ) -> Result<Vec<Bot>, RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
+    impl UnitOfWork for RacingDestroy {
+        async fn begin(&self) -> Result<Box<dyn RepositoryTransaction>, RepositoryError> {
+            if !self.raced.swap(true, Ordering::SeqCst) {
+                let bots = self.store.bots();
+                let bot = bots.get_by_id(self.bot_id).await?;
+                bots.transition_status(
+                    bot.id,
+                    bot.status,
+                    BotStatus::Destroyed,
+                    TransitionReason::Destroy,
+                    None,
+                )
+                .await?;
+                bots.decrement_bot_counter(bot.account_id).await?;
+            }
+            self.store.begin().await
         }
     }
 
-    #[derive(Default)]
-    struct FailingConfigCreateRepo;
-    #[async_trait]
-    impl ConfigRepository for FailingConfigCreateRepo {
-        async fn create(&self, _config: &StoredBotConfig) -> Result<(), RepositoryError> {
-            Err(RepositoryError::InvalidData(
-                "forced config create failure".to_string(),
-            ))
-        }
-        async fn get_by_id(&self, _id: Uuid) -> Result<StoredBotConfig, RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn get_latest_for_bot(
-            &self,
-            _bot_id: Uuid,
-        ) -> Result<Option<StoredBotConfig>, RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn list_by_bot(
-            &self,
-            _bot_id: Uuid,
-        ) -> Result<Vec<StoredBotConfig>, RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
-        async fn get_next_version_atomic(&self, _bot_id: Uuid) -> Result<i32, RepositoryError> {
-            Err(RepositoryError::InvalidData("noop".to_string()))
-        }
+    fn test_service(store: &MemoryStore, settings: ProvisioningSettings) -> TestService {
+        test_service_with_unit_of_work(store, Arc::new(store.clone()), settings)
     }
 
-    #[test]
-    fn f001_user_data_does_not_enable_xtrace() {
+    fn test_service_with_unit_of_work(
+        store: &MemoryStore,
+        unit_of_work: Arc<dyn UnitOfWork>,
+        settings: ProvisioningSettings,
+    ) -> TestService {
+        let do_client = DigitalOceanClient::new("test-token".to_string()).unwrap();
+        build_test_service(store, unit_of_work, do_client, settings)
+    }
+
+    /// A service whose DigitalOcean calls go to `stub`.
+    fn stub_service(
+        store: &MemoryStore,
+        stub: &DigitalOceanStub,
+        settings: ProvisioningSettings,
+    ) -> TestService {
+        build_test_service(store, Arc::new(store.clone()), stub.client(), settings)
+    }
+
+    fn build_test_service(
+        store: &MemoryStore,
+        unit_of_work: Arc<dyn UnitOfWork>,
+        do_client: DigitalOceanClient,
+        settings: ProvisioningSettings,
+    ) -> TestService {
         let encryption = Arc::new(
             SecretsEncryption::new("YWJjZGVmZ2hpamtsbW5vcHFyc3R1dnd4eXoxMjM0NTY=")
                 .expect("valid test key"),
         );
-        let do_client = Arc::new(DigitalOceanClient::new("test-token".to_string()).unwrap());
-
-        let svc: ProvisioningService<
-            NoopAccountRepo,
-            NoopBotRepo,
-            NoopConfigRepo,
-            NoopDropletRepo,
-        > = ProvisioningService::new(
-            do_client,
-            Arc::new(NoopAccountRepo),
-            Arc::new(NoopBotRepo),
-            Arc::new(NoopConfigRepo),
-            Arc::new(NoopDropletRepo),
+        ProvisioningService::new(
+            Arc::new(do_client),
+            Arc::new(store.accounts()),
+            Arc::new(store.bots()),
+            Arc::new(store.configs()),
+            Arc::new(store.droplets()),
+            unit_of_work,
             encryption,
-            "ubuntu-22-04-x64".to_string(),
-            "https://example.invalid".to_string(),
-            "https://github.com/janebot2026/janebot-cli.git".to_string(),
-            "4b170b4aa31f79bda84f7383b3992ca8681d06d3".to_string(),
-            "/opt/openclaw/workspace".to_string(),
-            "Jane".to_string(),
-            "Cedros".to_string(),
-            true,
-            true,
-            true,
-            true,
-            20,
-            true,
-            "".to_string(),
-            true,
-            "stable".to_string(),
-            "".to_string(),
-            "".to_string(),
-            "".to_string(),
+            settings,
+        )
+    }
+
+    async fn account(store: &MemoryStore) -> Account {
+        let account = Account::new(Uuid::new_v4().to_string(), SubscriptionTier::Basic);
+        store.accounts().create(&account).await.unwrap();
+        account
+    }
+
+    fn bot_config() -> BotConfig {
+        BotConfig {
+            id: Uuid::new_v4(),
+            bot_id: Uuid::new_v4(),
+            version: 1,
+            trading_config: crate::domain::TradingConfig {
+                asset_focus: crate::domain::AssetFocus::Majors,
+                algorithm: crate::domain::AlgorithmMode::Trend,
+                strictness: crate::domain::StrictnessLevel::Medium,
+                paper_mode: true,
+                signal_knobs: None,
+            },
+            risk_config: crate::domain::RiskConfig {
+                max_position_size_pct: 10.0,
+                max_daily_loss_pct: 5.0,
+                max_drawdown_pct: 10.0,
+                max_trades_per_day: 10,
+            },
+            secrets: crate::domain::BotSecrets {
+                llm_provider: "test".to_string(),
+                llm_api_key: "test-key".to_string(),
+            },
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn f001_user_data_does_not_enable_xtrace() {
+        let svc = test_service(
+            &MemoryStore::new(),
+            ProvisioningSettings::builder("https://example.invalid")
+                .build()
+                .unwrap(),
         );
 
         let bot_id = Uuid::new_v4();
@@ -534,43 +366,35 @@ mod tests {
 
     #[test]
     fn f002_user_data_exports_customizer_and_toolchain_values() {
-        let encryption = Arc::new(
-            SecretsEncryption::new("YWJjZGVmZ2hpamtsbW5vcHFyc3R1dnd4eXoxMjM0NTY=")
-                .expect("valid test key"),
-        );
-        let do_client = Arc::new(DigitalOceanClient::new("test-token".to_string()).unwrap());
-
-        let svc: ProvisioningService<
-            NoopAccountRepo,
-            NoopBotRepo,
-            NoopConfigRepo,
-            NoopDropletRepo,
-        > = ProvisioningService::new(
-            do_client,
-            Arc::new(NoopAccountRepo),
-            Arc::new(NoopBotRepo),
-            Arc::new(NoopConfigRepo),
-            Arc::new(NoopDropletRepo),
-            encryption,
-            "ubuntu-22-04-x64".to_string(),
-            "https://control.example".to_string(),
-            "https://example.com/customizer.git".to_string(),
-            "custom-ref".to_string(),
-            "/tmp/workspace".to_string(),
-            "Agent'X".to_string(),
-            "OwnerY".to_string(),
-            false,
-            true,
-            false,
-            true,
-            20,
-            true,
-            "9.12.0".to_string(),
-            true,
-            "stable".to_string(),
-            "ripgrep fd-find".to_string(),
-            "@openclaw/special-cli".to_string(),
-            "cargo-binstall".to_string(),
+        let svc = test_service(
+            &MemoryStore::new(),
+            ProvisioningSettings::builder("https://control.example")
+                .customizer(CustomizerSettings {
+                    repo_url: "https://example.com/customizer.git".to_string(),
+                    git_ref: "custom-ref".to_string(),
+                    agent_name: "Agent'X".to_string(),
+                    owner_name: "OwnerY".to_string(),
+                    skip_qmd: false,
+                    skip_cron: true,
+                    skip_git: false,
+                    skip_heartbeat: true,
+                })
+                .customizer_workspace_dir("/tmp/workspace")
+                .toolchain(ToolchainSettings {
+                    base: ToolchainProfile {
+                        node_major: 20,
+                        install_pnpm: true,
+                        pnpm_version: "9.12.0".to_string(),
+                        install_rust: true,
+                        rust_toolchain: "stable".to_string(),
+                        apt_packages: toolchain_tokens("ripgrep fd-find"),
+                        npm_packages: toolchain_tokens("@openclaw/special-cli"),
+                        cargo_crates: toolchain_tokens("cargo-binstall"),
+                    },
+                    ..Default::default()
+                })
+                .build()
+                .unwrap(),
         );
 
         let bot_id = Uuid::new_v4();
@@ -600,6 +424,9 @@ mod tests {
         assert!(embedded.contains("if command -v ufw >/dev/null 2>&1; then"));
         assert!(embedded.contains("--connect-timeout \"$CURL_CONNECT_TIMEOUT_SECONDS\""));
         assert!(embedded.contains("--max-time \"$CURL_MAX_TIME_SECONDS\""));
+        assert!(embedded.contains("/bot/$BOT_ID/bootstrap_progress"));
+        assert!(embedded.contains("trap on_exit EXIT"));
+        assert!(embedded.contains("trap on_error ERR"));
     }
 
     struct TestErr;
@@ -628,83 +455,179 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn f005_create_bot_rolls_back_partial_state_when_config_create_fails() {
-        let encryption = Arc::new(
-            SecretsEncryption::new("YWJjZGVmZ2hpamtsbW5vcHFyc3R1dnd4eXoxMjM0NTY=")
-                .expect("valid test key"),
-        );
-        let do_client = Arc::new(DigitalOceanClient::new("test-token".to_string()).unwrap());
-
-        let bot_repo = Arc::new(RollbackTrackingBotRepo::default());
-
-        let svc: ProvisioningService<
-            HappyAccountRepo,
-            RollbackTrackingBotRepo,
-            FailingConfigCreateRepo,
-            NoopDropletRepo,
-        > = ProvisioningService::new(
-            do_client,
-            Arc::new(HappyAccountRepo),
-            bot_repo.clone(),
-            Arc::new(FailingConfigCreateRepo),
-            Arc::new(NoopDropletRepo),
-            encryption,
-            "ubuntu-22-04-x64".to_string(),
-            "https://example.invalid".to_string(),
-            "https://github.com/janebot2026/janebot-cli.git".to_string(),
-            "4b170b4aa31f79bda84f7383b3992ca8681d06d3".to_string(),
-            "/opt/openclaw/workspace".to_string(),
-            "Jane".to_string(),
-            "Cedros".to_string(),
-            true,
-            true,
-            true,
-            true,
-            20,
-            true,
-            "".to_string(),
-            true,
-            "stable".to_string(),
-            "".to_string(),
-            "".to_string(),
-            "".to_string(),
+    async fn f005_create_bot_commits_nothing_when_config_create_fails() {
+        let store = MemoryStore::new();
+        let account = account(&store).await;
+        let svc = test_service_with_unit_of_work(
+            &store,
+            Arc::new(ConfigCreateFailingUnitOfWork(store.clone())),
+            ProvisioningSettings::builder("https://example.invalid")
+                .build()
+                .unwrap(),
         );
 
-        let account_id = Uuid::new_v4();
         let res = svc
             .create_bot(
-                account_id,
+                account.id,
                 "rollback-target".to_string(),
                 Persona::Beginner,
-                BotConfig {
-                    id: Uuid::new_v4(),
-                    bot_id: Uuid::new_v4(),
-                    version: 1,
-                    trading_config: crate::domain::TradingConfig {
-                        asset_focus: crate::domain::AssetFocus::Majors,
-                        algorithm: crate::domain::AlgorithmMode::Trend,
-                        strictness: crate::domain::StrictnessLevel::Medium,
-                        paper_mode: true,
-                        signal_knobs: None,
-                    },
-                    risk_config: crate::domain::RiskConfig {
-                        max_position_size_pct: 10.0,
-                        max_daily_loss_pct: 5.0,
-                        max_drawdown_pct: 10.0,
-                        max_trades_per_day: 10,
-                    },
-                    secrets: crate::domain::BotSecrets {
-                        llm_provider: "test".to_string(),
-                        llm_api_key: "test-key".to_string(),
-                    },
-                    created_at: Utc::now(),
-                },
+                bot_config(),
+                CustomizerOverrides::default(),
+                None,
             )
             .await;
 
+        // The transaction is dropped uncommitted; no compensating writes are needed.
         assert!(res.is_err());
-        assert_eq!(bot_repo.hard_deleted.load(Ordering::SeqCst), 1);
-        assert_eq!(bot_repo.decremented.load(Ordering::SeqCst), 1);
+        assert_eq!(store.bots().count_by_account(account.id).await.unwrap(), 0);
+        let claimed = store.jobs().claim_next(chrono::Duration::minutes(5)).await;
+        assert!(claimed.unwrap().is_none());
+        assert_eq!(
+            store
+                .bots()
+                .increment_bot_counter(account.id)
+                .await
+                .unwrap(),
+            (true, 1, 2)
+        );
+    }
+
+    #[tokio::test]
+    async fn create_bot_queues_its_spawn_job_in_the_same_commit() {
+        let store = MemoryStore::new();
+        let account = account(&store).await;
+        let svc = test_service(
+            &store,
+            ProvisioningSettings::builder("https://control.example")
+                .job_max_attempts(3)
+                .build()
+                .unwrap(),
+        );
+
+        let (bot, job) = svc
+            .create_bot(
+                account.id,
+                "spawned".to_string(),
+                Persona::Beginner,
+                bot_config(),
+                CustomizerOverrides::default(),
+                None,
+            )
+            .await
+            .unwrap();
+
+        let stored = store.jobs().get_by_id(job.id).await.unwrap();
+        assert_eq!((stored.kind, stored.bot_id), (JobKind::SpawnBot, bot.id));
+        assert_eq!(stored.max_attempts, 3);
+        let bot = store.bots().get_by_id(bot.id).await.unwrap();
+        assert_eq!(bot.status, BotStatus::Pending);
+    }
+
+    #[tokio::test]
+    async fn customizer_settings_are_validated_and_rendered_per_bot() {
+        let store = MemoryStore::new();
+        let account = account(&store).await;
+        let svc = test_service(
+            &store,
+            ProvisioningSettings::builder("https://control.example")
+                .customizer_allow_list(CustomizerAllowList {
+                    repo_urls: vec!["https://example.com/fork.git".to_string()],
+                    refs: Vec::new(),
+                })
+                .build()
+                .unwrap(),
+        );
+
+        let err = svc
+            .create_bot(
+                account.id,
+                "custom".to_string(),
+                Persona::Beginner,
+                bot_config(),
+                CustomizerOverrides {
+                    repo_url: Some("https://evil.example/x.git".to_string()),
+                    ..Default::default()
+                },
+                None,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ProvisioningError::Customizer(CustomizerError::RepoNotAllowed(_))
+        ));
+        // Rejected before a bot slot is reserved.
+        assert_eq!(store.bots().count_by_account(account.id).await.unwrap(), 0);
+
+        let mut bot = Bot::new(Uuid::new_v4(), "custom".to_string(), Persona::Beginner);
+        bot.customizer = Some(
+            CustomizerOverrides {
+                repo_url: Some("https://example.com/fork.git".to_string()),
+                git_ref: Some("v1.4.0".to_string()),
+                agent_name: Some("Ada".to_string()),
+                skip_git: Some(false),
+                ..Default::default()
+            }
+            .apply(&svc.default_customizer()),
+        );
+        let user_data = svc.test_only_generate_user_data_for("reg-token", &bot);
+        assert!(user_data.contains("export CUSTOMIZER_REPO_URL='https://example.com/fork.git'"));
+        assert!(user_data.contains("export CUSTOMIZER_REF='v1.4.0'"));
+        assert!(user_data.contains("export CUSTOMIZER_AGENT_NAME='Ada'"));
+        assert!(user_data.contains("export CUSTOMIZER_OWNER_NAME='Cedros'"));
+        assert!(user_data.contains("export CUSTOMIZER_SKIP_GIT=\"false\""));
+    }
+
+    #[tokio::test]
+    async fn toolchain_profile_is_selected_per_bot() {
+        let data = ToolchainProfile {
+            apt_packages: vec!["jq".to_string(), "ripgrep=13.0.0-2".to_string()],
+            cargo_crates: vec!["xsv@0.13.0".to_string()],
+            ..ToolchainProfile::builtin()["full-rust"].clone()
+        };
+        let store = MemoryStore::new();
+        let account = account(&store).await;
+        let svc = test_service(
+            &store,
+            ProvisioningSettings::builder("https://control.example")
+                .toolchain(ToolchainSettings {
+                    profiles: BTreeMap::from([("data".to_string(), data)]),
+                    default_profile: "minimal".to_string(),
+                    ..Default::default()
+                })
+                .build()
+                .unwrap(),
+        );
+
+        let err = svc
+            .create_bot(
+                account.id,
+                "gpu".to_string(),
+                Persona::Beginner,
+                bot_config(),
+                CustomizerOverrides::default(),
+                Some("gpu".to_string()),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ProvisioningError::Toolchain(ToolchainError::UnknownProfile(_))
+        ));
+        assert_eq!(store.bots().count_by_account(account.id).await.unwrap(), 0);
+
+        let mut bot = Bot::new(Uuid::new_v4(), "data".to_string(), Persona::Beginner);
+        bot.toolchain_profile = Some("data".to_string());
+        let user_data = svc.test_only_generate_user_data_for("reg-token", &bot);
+        assert!(user_data.contains("export TOOLCHAIN_INSTALL_RUST=\"true\""));
+        assert!(user_data.contains("export TOOLCHAIN_EXTRA_APT_PACKAGES='jq ripgrep=13.0.0-2'"));
+        assert!(user_data.contains("export TOOLCHAIN_CARGO_CRATES='xsv@0.13.0'"));
+
+        // Bots without a recorded profile get the configured default.
+        bot.toolchain_profile = None;
+        let user_data = svc.test_only_generate_user_data_for("reg-token", &bot);
+        assert!(user_data.contains("export TOOLCHAIN_INSTALL_PNPM=\"false\""));
+        assert!(user_data.contains("export TOOLCHAIN_INSTALL_RUST=\"false\""));
     }
 
     #[test]
@@ -720,21 +643,436 @@ mod tests {
         assert_eq!(sanitized, "Test___ Bot_");
     }
 
-    #[test]
-    fn f003_shell_escape_wraps_and_escapes_single_quotes() {
-        let value = "abc'def";
-        let escaped = shell_escape(value);
-        assert_eq!(escaped, "'abc'\"'\"'def'");
+    #[tokio::test]
+    async fn racing_destroys_release_the_slot_once() {
+        let store = MemoryStore::new();
+        let bots = store.bots();
+        let account = account(&store).await;
+        for _ in 0..2 {
+            bots.increment_bot_counter(account.id).await.unwrap();
+        }
+        let bot = Bot::new(account.id, "Bot".to_string(), Persona::Beginner);
+        bots.create(&bot).await.unwrap();
+        let racing = RacingDestroy {
+            store: store.clone(),
+            bot_id: bot.id,
+            raced: AtomicBool::new(false),
+        };
+        let svc = test_service_with_unit_of_work(
+            &store,
+            Arc::new(racing),
+            ProvisioningSettings::builder("https://control.example")
+                .build()
+                .unwrap(),
+        );
+
+        svc.destroy_bot(bot.id).await.unwrap();
+        let stored = bots.get_by_id(bot.id).await.unwrap();
+        assert_eq!(stored.status, BotStatus::Destroyed);
+        // Two slots were taken and only this bot's was released.
+        let counter = bots.increment_bot_counter(account.id).await.unwrap();
+        assert_eq!(counter, (true, 2, 2));
+    }
+
+    /// Claim `operation` as the outbox executor would after its writer died.
+    async fn reclaimed(store: &MemoryStore, operation: ProviderOperation) -> ProviderOperation {
+        let operations = store.provider_operations();
+        operations.create(&operation).await.unwrap();
+        let claimed = operations.claim_expired(chrono::Duration::minutes(5)).await;
+        let claimed = claimed.unwrap().expect("operation is claimable");
+        assert_eq!(claimed.id, operation.id);
+        claimed
+    }
+
+    #[tokio::test]
+    async fn retried_create_adopts_the_droplet_an_earlier_attempt_made() {
+        let store = MemoryStore::new();
+        let stub = DigitalOceanStub::start().await;
+        let svc = stub_service(
+            &store,
+            &stub,
+            ProvisioningSettings::builder("https://control.example")
+                .build()
+                .unwrap(),
+        );
+        let account = account(&store).await;
+        let (bot, _) = svc
+            .create_bot(
+                account.id,
+                "adopted".to_string(),
+                Persona::Beginner,
+                bot_config(),
+                CustomizerOverrides::default(),
+                None,
+            )
+            .await
+            .unwrap();
+        store
+            .bots()
+            .transition_status(
+                bot.id,
+                BotStatus::Pending,
+                BotStatus::Provisioning,
+                TransitionReason::Provision,
+                None,
+            )
+            .await
+            .unwrap();
+        let other = stub.add_bot_droplet(Uuid::new_v4());
+        let earlier = stub.add_bot_droplet(bot.id);
+        let lease = chrono::Duration::seconds(-1);
+        let operation = reclaimed(&store, ProviderOperation::create_droplet(bot.id, lease)).await;
+
+        svc.execute_provider_operation(&operation).await.unwrap();
+
+        let bot = store.bots().get_by_id(bot.id).await.unwrap();
+        assert_eq!(bot.droplet_id, Some(earlier));
+        let droplet = store.droplets().get_by_id(earlier).await.unwrap();
+        assert_eq!(droplet.bot_id, Some(bot.id));
+        // No second droplet, and another bot's is left alone.
+        assert!(!stub.requests().contains(&"POST /droplets".to_string()));
+        assert_eq!(stub.droplet_ids(), vec![other, earlier]);
+        let err = store
+            .provider_operations()
+            .mark_completed(&operation)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RepositoryError::Conflict(_)));
+    }
+
+    #[tokio::test]
+    async fn abandoned_create_destroys_unrecorded_droplets_once_the_bot_moved_on() {
+        let store = MemoryStore::new();
+        let stub = DigitalOceanStub::start().await;
+        let svc = stub_service(
+            &store,
+            &stub,
+            ProvisioningSettings::builder("https://control.example")
+                .build()
+                .unwrap(),
+        );
+        let account = account(&store).await;
+        let mut bot = Bot::new(account.id, "moved-on".to_string(), Persona::Beginner);
+        bot.status = BotStatus::Error;
+        store.bots().create(&bot).await.unwrap();
+        let recorded = stub.add_bot_droplet(bot.id);
+        let leaked = stub.add_bot_droplet(bot.id);
+        let mut droplet = stub.client().get_droplet(recorded).await.unwrap();
+        droplet.bot_id = Some(bot.id);
+        store.droplets().create(&droplet).await.unwrap();
+        let lease = chrono::Duration::seconds(-1);
+        let operation = reclaimed(&store, ProviderOperation::create_droplet(bot.id, lease)).await;
+
+        svc.execute_provider_operation(&operation).await.unwrap();
+
+        assert!(stub
+            .requests()
+            .contains(&format!("DELETE /droplets/{}", leaked)));
+        assert_eq!(stub.droplet_ids(), vec![recorded]);
+        let bot = store.bots().get_by_id(bot.id).await.unwrap();
+        assert_eq!(bot.status, BotStatus::Error);
+        let err = store
+            .provider_operations()
+            .mark_completed(&operation)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RepositoryError::Conflict(_)));
+    }
+
+    #[tokio::test]
+    async fn pause_powers_the_droplet_off_when_shutdown_stalls() {
+        let store = MemoryStore::new();
+        let stub = DigitalOceanStub::start().await;
+        let svc = stub_service(
+            &store,
+            &stub,
+            ProvisioningSettings::builder("https://control.example")
+                .droplet_action_timeout(Duration::from_millis(50))
+                .build()
+                .unwrap(),
+        );
+        let account = account(&store).await;
+        let mut bot = Bot::new(account.id, "napper".to_string(), Persona::Beginner);
+        bot.status = BotStatus::Online;
+        let droplet_id = stub.add_bot_droplet(bot.id);
+        bot.droplet_id = Some(droplet_id);
+        store.bots().create(&bot).await.unwrap();
+        let action = |kind: &str| format!("POST /droplets/{}/actions {}", droplet_id, kind);
+
+        svc.pause_bot(bot.id).await.unwrap();
+        assert_eq!(stub.requests(), vec![action("shutdown")]);
+        svc.resume_bot(bot.id).await.unwrap();
+        assert!(stub.requests().contains(&action("power_on")));
+        let stored = store.bots().get_by_id(bot.id).await.unwrap();
+        assert_eq!(stored.status, BotStatus::Online);
+
+        stub.stall("shutdown");
+        svc.pause_bot(bot.id).await.unwrap();
+        // The stalled shutdown is polled until the timeout, then the power is cut.
+        let requests = stub.requests();
+        let [shutdown, poll, power_off] = &requests[requests.len() - 3..] else {
+            unreachable!()
+        };
+        assert_eq!(shutdown, &action("shutdown"));
+        assert!(poll.starts_with("GET /actions/"), "{requests:?}");
+        assert_eq!(power_off, &action("power_off"));
+        let stored = store.bots().get_by_id(bot.id).await.unwrap();
+        assert_eq!(stored.status, BotStatus::Paused);
+    }
+
+    #[tokio::test]
+    async fn resume_refuses_bots_that_are_not_paused() {
+        let store = MemoryStore::new();
+        let stub = DigitalOceanStub::start().await;
+        let svc = stub_service(
+            &store,
+            &stub,
+            ProvisioningSettings::builder("https://control.example")
+                .build()
+                .unwrap(),
+        );
+        let account = account(&store).await;
+        let mut bot = Bot::new(account.id, "stuck".to_string(), Persona::Beginner);
+        bot.status = BotStatus::Error;
+        bot.droplet_id = Some(stub.add_bot_droplet(bot.id));
+        store.bots().create(&bot).await.unwrap();
+
+        let err = svc.resume_bot(bot.id).await.unwrap_err();
+        assert!(
+            matches!(err, ProvisioningError::InvalidTransition(_)),
+            "{err}"
+        );
+        assert!(stub.requests().is_empty());
+        let stored = store.bots().get_by_id(bot.id).await.unwrap();
+        assert_eq!(stored.status, BotStatus::Error);
+    }
+
+    #[tokio::test]
+    async fn hibernate_snapshots_the_droplet_and_wake_boots_from_it() {
+        let store = MemoryStore::new();
+        let stub = DigitalOceanStub::start().await;
+        let svc = stub_service(
+            &store,
+            &stub,
+            ProvisioningSettings::builder("https://control.example")
+                .build()
+                .unwrap(),
+        );
+        let account = account(&store).await;
+        let (bot, _) = svc
+            .create_bot(
+                account.id,
+                "sleeper".to_string(),
+                Persona::Beginner,
+                bot_config(),
+                CustomizerOverrides::default(),
+                None,
+            )
+            .await
+            .unwrap();
+
+        // Behind the spawn job, the hibernate is refused and the choice not saved.
+        let err = svc.queue_hibernate(bot.id, true).await.unwrap_err();
+        assert!(matches!(err, RepositoryError::Conflict(_)));
+        let stored = store.bots().get_by_id(bot.id).await.unwrap();
+        assert_eq!(stored.snapshot_on_hibernate, None);
+
+        let lease = chrono::Duration::minutes(10);
+        let spawn = store.jobs().claim_next(lease).await.unwrap().unwrap();
+        svc.provision_bot(bot.id).await.unwrap();
+        store.jobs().mark_succeeded(&spawn).await.unwrap();
+        store
+            .bots()
+            .transition_status(
+                bot.id,
+                BotStatus::Provisioning,
+                BotStatus::Online,
+                TransitionReason::ConfigAcknowledged,
+                None,
+            )
+            .await
+            .unwrap();
+        let [first_droplet] = stub.droplet_ids()[..] else {
+            unreachable!()
+        };
+
+        // A basic account doesn't snapshot unless the bot asks to.
+        let hibernate = svc.queue_hibernate(bot.id, true).await.unwrap();
+        assert_eq!(hibernate.kind, JobKind::HibernateBot);
+        svc.hibernate_bot(bot.id).await.unwrap();
+
+        let stored = store.bots().get_by_id(bot.id).await.unwrap();
+        assert_eq!(stored.status, BotStatus::Hibernated);
+        assert_eq!(stored.snapshot_on_hibernate, Some(true));
+        let [snapshot_id] = stub.snapshot_ids()[..] else {
+            unreachable!()
+        };
+        assert_eq!(stored.hibernation_snapshot_id, Some(snapshot_id));
+        assert!(stub.droplet_ids().is_empty());
+        let action = |kind: &str| format!("POST /droplets/{}/actions {}", first_droplet, kind);
+        assert!(stub.requests().contains(&action("shutdown")));
+
+        svc.wake_bot(bot.id).await.unwrap();
+
+        let stored = store.bots().get_by_id(bot.id).await.unwrap();
+        assert_eq!(stored.status, BotStatus::Provisioning);
+        assert_eq!(stored.hibernation_snapshot_id, None);
+        let [droplet_id] = stub.droplet_ids()[..] else {
+            unreachable!()
+        };
+        assert_eq!(stored.droplet_id, Some(droplet_id));
+        assert_eq!(
+            stub.droplet_image(droplet_id),
+            Some(snapshot_id.to_string())
+        );
+        // The new droplet has its own copy of the disk.
+        assert!(stub.snapshot_ids().is_empty());
+    }
+
+    #[tokio::test]
+    async fn rebuild_reinstalls_the_droplet_until_its_user_data_changes() {
+        let store = MemoryStore::new();
+        let stub = DigitalOceanStub::start().await;
+        let settings = |url: &str| ProvisioningSettings::builder(url).build().unwrap();
+        let svc = stub_service(&store, &stub, settings("https://control.example"));
+        let account = account(&store).await;
+        let (bot, spawn) = svc
+            .create_bot(
+                account.id,
+                "rebuilt".to_string(),
+                Persona::Beginner,
+                bot_config(),
+                CustomizerOverrides::default(),
+                None,
+            )
+            .await
+            .unwrap();
+        svc.provision_bot(bot.id).await.unwrap();
+        let lease = chrono::Duration::minutes(10);
+        let claimed = store.jobs().claim_next(lease).await.unwrap().unwrap();
+        assert_eq!(claimed.id, spawn.id);
+        store.jobs().mark_succeeded(&claimed).await.unwrap();
+        store
+            .bots()
+            .transition_status(
+                bot.id,
+                BotStatus::Provisioning,
+                BotStatus::Online,
+                TransitionReason::ConfigAcknowledged,
+                None,
+            )
+            .await
+            .unwrap();
+        store
+            .bots()
+            .record_registration(bot.id, Some("203.0.113.9"), Some("1.0.0"))
+            .await
+            .unwrap();
+        let droplet_id = svc.check_rebuild(bot.id).await.unwrap();
+        let rebuild = format!("POST /droplets/{}/actions rebuild", droplet_id);
+        // The droplet boots a loader that fetches the current bootstrap.
+        let user_data = stub.droplet_user_data(droplet_id).unwrap();
+        assert!(user_data.contains("/bot/${BOT_ID}/user_data"));
+        assert!(!user_data.contains("# Start of embedded bootstrap script"));
+
+        svc.rebuild_bot(bot.id).await.unwrap();
+
+        let stored = store.bots().get_by_id(bot.id).await.unwrap();
+        assert_eq!(stored.status, BotStatus::Provisioning);
+        assert_eq!(stored.droplet_id, Some(droplet_id));
+        assert_eq!(stored.registered_at, None);
+        assert_eq!(stored.registered_ip, None);
+        assert_eq!(stub.droplet_ids(), vec![droplet_id]);
+        assert!(stub.requests().contains(&rebuild));
+
+        // So a bootstrap change doesn't stand in the way of a rebuild...
+        let moved_workspace = stub_service(
+            &store,
+            &stub,
+            ProvisioningSettings::builder("https://control.example")
+                .customizer_workspace_dir("/srv/openclaw")
+                .build()
+                .unwrap(),
+        );
+        assert_ne!(
+            moved_workspace.preview_user_data(bot.id).await.unwrap(),
+            svc.preview_user_data(bot.id).await.unwrap()
+        );
+        moved_workspace.check_rebuild(bot.id).await.unwrap();
+        // ...but moving the control plane changes where the loader fetches it.
+        let moved = stub_service(&store, &stub, settings("https://moved.example"));
+        let err = moved.rebuild_bot(bot.id).await.unwrap_err();
+        assert!(matches!(err, ProvisioningError::InvalidConfig(_)), "{err}");
+        let requests = stub.requests();
+        assert_eq!(requests.iter().filter(|r| **r == rebuild).count(), 1);
+        svc.check_rebuild(bot.id).await.unwrap();
     }
 
+    #[tokio::test]
+    async fn redeploy_refuses_before_touching_the_old_droplet() {
+        let store = MemoryStore::new();
+        let stub = DigitalOceanStub::start().await;
+        let svc = stub_service(
+            &store,
+            &stub,
+            ProvisioningSettings::builder("https://control.example")
+                .build()
+                .unwrap(),
+        );
+        let account = account(&store).await;
+        let (bot, _) = svc
+            .create_bot(
+                account.id,
+                "redeployed".to_string(),
+                Persona::Beginner,
+                bot_config(),
+                CustomizerOverrides::default(),
+                None,
+            )
+            .await
+            .unwrap();
+        svc.provision_bot(bot.id).await.unwrap();
+        let old_droplet = store.bots().get_by_id(bot.id).await.unwrap().droplet_id;
+        let old_droplet = old_droplet.expect("provisioned bot has a droplet");
+
+        // Still provisioning with a droplet: there is no edge to redeploy it on.
+        let err = svc.redeploy_bot(bot.id).await.unwrap_err();
+        assert!(
+            matches!(err, ProvisioningError::InvalidTransition(_)),
+            "{err}"
+        );
+        let stored = store.bots().get_by_id(bot.id).await.unwrap();
+        assert_eq!(stored.status, BotStatus::Provisioning);
+        assert_eq!(stored.droplet_id, Some(old_droplet));
+        assert_eq!(stub.droplet_ids(), vec![old_droplet]);
+
+        store
+            .bots()
+            .transition_status(
+                bot.id,
+                BotStatus::Provisioning,
+                BotStatus::Online,
+                TransitionReason::ConfigAcknowledged,
+                None,
+            )
+            .await
+            .unwrap();
+        svc.redeploy_bot(bot.id).await.unwrap();
+
+        let stored = store.bots().get_by_id(bot.id).await.unwrap();
+        assert_eq!(stored.status, BotStatus::Provisioning);
+        let new_droplet = stored.droplet_id.expect("redeployed bot has a droplet");
+        assert_ne!(new_droplet, old_droplet);
+        assert_eq!(stub.droplet_ids(), vec![new_droplet]);
+    }
 }
 
 impl<A, B, C, D> ProvisioningService<A, B, C, D>
 where
-    A: AccountRepository,
-    B: BotRepository,
-    C: ConfigRepository,
-    D: DropletRepository,
+    A: AccountRepository + ?Sized,
+    B: BotRepository + ?Sized,
+    C: ConfigRepository + ?Sized,
+    D: DropletRepository + ?Sized,
 {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
@@ -743,27 +1081,9 @@ where
         bot_repo: Arc<B>,
         config_repo: Arc<C>,
         droplet_repo: Arc<D>,
+        unit_of_work: Arc<dyn UnitOfWork>,
         encryption: Arc<SecretsEncryption>,
-        openclaw_image: String,
-        control_plane_url: String,
-
-        customizer_repo_url: String,
-        customizer_ref: String,
-        customizer_workspace_dir: String,
-        customizer_agent_name: String,
-        customizer_owner_name: String,
-        customizer_skip_qmd: bool,
-        customizer_skip_cron: bool,
-        customizer_skip_git: bool,
-        customizer_skip_heartbeat: bool,
-        toolchain_node_major: u8,
-        toolchain_install_pnpm: bool,
-        toolchain_pnpm_version: String,
-        toolchain_install_rust: bool,
-        toolchain_rust_toolchain: String,
-        toolchain_extra_apt_packages: String,
-        toolchain_global_npm_packages: String,
-        toolchain_cargo_crates: String,
+        settings: ProvisioningSettings,
     ) -> Self {
         Self {
             do_client,
@@ -771,46 +1091,79 @@ where
             bot_repo,
             config_repo,
             droplet_repo,
+            unit_of_work,
             encryption,
-            openclaw_image,
-            control_plane_url,
-
-            customizer_repo_url,
-            customizer_ref,
-            customizer_workspace_dir,
-            customizer_agent_name,
-            customizer_owner_name,
-            customizer_skip_qmd,
-            customizer_skip_cron,
-            customizer_skip_git,
-            customizer_skip_heartbeat,
-            toolchain_node_major,
-            toolchain_install_pnpm,
-            toolchain_pnpm_version,
-            toolchain_install_rust,
-            toolchain_rust_toolchain,
-            toolchain_extra_apt_packages,
-            toolchain_global_npm_packages,
-            toolchain_cargo_crates,
+            settings,
+            user_data: UserDataRenderer::default(),
         }
     }
 
-    pub async fn create_bot(
-        &self,
+    /// Append operator snippets (see [`UserDataOverlays`]) to every droplet's user data.
+    pub fn with_user_data_overlays(mut self, overlays: UserDataOverlays) -> Self {
+        self.user_data = self.user_data.with_overlays(overlays);
+        self
+    }
+
+    /// The customizer settings bots get when neither they nor their account override them.
+    pub fn default_customizer(&self) -> CustomizerSettings {
+        self.settings.customizer.clone()
+    }
+
+    /// Check account- or bot-level overrides against the allow-list.
+    pub fn validate_customizer(
+        &self,
+        overrides: &CustomizerOverrides,
+    ) -> Result<(), CustomizerError> {
+        overrides.validate(
+            &self.settings.customizer.repo_url,
+            &self.settings.customizer_allow,
+        )
+    }
+
+    /// Deliver user data as a bash script (the default) or a `#cloud-config` document.
+    pub fn with_user_data_format(mut self, format: UserDataFormat) -> Self {
+        self.user_data = self.user_data.with_format(format);
+        self
+    }
+
+    /// Record a new bot and its first config version, leaving it `pending`, and
+    /// queue the `SpawnBot` job whose `provision_bot` creates its droplet.
+    ///
+    /// `customizer` overrides the account's customizer settings, which override the
+    /// service defaults; the result is stored with the bot, as is the name of the
+    /// selected toolchain profile (the default when `toolchain_profile` is `None`).
+    /// The counter increment, bot row, config and job are committed together, so
+    /// no bot is left `pending` without a job to spawn it.
+    // REL-003: Structured logging context; `bot_id` is recorded once the bot exists.
+    #[instrument(
+        skip(self, name, config, customizer, toolchain_profile),
+        fields(account_id = %account_id, bot_id = Empty)
+    )]
+    pub async fn create_bot(
+        &self,
         account_id: Uuid,
         name: String,
         persona: Persona,
         config: BotConfig,
-    ) -> Result<Bot, ProvisioningError> {
-        // REL-003: Structured logging context
-        let span = Span::current();
-        span.record("account_id", account_id.to_string());
-
-        let _account = self.account_repo.get_by_id(account_id).await?;
-
-        // CRIT-002: Use atomic counter for race-condition-free limit checking
+        customizer: CustomizerOverrides,
+        toolchain_profile: Option<String>,
+    ) -> Result<(Bot, Job), ProvisioningError> {
+        let account = self.account_repo.get_by_id(account_id).await?;
+
+        let customizer = customizer.or(&account.customizer);
+        self.validate_customizer(&customizer)?;
+        let customizer = customizer.apply(&self.default_customizer());
+        let (toolchain_profile, _) = self
+            .settings
+            .toolchains
+            .resolve(toolchain_profile.as_deref())?;
+        let toolchain_profile = toolchain_profile.to_string();
+
+        // CRIT-002: Use atomic counter for race-condition-free limit checking.
+        // Returning early drops the transaction, which rolls the increment back.
+        let tx = self.unit_of_work.begin().await?;
         let (success, _current_count, max_count) =
-            self.bot_repo.increment_bot_counter(account_id).await?;
+            tx.bots().increment_bot_counter(account_id).await?;
 
         if !success {
             warn!(
@@ -831,43 +1184,29 @@ where
         );
 
         let mut bot = Bot::new(account_id, sanitized_name, persona);
+        bot.customizer = Some(customizer);
+        bot.toolchain_profile = Some(toolchain_profile);
+        Span::current().record("bot_id", tracing::field::display(bot.id));
 
-        // CRIT-005: Resource cleanup - if DB operations fail after this point,
-        // we need to decrement the counter we just incremented
-        let result = self.create_bot_internal(&mut bot, config).await;
-
-        if result.is_err() {
-            if let Err(e) = self.bot_repo.hard_delete(bot.id).await {
-                if !matches!(e, RepositoryError::NotFound(_)) {
-                    error!(
-                        account_id = %account_id,
-                        bot_id = %bot.id,
-                        error = %e,
-                        "Failed to rollback bot row after failed creation"
-                    );
-                }
-            }
-
-            // Decrement counter on failure to allow retry
-            if let Err(e) = self.bot_repo.decrement_bot_counter(account_id).await {
-                error!(
-                    account_id = %account_id,
-                    bot_id = %bot.id,
-                    error = %e,
-                    "Failed to decrement bot counter after failed creation"
-                );
-            }
-        }
+        // CRIT-005: Nothing is visible until the commit, so a failure part-way
+        // through leaves neither a bot row nor a spent counter slot behind.
+        self.create_bot_internal(tx.as_ref(), &mut bot, config)
+            .await?;
+        let job = Job::new(JobKind::SpawnBot, bot.id, self.settings.job_max_attempts);
+        tx.jobs().enqueue(&job).await?;
+        tx.commit().await?;
+        info!(job_id = %job.id, "Queued spawn job");
 
-        result.map(|_| bot)
+        Ok((bot, job))
     }
 
     async fn create_bot_internal(
         &self,
+        tx: &dyn RepositoryTransaction,
         bot: &mut Bot,
         config: BotConfig,
     ) -> Result<(), ProvisioningError> {
-        self.bot_repo.create(bot).await?;
+        tx.bots().create(bot).await?;
         info!("Created bot record: {}", bot.id);
 
         let encrypted_key = self
@@ -889,40 +1228,175 @@ where
             created_at: chrono::Utc::now(),
         };
 
-        self.config_repo.create(&config_with_encrypted).await?;
+        tx.configs().create(&config_with_encrypted).await?;
         info!("Created bot config version: {}", config_with_encrypted.id);
 
-        self.bot_repo
+        tx.bots()
             .update_config_version(bot.id, Some(config_with_encrypted.id), None)
             .await?;
         bot.desired_config_version_id = Some(config_with_encrypted.id);
 
-        self.spawn_bot(bot, &config_with_encrypted).await?;
-
         Ok(())
     }
 
+    /// Spawn the droplet for a bot recorded by `create_bot`.
+    ///
+    /// Runs from the job queue, so it must be safe to repeat: a bot that already
+    /// has a droplet is left as is.
+    #[instrument(skip(self), fields(bot_id = %bot_id))]
+    pub async fn provision_bot(&self, bot_id: Uuid) -> Result<(), ProvisioningError> {
+        let mut bot = self.bot_repo.get_by_id(bot_id).await?;
+
+        if bot.status == BotStatus::Destroyed {
+            return Err(ProvisioningError::InvalidConfig(format!(
+                "Bot {} has been destroyed",
+                bot_id
+            )));
+        }
+
+        if let Some(droplet_id) = bot.droplet_id {
+            info!(
+                bot_id = %bot_id,
+                droplet_id = droplet_id,
+                "Bot already has a droplet, nothing to provision"
+            );
+            return Ok(());
+        }
+
+        let config = self
+            .config_repo
+            .get_latest_for_bot(bot_id)
+            .await?
+            .ok_or_else(|| {
+                ProvisioningError::InvalidConfig("No config found for provisioning".to_string())
+            })?;
+
+        self.spawn_bot(&mut bot, &config, TransitionReason::Provision)
+            .await
+    }
+
+    /// Render the user data the bot's next droplet would get, for debugging.
+    ///
+    /// Nothing is stored and no droplet is created; the registration token is
+    /// replaced by a placeholder.
+    #[instrument(skip(self), fields(bot_id = %bot_id))]
+    pub async fn preview_user_data(&self, bot_id: Uuid) -> Result<String, ProvisioningError> {
+        let bot = self.bot_repo.get_by_id(bot_id).await?;
+        self.render_user_data(&bot, PREVIEW_REGISTRATION_TOKEN)
+            .await
+    }
+
+    /// Render the bot's current user data for the loader its droplet boots
+    /// (see [`UserDataRenderer::render_loader`]). The caller has checked
+    /// `registration_token` against the bot.
+    #[instrument(skip(self, registration_token), fields(bot_id = %bot_id))]
+    pub async fn bootstrap_user_data(
+        &self,
+        bot_id: Uuid,
+        registration_token: &str,
+    ) -> Result<String, ProvisioningError> {
+        let bot = self.bot_repo.get_by_id(bot_id).await?;
+        self.render_user_data(&bot, registration_token).await
+    }
+
+    async fn render_user_data(
+        &self,
+        bot: &Bot,
+        registration_token: &str,
+    ) -> Result<String, ProvisioningError> {
+        let config = self
+            .config_repo
+            .get_latest_for_bot(bot.id)
+            .await?
+            .ok_or_else(|| {
+                ProvisioningError::InvalidConfig("No config found for bot".to_string())
+            })?;
+        let tier = if self.user_data.needs_tier() {
+            Some(
+                self.account_repo
+                    .get_by_id(bot.account_id)
+                    .await?
+                    .subscription_tier,
+            )
+        } else {
+            None
+        };
+        let (_, toolchain) = self
+            .settings
+            .toolchains
+            .resolve(bot.toolchain_profile.as_deref())?;
+
+        Ok(self.generate_user_data(registration_token, bot, toolchain, tier.as_ref(), &config)?)
+    }
+
+    // REL-003: Add structured logging context
+    #[instrument(
+        skip_all,
+        fields(bot_id = %bot.id, account_id = %bot.account_id, droplet_id = Empty)
+    )]
     async fn spawn_bot(
         &self,
         bot: &mut Bot,
         config: &StoredBotConfig,
+        reason: TransitionReason,
     ) -> Result<(), ProvisioningError> {
-        // REL-003: Add structured logging context
-        let span = Span::current();
-        span.record("bot_id", bot.id.to_string());
-        span.record("account_id", bot.account_id.to_string());
-
-        self.bot_repo
-            .update_status(bot.id, BotStatus::Provisioning)
-            .await?;
+        // The droplet create is recorded in the outbox together with the transition,
+        // so a crash before it is recorded in `droplets` can't leak the droplet.
+        let operation = ProviderOperation::create_droplet(bot.id, self.operation_lease());
+        let tx = self.unit_of_work.begin().await?;
+        // A job that lost its worker mid-spawn finds the bot already provisioning.
+        if bot.status != BotStatus::Provisioning {
+            bot.check_transition(BotStatus::Provisioning, reason)?;
+            tx.bots()
+                .transition_status(bot.id, bot.status, BotStatus::Provisioning, reason, None)
+                .await?;
+        }
+        match tx.provider_operations().create(&operation).await {
+            Ok(()) => {}
+            Err(RepositoryError::Conflict(_)) => {
+                // The caller that recorded it creates the droplet, or the outbox
+                // executor does once that caller's lease expires.
+                info!(
+                    bot_id = %bot.id,
+                    "Droplet create already pending for bot, leaving it to its owner"
+                );
+                return Ok(());
+            }
+            Err(e) => return Err(e.into()),
+        }
+        tx.commit().await?;
         bot.status = BotStatus::Provisioning;
 
+        self.create_droplet(bot, config, &operation).await
+    }
+
+    /// Create (or, on a retried operation, adopt) the droplet for a provisioning
+    /// bot and record it, completing `operation`.
+    async fn create_droplet(
+        &self,
+        bot: &mut Bot,
+        config: &StoredBotConfig,
+        operation: &ProviderOperation,
+    ) -> Result<(), ProvisioningError> {
         info!(
             bot_id = %bot.id,
             account_id = %bot.account_id,
             "Starting bot spawn process"
         );
 
+        // An earlier attempt may have created the droplet before its process died.
+        // The registration token it booted with is still the stored one.
+        if operation.attempts > 0 {
+            if let Some(droplet) = self.unrecorded_droplets(bot.id).await?.into_iter().next() {
+                info!(
+                    bot_id = %bot.id,
+                    droplet_id = droplet.id,
+                    "Adopting droplet created by an earlier attempt"
+                );
+                return self.record_droplet(bot, droplet, operation).await;
+            }
+        }
+
         // MED-002: Safe string truncation instead of split
         let id_str = bot.id.to_string();
         let droplet_name = format!("openclaw-bot-{}", &id_str[..8.min(id_str.len())]);
@@ -934,29 +1408,86 @@ where
             .await?;
         bot.registration_token = Some(registration_token.clone());
 
-        let user_data = self.generate_user_data(&registration_token, bot.id, config);
+        let tier = if self.user_data.needs_tier() {
+            Some(
+                self.account_repo
+                    .get_by_id(bot.account_id)
+                    .await?
+                    .subscription_tier,
+            )
+        } else {
+            None
+        };
+        // Bots created before profiles existed fall back to the default profile.
+        let rendered = self
+            .settings
+            .toolchains
+            .resolve(bot.toolchain_profile.as_deref())
+            .map_err(ProvisioningError::from)
+            .and_then(|(name, toolchain)| {
+                let user_data = self.boot_user_data(
+                    &registration_token,
+                    bot,
+                    toolchain,
+                    tier.as_ref(),
+                    config,
+                )?;
+                let digest = user_data_digest(&self.boot_user_data(
+                    PREVIEW_REGISTRATION_TOKEN,
+                    bot,
+                    toolchain,
+                    tier.as_ref(),
+                    config,
+                )?);
+                Ok((name.to_string(), user_data, digest))
+            });
+        let (toolchain_profile, user_data, digest) = match rendered {
+            Ok(rendered) => rendered,
+            Err(e) => {
+                error!(bot_id = %bot.id, error = %e, "Failed to render user data");
+                self.fail_create(
+                    bot,
+                    operation,
+                    BotStatus::Error,
+                    TransitionReason::ProvisionFailed,
+                    &format!("Could not render user data: {}", e),
+                    None,
+                )
+                .await?;
+                return Err(e);
+            }
+        };
 
         let droplet_request = DropletCreateRequest {
             name: droplet_name,
             region: "nyc3".to_string(),
             size: "s-1vcpu-2gb".to_string(),
-            image: self.openclaw_image.clone(),
+            // A bot woken from hibernation boots from the snapshot it took.
+            image: match bot.hibernation_snapshot_id {
+                Some(snapshot_id) => snapshot_id.to_string(),
+                None => self.settings.openclaw_image.clone(),
+            },
             user_data,
-            tags: vec!["openclaw".to_string(), format!("bot-{}", bot.id)],
+            tags: vec!["openclaw".to_string(), ProviderOperation::bot_tag(bot.id)],
         };
 
         // CRIT-005: Create droplet first, then attempt DB persistence with cleanup on failure
-        let droplet = match self.do_client.create_droplet(droplet_request).await {
+        let mut droplet = match self.do_client.create_droplet(droplet_request).await {
             Ok(d) => d,
             Err(DigitalOceanError::RateLimited) => {
                 warn!(
                     bot_id = %bot.id,
                     "Rate limited by DigitalOcean, bot will retry"
                 );
-                self.bot_repo
-                    .update_status(bot.id, BotStatus::Pending)
-                    .await?;
-                bot.status = BotStatus::Pending;
+                self.fail_create(
+                    bot,
+                    operation,
+                    BotStatus::Pending,
+                    TransitionReason::ProvisionDeferred,
+                    "Rate limited by DigitalOcean while creating the droplet",
+                    None,
+                )
+                .await?;
                 return Err(DigitalOceanError::RateLimited.into());
             }
             Err(e) => {
@@ -965,28 +1496,47 @@ where
                     error = %e,
                     "Failed to create droplet for bot"
                 );
-                self.bot_repo
-                    .update_status(bot.id, BotStatus::Error)
-                    .await?;
-                bot.status = BotStatus::Error;
+                self.fail_create(
+                    bot,
+                    operation,
+                    BotStatus::Error,
+                    TransitionReason::ProvisionFailed,
+                    &format!("Could not create droplet: {}", e),
+                    None,
+                )
+                .await?;
                 return Err(e.into());
             }
         };
 
-        // CRIT-005: Attempt DB operations with compensating cleanup on failure
+        droplet.toolchain_profile = Some(toolchain_profile);
+        droplet.user_data_digest = Some(digest);
+        self.record_droplet(bot, droplet, operation).await
+    }
+
+    /// Record `droplet` as the bot's and complete `operation`, in one transaction.
+    async fn record_droplet(
+        &self,
+        bot: &mut Bot,
+        droplet: Droplet,
+        operation: &ProviderOperation,
+    ) -> Result<(), ProvisioningError> {
+        Span::current().record("droplet_id", droplet.id);
+
         let db_result: Result<(), ProvisioningError> = async {
-            self.droplet_repo.create(&droplet).await?;
-            self.droplet_repo
+            let tx = self.unit_of_work.begin().await?;
+            tx.droplets().create(&droplet).await?;
+            tx.droplets()
                 .update_bot_assignment(droplet.id, Some(bot.id))
                 .await?;
-            self.bot_repo
-                .update_droplet(bot.id, Some(droplet.id))
-                .await?;
+            tx.bots().update_droplet(bot.id, Some(droplet.id)).await?;
+            tx.provider_operations().mark_completed(operation).await?;
+            tx.commit().await?;
             Ok(())
         }
         .await;
 
-        if let Err(ref e) = db_result {
+        if let Err(e) = db_result {
             // CRIT-005: DB persistence failed - attempt to clean up DO droplet
             error!(
                 bot_id = %bot.id,
@@ -995,35 +1545,51 @@ where
                 "DB persistence failed after DO droplet created. Attempting cleanup"
             );
 
-            match self.do_client.destroy_droplet(droplet.id).await {
-                Ok(_) => {
+            let (cleanup, orphaned) = match self.do_client.destroy_droplet(droplet.id).await {
+                Ok(_) | Err(DigitalOceanError::NotFound(_)) => {
                     info!(
                         bot_id = %bot.id,
                         droplet_id = droplet.id,
                         "Successfully cleaned up droplet after DB failure"
                     );
+                    ("droplet was destroyed", None)
                 }
                 Err(cleanup_err) => {
-                    error!(
+                    warn!(
                         bot_id = %bot.id,
                         droplet_id = droplet.id,
                         error = %cleanup_err,
-                        "FAILED TO CLEANUP: Droplet may be orphaned"
+                        "Failed to clean up droplet; the outbox executor will retry"
                     );
+                    ("droplet destroy is queued", Some(droplet.id))
                 }
-            }
+            };
 
             // Update bot status to error since droplet creation failed at persistence stage
-            if let Err(status_err) = self.bot_repo.update_status(bot.id, BotStatus::Error).await {
+            let detail = format!(
+                "Failed to record droplet {}: {} ({})",
+                droplet.id, e, cleanup
+            );
+            if let Err(status_err) = self
+                .fail_create(
+                    bot,
+                    operation,
+                    BotStatus::Error,
+                    TransitionReason::ProvisionFailed,
+                    &detail,
+                    orphaned,
+                )
+                .await
+            {
                 error!(
                     bot_id = %bot.id,
+                    droplet_id = droplet.id,
                     error = %status_err,
-                    "Failed to update bot status to error"
+                    "Failed to update bot status to error; droplet may be orphaned"
                 );
             }
-            bot.status = BotStatus::Error;
 
-            return Err(db_result.unwrap_err());
+            return Err(e);
         }
 
         bot.droplet_id = Some(droplet.id);
@@ -1034,89 +1600,184 @@ where
             "Successfully spawned droplet for bot"
         );
 
+        // The droplet has its own copy of the disk now.
+        if let Some(snapshot_id) = bot.hibernation_snapshot_id.take() {
+            self.release_hibernation_snapshot(bot.id, snapshot_id).await;
+        }
+
         Ok(())
     }
 
+    /// Move a provisioning bot to `to` and give up on `operation`, in one
+    /// transaction. `orphaned` is a droplet the outbox must still destroy.
+    async fn fail_create(
+        &self,
+        bot: &mut Bot,
+        operation: &ProviderOperation,
+        to: BotStatus,
+        reason: TransitionReason,
+        detail: &str,
+        orphaned: Option<i64>,
+    ) -> Result<(), RepositoryError> {
+        let tx = self.unit_of_work.begin().await?;
+        tx.bots()
+            .transition_status(bot.id, BotStatus::Provisioning, to, reason, Some(detail))
+            .await?;
+        tx.provider_operations()
+            .mark_failed(operation, detail)
+            .await?;
+        if let Some(droplet_id) = orphaned {
+            tx.provider_operations()
+                .create(&ProviderOperation::destroy_droplet(
+                    bot.id,
+                    droplet_id,
+                    self.operation_lease(),
+                ))
+                .await?;
+        }
+        tx.commit().await?;
+        bot.status = to;
+        Ok(())
+    }
+
+    /// Droplets tagged for the bot that `droplets` has no row for.
+    async fn unrecorded_droplets(&self, bot_id: Uuid) -> Result<Vec<Droplet>, ProvisioningError> {
+        let mut unrecorded = Vec::new();
+        for droplet in self
+            .do_client
+            .list_droplets_by_tag(&ProviderOperation::bot_tag(bot_id))
+            .await?
+        {
+            match self.droplet_repo.get_by_id(droplet.id).await {
+                Ok(_) => {}
+                Err(RepositoryError::NotFound(_)) => unrecorded.push(droplet),
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(unrecorded)
+    }
+
+    fn operation_lease(&self) -> chrono::Duration {
+        chrono::Duration::from_std(self.settings.operation_lease).unwrap_or(chrono::Duration::MAX)
+    }
+
+    /// What a droplet is created with: the loader where the user data format
+    /// has one, otherwise the full user data. The full user data is rendered
+    /// either way, so a bot that can't boot is caught before its droplet exists.
+    fn boot_user_data(
+        &self,
+        registration_token: &str,
+        bot: &Bot,
+        toolchain: &ToolchainProfile,
+        tier: Option<&SubscriptionTier>,
+        config: &StoredBotConfig,
+    ) -> Result<String, UserDataError> {
+        let user_data =
+            self.generate_user_data(registration_token, bot, toolchain, tier, config)?;
+        let loader = self
+            .user_data
+            .render_loader(bot.id, &self.loader_vars(registration_token, bot))?;
+        Ok(loader.unwrap_or(user_data))
+    }
+
+    /// The variables the droplet needs to reach the control plane.
+    fn loader_vars(&self, registration_token: &str, bot: &Bot) -> UserDataVars {
+        use UserDataValue::{Id, Secret, Text};
+
+        // CRIT-006: Use configured control plane URL instead of hardcoded value
+        UserDataVars::new()
+            .set("REGISTRATION_TOKEN", Secret(registration_token.to_string()))
+            .set("BOT_ID", Id(bot.id))
+            .set(
+                "CONTROL_PLANE_URL",
+                Text(self.settings.control_plane_url.clone()),
+            )
+    }
+
     fn generate_user_data(
         &self,
         registration_token: &str,
-        bot_id: Uuid,
+        bot: &Bot,
+        toolchain: &ToolchainProfile,
+        tier: Option<&SubscriptionTier>,
         _config: &StoredBotConfig,
-    ) -> String {
-        // Read the bootstrap script and prepend environment variables
-        let bootstrap_script = include_str!("../../scripts/openclaw-bootstrap.sh");
+    ) -> Result<String, UserDataError> {
+        use UserDataValue::{Flag, Number, Text};
+
+        let customizer = bot
+            .customizer
+            .clone()
+            .unwrap_or_else(|| self.default_customizer());
+
+        let vars = self
+            .loader_vars(registration_token, bot)
+            .section("Workspace/customization (janebot-cli)")
+            .set("CUSTOMIZER_REPO_URL", Text(customizer.repo_url))
+            .set("CUSTOMIZER_REF", Text(customizer.git_ref))
+            .set(
+                "CUSTOMIZER_WORKSPACE_DIR",
+                Text(self.settings.customizer_workspace_dir.clone()),
+            )
+            .set("CUSTOMIZER_AGENT_NAME", Text(customizer.agent_name))
+            .set("CUSTOMIZER_OWNER_NAME", Text(customizer.owner_name))
+            .set("CUSTOMIZER_SKIP_QMD", Flag(customizer.skip_qmd))
+            .set("CUSTOMIZER_SKIP_CRON", Flag(customizer.skip_cron))
+            .set("CUSTOMIZER_SKIP_GIT", Flag(customizer.skip_git))
+            .set("CUSTOMIZER_SKIP_HEARTBEAT", Flag(customizer.skip_heartbeat))
+            .section("Toolchain/bootstrap customization")
+            .set(
+                "TOOLCHAIN_NODE_MAJOR",
+                Number(u32::from(toolchain.node_major)),
+            )
+            .set("TOOLCHAIN_INSTALL_PNPM", Flag(toolchain.install_pnpm))
+            .set(
+                "TOOLCHAIN_PNPM_VERSION",
+                Text(toolchain.pnpm_version.clone()),
+            )
+            .set("TOOLCHAIN_INSTALL_RUST", Flag(toolchain.install_rust))
+            .set(
+                "TOOLCHAIN_RUST_TOOLCHAIN",
+                Text(toolchain.rust_toolchain.clone()),
+            )
+            .set(
+                "TOOLCHAIN_EXTRA_APT_PACKAGES",
+                Text(toolchain.apt_packages.join(" ")),
+            )
+            .set(
+                "TOOLCHAIN_GLOBAL_NPM_PACKAGES",
+                Text(toolchain.npm_packages.join(" ")),
+            )
+            .set(
+                "TOOLCHAIN_CARGO_CRATES",
+                Text(toolchain.cargo_crates.join(" ")),
+            );
 
-        // CRIT-006: Use configured control plane URL instead of hardcoded value
-        format!(
-            r##"#!/bin/bash
-# OpenClaw Bot Bootstrap for Bot {}
-set -e
-
-# NOTE: Do not enable `set -x` (xtrace). This user-data includes secrets
-# (registration token) and xtrace would leak them into cloud-init logs.
-
-export REGISTRATION_TOKEN={}
-export BOT_ID={}
-export CONTROL_PLANE_URL={}
-
-# Workspace/customization (janebot-cli)
-export CUSTOMIZER_REPO_URL={}
-export CUSTOMIZER_REF={}
-export CUSTOMIZER_WORKSPACE_DIR={}
-export CUSTOMIZER_AGENT_NAME={}
-export CUSTOMIZER_OWNER_NAME={}
-export CUSTOMIZER_SKIP_QMD="{}"
-export CUSTOMIZER_SKIP_CRON="{}"
-export CUSTOMIZER_SKIP_GIT="{}"
-export CUSTOMIZER_SKIP_HEARTBEAT="{}"
-
-# Toolchain/bootstrap customization
-export TOOLCHAIN_NODE_MAJOR="{}"
-export TOOLCHAIN_INSTALL_PNPM="{}"
-export TOOLCHAIN_PNPM_VERSION={}
-export TOOLCHAIN_INSTALL_RUST="{}"
-export TOOLCHAIN_RUST_TOOLCHAIN={}
-export TOOLCHAIN_EXTRA_APT_PACKAGES={}
-export TOOLCHAIN_GLOBAL_NPM_PACKAGES={}
-export TOOLCHAIN_CARGO_CRATES={}
-
-# Start of embedded bootstrap script
-{}
-"##,
-            bot_id,
-            shell_escape(registration_token),
-            shell_escape(&bot_id.to_string()),
-            shell_escape(&self.control_plane_url),
-            shell_escape(&self.customizer_repo_url),
-            shell_escape(&self.customizer_ref),
-            shell_escape(&self.customizer_workspace_dir),
-            shell_escape(&self.customizer_agent_name),
-            shell_escape(&self.customizer_owner_name),
-            self.customizer_skip_qmd,
-            self.customizer_skip_cron,
-            self.customizer_skip_git,
-            self.customizer_skip_heartbeat,
-            self.toolchain_node_major,
-            self.toolchain_install_pnpm,
-            shell_escape(&self.toolchain_pnpm_version),
-            self.toolchain_install_rust,
-            shell_escape(&self.toolchain_rust_toolchain),
-            shell_escape(&self.toolchain_extra_apt_packages),
-            shell_escape(&self.toolchain_global_npm_packages),
-            shell_escape(&self.toolchain_cargo_crates),
-            bootstrap_script
-        )
+        self.user_data.render(bot.id, &vars, &bot.persona, tier)
     }
 
     #[cfg(test)]
     fn test_only_generate_user_data(&self, registration_token: &str, bot_id: Uuid) -> String {
         // Helper to keep tests focused without additional config setup.
+        let mut bot = Bot::new(Uuid::new_v4(), "test".to_string(), Persona::Beginner);
+        bot.id = bot_id;
+        self.test_only_generate_user_data_for(registration_token, &bot)
+    }
+
+    #[cfg(test)]
+    fn test_only_generate_user_data_for(&self, registration_token: &str, bot: &Bot) -> String {
+        let (_, toolchain) = self
+            .settings
+            .toolchains
+            .resolve(bot.toolchain_profile.as_deref())
+            .expect("test toolchain profile resolves");
         self.generate_user_data(
             registration_token,
-            bot_id,
+            bot,
+            toolchain,
+            None,
             &StoredBotConfig {
                 id: Uuid::new_v4(),
-                bot_id,
+                bot_id: bot.id,
                 version: 1,
                 trading_config: crate::domain::TradingConfig {
                     asset_focus: crate::domain::AssetFocus::Majors,
@@ -1138,6 +1799,7 @@ export TOOLCHAIN_CARGO_CRATES={}
                 created_at: chrono::Utc::now(),
             },
         )
+        .expect("test user data renders")
     }
 
     fn generate_registration_token(&self, _bot_id: Uuid) -> String {
@@ -1146,112 +1808,58 @@ export TOOLCHAIN_CARGO_CRATES={}
         base64::Engine::encode(&base64::engine::general_purpose::STANDARD, token)
     }
 
+    // REL-003: Add structured logging span with context
+    #[instrument(skip(self), fields(bot_id = %bot_id, account_id = Empty, droplet_id = Empty))]
     pub async fn destroy_bot(&self, bot_id: Uuid) -> Result<(), ProvisioningError> {
         let bot = self.bot_repo.get_by_id(bot_id).await?;
 
-        // REL-003: Add structured logging span with context
-        let span = Span::current();
-        span.record("bot_id", bot_id.to_string());
-        span.record("account_id", bot.account_id.to_string());
+        // Job retries may re-run a destroy that already completed; don't decrement twice.
+        if bot.status == BotStatus::Destroyed {
+            info!(bot_id = %bot_id, "Bot already destroyed");
+            return Ok(());
+        }
 
+        let span = Span::current();
+        span.record("account_id", tracing::field::display(bot.account_id));
         if let Some(droplet_id) = bot.droplet_id {
             span.record("droplet_id", droplet_id);
-
-            match self.do_client.destroy_droplet(droplet_id).await {
-                Ok(_) => {
-                    info!(
-                        bot_id = %bot_id,
-                        droplet_id = droplet_id,
-                        "Destroyed droplet for bot"
-                    );
-
-                    // REL-001: Retry on failure for compensating transaction
-                    if let Err(e) = retry_with_backoff("mark_destroyed", bot_id, || {
-                        self.droplet_repo.mark_destroyed(droplet_id)
-                    })
-                    .await
-                    {
-                        error!(
-                            bot_id = %bot_id,
-                            droplet_id = droplet_id,
-                            error = %e,
-                            "Failed to mark droplet as destroyed after retries"
-                        );
-                        return Err(e.into());
-                    }
-                }
-                Err(DigitalOceanError::NotFound(_)) => {
-                    warn!(
-                        bot_id = %bot_id,
-                        droplet_id = droplet_id,
-                        "Droplet already destroyed or not found"
-                    );
-
-                    // REL-001: Retry on failure for compensating transaction
-                    if let Err(e) = retry_with_backoff("mark_destroyed", bot_id, || {
-                        self.droplet_repo.mark_destroyed(droplet_id)
-                    })
-                    .await
-                    {
-                        error!(
-                            bot_id = %bot_id,
-                            droplet_id = droplet_id,
-                            error = %e,
-                            "Failed to mark droplet as destroyed after retries"
-                        );
-                        return Err(e.into());
-                    }
-                }
-                Err(e) => {
-                    error!(
-                        bot_id = %bot_id,
-                        droplet_id = droplet_id,
-                        error = %e,
-                        "Failed to destroy droplet"
-                    );
-                    return Err(e.into());
-                }
-            }
         }
 
-        // REL-001: Retry DB updates with backoff
-        if let Err(e) = retry_with_backoff("update_droplet", bot_id, || {
-            self.bot_repo.update_droplet(bot_id, None)
+        // REL-001: Retry the bookkeeping with backoff. Each attempt rereads the bot
+        // and is one transaction, so losing a race to another status change retries.
+        let recorded = match retry_with_backoff("record_destroy", bot_id, || {
+            self.record_destroy(bot_id)
         })
         .await
         {
-            error!(
-                bot_id = %bot_id,
-                error = %e,
-                "Failed to update bot droplet reference after retries"
-            );
-            return Err(e.into());
-        }
+            Ok(recorded) => recorded,
+            Err(e) => {
+                error!(
+                    bot_id = %bot_id,
+                    account_id = %bot.account_id,
+                    error = %e,
+                    "Failed to record bot destruction after retries"
+                );
+                return Err(e.into());
+            }
+        };
+        let Some((bot, operation)) = recorded else {
+            info!(bot_id = %bot_id, "Bot destroyed concurrently");
+            return Ok(());
+        };
 
-        if let Err(e) =
-            retry_with_backoff("delete_bot", bot_id, || self.bot_repo.delete(bot_id)).await
-        {
-            error!(
-                bot_id = %bot_id,
-                error = %e,
-                "Failed to delete bot after retries"
-            );
-            return Err(e.into());
+        if let Some(operation) = operation {
+            if let Err(e) = self.destroy_provider_droplet(&operation).await {
+                warn!(
+                    bot_id = %bot_id,
+                    droplet_id = operation.droplet_id,
+                    error = %e,
+                    "Failed to destroy droplet; the outbox executor will retry"
+                );
+            }
         }
-
-        // CRIT-002: Decrement bot counter when bot is destroyed
-        // REL-001: Retry counter decrement
-        if let Err(e) = retry_with_backoff("decrement_bot_counter", bot_id, || {
-            self.bot_repo.decrement_bot_counter(bot.account_id)
-        })
-        .await
-        {
-            error!(
-                bot_id = %bot_id,
-                account_id = %bot.account_id,
-                error = %e,
-                "Failed to decrement bot counter after retries - counter may be inconsistent"
-            );
+        if let Some(snapshot_id) = bot.hibernation_snapshot_id {
+            self.delete_snapshot(bot_id, snapshot_id).await;
         }
 
         info!(
@@ -1262,29 +1870,207 @@ export TOOLCHAIN_CARGO_CRATES={}
         Ok(())
     }
 
+    /// Mark the droplet destroyed, queue its DigitalOcean destroy, unlink it,
+    /// soft-delete the bot and release its counter slot (CRIT-002), all or nothing.
+    /// Returns the bot as it was, or `None` if it is already destroyed.
+    async fn record_destroy(
+        &self,
+        bot_id: Uuid,
+    ) -> Result<Option<(Bot, Option<ProviderOperation>)>, RepositoryError> {
+        let bot = self.bot_repo.get_by_id(bot_id).await?;
+        if bot.status == BotStatus::Destroyed {
+            return Ok(None);
+        }
+        let tx = self.unit_of_work.begin().await?;
+        let operation = match bot.droplet_id {
+            Some(droplet_id) => Some(self.queue_droplet_destroy(&*tx, bot.id, droplet_id).await?),
+            None => None,
+        };
+        // Compare-and-set, so the counter is released once however many destroys race.
+        tx.bots()
+            .transition_status(
+                bot.id,
+                bot.status,
+                BotStatus::Destroyed,
+                TransitionReason::Destroy,
+                None,
+            )
+            .await?;
+        tx.bots().decrement_bot_counter(bot.account_id).await?;
+        tx.commit().await?;
+        Ok(Some((bot, operation)))
+    }
+
+    /// Within `tx`, mark the bot's droplet destroyed, unlink it and record the
+    /// DigitalOcean destroy in the outbox.
+    async fn queue_droplet_destroy(
+        &self,
+        tx: &dyn RepositoryTransaction,
+        bot_id: Uuid,
+        droplet_id: i64,
+    ) -> Result<ProviderOperation, RepositoryError> {
+        let operation =
+            ProviderOperation::destroy_droplet(bot_id, droplet_id, self.operation_lease());
+        tx.droplets().mark_destroyed(droplet_id).await?;
+        tx.bots().update_droplet(bot_id, None).await?;
+        tx.provider_operations().create(&operation).await?;
+        Ok(operation)
+    }
+
+    /// Destroy the droplet of a recorded destroy operation and complete it.
+    /// A droplet DigitalOcean no longer has counts as destroyed.
+    async fn destroy_provider_droplet(
+        &self,
+        operation: &ProviderOperation,
+    ) -> Result<(), ProvisioningError> {
+        let droplet_id = operation.droplet_id.ok_or_else(|| {
+            RepositoryError::InvalidData(format!(
+                "Destroy operation {} has no droplet",
+                operation.id
+            ))
+        })?;
+
+        match self.do_client.destroy_droplet(droplet_id).await {
+            Ok(_) => {
+                info!(
+                    bot_id = %operation.bot_id,
+                    droplet_id = droplet_id,
+                    "Destroyed droplet for bot"
+                );
+            }
+            Err(DigitalOceanError::NotFound(_)) => {
+                warn!(
+                    bot_id = %operation.bot_id,
+                    droplet_id = droplet_id,
+                    "Droplet already destroyed or not found"
+                );
+            }
+            Err(e) => return Err(e.into()),
+        }
+
+        let tx = self.unit_of_work.begin().await?;
+        tx.provider_operations().mark_completed(operation).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Finish an operation whose writer never did, as claimed by the outbox
+    /// executor. A create whose bot moved on (destroyed, failed, or already
+    /// has a droplet) only cleans up droplets the earlier attempt left behind.
+    #[instrument(
+        skip_all,
+        fields(operation_id = %operation.id, bot_id = %operation.bot_id, kind = %operation.kind)
+    )]
+    pub async fn execute_provider_operation(
+        &self,
+        operation: &ProviderOperation,
+    ) -> Result<(), ProvisioningError> {
+        if operation.kind == ProviderOperationKind::DestroyDroplet {
+            return self.destroy_provider_droplet(operation).await;
+        }
+
+        let bot = match self.bot_repo.get_by_id(operation.bot_id).await {
+            Ok(bot) => Some(bot),
+            Err(RepositoryError::NotFound(_)) => None,
+            Err(e) => return Err(e.into()),
+        };
+        match bot {
+            Some(mut bot) if bot.status == BotStatus::Provisioning && bot.droplet_id.is_none() => {
+                let config = self
+                    .config_repo
+                    .get_latest_for_bot(bot.id)
+                    .await?
+                    .ok_or_else(|| {
+                        ProvisioningError::InvalidConfig(
+                            "No config found for provisioning".to_string(),
+                        )
+                    })?;
+                self.create_droplet(&mut bot, &config, operation).await
+            }
+            _ => {
+                for droplet in self.unrecorded_droplets(operation.bot_id).await? {
+                    match self.do_client.destroy_droplet(droplet.id).await {
+                        Ok(_) | Err(DigitalOceanError::NotFound(_)) => {
+                            info!(
+                                bot_id = %operation.bot_id,
+                                droplet_id = droplet.id,
+                                "Destroyed droplet left behind by an abandoned create"
+                            );
+                        }
+                        Err(e) => return Err(e.into()),
+                    }
+                }
+                let tx = self.unit_of_work.begin().await?;
+                tx.provider_operations().mark_completed(operation).await?;
+                tx.commit().await?;
+                Ok(())
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(bot_id = %bot_id))]
     pub async fn pause_bot(&self, bot_id: Uuid) -> Result<(), ProvisioningError> {
         let bot = self.bot_repo.get_by_id(bot_id).await?;
+        if bot.status == BotStatus::Paused {
+            info!(bot_id = %bot_id, "Bot already paused");
+            return Ok(());
+        }
+        // The guard guarantees a droplet; the compare-and-set below catches a
+        // destroy or redeploy that lands while we are shutting it down.
+        bot.check_transition(BotStatus::Paused, TransitionReason::Pause)?;
 
         if let Some(droplet_id) = bot.droplet_id {
-            self.do_client.shutdown_droplet(droplet_id).await?;
+            self.power_off_droplet(droplet_id).await?;
             info!("Paused droplet {} for bot {}", droplet_id, bot_id);
         }
 
         self.bot_repo
-            .update_status(bot_id, BotStatus::Paused)
+            .transition_status(
+                bot_id,
+                bot.status,
+                BotStatus::Paused,
+                TransitionReason::Pause,
+                None,
+            )
             .await?;
         Ok(())
     }
 
+    /// Shut the droplet down gracefully, cutting its power if the shutdown
+    /// doesn't finish within the action timeout. Returns once it is off.
+    async fn power_off_droplet(&self, droplet_id: i64) -> Result<(), DigitalOceanError> {
+        let timeout = self.settings.droplet_action_timeout;
+        let shutdown = self.do_client.shutdown_droplet(droplet_id).await?;
+        match self.do_client.wait_for_action(&shutdown, timeout).await {
+            Ok(_) => return Ok(()),
+            Err(
+                e @ (DigitalOceanError::ActionTimedOut { .. }
+                | DigitalOceanError::ActionFailed { .. }),
+            ) => {
+                warn!(
+                    droplet_id = droplet_id,
+                    error = %e,
+                    "Graceful shutdown did not finish, powering droplet off"
+                );
+            }
+            Err(e) => return Err(e),
+        }
+
+        let power_off = self.do_client.power_off_droplet(droplet_id).await?;
+        self.do_client.wait_for_action(&power_off, timeout).await?;
+        Ok(())
+    }
+
+    #[instrument(skip(self), fields(bot_id = %bot_id))]
     pub async fn resume_bot(&self, bot_id: Uuid) -> Result<(), ProvisioningError> {
         let bot = self.bot_repo.get_by_id(bot_id).await?;
-
-        if bot.status != BotStatus::Paused {
-            return Err(ProvisioningError::InvalidConfig(format!(
-                "Bot {} is not in paused state (current: {:?})",
-                bot_id, bot.status
-            )));
+        if bot.status == BotStatus::Online {
+            info!(bot_id = %bot_id, "Bot already online");
+            return Ok(());
         }
+        // The guard guarantees a droplet; the compare-and-set below catches a
+        // destroy or redeploy that lands while we are powering it on.
+        bot.check_transition(BotStatus::Online, TransitionReason::Resume)?;
 
         if let Some(droplet_id) = bot.droplet_id {
             // HIGH-002: Check droplet status before attempting reboot
@@ -1292,8 +2078,10 @@ export TOOLCHAIN_CARGO_CRATES={}
                 Ok(droplet) => {
                     match droplet.status {
                         crate::domain::DropletStatus::Off => {
-                            // Droplet is off, safe to reboot
-                            self.do_client.reboot_droplet(droplet_id).await?;
+                            let action = self.do_client.power_on_droplet(droplet_id).await?;
+                            self.do_client
+                                .wait_for_action(&action, self.settings.droplet_action_timeout)
+                                .await?;
                             info!("Resumed droplet {} for bot {}", droplet_id, bot_id);
                         }
                         crate::domain::DropletStatus::Active => {
@@ -1326,32 +2114,251 @@ export TOOLCHAIN_CARGO_CRATES={}
                 }
                 Err(e) => return Err(e.into()),
             }
-        } else {
-            return Err(ProvisioningError::InvalidConfig(format!(
-                "Bot {} has no associated droplet",
-                bot_id
-            )));
         }
 
         self.bot_repo
-            .update_status(bot_id, BotStatus::Online)
+            .transition_status(
+                bot_id,
+                bot.status,
+                BotStatus::Online,
+                TransitionReason::Resume,
+                None,
+            )
             .await?;
         Ok(())
     }
 
-    pub async fn redeploy_bot(&self, bot_id: Uuid) -> Result<(), ProvisioningError> {
+    /// Destroy the bot's droplet to stop its billing, keeping the bot, its config
+    /// and its token. The droplet is snapshotted first if the bot (or, when it
+    /// hasn't chosen, its account tier) asks for it; [`Self::wake_bot`] boots
+    /// the next droplet from that snapshot.
+    ///
+    /// Runs from the job queue; a retry reuses a snapshot an earlier attempt took.
+    #[instrument(skip(self), fields(bot_id = %bot_id, droplet_id = Empty))]
+    pub async fn hibernate_bot(&self, bot_id: Uuid) -> Result<(), ProvisioningError> {
+        let bot = self.bot_repo.get_by_id(bot_id).await?;
+        if bot.status == BotStatus::Hibernated {
+            info!(bot_id = %bot_id, "Bot already hibernated");
+            return Ok(());
+        }
+        // The guard guarantees a droplet; the compare-and-set below catches a
+        // destroy or redeploy that lands while we are snapshotting it.
+        bot.check_transition(BotStatus::Hibernated, TransitionReason::Hibernate)?;
+        let droplet_id = bot.droplet_id.ok_or_else(|| {
+            ProvisioningError::InvalidConfig(format!("Bot {} has no associated droplet", bot_id))
+        })?;
+        Span::current().record("droplet_id", droplet_id);
+
+        let snapshot_id = if self.snapshots_on_hibernate(&bot).await? {
+            Some(self.snapshot_droplet(&bot, droplet_id).await?)
+        } else {
+            None
+        };
+
+        let recorded: Result<ProviderOperation, ProvisioningError> = async {
+            let tx = self.unit_of_work.begin().await?;
+            let operation = self.queue_droplet_destroy(&*tx, bot_id, droplet_id).await?;
+            tx.bots()
+                .update_hibernation_snapshot(bot_id, snapshot_id)
+                .await?;
+            tx.bots()
+                .transition_status(
+                    bot_id,
+                    bot.status,
+                    BotStatus::Hibernated,
+                    TransitionReason::Hibernate,
+                    None,
+                )
+                .await?;
+            tx.commit().await?;
+            Ok(operation)
+        }
+        .await;
+        let operation = match recorded {
+            Ok(operation) => operation,
+            Err(e) => {
+                if let Some(snapshot_id) = snapshot_id {
+                    self.delete_snapshot(bot_id, snapshot_id).await;
+                }
+                return Err(e);
+            }
+        };
+
+        if let Err(e) = self.destroy_provider_droplet(&operation).await {
+            warn!(
+                bot_id = %bot_id,
+                droplet_id = droplet_id,
+                error = %e,
+                "Failed to destroy droplet; the outbox executor will retry"
+            );
+        }
+
+        info!(
+            bot_id = %bot_id,
+            droplet_id = droplet_id,
+            snapshot_id = ?snapshot_id,
+            "Hibernated bot"
+        );
+        Ok(())
+    }
+
+    /// Provision a new droplet for a hibernated bot, from its snapshot if it has
+    /// one. Runs from the job queue; a retry finds the bot provisioning or
+    /// deferred and carries on as [`Self::provision_bot`] would.
+    #[instrument(skip(self), fields(bot_id = %bot_id))]
+    pub async fn wake_bot(&self, bot_id: Uuid) -> Result<(), ProvisioningError> {
         let mut bot = self.bot_repo.get_by_id(bot_id).await?;
+        if bot.status != BotStatus::Hibernated {
+            return self.provision_bot(bot_id).await;
+        }
 
-        if let Some(droplet_id) = bot.droplet_id {
-            match self.do_client.destroy_droplet(droplet_id).await {
-                Ok(_) | Err(DigitalOceanError::NotFound(_)) => {
-                    self.droplet_repo.mark_destroyed(droplet_id).await?;
+        let config = self
+            .config_repo
+            .get_latest_for_bot(bot_id)
+            .await?
+            .ok_or_else(|| {
+                ProvisioningError::InvalidConfig("No config found for provisioning".to_string())
+            })?;
+
+        self.spawn_bot(&mut bot, &config, TransitionReason::Resume)
+            .await
+    }
+
+    /// Queue a hibernate job, recording whether this and later hibernations
+    /// snapshot the droplet, overriding the account tier. Neither happens if
+    /// the bot already has a job in progress, which fails with `Conflict`.
+    pub async fn queue_hibernate(
+        &self,
+        bot_id: Uuid,
+        snapshot: bool,
+    ) -> Result<Job, RepositoryError> {
+        let tx = self.unit_of_work.begin().await?;
+        tx.bots()
+            .update_snapshot_on_hibernate(bot_id, Some(snapshot))
+            .await?;
+        let job = Job::new(
+            JobKind::HibernateBot,
+            bot_id,
+            self.settings.job_max_attempts,
+        );
+        tx.jobs().enqueue(&job).await?;
+        tx.commit().await?;
+        info!(job_id = %job.id, "Queued hibernate job");
+        Ok(job)
+    }
+
+    async fn snapshots_on_hibernate(&self, bot: &Bot) -> Result<bool, ProvisioningError> {
+        if let Some(snapshot) = bot.snapshot_on_hibernate {
+            return Ok(snapshot);
+        }
+        let account = self.account_repo.get_by_id(bot.account_id).await?;
+        Ok(account.subscription_tier.snapshots_on_hibernate())
+    }
+
+    /// Power the droplet off and snapshot it, returning the snapshot's ID. If the
+    /// snapshot fails, a droplet that was running is powered back on.
+    async fn snapshot_droplet(&self, bot: &Bot, droplet_id: i64) -> Result<i64, ProvisioningError> {
+        // Named after the droplet, so a retried job finds its earlier snapshot.
+        let name = format!("openclaw-bot-{}-{}", bot.id, droplet_id);
+        let find = || async {
+            Ok::<_, DigitalOceanError>(
+                self.do_client
+                    .list_droplet_snapshots(droplet_id)
+                    .await?
+                    .into_iter()
+                    .find(|snapshot| snapshot.name == name),
+            )
+        };
+        if let Some(snapshot) = find().await? {
+            return Ok(snapshot.id);
+        }
+
+        let was_running =
+            self.do_client.get_droplet(droplet_id).await?.status != DropletStatus::Off;
+        if was_running {
+            self.power_off_droplet(droplet_id).await?;
+        }
+
+        let snapshot: Result<i64, DigitalOceanError> = async {
+            let action = self.do_client.snapshot_droplet(droplet_id, &name).await?;
+            self.do_client
+                .wait_for_action(&action, self.settings.snapshot_timeout)
+                .await?;
+            find().await?.map(|snapshot| snapshot.id).ok_or_else(|| {
+                DigitalOceanError::InvalidResponse(format!(
+                    "Snapshot {} of droplet {} is not listed",
+                    name, droplet_id
+                ))
+            })
+        }
+        .await;
+
+        if let Err(e) = &snapshot {
+            error!(
+                bot_id = %bot.id,
+                droplet_id = droplet_id,
+                error = %e,
+                "Failed to snapshot droplet"
+            );
+            if was_running && bot.status == BotStatus::Online {
+                if let Err(e) = self.do_client.power_on_droplet(droplet_id).await {
+                    warn!(
+                        bot_id = %bot.id,
+                        droplet_id = droplet_id,
+                        error = %e,
+                        "Failed to power droplet back on after snapshot failure"
+                    );
                 }
-                Err(e) => return Err(e.into()),
             }
         }
+        Ok(snapshot?)
+    }
 
-        // Get the latest config for redeployment
+    /// Stop booting the bot from `snapshot_id` and delete it.
+    async fn release_hibernation_snapshot(&self, bot_id: Uuid, snapshot_id: i64) {
+        // Cleared first: a bot must never point at a deleted snapshot.
+        if let Err(e) = self
+            .bot_repo
+            .update_hibernation_snapshot(bot_id, None)
+            .await
+        {
+            warn!(
+                bot_id = %bot_id,
+                snapshot_id = snapshot_id,
+                error = %e,
+                "Failed to clear hibernation snapshot; keeping it"
+            );
+            return;
+        }
+        self.delete_snapshot(bot_id, snapshot_id).await;
+    }
+
+    /// Best effort: a snapshot left behind only costs storage, so log it for
+    /// manual cleanup rather than fail the caller.
+    async fn delete_snapshot(&self, bot_id: Uuid, snapshot_id: i64) {
+        match self.do_client.delete_snapshot(snapshot_id).await {
+            Ok(()) => info!(bot_id = %bot_id, snapshot_id = snapshot_id, "Deleted snapshot"),
+            Err(e) => warn!(
+                bot_id = %bot_id,
+                snapshot_id = snapshot_id,
+                error = %e,
+                "Failed to delete snapshot; delete it manually"
+            ),
+        }
+    }
+
+    #[instrument(skip(self), fields(bot_id = %bot_id))]
+    pub async fn redeploy_bot(&self, bot_id: Uuid) -> Result<(), ProvisioningError> {
+        let mut bot = self.bot_repo.get_by_id(bot_id).await?;
+
+        // A retried job finds the bot provisioning with its old droplet gone.
+        let resumed = bot.status == BotStatus::Provisioning && bot.droplet_id.is_none();
+        if !resumed {
+            bot.check_transition(BotStatus::Provisioning, TransitionReason::Redeploy)?;
+        }
+
+        // Everything that can refuse the redeploy runs before the old droplet
+        // is touched, so a refusal leaves the bot as it was.
         let config = self
             .config_repo
             .get_latest_for_bot(bot_id)
@@ -1359,12 +2366,164 @@ export TOOLCHAIN_CARGO_CRATES={}
             .ok_or_else(|| {
                 ProvisioningError::InvalidConfig("No config found for redeployment".to_string())
             })?;
+        self.settings
+            .toolchains
+            .resolve(bot.toolchain_profile.as_deref())?;
+
+        if !resumed {
+            let tx = self.unit_of_work.begin().await?;
+            let operation = match bot.droplet_id {
+                Some(droplet_id) => {
+                    Some(self.queue_droplet_destroy(&*tx, bot_id, droplet_id).await?)
+                }
+                None => None,
+            };
+            tx.bots()
+                .transition_status(
+                    bot_id,
+                    bot.status,
+                    BotStatus::Provisioning,
+                    TransitionReason::Redeploy,
+                    None,
+                )
+                .await?;
+            tx.commit().await?;
+            bot.status = BotStatus::Provisioning;
+
+            if let (Some(operation), Some(droplet_id)) = (operation, bot.droplet_id) {
+                if let Err(e) = self.destroy_provider_droplet(&operation).await {
+                    warn!(
+                        bot_id = %bot_id,
+                        droplet_id = droplet_id,
+                        error = %e,
+                        "Failed to destroy old droplet; the outbox executor will retry"
+                    );
+                }
+            }
+            bot.droplet_id = None;
+        }
 
-        bot.droplet_id = None;
-        self.spawn_bot(&mut bot, &config).await?;
+        // A redeploy starts from the base image, not a hibernation snapshot.
+        if let Some(snapshot_id) = bot.hibernation_snapshot_id.take() {
+            self.release_hibernation_snapshot(bot_id, snapshot_id).await;
+        }
+
+        self.spawn_bot(&mut bot, &config, TransitionReason::Redeploy)
+            .await?;
 
         info!("Successfully redeployed bot {}", bot_id);
         Ok(())
     }
 
+    /// Digest of the user data the bot's next droplet would be created with,
+    /// as recorded on droplets.
+    pub(crate) async fn boot_user_data_digest(
+        &self,
+        bot: &Bot,
+    ) -> Result<String, ProvisioningError> {
+        let loader = self
+            .user_data
+            .render_loader(bot.id, &self.loader_vars(PREVIEW_REGISTRATION_TOKEN, bot))?;
+        let user_data = match loader {
+            Some(loader) => loader,
+            None => {
+                self.render_user_data(bot, PREVIEW_REGISTRATION_TOKEN)
+                    .await?
+            }
+        };
+        Ok(user_data_digest(&user_data))
+    }
+
+    /// Fail with `InvalidConfig` unless the bot's droplet can be rebuilt in
+    /// place. DigitalOcean's `rebuild` keeps the user data the droplet was
+    /// created with, so that must match what the bot would be given now. A
+    /// loader only changes with the control plane URL; full `cloud_config`
+    /// user data changes with every toolchain, customizer or template change.
+    /// Droplets created before digests were recorded never match.
+    pub async fn check_rebuild(&self, bot_id: Uuid) -> Result<i64, ProvisioningError> {
+        let bot = self.bot_repo.get_by_id(bot_id).await?;
+        let droplet_id = bot.droplet_id.ok_or_else(|| {
+            ProvisioningError::InvalidConfig(format!("Bot {} has no droplet to rebuild", bot_id))
+        })?;
+        let droplet = self.droplet_repo.get_by_id(droplet_id).await?;
+        let current = self.boot_user_data_digest(&bot).await?;
+        if droplet.user_data_digest.as_deref() != Some(current.as_str()) {
+            return Err(ProvisioningError::InvalidConfig(format!(
+                "Droplet {} was created with different user data than bot {} has now; redeploy with the recreate strategy",
+                droplet_id, bot_id
+            )));
+        }
+        Ok(droplet_id)
+    }
+
+    /// Redeploy in place: reinstall the bot's droplet from the base image with
+    /// DigitalOcean's `rebuild` action, keeping its ID and IP (and so any IP
+    /// whitelists). The droplet re-runs the user data it was created with, which
+    /// fetches the current bootstrap, registers with the bot's current token and
+    /// pulls the latest config.
+    /// Refused, leaving the bot as it was, when [`Self::check_rebuild`] fails.
+    ///
+    /// Runs from the job queue; a retry issues the rebuild again.
+    #[instrument(skip(self), fields(bot_id = %bot_id, droplet_id = Empty))]
+    pub async fn rebuild_bot(&self, bot_id: Uuid) -> Result<(), ProvisioningError> {
+        let droplet_id = self.check_rebuild(bot_id).await?;
+        Span::current().record("droplet_id", droplet_id);
+        let bot = self.bot_repo.get_by_id(bot_id).await?;
+
+        let tx = self.unit_of_work.begin().await?;
+        // A job that lost its worker mid-rebuild finds the bot already provisioning.
+        if bot.status != BotStatus::Provisioning {
+            bot.check_transition(BotStatus::Provisioning, TransitionReason::Redeploy)?;
+            tx.bots()
+                .transition_status(
+                    bot_id,
+                    bot.status,
+                    BotStatus::Provisioning,
+                    TransitionReason::Redeploy,
+                    None,
+                )
+                .await?;
+        }
+        // The registration watchdog then waits for the rebuilt droplet.
+        tx.bots().reset_registration(bot_id).await?;
+        tx.commit().await?;
+
+        match self
+            .do_client
+            .rebuild_droplet(droplet_id, &self.settings.openclaw_image)
+            .await
+        {
+            Ok(action) => {
+                info!(
+                    bot_id = %bot_id,
+                    droplet_id = droplet_id,
+                    action_id = action.id,
+                    "Rebuilding droplet for bot"
+                );
+                Ok(())
+            }
+            Err(e) => {
+                error!(
+                    bot_id = %bot_id,
+                    droplet_id = droplet_id,
+                    error = %e,
+                    "Failed to rebuild droplet for bot"
+                );
+                let e = ProvisioningError::from(e);
+                // Transient failures keep the bot provisioning for the job's retry.
+                if !e.is_retryable() {
+                    self.bot_repo
+                        .transition_status(
+                            bot_id,
+                            BotStatus::Provisioning,
+                            BotStatus::Error,
+                            TransitionReason::ProvisionFailed,
+                            Some(&format!("Could not rebuild droplet: {}", e)),
+                        )
+                        .await?;
+                }
+                Err(e)
+            }
+        }
+    }
 }
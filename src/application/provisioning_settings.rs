@@ -0,0 +1,411 @@
+//! Typed, validated settings for [`ProvisioningService`](super::ProvisioningService).
+//!
+//! Build them from an [`AppConfig`] with [`ProvisioningSettings::from_config`], or
+//! start from the defaults with [`ProvisioningSettings::builder`] when embedding.
+//! Both validate, so a service never starts with a settings value that would only
+//! fail once the first droplet boots.
+
+use crate::domain::{
+    toolchain_tokens, CustomizerAllowList, CustomizerSettings, ToolchainError, ToolchainProfile,
+    ToolchainProfiles, DEFAULT_TOOLCHAIN_PROFILE,
+};
+use crate::infrastructure::AppConfig;
+use reqwest::Url;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use thiserror::Error;
+
+const DEFAULT_OPENCLAW_IMAGE: &str = "ubuntu-22-04-x64";
+const DEFAULT_CUSTOMIZER_REPO_URL: &str = "https://github.com/janebot2026/janebot-cli.git";
+/// Pinned for reproducibility.
+const DEFAULT_CUSTOMIZER_REF: &str = "4b170b4aa31f79bda84f7383b3992ca8681d06d3";
+const DEFAULT_CUSTOMIZER_WORKSPACE_DIR: &str = "/opt/openclaw/workspace";
+const DEFAULT_OPERATION_LEASE: Duration = Duration::from_secs(600);
+const DEFAULT_DROPLET_ACTION_TIMEOUT: Duration = Duration::from_secs(60);
+const DEFAULT_SNAPSHOT_TIMEOUT: Duration = Duration::from_secs(480);
+const DEFAULT_JOB_MAX_ATTEMPTS: i32 = 5;
+
+/// URL schemes `git clone` on the droplet may use for a customizer repository.
+const REPO_URL_SCHEMES: [&str; 4] = ["https", "http", "ssh", "git"];
+
+#[derive(Error, Debug)]
+pub enum SettingsError {
+    #[error("{0} must not be empty")]
+    Empty(&'static str),
+    #[error("{field} is not a valid {schemes} URL: {url:?}")]
+    InvalidUrl {
+        field: &'static str,
+        schemes: String,
+        url: String,
+    },
+    #[error(transparent)]
+    Toolchain(#[from] ToolchainError),
+}
+
+/// What droplets install: the `default` profile built from the `toolchain_*`
+/// config values, plus the named profiles bots may select instead.
+#[derive(Debug, Clone)]
+pub struct ToolchainSettings {
+    /// Registered as [`DEFAULT_TOOLCHAIN_PROFILE`].
+    pub base: ToolchainProfile,
+    /// Named profiles; these replace built-in profiles of the same name.
+    pub profiles: BTreeMap<String, ToolchainProfile>,
+    /// Profile used by bots that don't select one.
+    pub default_profile: String,
+}
+
+impl Default for ToolchainSettings {
+    /// Node 20, `pnpm` and stable Rust, matching the config defaults.
+    fn default() -> Self {
+        Self {
+            base: ToolchainProfile::builtin()["full-rust"].clone(),
+            profiles: BTreeMap::new(),
+            default_profile: DEFAULT_TOOLCHAIN_PROFILE.to_string(),
+        }
+    }
+}
+
+impl ToolchainSettings {
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            base: ToolchainProfile {
+                node_major: config.toolchain_node_major,
+                install_pnpm: config.toolchain_install_pnpm,
+                pnpm_version: config.toolchain_pnpm_version.clone(),
+                install_rust: config.toolchain_install_rust,
+                rust_toolchain: config.toolchain_rust_toolchain.clone(),
+                apt_packages: toolchain_tokens(&config.toolchain_extra_apt_packages),
+                npm_packages: toolchain_tokens(&config.toolchain_global_npm_packages),
+                cargo_crates: toolchain_tokens(&config.toolchain_cargo_crates),
+            },
+            profiles: config
+                .toolchain_profiles
+                .iter()
+                .map(|(name, profile)| (name.clone(), profile.clone()))
+                .collect(),
+            default_profile: config.toolchain_default_profile.clone(),
+        }
+    }
+
+    /// The built-in profiles, `base` and `profiles`, all validated.
+    pub fn profiles(&self) -> Result<ToolchainProfiles, ToolchainError> {
+        let mut profiles = ToolchainProfile::builtin();
+        profiles.insert(DEFAULT_TOOLCHAIN_PROFILE.to_string(), self.base.clone());
+        profiles.extend(self.profiles.clone());
+        ToolchainProfiles::new(&self.default_profile, profiles)
+    }
+}
+
+/// Everything [`ProvisioningService`](super::ProvisioningService) needs besides its
+/// repositories and clients.
+#[derive(Debug, Clone)]
+pub struct ProvisioningSettings {
+    pub(crate) openclaw_image: String,
+    pub(crate) control_plane_url: String,
+    /// Service-wide customizer defaults; accounts and bots may override them.
+    pub(crate) customizer: CustomizerSettings,
+    pub(crate) customizer_workspace_dir: String,
+    pub(crate) customizer_allow: CustomizerAllowList,
+    pub(crate) toolchains: ToolchainProfiles,
+    /// How long a recorded DigitalOcean call belongs to its writer (see
+    /// [`ProviderOperation`](crate::domain::ProviderOperation)).
+    pub(crate) operation_lease: Duration,
+    /// How long pause and resume wait for a droplet power action.
+    pub(crate) droplet_action_timeout: Duration,
+    /// How long hibernating waits for the droplet snapshot.
+    pub(crate) snapshot_timeout: Duration,
+    /// Attempts for the spawn job queued with each new bot.
+    pub(crate) job_max_attempts: i32,
+}
+
+impl ProvisioningSettings {
+    /// Defaults for everything except the control plane URL droplets call back to.
+    pub fn builder(control_plane_url: impl Into<String>) -> ProvisioningSettingsBuilder {
+        ProvisioningSettingsBuilder {
+            openclaw_image: DEFAULT_OPENCLAW_IMAGE.to_string(),
+            control_plane_url: control_plane_url.into(),
+            customizer: CustomizerSettings {
+                repo_url: DEFAULT_CUSTOMIZER_REPO_URL.to_string(),
+                git_ref: DEFAULT_CUSTOMIZER_REF.to_string(),
+                agent_name: "Jane".to_string(),
+                owner_name: "Cedros".to_string(),
+                skip_qmd: true,
+                skip_cron: true,
+                skip_git: true,
+                skip_heartbeat: true,
+            },
+            customizer_workspace_dir: DEFAULT_CUSTOMIZER_WORKSPACE_DIR.to_string(),
+            customizer_allow: CustomizerAllowList::default(),
+            toolchain: ToolchainSettings::default(),
+            operation_lease: DEFAULT_OPERATION_LEASE,
+            droplet_action_timeout: DEFAULT_DROPLET_ACTION_TIMEOUT,
+            snapshot_timeout: DEFAULT_SNAPSHOT_TIMEOUT,
+            job_max_attempts: DEFAULT_JOB_MAX_ATTEMPTS,
+        }
+    }
+
+    pub fn from_config(config: &AppConfig) -> Result<Self, SettingsError> {
+        Self::builder(config.control_plane_url.clone())
+            .openclaw_image(config.openclaw_image.clone())
+            .customizer(CustomizerSettings {
+                repo_url: config.customizer_repo_url.clone(),
+                git_ref: config.customizer_ref.clone(),
+                agent_name: config.customizer_agent_name.clone(),
+                owner_name: config.customizer_owner_name.clone(),
+                skip_qmd: config.customizer_skip_qmd,
+                skip_cron: config.customizer_skip_cron,
+                skip_git: config.customizer_skip_git,
+                skip_heartbeat: config.customizer_skip_heartbeat,
+            })
+            .customizer_workspace_dir(config.customizer_workspace_dir.clone())
+            .customizer_allow_list(CustomizerAllowList {
+                repo_urls: comma_list(&config.customizer_allowed_repo_urls),
+                refs: comma_list(&config.customizer_allowed_refs),
+            })
+            .toolchain(ToolchainSettings::from_config(config))
+            .operation_lease(Duration::from_secs(config.outbox_lease_secs))
+            .droplet_action_timeout(Duration::from_secs(config.droplet_action_timeout_secs))
+            .snapshot_timeout(Duration::from_secs(config.droplet_snapshot_timeout_secs))
+            .job_max_attempts(config.job_max_attempts)
+            .build()
+    }
+
+    pub fn control_plane_url(&self) -> &str {
+        &self.control_plane_url
+    }
+
+    pub fn customizer(&self) -> &CustomizerSettings {
+        &self.customizer
+    }
+
+    pub fn toolchains(&self) -> &ToolchainProfiles {
+        &self.toolchains
+    }
+
+    pub fn operation_lease(&self) -> Duration {
+        self.operation_lease
+    }
+
+    pub fn droplet_action_timeout(&self) -> Duration {
+        self.droplet_action_timeout
+    }
+
+    pub fn snapshot_timeout(&self) -> Duration {
+        self.snapshot_timeout
+    }
+
+    pub fn job_max_attempts(&self) -> i32 {
+        self.job_max_attempts
+    }
+}
+
+/// Builder returned by [`ProvisioningSettings::builder`].
+#[derive(Debug, Clone)]
+pub struct ProvisioningSettingsBuilder {
+    openclaw_image: String,
+    control_plane_url: String,
+    customizer: CustomizerSettings,
+    customizer_workspace_dir: String,
+    customizer_allow: CustomizerAllowList,
+    toolchain: ToolchainSettings,
+    operation_lease: Duration,
+    droplet_action_timeout: Duration,
+    snapshot_timeout: Duration,
+    job_max_attempts: i32,
+}
+
+impl ProvisioningSettingsBuilder {
+    /// DigitalOcean image slug for new droplets.
+    pub fn openclaw_image(mut self, image: impl Into<String>) -> Self {
+        self.openclaw_image = image.into();
+        self
+    }
+
+    /// janebot-cli defaults for bots whose account doesn't override them.
+    pub fn customizer(mut self, customizer: CustomizerSettings) -> Self {
+        self.customizer = customizer;
+        self
+    }
+
+    pub fn customizer_workspace_dir(mut self, dir: impl Into<String>) -> Self {
+        self.customizer_workspace_dir = dir.into();
+        self
+    }
+
+    /// Repositories and refs accounts and bots may select besides the defaults.
+    pub fn customizer_allow_list(mut self, allow: CustomizerAllowList) -> Self {
+        self.customizer_allow = allow;
+        self
+    }
+
+    pub fn toolchain(mut self, toolchain: ToolchainSettings) -> Self {
+        self.toolchain = toolchain;
+        self
+    }
+
+    /// How long the request or job that records a DigitalOcean call has to make
+    /// it before the outbox executor does. Longer than any single DO call.
+    pub fn operation_lease(mut self, lease: Duration) -> Self {
+        self.operation_lease = lease;
+        self
+    }
+
+    /// How long pause waits for a graceful shutdown before powering the droplet
+    /// off, and then for the power-off or a resume's power-on to finish.
+    pub fn droplet_action_timeout(mut self, timeout: Duration) -> Self {
+        self.droplet_action_timeout = timeout;
+        self
+    }
+
+    /// How long hibernating waits for the droplet snapshot. Hibernation runs as
+    /// a job, so keep this below the job lease.
+    pub fn snapshot_timeout(mut self, timeout: Duration) -> Self {
+        self.snapshot_timeout = timeout;
+        self
+    }
+
+    /// Attempts for the spawn job [`create_bot`](super::ProvisioningService::create_bot)
+    /// queues with each bot; match the job queue's `job_max_attempts`.
+    pub fn job_max_attempts(mut self, attempts: i32) -> Self {
+        self.job_max_attempts = attempts;
+        self
+    }
+
+    pub fn build(self) -> Result<ProvisioningSettings, SettingsError> {
+        if self.control_plane_url.trim().is_empty() {
+            return Err(SettingsError::Empty("control_plane_url"));
+        }
+        check_url(
+            "control_plane_url",
+            &self.control_plane_url,
+            &["https", "http"],
+        )?;
+        if self.openclaw_image.trim().is_empty() {
+            return Err(SettingsError::Empty("openclaw_image"));
+        }
+        check_url(
+            "customizer_repo_url",
+            &self.customizer.repo_url,
+            &REPO_URL_SCHEMES,
+        )?;
+        for repo_url in &self.customizer_allow.repo_urls {
+            check_url("customizer_allowed_repo_urls", repo_url, &REPO_URL_SCHEMES)?;
+        }
+        if self.customizer_workspace_dir.trim().is_empty() {
+            return Err(SettingsError::Empty("customizer_workspace_dir"));
+        }
+        let toolchains = self.toolchain.profiles()?;
+
+        Ok(ProvisioningSettings {
+            openclaw_image: self.openclaw_image,
+            control_plane_url: self.control_plane_url,
+            customizer: self.customizer,
+            customizer_workspace_dir: self.customizer_workspace_dir,
+            customizer_allow: self.customizer_allow,
+            toolchains,
+            operation_lease: self.operation_lease,
+            droplet_action_timeout: self.droplet_action_timeout,
+            snapshot_timeout: self.snapshot_timeout,
+            job_max_attempts: self.job_max_attempts,
+        })
+    }
+}
+
+/// An absolute URL with a host and one of `schemes`.
+fn check_url(field: &'static str, url: &str, schemes: &[&str]) -> Result<(), SettingsError> {
+    match Url::parse(url) {
+        Ok(parsed) if schemes.contains(&parsed.scheme()) && parsed.has_host() => Ok(()),
+        _ => Err(SettingsError::InvalidUrl {
+            field,
+            schemes: schemes.join("/"),
+            url: url.to_string(),
+        }),
+    }
+}
+
+/// Split a comma-separated config value, dropping blanks.
+fn comma_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_defaults_are_valid() {
+        let settings = ProvisioningSettings::builder("https://control.example")
+            .build()
+            .unwrap();
+        assert_eq!(settings.control_plane_url(), "https://control.example");
+        assert_eq!(settings.customizer().agent_name, "Jane");
+
+        let (name, toolchain) = settings.toolchains().resolve(None).unwrap();
+        assert_eq!(name, DEFAULT_TOOLCHAIN_PROFILE);
+        assert!(toolchain.install_pnpm && toolchain.install_rust);
+    }
+
+    #[test]
+    fn urls_are_checked() {
+        assert!(matches!(
+            ProvisioningSettings::builder("  ").build(),
+            Err(SettingsError::Empty("control_plane_url"))
+        ));
+        for bad in ["api.example.com", "ftp://api.example.com", "https://"] {
+            assert!(
+                matches!(
+                    ProvisioningSettings::builder(bad).build(),
+                    Err(SettingsError::InvalidUrl {
+                        field: "control_plane_url",
+                        ..
+                    })
+                ),
+                "{bad:?} should be rejected"
+            );
+        }
+
+        let builder = ProvisioningSettings::builder("http://localhost:8080");
+        let mut customizer = builder.customizer.clone();
+        customizer.repo_url = "ssh://git@github.com/acme/janebot-cli.git".to_string();
+        assert!(builder.clone().customizer(customizer).build().is_ok());
+
+        let err = builder
+            .customizer_allow_list(CustomizerAllowList {
+                repo_urls: vec!["file:///etc".to_string()],
+                refs: Vec::new(),
+            })
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            SettingsError::InvalidUrl {
+                field: "customizer_allowed_repo_urls",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn node_major_must_be_in_range() {
+        let toolchain = ToolchainSettings {
+            base: ToolchainProfile {
+                node_major: 16,
+                ..ToolchainSettings::default().base
+            },
+            ..ToolchainSettings::default()
+        };
+        let err = ProvisioningSettings::builder("https://control.example")
+            .toolchain(toolchain)
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            SettingsError::Toolchain(ToolchainError::UnsupportedNodeMajor { node_major: 16, .. })
+        ));
+    }
+}
@@ -0,0 +1,775 @@
+//! Droplet user-data rendering.
+//!
+//! User data is a header of typed, named variables followed by the embedded
+//! `openclaw-bootstrap.sh` and any operator overlays. Each [`UserDataValue`]
+//! variant has its own escaping, so callers never quote values by hand, and the
+//! result is checked against DigitalOcean's size limit before it is sent.
+//!
+//! The same pieces can instead be rendered as a `#cloud-config` document (see
+//! [`UserDataFormat`]), which keeps the variables in root-only files.
+
+use crate::domain::{Persona, SubscriptionTier};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use strum::{Display, EnumString};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// DigitalOcean rejects droplets whose user data is larger than this.
+pub const MAX_USER_DATA_BYTES: usize = 64 * 1024;
+
+const BOOTSTRAP_SCRIPT: &str = include_str!("../../scripts/openclaw-bootstrap.sh");
+
+/// Packages the bootstrap script installs before anything else; cloud-init
+/// installs them up front in `cloud_config` mode.
+const BASE_PACKAGES: &[&str] = &[
+    "curl",
+    "wget",
+    "git",
+    "ca-certificates",
+    "gnupg",
+    "lsb-release",
+    "software-properties-common",
+    "apt-transport-https",
+    "jq",
+];
+
+/// Bootstrap variables the bot service itself needs at runtime.
+const SERVICE_ENV_VARS: &[&str] = &["CONTROL_PLANE_URL", "BOT_ID", "REGISTRATION_TOKEN"];
+
+const BOOTSTRAP_ENV_PATH: &str = "/etc/openclaw/bootstrap.env";
+const SERVICE_ENV_PATH: &str = "/etc/openclaw/bot.env";
+const BOOTSTRAP_SCRIPT_PATH: &str = "/usr/local/sbin/openclaw-bootstrap.sh";
+
+/// Body of [`UserDataRenderer::render_loader`], after the exported variables.
+/// Retries until the control plane answers, as registration would.
+const LOADER_SCRIPT: &str = r#"
+# Fetch and run the bootstrap the control plane renders for this bot now.
+user_data="$(mktemp)"
+until curl -fsS -H "Authorization: Bearer ${REGISTRATION_TOKEN}" \
+    "${CONTROL_PLANE_URL}/bot/${BOT_ID}/user_data" -o "$user_data"; do
+  sleep 10
+done
+exec bash "$user_data"
+"#;
+
+/// `openclaw-bot.service` as the bootstrap script writes it, except that the
+/// credentials come from a root-only `EnvironmentFile` instead of
+/// `Environment=` lines in a world-readable unit.
+const SERVICE_UNIT: &str = "\
+[Unit]
+Description=OpenClaw Bot
+After=docker.service network.target
+Wants=docker.service
+
+[Service]
+Type=simple
+User=openclaw
+Group=openclaw
+WorkingDirectory=/opt/openclaw
+EnvironmentFile=/etc/openclaw/bot.env
+ExecStart=/opt/openclaw/run.sh
+Restart=always
+RestartSec=10
+StandardOutput=append:/var/log/openclaw-bot.log
+StandardError=append:/var/log/openclaw-bot.log
+
+[Install]
+WantedBy=multi-user.target
+";
+
+/// How user data is delivered to the droplet.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Display, EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum UserDataFormat {
+    /// A bash script with the variables exported at the top.
+    #[default]
+    Script,
+    /// A `#cloud-config` document. Packages, the `openclaw` user, the bot runner
+    /// and its systemd unit are declared, and the variables are written to
+    /// root-only env files that the bootstrap script and the unit read.
+    CloudConfig,
+}
+
+#[derive(Error, Debug)]
+pub enum UserDataError {
+    #[error("Invalid user-data variable name: {0}")]
+    InvalidName(String),
+    #[error("User-data variable {0} is set twice")]
+    DuplicateName(String),
+    #[error("Invalid value for user-data variable {name}: {reason}")]
+    InvalidValue { name: String, reason: &'static str },
+    #[error("Could not read user-data overlay {path}: {source}")]
+    Overlay {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("User-data overlay {0} does not match a known persona or tier")]
+    UnknownOverlay(PathBuf),
+    #[error("Rendered user data is {size} bytes, over the {limit} byte limit")]
+    TooLarge { size: usize, limit: usize },
+}
+
+/// A user-data variable value. The variant decides how it is escaped.
+#[derive(Clone)]
+pub enum UserDataValue {
+    /// Arbitrary text, single-quoted.
+    Text(String),
+    /// A credential. Restricted to token characters and single-quoted; never
+    /// included in error messages.
+    Secret(String),
+    Flag(bool),
+    Number(u32),
+    Id(Uuid),
+}
+
+impl UserDataValue {
+    fn render(&self, name: &str) -> Result<String, UserDataError> {
+        let invalid = |reason| UserDataError::InvalidValue {
+            name: name.to_string(),
+            reason,
+        };
+        match self {
+            UserDataValue::Text(text) => {
+                if text.contains('\0') {
+                    return Err(invalid("contains a NUL byte"));
+                }
+                Ok(shell_escape(text))
+            }
+            UserDataValue::Secret(secret) => {
+                let valid = !secret.is_empty()
+                    && secret
+                        .bytes()
+                        .all(|b| b.is_ascii_alphanumeric() || b"+/=_.-".contains(&b));
+                if !valid {
+                    return Err(invalid("is empty or contains non-token characters"));
+                }
+                Ok(shell_escape(secret))
+            }
+            UserDataValue::Flag(flag) => Ok(format!("\"{flag}\"")),
+            UserDataValue::Number(number) => Ok(format!("\"{number}\"")),
+            UserDataValue::Id(id) => Ok(shell_escape(&id.to_string())),
+        }
+    }
+}
+
+/// Wrap `value` in single quotes for POSIX shells.
+pub(crate) fn shell_escape(value: &str) -> String {
+    let escaped = value.replace('\'', "'\"'\"'");
+    format!("'{escaped}'")
+}
+
+struct VarSection {
+    comment: Option<String>,
+    vars: Vec<(String, UserDataValue)>,
+}
+
+/// Named variables exported at the top of the user data, in groups.
+#[derive(Default)]
+pub struct UserDataVars {
+    sections: Vec<VarSection>,
+}
+
+impl UserDataVars {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a new group of variables, rendered under `# comment`.
+    pub fn section(mut self, comment: &str) -> Self {
+        self.sections.push(VarSection {
+            comment: Some(comment.to_string()),
+            vars: Vec::new(),
+        });
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&UserDataValue> {
+        self.sections
+            .iter()
+            .flat_map(|section| section.vars.iter())
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value)
+    }
+
+    pub fn set(mut self, name: &str, value: UserDataValue) -> Self {
+        if self.sections.is_empty() {
+            self.sections.push(VarSection {
+                comment: None,
+                vars: Vec::new(),
+            });
+        }
+        if let Some(section) = self.sections.last_mut() {
+            section.vars.push((name.to_string(), value));
+        }
+        self
+    }
+
+    /// Render as `NAME=value` lines, prefixed with `export ` when `export` is set.
+    fn render(&self, out: &mut String, export: bool) -> Result<(), UserDataError> {
+        let mut seen = std::collections::HashSet::new();
+        for section in &self.sections {
+            out.push('\n');
+            if let Some(comment) = &section.comment {
+                out.push_str(&format!("# {comment}\n"));
+            }
+            for (name, value) in &section.vars {
+                if !is_valid_name(name) {
+                    return Err(UserDataError::InvalidName(name.clone()));
+                }
+                if !seen.insert(name.as_str()) {
+                    return Err(UserDataError::DuplicateName(name.clone()));
+                }
+                if export {
+                    out.push_str("export ");
+                }
+                out.push_str(&format!("{name}={}\n", value.render(name)?));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let mut bytes = name.bytes();
+    matches!(bytes.next(), Some(b'A'..=b'Z' | b'_'))
+        && bytes.all(|b| matches!(b, b'A'..=b'Z' | b'0'..=b'9' | b'_'))
+}
+
+struct Snippet {
+    /// Path relative to the overlay directory, e.g. `persona/beginner.sh`.
+    name: String,
+    body: String,
+}
+
+/// Operator-supplied shell snippets appended after the bootstrap script.
+///
+/// Loaded from a directory laid out as:
+/// - `persona/<persona>.sh` — for bots with that persona (e.g. `persona/quant_lite.sh`)
+/// - `tier/<tier>.sh` — for bots whose account has that tier (e.g. `tier/pro.sh`)
+/// - `custom/*.sh` — for every bot, in file-name order
+#[derive(Default)]
+pub struct UserDataOverlays {
+    by_tier: Vec<(SubscriptionTier, Snippet)>,
+    by_persona: Vec<(Persona, Snippet)>,
+    custom: Vec<Snippet>,
+}
+
+impl UserDataOverlays {
+    pub fn load(dir: impl AsRef<Path>) -> Result<Self, UserDataError> {
+        let dir = dir.as_ref();
+        let mut overlays = Self::default();
+
+        for (path, snippet) in read_snippets(dir, "tier")? {
+            let tier = parse_stem::<SubscriptionTier>(&path)?;
+            overlays.by_tier.push((tier, snippet));
+        }
+        for (path, snippet) in read_snippets(dir, "persona")? {
+            let persona = parse_stem::<Persona>(&path)?;
+            overlays.by_persona.push((persona, snippet));
+        }
+        overlays.custom = read_snippets(dir, "custom")?
+            .into_iter()
+            .map(|(_, snippet)| snippet)
+            .collect();
+
+        Ok(overlays)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.by_tier.is_empty() && self.by_persona.is_empty() && self.custom.is_empty()
+    }
+
+    fn matching<'a>(
+        &'a self,
+        persona: &'a Persona,
+        tier: Option<&'a SubscriptionTier>,
+    ) -> impl Iterator<Item = &'a Snippet> + 'a {
+        let tier_snippets = self
+            .by_tier
+            .iter()
+            .filter(move |(t, _)| Some(t) == tier)
+            .map(|(_, s)| s);
+        let persona_snippets = self
+            .by_persona
+            .iter()
+            .filter(move |(p, _)| p == persona)
+            .map(|(_, s)| s);
+        tier_snippets
+            .chain(persona_snippets)
+            .chain(self.custom.iter())
+    }
+}
+
+/// `*.sh` files in `dir/kind`, sorted by name; a missing directory has none.
+fn read_snippets(dir: &Path, kind: &str) -> Result<Vec<(PathBuf, Snippet)>, UserDataError> {
+    let kind_dir = dir.join(kind);
+    if !kind_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let io_err = |path: &Path| {
+        let path = path.to_path_buf();
+        move |source| UserDataError::Overlay { path, source }
+    };
+
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(&kind_dir).map_err(io_err(&kind_dir))? {
+        let path = entry.map_err(io_err(&kind_dir))?.path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "sh") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let body = fs::read_to_string(&path).map_err(io_err(&path))?;
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+            let name = format!("{kind}/{file_name}");
+            Ok((path, Snippet { name, body }))
+        })
+        .collect()
+}
+
+fn parse_stem<T: FromStr>(path: &Path) -> Result<T, UserDataError> {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .and_then(|stem| T::from_str(stem).ok())
+        .ok_or_else(|| UserDataError::UnknownOverlay(path.to_path_buf()))
+}
+
+/// Renders droplet user data from variables, the bootstrap script and overlays.
+#[derive(Default)]
+pub struct UserDataRenderer {
+    overlays: UserDataOverlays,
+    format: UserDataFormat,
+}
+
+impl UserDataRenderer {
+    pub fn new(overlays: UserDataOverlays) -> Self {
+        Self::default().with_overlays(overlays)
+    }
+
+    pub fn with_overlays(mut self, overlays: UserDataOverlays) -> Self {
+        self.overlays = overlays;
+        self
+    }
+
+    pub fn with_format(mut self, format: UserDataFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Whether rendering depends on the account tier, so callers can skip the lookup.
+    pub fn needs_tier(&self) -> bool {
+        !self.overlays.by_tier.is_empty()
+    }
+
+    pub fn render(
+        &self,
+        bot_id: Uuid,
+        vars: &UserDataVars,
+        persona: &Persona,
+        tier: Option<&SubscriptionTier>,
+    ) -> Result<String, UserDataError> {
+        let out = match self.format {
+            UserDataFormat::Script => {
+                let mut out = script_header(bot_id);
+                vars.render(&mut out, true)?;
+                self.push_bootstrap(&mut out, persona, tier);
+                out
+            }
+            UserDataFormat::CloudConfig => self.render_cloud_config(bot_id, vars, persona, tier)?,
+        };
+
+        if out.len() > MAX_USER_DATA_BYTES {
+            return Err(UserDataError::TooLarge {
+                size: out.len(),
+                limit: MAX_USER_DATA_BYTES,
+            });
+        }
+        Ok(out)
+    }
+
+    /// In `script` format, a few lines that fetch the full user data from
+    /// `GET /bot/{id}/user_data` at boot. A droplet created with it runs the
+    /// current bootstrap even when DigitalOcean rebuilds it with its original
+    /// user data. `None` in `cloud_config` format, whose document cloud-init
+    /// has to be given up front.
+    ///
+    /// `vars` are exported for the fetch: `CONTROL_PLANE_URL`, `BOT_ID` and
+    /// `REGISTRATION_TOKEN`.
+    pub fn render_loader(
+        &self,
+        bot_id: Uuid,
+        vars: &UserDataVars,
+    ) -> Result<Option<String>, UserDataError> {
+        if self.format != UserDataFormat::Script {
+            return Ok(None);
+        }
+        let mut out = script_header(bot_id);
+        vars.render(&mut out, true)?;
+        out.push_str(LOADER_SCRIPT);
+        Ok(Some(out))
+    }
+
+    /// Append the embedded bootstrap script and the overlays that apply.
+    fn push_bootstrap(&self, out: &mut String, persona: &Persona, tier: Option<&SubscriptionTier>) {
+        out.push_str("\n# Start of embedded bootstrap script\n");
+        out.push_str(BOOTSTRAP_SCRIPT);
+
+        if !self.overlays.is_empty() {
+            for snippet in self.overlays.matching(persona, tier) {
+                if !out.ends_with('\n') {
+                    out.push('\n');
+                }
+                out.push_str(&format!("\n# Overlay: {}\n", snippet.name));
+                out.push_str(&snippet.body);
+            }
+        }
+        if !out.ends_with('\n') {
+            out.push('\n');
+        }
+    }
+
+    fn render_cloud_config(
+        &self,
+        bot_id: Uuid,
+        vars: &UserDataVars,
+        persona: &Persona,
+        tier: Option<&SubscriptionTier>,
+    ) -> Result<String, UserDataError> {
+        let mut bootstrap_env = format!("# OpenClaw bootstrap settings for Bot {bot_id}\n");
+        vars.render(&mut bootstrap_env, false)?;
+        bootstrap_env.push_str(&format!(
+            "\n# Steps already handled by cloud-init\nUSER_DATA_FORMAT='{}'\n",
+            UserDataFormat::CloudConfig
+        ));
+
+        let mut service_env = format!("# OpenClaw bot service environment for Bot {bot_id}\n");
+        for name in SERVICE_ENV_VARS {
+            if let Some(value) = vars.get(name) {
+                service_env.push_str(&format!("{name}={}\n", value.render(name)?));
+            }
+        }
+
+        let mut bootstrap = script_header(bot_id);
+        bootstrap.push_str(&format!(
+            "\n# Settings are read from a root-only file rather than exported.\n. {BOOTSTRAP_ENV_PATH}\n"
+        ));
+        self.push_bootstrap(&mut bootstrap, persona, tier);
+
+        let mut out = format!(
+            "#cloud-config\n\
+             # OpenClaw Bot Bootstrap for Bot {bot_id}\n\
+             package_update: true\n\
+             package_upgrade: true\n\
+             packages:\n"
+        );
+        for package in BASE_PACKAGES {
+            out.push_str(&format!("  - {package}\n"));
+        }
+        out.push_str(
+            "users:\n  \
+               - default\n  \
+               - name: openclaw\n    \
+                 shell: /bin/bash\n    \
+                 lock_passwd: true\n",
+        );
+        out.push_str("write_files:\n");
+        push_write_file(&mut out, BOOTSTRAP_ENV_PATH, "0600", &bootstrap_env);
+        push_write_file(&mut out, SERVICE_ENV_PATH, "0600", &service_env);
+        push_write_file(&mut out, "/opt/openclaw/run.sh", "0755", runner_script());
+        push_write_file(
+            &mut out,
+            "/etc/systemd/system/openclaw-bot.service",
+            "0644",
+            SERVICE_UNIT,
+        );
+        push_write_file(&mut out, BOOTSTRAP_SCRIPT_PATH, "0700", &bootstrap);
+        out.push_str(&format!("runcmd:\n  - [bash, {BOOTSTRAP_SCRIPT_PATH}]\n"));
+        Ok(out)
+    }
+}
+
+fn script_header(bot_id: Uuid) -> String {
+    format!(
+        "#!/bin/bash\n\
+         # OpenClaw Bot Bootstrap for Bot {bot_id}\n\
+         set -e\n\
+         \n\
+         # NOTE: Do not enable `set -x` (xtrace). This user-data includes secrets\n\
+         # (registration token) and xtrace would leak them into cloud-init logs.\n"
+    )
+}
+
+/// The bot runner, taken from the `run.sh` heredoc in the bootstrap script so
+/// both formats install the same file.
+fn runner_script() -> &'static str {
+    const START: &str = "cat > /opt/openclaw/run.sh << 'EOFSCRIPT'\n";
+    const END: &str = "\nEOFSCRIPT\n";
+    BOOTSTRAP_SCRIPT
+        .split_once(START)
+        .and_then(|(_, rest)| rest.find(END).map(|end| &rest[..=end]))
+        .expect("bootstrap script embeds run.sh")
+}
+
+/// Append a root-owned `write_files` entry. Content goes in a literal block
+/// unless it has characters YAML can't carry there, in which case it is base64.
+fn push_write_file(out: &mut String, path: &str, permissions: &str, content: &str) {
+    out.push_str(&format!(
+        "  - path: {path}\n    owner: root:root\n    permissions: '{permissions}'\n"
+    ));
+    let literal_ok = content.ends_with('\n')
+        && !content.starts_with([' ', '\t', '\n'])
+        && content
+            .chars()
+            .all(|c| c == '\n' || c == '\t' || !c.is_control());
+    if literal_ok {
+        out.push_str("    content: |\n");
+        for line in content.lines() {
+            if !line.is_empty() {
+                out.push_str("      ");
+                out.push_str(line);
+            }
+            out.push('\n');
+        }
+    } else {
+        out.push_str(&format!(
+            "    encoding: b64\n    content: {}\n",
+            BASE64.encode(content)
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> UserDataVars {
+        UserDataVars::new()
+            .set(
+                "REGISTRATION_TOKEN",
+                UserDataValue::Secret("abc+/=".to_string()),
+            )
+            .set("BOT_ID", UserDataValue::Id(Uuid::nil()))
+            .set(
+                "CONTROL_PLANE_URL",
+                UserDataValue::Text("https://control.example".to_string()),
+            )
+            .section("Workspace")
+            .set("AGENT_NAME", UserDataValue::Text("Agent'X".to_string()))
+            .set("SKIP_GIT", UserDataValue::Flag(true))
+            .set("NODE_MAJOR", UserDataValue::Number(20))
+    }
+
+    #[test]
+    fn f003_shell_escape_wraps_and_escapes_single_quotes() {
+        assert_eq!(shell_escape("abc"), "'abc'");
+        assert_eq!(shell_escape("a'b"), "'a'\"'\"'b'");
+    }
+
+    #[test]
+    fn values_are_escaped_by_type() {
+        let rendered = UserDataRenderer::default()
+            .render(Uuid::nil(), &vars(), &Persona::Beginner, None)
+            .unwrap();
+
+        assert!(rendered.starts_with("#!/bin/bash\n"));
+        assert!(rendered.contains("export REGISTRATION_TOKEN='abc+/='\n"));
+        assert!(rendered.contains("# Workspace\nexport AGENT_NAME='Agent'\"'\"'X'\n"));
+        assert!(rendered.contains("export SKIP_GIT=\"true\"\n"));
+        assert!(rendered.contains("export NODE_MAJOR=\"20\"\n"));
+        assert!(rendered.contains("# OpenClaw Bot Bootstrap Script"));
+    }
+
+    #[test]
+    fn loader_fetches_the_user_data_in_script_format_only() {
+        let loader_vars = UserDataVars::new()
+            .set(
+                "REGISTRATION_TOKEN",
+                UserDataValue::Secret("abc+/=".to_string()),
+            )
+            .set("BOT_ID", UserDataValue::Id(Uuid::nil()));
+        let loader = UserDataRenderer::default()
+            .render_loader(Uuid::nil(), &loader_vars)
+            .unwrap()
+            .unwrap();
+
+        assert!(loader.starts_with("#!/bin/bash\n"));
+        assert!(loader.contains("export REGISTRATION_TOKEN='abc+/='\n"));
+        assert!(loader.contains("\"${CONTROL_PLANE_URL}/bot/${BOT_ID}/user_data\""));
+        assert!(!loader.contains("# OpenClaw Bot Bootstrap Script"));
+
+        let cloud_config = UserDataRenderer::default().with_format(UserDataFormat::CloudConfig);
+        assert!(matches!(
+            cloud_config.render_loader(Uuid::nil(), &loader_vars),
+            Ok(None)
+        ));
+    }
+
+    #[test]
+    fn bad_names_duplicates_and_secrets_are_rejected() {
+        let render = |vars: UserDataVars| {
+            UserDataRenderer::default().render(Uuid::nil(), &vars, &Persona::Beginner, None)
+        };
+
+        let err = render(UserDataVars::new().set("bad-name", UserDataValue::Flag(true)));
+        assert!(matches!(err, Err(UserDataError::InvalidName(_))));
+
+        let err = render(vars().set("SKIP_GIT", UserDataValue::Flag(false)));
+        assert!(matches!(err, Err(UserDataError::DuplicateName(_))));
+
+        let err = render(
+            UserDataVars::new().set("TOKEN", UserDataValue::Secret("a'; rm -rf /".to_string())),
+        );
+        match err {
+            Err(e @ UserDataError::InvalidValue { .. }) => {
+                assert!(!e.to_string().contains("rm -rf"))
+            }
+            other => panic!("expected InvalidValue, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn overlays_apply_by_tier_then_persona_then_custom() {
+        let dir = std::env::temp_dir().join(format!("claw-spawn-overlays-{}", Uuid::new_v4()));
+        for kind in ["tier", "persona", "custom"] {
+            fs::create_dir_all(dir.join(kind)).unwrap();
+        }
+        fs::write(dir.join("tier/pro.sh"), "echo tier-pro\n").unwrap();
+        fs::write(dir.join("persona/quant_lite.sh"), "echo persona-quant\n").unwrap();
+        fs::write(dir.join("persona/beginner.sh"), "echo persona-beginner\n").unwrap();
+        fs::write(dir.join("custom/10-motd.sh"), "echo custom-motd").unwrap();
+        fs::write(dir.join("custom/README.md"), "not a snippet").unwrap();
+
+        let renderer = UserDataRenderer::new(UserDataOverlays::load(&dir).unwrap());
+        assert!(renderer.needs_tier());
+        let rendered = renderer
+            .render(
+                Uuid::nil(),
+                &vars(),
+                &Persona::QuantLite,
+                Some(&SubscriptionTier::Pro),
+            )
+            .unwrap();
+
+        let tier = rendered
+            .find("# Overlay: tier/pro.sh\necho tier-pro")
+            .unwrap();
+        let persona = rendered.find("echo persona-quant").unwrap();
+        let custom = rendered.find("# Overlay: custom/10-motd.sh").unwrap();
+        assert!(tier < persona && persona < custom);
+        assert!(!rendered.contains("persona-beginner"));
+        assert!(!rendered.contains("not a snippet"));
+        assert!(rendered.ends_with("echo custom-motd\n"));
+
+        fs::write(dir.join("persona/jane.sh"), "echo typo\n").unwrap();
+        assert!(matches!(
+            UserDataOverlays::load(&dir),
+            Err(UserDataError::UnknownOverlay(_))
+        ));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn oversized_user_data_is_rejected() {
+        let dir = std::env::temp_dir().join(format!("claw-spawn-overlays-{}", Uuid::new_v4()));
+        fs::create_dir_all(dir.join("custom")).unwrap();
+        fs::write(
+            dir.join("custom/big.sh"),
+            format!("# {}\n", "x".repeat(MAX_USER_DATA_BYTES)),
+        )
+        .unwrap();
+
+        let renderer = UserDataRenderer::new(UserDataOverlays::load(&dir).unwrap());
+        let err = renderer
+            .render(Uuid::nil(), &vars(), &Persona::Beginner, None)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            UserDataError::TooLarge {
+                limit: MAX_USER_DATA_BYTES,
+                ..
+            }
+        ));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    #[test]
+    fn cloud_config_keeps_variables_in_root_only_files() {
+        let rendered = UserDataRenderer::default()
+            .with_format(UserDataFormat::CloudConfig)
+            .render(Uuid::nil(), &vars(), &Persona::Beginner, None)
+            .unwrap();
+
+        assert!(rendered.starts_with("#cloud-config\n"));
+        assert!(rendered.contains("packages:\n  - curl\n"));
+        assert!(rendered.contains("  - name: openclaw\n    shell: /bin/bash\n"));
+        assert!(!rendered.contains("export REGISTRATION_TOKEN"));
+
+        assert!(rendered.contains(
+            "  - path: /etc/openclaw/bootstrap.env\n    owner: root:root\n    permissions: '0600'\n"
+        ));
+        assert!(rendered.contains("      AGENT_NAME='Agent'\"'\"'X'\n"));
+        assert!(rendered.contains("      USER_DATA_FORMAT='cloud_config'\n"));
+        assert!(rendered.contains(&format!(
+            "  - path: /etc/openclaw/bot.env\n    owner: root:root\n    permissions: '0600'\n    \
+             content: |\n      # OpenClaw bot service environment for Bot {nil}\n      \
+             CONTROL_PLANE_URL='https://control.example'\n      BOT_ID='{nil}'\n      \
+             REGISTRATION_TOKEN='abc+/='\n  - path: /opt/openclaw/run.sh\n",
+            nil = Uuid::nil()
+        )));
+        assert!(rendered.contains("      EnvironmentFile=/etc/openclaw/bot.env\n"));
+        assert!(rendered.contains("      . /etc/openclaw/bootstrap.env\n"));
+        assert!(rendered.contains("      # OpenClaw Bot Bootstrap Script\n"));
+        assert!(rendered.ends_with("runcmd:\n  - [bash, /usr/local/sbin/openclaw-bootstrap.sh]\n"));
+    }
+
+    #[test]
+    fn cloud_config_base64_encodes_content_yaml_cannot_hold() {
+        let vars = vars().set("MOTD", UserDataValue::Text("bell\x07".to_string()));
+        let rendered = UserDataRenderer::default()
+            .with_format(UserDataFormat::CloudConfig)
+            .render(Uuid::nil(), &vars, &Persona::Beginner, None)
+            .unwrap();
+
+        assert!(rendered.contains(
+            "  - path: /etc/openclaw/bootstrap.env\n    owner: root:root\n    permissions: '0600'\n    \
+             encoding: b64\n"
+        ));
+        assert!(!rendered.contains('\x07'));
+        assert!(!rendered.contains("      AGENT_NAME="));
+    }
+
+    #[test]
+    fn cloud_config_files_match_the_bootstrap_script() {
+        assert!(runner_script().starts_with("#!/bin/bash\n"));
+        assert!(runner_script().ends_with("done\n"));
+
+        let (_, rest) = BOOTSTRAP_SCRIPT
+            .split_once("cat > /etc/systemd/system/openclaw-bot.service << EOFSERVICE\n")
+            .unwrap();
+        let (script_unit, _) = rest.split_once("EOFSERVICE\n").unwrap();
+        let without = |unit: &'static str, prefix: &'static str| {
+            unit.lines()
+                .filter(move |line| !line.starts_with(prefix))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            without(script_unit, "Environment="),
+            without(SERVICE_UNIT, "EnvironmentFile=")
+        );
+
+        for package in BASE_PACKAGES {
+            assert!(
+                BOOTSTRAP_SCRIPT.contains(&format!("    {package} \\\n"))
+                    || BOOTSTRAP_SCRIPT.contains(&format!("    {package}\nfi\n")),
+                "{package} is not installed by the bootstrap script"
+            );
+        }
+    }
+}
@@ -0,0 +1,259 @@
+//! Periodic checks for bots that would otherwise wait forever.
+//!
+//! A droplet that boots but never calls `POST /bot/register` leaves its bot in
+//! `Provisioning`; [`RegistrationWatchdog`] moves such bots to `Error` once the
+//! deadline passes and can queue a bounded number of redeploys for them. On
+//! the same schedule it purges `Idempotency-Key` records past their TTL, which
+//! nothing else deletes.
+
+use crate::application::{BotLifecycleService, JobService, LifecycleError};
+use crate::domain::{JobKind, IDEMPOTENCY_KEY_TTL_HOURS};
+use crate::infrastructure::{
+    BotRepository, ConfigRepository, IdempotencyRepository, JobRepository, RepositoryError,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info};
+
+#[derive(Debug, Clone)]
+pub struct WatchdogSettings {
+    /// Time between checks.
+    pub interval: Duration,
+    /// How long a bot may stay in `Provisioning` without its droplet registering.
+    pub registration_timeout: Duration,
+    /// Consecutive registration timeouts that still trigger a redeploy; 0 disables
+    /// automatic redeploys.
+    pub registration_redeploys: u32,
+}
+
+impl Default for WatchdogSettings {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(60),
+            registration_timeout: Duration::from_secs(900),
+            registration_redeploys: 0,
+        }
+    }
+}
+
+impl WatchdogSettings {
+    /// Whether a bot on its `timeouts`-th consecutive registration timeout gets redeployed.
+    pub fn should_redeploy(&self, timeouts: i32) -> bool {
+        i64::from(timeouts) <= i64::from(self.registration_redeploys)
+    }
+}
+
+pub struct RegistrationWatchdog<B, C, J>
+where
+    B: BotRepository + ?Sized,
+    C: ConfigRepository + ?Sized,
+    J: JobRepository + ?Sized,
+{
+    lifecycle: Arc<BotLifecycleService<B, C>>,
+    jobs: Arc<JobService<J>>,
+    idempotency: Option<Arc<dyn IdempotencyRepository>>,
+    settings: WatchdogSettings,
+}
+
+impl<B, C, J> RegistrationWatchdog<B, C, J>
+where
+    B: BotRepository + ?Sized,
+    C: ConfigRepository + ?Sized,
+    J: JobRepository + ?Sized,
+{
+    pub fn new(
+        lifecycle: Arc<BotLifecycleService<B, C>>,
+        jobs: Arc<JobService<J>>,
+        settings: WatchdogSettings,
+    ) -> Self {
+        Self {
+            lifecycle,
+            jobs,
+            idempotency: None,
+            settings,
+        }
+    }
+
+    /// Also purge expired `Idempotency-Key` records on every check.
+    pub fn with_idempotency_keys(mut self, idempotency: Arc<dyn IdempotencyRepository>) -> Self {
+        self.idempotency = Some(idempotency);
+        self
+    }
+
+    /// Check every `interval` until `shutdown` is cancelled.
+    pub async fn run(&self, shutdown: CancellationToken) {
+        while !shutdown.is_cancelled() {
+            if let Err(e) = self.run_once().await {
+                error!(error = %e, "Registration watchdog check failed");
+            }
+            match self.purge_idempotency_keys().await {
+                Ok(0) => {}
+                Ok(purged) => info!(purged, "Purged expired idempotency keys"),
+                Err(e) => error!(error = %e, "Failed to purge expired idempotency keys"),
+            }
+            tokio::select! {
+                _ = sleep(self.settings.interval) => {}
+                _ = shutdown.cancelled() => {}
+            }
+        }
+        info!("Registration watchdog stopped");
+    }
+
+    /// Time out overdue bots and queue redeploys. Returns how many bots timed out.
+    pub async fn run_once(&self) -> Result<usize, LifecycleError> {
+        let deadline = chrono::Duration::from_std(self.settings.registration_timeout)
+            .unwrap_or(chrono::Duration::MAX);
+        let timed_out = self.lifecycle.check_registration_timeouts(deadline).await?;
+
+        for bot in &timed_out {
+            if !self.settings.should_redeploy(bot.registration_timeouts) {
+                continue;
+            }
+            match self.jobs.enqueue(JobKind::RedeployBot, bot.id).await {
+                Ok(job) => info!(
+                    bot_id = %bot.id,
+                    job_id = %job.id,
+                    attempt = bot.registration_timeouts,
+                    "Queued redeploy after registration timeout"
+                ),
+                Err(RepositoryError::Conflict(_)) => info!(
+                    bot_id = %bot.id,
+                    "Bot already has a job in progress, not redeploying"
+                ),
+                Err(e) => error!(
+                    bot_id = %bot.id,
+                    error = %e,
+                    "Failed to queue redeploy after registration timeout"
+                ),
+            }
+        }
+
+        Ok(timed_out.len())
+    }
+
+    /// Delete idempotency keys older than their TTL. Returns how many went.
+    pub async fn purge_idempotency_keys(&self) -> Result<u64, RepositoryError> {
+        let Some(idempotency) = &self.idempotency else {
+            return Ok(0);
+        };
+        let expired_before =
+            chrono::Utc::now() - chrono::Duration::hours(IDEMPOTENCY_KEY_TTL_HOURS);
+        idempotency.purge_expired(expired_before).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        Account, Bot, BotStatus, IdempotencyRecord, Persona, SubscriptionTier, TransitionReason,
+    };
+    use crate::infrastructure::{AccountRepository, MemoryStore};
+
+    #[test]
+    fn redeploys_are_disabled_by_default() {
+        assert!(!WatchdogSettings::default().should_redeploy(1));
+    }
+
+    #[test]
+    fn redeploys_stop_after_the_configured_count() {
+        let settings = WatchdogSettings {
+            registration_redeploys: 2,
+            ..WatchdogSettings::default()
+        };
+        assert!(settings.should_redeploy(1));
+        assert!(settings.should_redeploy(2));
+        assert!(!settings.should_redeploy(3));
+    }
+
+    #[tokio::test]
+    async fn timed_out_bots_are_redeployed_up_to_the_configured_count() {
+        let store = MemoryStore::new();
+        let watchdog = RegistrationWatchdog::new(
+            Arc::new(BotLifecycleService::new(
+                Arc::new(store.bots()),
+                Arc::new(store.configs()),
+            )),
+            Arc::new(JobService::new(Arc::new(store.jobs()), 5)),
+            WatchdogSettings {
+                registration_timeout: Duration::ZERO,
+                registration_redeploys: 1,
+                ..WatchdogSettings::default()
+            },
+        );
+        let account = Account::new("u1".to_string(), SubscriptionTier::Basic);
+        store.accounts().create(&account).await.unwrap();
+        let bot = Bot::new(account.id, "silent".to_string(), Persona::Beginner);
+        store.bots().create(&bot).await.unwrap();
+        let provision = |from, reason| {
+            let bots = store.bots();
+            async move {
+                bots.transition_status(bot.id, from, BotStatus::Provisioning, reason, None)
+                    .await
+                    .unwrap();
+                sleep(Duration::from_millis(5)).await;
+            }
+        };
+        let lease = chrono::Duration::minutes(10);
+
+        provision(BotStatus::Pending, TransitionReason::Provision).await;
+        assert_eq!(watchdog.run_once().await.unwrap(), 1);
+        let stored = store.bots().get_by_id(bot.id).await.unwrap();
+        assert_eq!(stored.status, BotStatus::Error);
+        assert_eq!(
+            stored.status_reason,
+            Some(TransitionReason::RegistrationTimeout)
+        );
+        assert_eq!(stored.registration_timeouts, 1);
+        let redeploy = store.jobs().claim_next(lease).await.unwrap().unwrap();
+        assert_eq!(
+            (redeploy.kind, redeploy.bot_id),
+            (JobKind::RedeployBot, bot.id)
+        );
+        store.jobs().mark_succeeded(&redeploy).await.unwrap();
+        // Nothing left overdue.
+        assert_eq!(watchdog.run_once().await.unwrap(), 0);
+
+        // The redeployed droplet doesn't register either; that was the last redeploy.
+        provision(BotStatus::Error, TransitionReason::Redeploy).await;
+        assert_eq!(watchdog.run_once().await.unwrap(), 1);
+        let stored = store.bots().get_by_id(bot.id).await.unwrap();
+        assert_eq!(stored.status, BotStatus::Error);
+        assert_eq!(stored.registration_timeouts, 2);
+        assert!(store.jobs().claim_next(lease).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn expired_idempotency_keys_are_purged() {
+        let store = MemoryStore::new();
+        let idempotency = Arc::new(store.idempotency());
+        let watchdog = RegistrationWatchdog::new(
+            Arc::new(BotLifecycleService::new(
+                Arc::new(store.bots()),
+                Arc::new(store.configs()),
+            )),
+            Arc::new(JobService::new(Arc::new(store.jobs()), 5)),
+            WatchdogSettings::default(),
+        );
+        assert_eq!(watchdog.purge_idempotency_keys().await.unwrap(), 0);
+
+        let watchdog = watchdog.with_idempotency_keys(idempotency.clone());
+        let cutoff = chrono::Utc::now() - chrono::Duration::days(1);
+        let mut expired = IdempotencyRecord::new("expired".to_string(), "f".to_string());
+        expired.created_at =
+            chrono::Utc::now() - chrono::Duration::hours(IDEMPOTENCY_KEY_TTL_HOURS + 1);
+        let live = IdempotencyRecord::new("live".to_string(), "f".to_string());
+        for record in [&expired, &live] {
+            idempotency.reserve(record, cutoff, cutoff).await.unwrap();
+        }
+
+        assert_eq!(watchdog.purge_idempotency_keys().await.unwrap(), 1);
+        assert!(idempotency
+            .reserve(&live, cutoff, cutoff)
+            .await
+            .unwrap()
+            .is_some());
+    }
+}
@@ -0,0 +1,554 @@
+use super::output::{opt, print_json, OutputFormat, Table};
+use crate::application::{OutboxExecutor, RegistrationWatchdog};
+use crate::domain::{
+    Account, Bot, BotConfig, BotSecrets, BotStatus, EncryptedBotSecrets, JobKind, RiskConfig,
+    SignalKnobs, StoredBotConfig, TradingConfig,
+};
+use crate::infrastructure::{AppConfig, BotFilter, SecretsEncryption};
+use crate::server::http_parse::{
+    parse_algorithm, parse_asset_focus, parse_persona, parse_strictness, parse_subscription_tier,
+};
+use crate::server::http_types::{BootstrapEventResponse, BotResponse, JobAcceptedResponse};
+use crate::server::AppState;
+use anyhow::{bail, Context as _};
+use clap::Args;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+use uuid::Uuid;
+
+#[derive(Args)]
+pub(super) struct CreateBot {
+    #[arg(long)]
+    account: Uuid,
+    #[arg(long)]
+    name: String,
+    /// `beginner`, `tweaker` or `quant_lite`.
+    #[arg(long)]
+    persona: String,
+    /// `majors` or `memes`.
+    #[arg(long)]
+    asset_focus: String,
+    /// `trend`, `mean_reversion` or `breakout`.
+    #[arg(long)]
+    algorithm: String,
+    /// `low`, `medium` or `high`.
+    #[arg(long)]
+    strictness: String,
+    /// Trade for real; bots start in paper mode otherwise.
+    #[arg(long)]
+    live: bool,
+    #[arg(long)]
+    max_position_size_pct: f64,
+    #[arg(long)]
+    max_daily_loss_pct: f64,
+    #[arg(long)]
+    max_drawdown_pct: f64,
+    #[arg(long)]
+    max_trades_per_day: i32,
+    #[arg(long)]
+    llm_provider: String,
+    /// Read from the environment so the key stays out of shell history.
+    #[arg(long, env = "CLAW_LLM_API_KEY", hide_env_values = true)]
+    llm_api_key: String,
+    /// Named toolchain profile; the service default when omitted.
+    #[arg(long)]
+    toolchain_profile: Option<String>,
+}
+
+/// Body of `configs publish --file`.
+#[derive(Deserialize)]
+struct ConfigFile {
+    trading_config: TradingConfig,
+    risk_config: RiskConfig,
+    secrets: BotSecrets,
+}
+
+pub(super) struct Context {
+    state: AppState,
+    encryption: SecretsEncryption,
+    output: OutputFormat,
+}
+
+impl Context {
+    pub(super) fn new(
+        state: AppState,
+        config: &AppConfig,
+        output: OutputFormat,
+    ) -> anyhow::Result<Self> {
+        let encryption =
+            SecretsEncryption::new(&config.encryption_key).context("init encryption")?;
+        Ok(Self {
+            state,
+            encryption,
+            output,
+        })
+    }
+
+    pub(super) async fn list_accounts(
+        &self,
+        tier: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<()> {
+        let tier = tier
+            .map(|t| {
+                parse_subscription_tier(t)
+                    .with_context(|| format!("invalid tier {t:?}; expected free, basic or pro"))
+            })
+            .transpose()?;
+        let accounts = self.state.account_repo.list(tier, limit, offset).await?;
+
+        match self.output {
+            OutputFormat::Json => print_json(&accounts),
+            OutputFormat::Table => {
+                print!("{}", accounts_table(&accounts).render());
+                Ok(())
+            }
+        }
+    }
+
+    pub(super) async fn list_bots(
+        &self,
+        account: Option<Uuid>,
+        status: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<()> {
+        let bots = self.find_bots(account, status, limit, offset).await?;
+        self.print_bots(bots)
+    }
+
+    async fn find_bots(
+        &self,
+        account: Option<Uuid>,
+        status: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<Vec<Bot>> {
+        let status = status
+            .map(|s| BotStatus::from_str(s).map_err(|_| anyhow::anyhow!("invalid status {s:?}")))
+            .transpose()?;
+        let filter = BotFilter {
+            account_id: account,
+            status,
+        };
+        Ok(self
+            .state
+            .lifecycle
+            .list_bots(&filter, limit, offset)
+            .await?)
+    }
+
+    pub(super) async fn show_bot(&self, id: Uuid) -> anyhow::Result<()> {
+        let bot = self.state.lifecycle.get_bot(id).await?;
+        self.print_bots(vec![bot])
+    }
+
+    /// Same steps as `POST /bots`: record the bot, then queue its spawn.
+    pub(super) async fn create_bot(&self, args: CreateBot) -> anyhow::Result<()> {
+        let persona = parse_persona(&args.persona).with_context(|| {
+            format!(
+                "invalid persona {:?}; expected beginner, tweaker or quant_lite",
+                args.persona
+            )
+        })?;
+        let asset_focus = parse_asset_focus(&args.asset_focus).with_context(|| {
+            format!(
+                "invalid asset focus {:?}; expected majors or memes",
+                args.asset_focus
+            )
+        })?;
+        let algorithm = parse_algorithm(&args.algorithm).with_context(|| {
+            format!(
+                "invalid algorithm {:?}; expected trend, mean_reversion or breakout",
+                args.algorithm
+            )
+        })?;
+        let strictness = parse_strictness(&args.strictness).with_context(|| {
+            format!(
+                "invalid strictness {:?}; expected low, medium or high",
+                args.strictness
+            )
+        })?;
+
+        let risk_config = RiskConfig {
+            max_position_size_pct: args.max_position_size_pct,
+            max_daily_loss_pct: args.max_daily_loss_pct,
+            max_drawdown_pct: args.max_drawdown_pct,
+            max_trades_per_day: args.max_trades_per_day,
+        };
+        validate_risk(&risk_config)?;
+
+        let config = BotConfig {
+            id: Uuid::new_v4(),
+            bot_id: Uuid::new_v4(),
+            version: 1,
+            trading_config: TradingConfig {
+                asset_focus,
+                algorithm,
+                strictness,
+                paper_mode: !args.live,
+                signal_knobs: SignalKnobs::for_persona(&persona),
+            },
+            risk_config,
+            secrets: BotSecrets {
+                llm_provider: args.llm_provider,
+                llm_api_key: args.llm_api_key,
+            },
+            created_at: chrono::Utc::now(),
+        };
+
+        let (bot, job) = self
+            .state
+            .provisioning
+            .create_bot(
+                args.account,
+                args.name,
+                persona,
+                config,
+                Default::default(),
+                args.toolchain_profile,
+            )
+            .await?;
+
+        match self.output {
+            OutputFormat::Json => print_json(&JobAcceptedResponse {
+                job_id: job.id,
+                bot: Some(BotResponse::from(bot)),
+            }),
+            OutputFormat::Table => {
+                let mut table = Table::new(&["BOT", "NAME", "JOB"]);
+                table.row(vec![bot.id.to_string(), bot.name, job.id.to_string()]);
+                print!("{}", table.render());
+                Ok(())
+            }
+        }
+    }
+
+    pub(super) async fn destroy_bot(&self, id: Uuid) -> anyhow::Result<()> {
+        let job_id = self.queue_destroy(id).await?;
+        self.print_job(id, job_id)
+    }
+
+    async fn queue_destroy(&self, id: Uuid) -> anyhow::Result<Uuid> {
+        // Reject unknown bots up front rather than queueing a job that can only fail.
+        self.state.lifecycle.get_bot(id).await?;
+        let job = self.state.jobs.enqueue(JobKind::DestroyBot, id).await?;
+        Ok(job.id)
+    }
+
+    pub(super) async fn publish_config(&self, bot_id: Uuid, file: &Path) -> anyhow::Result<()> {
+        let raw =
+            std::fs::read_to_string(file).with_context(|| format!("read {}", file.display()))?;
+        let ConfigFile {
+            trading_config,
+            risk_config,
+            secrets,
+        } = serde_json::from_str(&raw).with_context(|| format!("parse {}", file.display()))?;
+        validate_risk(&risk_config)?;
+
+        let llm_api_key_encrypted = self
+            .encryption
+            .encrypt(&secrets.llm_api_key)
+            .context("encrypt LLM API key")?;
+        // `create_bot_config` assigns the id, version and timestamp.
+        let config = StoredBotConfig {
+            id: Uuid::nil(),
+            bot_id,
+            version: 0,
+            trading_config,
+            risk_config,
+            secrets: EncryptedBotSecrets {
+                llm_provider: secrets.llm_provider,
+                llm_api_key_encrypted,
+            },
+            created_at: chrono::Utc::now(),
+        };
+        let stored = self
+            .state
+            .lifecycle
+            .create_bot_config(bot_id, config)
+            .await?;
+
+        match self.output {
+            OutputFormat::Json => print_json(&serde_json::json!({
+                "bot_id": stored.bot_id,
+                "config_id": stored.id,
+                "version": stored.version,
+            })),
+            OutputFormat::Table => {
+                let mut table = Table::new(&["BOT", "CONFIG", "VERSION"]);
+                table.row(vec![
+                    stored.bot_id.to_string(),
+                    stored.id.to_string(),
+                    stored.version.to_string(),
+                ]);
+                print!("{}", table.render());
+                Ok(())
+            }
+        }
+    }
+
+    /// Print the bot's bootstrap events; with `follow`, poll for new ones until Ctrl+C.
+    /// JSON output is one event per line.
+    pub(super) async fn tail_events(
+        &self,
+        bot_id: Uuid,
+        follow: bool,
+        interval_secs: u64,
+    ) -> anyhow::Result<()> {
+        let mut seen = HashSet::new();
+        let interval = Duration::from_secs(interval_secs.max(1));
+
+        loop {
+            for event in self.state.bootstrap.timeline(bot_id).await? {
+                if !seen.insert(event.id) {
+                    continue;
+                }
+                match self.output {
+                    OutputFormat::Json => println!(
+                        "{}",
+                        serde_json::to_string(&BootstrapEventResponse::from(event))?
+                    ),
+                    OutputFormat::Table => println!(
+                        "{}  {:<13}  exit={:<3}  {}",
+                        event.reported_at.to_rfc3339(),
+                        event.phase.to_string(),
+                        event.exit_code,
+                        event.message.unwrap_or_default(),
+                    ),
+                }
+            }
+            if !follow {
+                return Ok(());
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(interval) => {}
+                _ = tokio::signal::ctrl_c() => return Ok(()),
+            }
+        }
+    }
+
+    pub(super) async fn stale_check(&self, heartbeat_timeout_secs: u64) -> anyhow::Result<()> {
+        let timeout = chrono::Duration::seconds(
+            i64::try_from(heartbeat_timeout_secs).context("heartbeat timeout too large")?,
+        );
+        let marked = self.state.lifecycle.check_stale_bots(timeout).await?;
+        self.print_bots(marked)
+    }
+
+    pub(super) async fn reconcile(&self) -> anyhow::Result<()> {
+        let (timed_out, provider_operations) = self.run_reconcile().await?;
+
+        match self.output {
+            OutputFormat::Json => print_json(&serde_json::json!({
+                "timed_out": timed_out,
+                "provider_operations": provider_operations,
+            })),
+            OutputFormat::Table => {
+                println!("Timed out {timed_out} bot(s) awaiting registration");
+                println!("Retried {provider_operations} abandoned provider operation(s)");
+                Ok(())
+            }
+        }
+    }
+
+    /// One registration watchdog check and one outbox scan. Returns how many
+    /// bots timed out and how many provider operations were retried.
+    async fn run_reconcile(&self) -> anyhow::Result<(usize, usize)> {
+        let watchdog = RegistrationWatchdog::new(
+            self.state.lifecycle.clone(),
+            self.state.jobs.clone(),
+            self.state.watchdog.clone(),
+        );
+        let timed_out = watchdog.run_once().await?;
+        let outbox = OutboxExecutor::new(
+            self.state.provider_operations.clone(),
+            self.state.provisioning.clone(),
+            self.state.outbox.clone(),
+        );
+        let provider_operations = outbox.run_once().await?;
+        Ok((timed_out, provider_operations))
+    }
+
+    fn print_bots(&self, bots: Vec<Bot>) -> anyhow::Result<()> {
+        match self.output {
+            OutputFormat::Json => {
+                let bots: Vec<BotResponse> = bots.into_iter().map(Into::into).collect();
+                print_json(&bots)
+            }
+            OutputFormat::Table => {
+                print!("{}", bots_table(&bots).render());
+                Ok(())
+            }
+        }
+    }
+
+    fn print_job(&self, bot_id: Uuid, job_id: Uuid) -> anyhow::Result<()> {
+        match self.output {
+            OutputFormat::Json => print_json(&JobAcceptedResponse { job_id, bot: None }),
+            OutputFormat::Table => {
+                let mut table = Table::new(&["BOT", "JOB"]);
+                table.row(vec![bot_id.to_string(), job_id.to_string()]);
+                print!("{}", table.render());
+                Ok(())
+            }
+        }
+    }
+}
+
+fn validate_risk(risk_config: &RiskConfig) -> anyhow::Result<()> {
+    if let Err(errors) = risk_config.validate() {
+        bail!("invalid risk configuration: {}", errors.join("; "));
+    }
+    Ok(())
+}
+
+fn accounts_table(accounts: &[Account]) -> Table {
+    let mut table = Table::new(&["ID", "EXTERNAL ID", "TIER", "MAX BOTS", "CREATED"]);
+    for account in accounts {
+        table.row(vec![
+            account.id.to_string(),
+            account.external_id.clone(),
+            account.subscription_tier.to_string(),
+            account.max_bots.to_string(),
+            account.created_at.to_rfc3339(),
+        ]);
+    }
+    table
+}
+
+fn bots_table(bots: &[Bot]) -> Table {
+    let mut table = Table::new(&[
+        "ID",
+        "NAME",
+        "ACCOUNT",
+        "STATUS",
+        "REASON",
+        "DROPLET",
+        "LAST HEARTBEAT",
+    ]);
+    for bot in bots {
+        table.row(vec![
+            bot.id.to_string(),
+            bot.name.clone(),
+            bot.account_id.to_string(),
+            bot.status.to_string(),
+            opt(bot.status_reason),
+            opt(bot.droplet_id),
+            opt(bot.last_heartbeat_at.map(|at| at.to_rfc3339())),
+        ]);
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Persona, SubscriptionTier, TransitionReason};
+    use crate::infrastructure::{AccountRepository, BotRepository, JobRepository, MemoryStore};
+    use crate::server::{build_state_with_repositories, Repositories};
+
+    async fn context(store: &MemoryStore) -> Context {
+        let config = AppConfig {
+            registration_timeout_secs: 0,
+            ..AppConfig::for_tests()
+        };
+        let state =
+            build_state_with_repositories(config.clone(), Repositories::memory(store.clone()))
+                .await
+                .unwrap();
+        Context::new(state, &config, OutputFormat::Json).unwrap()
+    }
+
+    async fn bot(store: &MemoryStore, account: &Account, status: BotStatus) -> Bot {
+        let bot = Bot::new(account.id, "bot".to_string(), Persona::Beginner);
+        store.bots().create(&bot).await.unwrap();
+        if status == BotStatus::Provisioning {
+            store
+                .bots()
+                .transition_status(
+                    bot.id,
+                    BotStatus::Pending,
+                    BotStatus::Provisioning,
+                    TransitionReason::Provision,
+                    None,
+                )
+                .await
+                .unwrap();
+        }
+        store.bots().get_by_id(bot.id).await.unwrap()
+    }
+
+    async fn account(store: &MemoryStore) -> Account {
+        let account = Account::new(Uuid::new_v4().to_string(), SubscriptionTier::Pro);
+        store.accounts().create(&account).await.unwrap();
+        account
+    }
+
+    #[tokio::test]
+    async fn list_bots_filters_by_account_and_status() {
+        let store = MemoryStore::new();
+        let ctx = context(&store).await;
+        let (first, second) = (account(&store).await, account(&store).await);
+        let pending = bot(&store, &first, BotStatus::Pending).await;
+        let provisioning = bot(&store, &first, BotStatus::Provisioning).await;
+        let other = bot(&store, &second, BotStatus::Pending).await;
+        let ids = |bots: Vec<Bot>| bots.into_iter().map(|b| b.id).collect::<HashSet<_>>();
+
+        let all = ctx.find_bots(None, None, 10, 0).await.unwrap();
+        assert_eq!(ids(all), [pending.id, provisioning.id, other.id].into());
+        let mine = ctx.find_bots(Some(first.id), None, 10, 0).await.unwrap();
+        assert_eq!(ids(mine), [pending.id, provisioning.id].into());
+        let pending_anywhere = ctx.find_bots(None, Some("pending"), 10, 0).await.unwrap();
+        assert_eq!(ids(pending_anywhere), [pending.id, other.id].into());
+        let mine_provisioning = ctx
+            .find_bots(Some(first.id), Some("provisioning"), 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(ids(mine_provisioning), [provisioning.id].into());
+
+        let err = ctx
+            .find_bots(None, Some("sleeping"), 10, 0)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("invalid status"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn destroy_queues_a_job_for_known_bots_only() {
+        let store = MemoryStore::new();
+        let ctx = context(&store).await;
+        let bot = bot(&store, &account(&store).await, BotStatus::Pending).await;
+        let lease = chrono::Duration::minutes(10);
+
+        assert!(ctx.queue_destroy(Uuid::new_v4()).await.is_err());
+        assert!(store.jobs().claim_next(lease).await.unwrap().is_none());
+
+        let job_id = ctx.queue_destroy(bot.id).await.unwrap();
+        let job = store.jobs().get_by_id(job_id).await.unwrap();
+        assert_eq!((job.kind, job.bot_id), (JobKind::DestroyBot, bot.id));
+        // A second destroy waits for the first.
+        assert!(ctx.queue_destroy(bot.id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn reconcile_times_out_bots_awaiting_registration() {
+        let store = MemoryStore::new();
+        let ctx = context(&store).await;
+        let account = account(&store).await;
+        let waiting = bot(&store, &account, BotStatus::Provisioning).await;
+        let pending = bot(&store, &account, BotStatus::Pending).await;
+        tokio::time::sleep(Duration::from_millis(5)).await;
+
+        assert_eq!(ctx.run_reconcile().await.unwrap(), (1, 0));
+        let waiting = store.bots().get_by_id(waiting.id).await.unwrap();
+        assert_eq!(waiting.status, BotStatus::Error);
+        let pending = store.bots().get_by_id(pending.id).await.unwrap();
+        assert_eq!(pending.status, BotStatus::Pending);
+        assert_eq!(ctx.run_reconcile().await.unwrap(), (0, 0));
+    }
+}
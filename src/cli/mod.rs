@@ -0,0 +1,172 @@
+//! Admin CLI for fleet operations (`claw-spawn-cli`, `cli` feature).
+//!
+//! Talks to the database and DigitalOcean directly with the same `CLAW_*`
+//! settings as the server, on either storage backend, wiring services without
+//! running migrations. Queued work (spawns, destroys) is still carried out by
+//! the server's job workers.
+
+mod commands;
+mod output;
+
+use crate::infrastructure::AppConfig;
+use crate::server::{check_config, connect_and_build_state};
+use anyhow::Context;
+use clap::{Args, Parser, Subcommand};
+use output::OutputFormat;
+use tracing_subscriber::EnvFilter;
+use uuid::Uuid;
+
+#[derive(Parser)]
+#[command(
+    name = "claw-spawn-cli",
+    version,
+    about = "Claw Spawn fleet operations"
+)]
+struct Cli {
+    /// How to print results.
+    #[arg(long, short, global = true, value_enum, default_value_t = OutputFormat::Table)]
+    output: OutputFormat,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List accounts.
+    #[command(subcommand)]
+    Accounts(AccountsCommand),
+    /// List, inspect, create and destroy bots.
+    #[command(subcommand)]
+    Bots(BotsCommand),
+    /// Publish bot configs.
+    #[command(subcommand)]
+    Configs(ConfigsCommand),
+    /// Follow bootstrap progress reported by droplets.
+    #[command(subcommand)]
+    Events(EventsCommand),
+    /// Mark online bots without a recent heartbeat as `error`, once.
+    StaleCheck {
+        /// Seconds since the last heartbeat before a bot counts as stale
+        /// (droplets send one every 30 seconds).
+        #[arg(long, default_value_t = 120)]
+        heartbeat_timeout_secs: u64,
+    },
+    /// Run the registration watchdog once: time out bots whose droplet never
+    /// registered and queue redeploys as configured.
+    Reconcile,
+}
+
+#[derive(Subcommand)]
+enum AccountsCommand {
+    List {
+        /// Only accounts on this tier (`free`, `basic` or `pro`).
+        #[arg(long)]
+        tier: Option<String>,
+        #[command(flatten)]
+        page: Page,
+    },
+}
+
+#[derive(Subcommand)]
+enum BotsCommand {
+    List {
+        #[arg(long)]
+        account: Option<Uuid>,
+        /// Only bots in this status, e.g. `error` or `provisioning`.
+        #[arg(long)]
+        status: Option<String>,
+        #[command(flatten)]
+        page: Page,
+    },
+    Show {
+        id: Uuid,
+    },
+    /// Record a bot and queue its droplet spawn.
+    Create(commands::CreateBot),
+    /// Queue the bot's droplet for destruction.
+    Destroy {
+        id: Uuid,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigsCommand {
+    /// Publish a new config version for the bot to pick up.
+    Publish {
+        bot_id: Uuid,
+        /// JSON file with `trading_config`, `risk_config` and `secrets`
+        /// (`llm_provider`, `llm_api_key`), as in `GET /bots/{id}/config`.
+        #[arg(long)]
+        file: std::path::PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum EventsCommand {
+    /// Print the bot's bootstrap events.
+    Tail {
+        bot_id: Uuid,
+        /// Keep polling for new events until interrupted.
+        #[arg(long, short)]
+        follow: bool,
+        #[arg(long, default_value_t = 2)]
+        interval_secs: u64,
+    },
+}
+
+#[derive(Args)]
+struct Page {
+    #[arg(long, default_value_t = 100)]
+    limit: i64,
+    #[arg(long, default_value_t = 0)]
+    offset: i64,
+}
+
+/// Entrypoint for the `claw-spawn-cli` binary.
+pub async fn run() -> anyhow::Result<()> {
+    dotenvy::dotenv().ok();
+    let cli = Cli::parse();
+
+    // Logs go to stderr so `--output json` stays machine-readable.
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn")),
+        )
+        .with_writer(std::io::stderr)
+        .init();
+
+    let config = AppConfig::from_env().context("load config")?;
+    check_config(&config)?;
+    let state = connect_and_build_state(config.clone(), false).await?;
+    let ctx = commands::Context::new(state, &config, cli.output)?;
+
+    match cli.command {
+        Command::Accounts(AccountsCommand::List { tier, page }) => {
+            ctx.list_accounts(tier.as_deref(), page.limit, page.offset)
+                .await
+        }
+        Command::Bots(BotsCommand::List {
+            account,
+            status,
+            page,
+        }) => {
+            ctx.list_bots(account, status.as_deref(), page.limit, page.offset)
+                .await
+        }
+        Command::Bots(BotsCommand::Show { id }) => ctx.show_bot(id).await,
+        Command::Bots(BotsCommand::Create(args)) => ctx.create_bot(args).await,
+        Command::Bots(BotsCommand::Destroy { id }) => ctx.destroy_bot(id).await,
+        Command::Configs(ConfigsCommand::Publish { bot_id, file }) => {
+            ctx.publish_config(bot_id, &file).await
+        }
+        Command::Events(EventsCommand::Tail {
+            bot_id,
+            follow,
+            interval_secs,
+        }) => ctx.tail_events(bot_id, follow, interval_secs).await,
+        Command::StaleCheck {
+            heartbeat_timeout_secs,
+        } => ctx.stale_check(heartbeat_timeout_secs).await,
+        Command::Reconcile => ctx.reconcile().await,
+    }
+}
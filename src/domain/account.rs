@@ -1,5 +1,7 @@
+use super::CustomizerOverrides;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -8,31 +10,47 @@ pub struct Account {
     pub external_id: String,
     pub subscription_tier: SubscriptionTier,
     pub max_bots: i32,
+    /// Customizer defaults for this account's bots.
+    pub customizer: CustomizerOverrides,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Display, EnumString)]
+#[strum(serialize_all = "snake_case")]
 pub enum SubscriptionTier {
     Free,
     Basic,
     Pro,
 }
 
-impl Account {
-    pub fn new(external_id: String, tier: SubscriptionTier) -> Self {
-        let now = Utc::now();
-        let max_bots = match tier {
+impl SubscriptionTier {
+    /// Bots an account on this tier may run at once.
+    pub fn max_bots(&self) -> i32 {
+        match self {
             SubscriptionTier::Free => 0,
             SubscriptionTier::Basic => 2,
             SubscriptionTier::Pro => 4,
-        };
+        }
+    }
+
+    /// Whether hibernating a bot snapshots its droplet first, for bots that
+    /// don't choose. Snapshots are billed per GB, so only `pro` keeps one.
+    pub fn snapshots_on_hibernate(&self) -> bool {
+        matches!(self, SubscriptionTier::Pro)
+    }
+}
+
+impl Account {
+    pub fn new(external_id: String, tier: SubscriptionTier) -> Self {
+        let now = Utc::now();
 
         Self {
             id: Uuid::new_v4(),
             external_id,
+            max_bots: tier.max_bots(),
             subscription_tier: tier,
-            max_bots,
+            customizer: CustomizerOverrides::default(),
             created_at: now,
             updated_at: now,
         }
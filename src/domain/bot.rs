@@ -1,6 +1,8 @@
+use super::CustomizerSettings;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use strum::{Display, EnumString};
+use thiserror::Error;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -17,6 +19,26 @@ pub struct Bot {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub last_heartbeat_at: Option<DateTime<Utc>>,
+    /// Why the bot entered its current status; `None` until the first transition.
+    pub status_reason: Option<TransitionReason>,
+    /// Human-readable cause for the last transition, e.g. the provider error.
+    pub status_detail: Option<String>,
+    pub status_changed_at: DateTime<Utc>,
+    /// First `POST /bot/register` from the current droplet; reset when a new droplet is spawned.
+    pub registered_at: Option<DateTime<Utc>>,
+    pub registered_ip: Option<String>,
+    pub agent_version: Option<String>,
+    /// Consecutive provisioning attempts that hit the registration deadline.
+    pub registration_timeouts: i32,
+    /// Workspace customizer settings resolved at creation; `None` uses the service defaults.
+    pub customizer: Option<CustomizerSettings>,
+    /// Toolchain profile chosen at creation; `None` uses the service default.
+    pub toolchain_profile: Option<String>,
+    /// Whether hibernating snapshots the droplet first; `None` follows the account
+    /// tier ([`SubscriptionTier::snapshots_on_hibernate`](super::SubscriptionTier::snapshots_on_hibernate)).
+    pub snapshot_on_hibernate: Option<bool>,
+    /// Snapshot taken when the bot hibernated; its next droplet boots from it.
+    pub hibernation_snapshot_id: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Display, EnumString)]
@@ -27,17 +49,106 @@ pub enum Persona {
     QuantLite,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Display, EnumString)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Display, EnumString)]
 #[strum(serialize_all = "snake_case")]
 pub enum BotStatus {
     Pending,
     Provisioning,
     Online,
     Paused,
+    /// The droplet was destroyed to stop billing; the bot, its config and its
+    /// token are kept and resuming provisions a new droplet.
+    Hibernated,
     Error,
     Destroyed,
 }
 
+/// Why a bot moves between statuses; every edge in [`TRANSITIONS`] carries one.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Display, EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum TransitionReason {
+    /// A spawn job started creating the droplet.
+    Provision,
+    /// The droplet was torn down to be spawned again from the latest config.
+    Redeploy,
+    /// DigitalOcean rate limited the spawn; the job will try again.
+    ProvisionDeferred,
+    /// The droplet could not be created or recorded.
+    ProvisionFailed,
+    /// The droplet never called `POST /bot/register` within the deadline.
+    RegistrationTimeout,
+    /// The agent on the droplet acknowledged its config.
+    ConfigAcknowledged,
+    Pause,
+    Resume,
+    /// The droplet was (optionally snapshotted and) destroyed to stop billing.
+    Hibernate,
+    /// No heartbeat arrived within the timeout.
+    HeartbeatTimeout,
+    Destroy,
+}
+
+/// The bot state machine: `(from, to, reason)`. Anything not listed is rejected,
+/// and `Destroyed` is terminal.
+pub const TRANSITIONS: &[(BotStatus, BotStatus, TransitionReason)] = {
+    use BotStatus::*;
+    use TransitionReason as R;
+    &[
+        (Pending, Provisioning, R::Provision),
+        (Error, Provisioning, R::Provision),
+        (Pending, Provisioning, R::Redeploy),
+        (Online, Provisioning, R::Redeploy),
+        (Paused, Provisioning, R::Redeploy),
+        (Error, Provisioning, R::Redeploy),
+        (Hibernated, Provisioning, R::Redeploy),
+        (Provisioning, Pending, R::ProvisionDeferred),
+        (Provisioning, Error, R::ProvisionFailed),
+        (Provisioning, Error, R::RegistrationTimeout),
+        (Provisioning, Online, R::ConfigAcknowledged),
+        (Error, Online, R::ConfigAcknowledged),
+        (Online, Paused, R::Pause),
+        (Error, Paused, R::Pause),
+        (Paused, Online, R::Resume),
+        (Online, Hibernated, R::Hibernate),
+        (Paused, Hibernated, R::Hibernate),
+        (Error, Hibernated, R::Hibernate),
+        (Hibernated, Provisioning, R::Resume),
+        (Online, Error, R::HeartbeatTimeout),
+        (Pending, Destroyed, R::Destroy),
+        (Provisioning, Destroyed, R::Destroy),
+        (Online, Destroyed, R::Destroy),
+        (Paused, Destroyed, R::Destroy),
+        (Error, Destroyed, R::Destroy),
+        (Hibernated, Destroyed, R::Destroy),
+    ]
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Bot cannot move from {from} to {to}: {reason}")]
+pub struct InvalidTransition {
+    pub from: BotStatus,
+    pub to: BotStatus,
+    pub reason: String,
+}
+
+impl BotStatus {
+    /// Checks that `self -> to` for `reason` is an edge of [`TRANSITIONS`].
+    pub fn transition_to(
+        self,
+        to: BotStatus,
+        reason: TransitionReason,
+    ) -> Result<(), InvalidTransition> {
+        if TRANSITIONS.contains(&(self, to, reason)) {
+            return Ok(());
+        }
+        Err(InvalidTransition {
+            from: self,
+            to,
+            reason: format!("{reason} is not allowed from {self}"),
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BotConfig {
     pub id: Uuid,
@@ -98,6 +209,18 @@ pub struct SignalKnobs {
     pub correlation_brake: bool,
 }
 
+impl SignalKnobs {
+    /// The preset knobs for `persona`; only `quant_lite` bots get any.
+    pub fn for_persona(persona: &Persona) -> Option<Self> {
+        matches!(persona, Persona::QuantLite).then_some(Self {
+            volume_confirmation: true,
+            volatility_brake: true,
+            liquidity_filter: StrictnessLevel::Medium,
+            correlation_brake: true,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RiskConfig {
     pub max_position_size_pct: f64,
@@ -175,6 +298,129 @@ impl Bot {
             created_at: now,
             updated_at: now,
             last_heartbeat_at: None,
+            status_reason: None,
+            status_detail: None,
+            status_changed_at: now,
+            registered_at: None,
+            registered_ip: None,
+            agent_version: None,
+            registration_timeouts: 0,
+            customizer: None,
+            toolchain_profile: None,
+            snapshot_on_hibernate: None,
+            hibernation_snapshot_id: None,
+        }
+    }
+
+    /// Checks the edge from the current status plus the guards that depend on the
+    /// rest of the bot. Callers still persist with a compare-and-set on `status`.
+    pub fn check_transition(
+        &self,
+        to: BotStatus,
+        reason: TransitionReason,
+    ) -> Result<(), InvalidTransition> {
+        self.status.transition_to(to, reason)?;
+
+        // Paused and online both mean "has a droplet we can power on or off",
+        // and hibernating is what gets rid of one.
+        if matches!(
+            to,
+            BotStatus::Online | BotStatus::Paused | BotStatus::Hibernated
+        ) && self.droplet_id.is_none()
+        {
+            return Err(InvalidTransition {
+                from: self.status,
+                to,
+                reason: "bot has no droplet".to_string(),
+            });
         }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bot_with(status: BotStatus, droplet_id: Option<i64>) -> Bot {
+        let mut bot = Bot::new(Uuid::new_v4(), "bot".to_string(), Persona::Beginner);
+        bot.status = status;
+        bot.droplet_id = droplet_id;
+        bot
+    }
+
+    #[test]
+    fn destroyed_is_terminal() {
+        for (from, _, _) in TRANSITIONS {
+            assert_ne!(*from, BotStatus::Destroyed);
+        }
+    }
+
+    #[test]
+    fn every_live_status_can_be_destroyed() {
+        use BotStatus::*;
+        for status in [Pending, Provisioning, Online, Paused, Hibernated, Error] {
+            assert!(status
+                .transition_to(Destroyed, TransitionReason::Destroy)
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn pause_is_rejected_for_pending_and_destroyed_bots() {
+        let pause = |bot: Bot| bot.check_transition(BotStatus::Paused, TransitionReason::Pause);
+        assert!(pause(bot_with(BotStatus::Pending, None)).is_err());
+        assert!(pause(bot_with(BotStatus::Destroyed, Some(1))).is_err());
+        assert!(pause(bot_with(BotStatus::Online, Some(1))).is_ok());
+    }
+
+    #[test]
+    fn hibernated_bot_resumes_through_provisioning() {
+        let hibernate =
+            |bot: Bot| bot.check_transition(BotStatus::Hibernated, TransitionReason::Hibernate);
+        assert!(hibernate(bot_with(BotStatus::Paused, Some(1))).is_ok());
+        assert!(hibernate(bot_with(BotStatus::Error, None)).is_err());
+        assert!(hibernate(bot_with(BotStatus::Provisioning, Some(1))).is_err());
+
+        let hibernated = bot_with(BotStatus::Hibernated, None);
+        assert!(hibernated
+            .check_transition(BotStatus::Provisioning, TransitionReason::Resume)
+            .is_ok());
+        assert!(hibernated
+            .check_transition(BotStatus::Online, TransitionReason::Resume)
+            .is_err());
+    }
+
+    #[test]
+    fn going_online_requires_a_droplet() {
+        let ack = TransitionReason::ConfigAcknowledged;
+        let err = bot_with(BotStatus::Provisioning, None)
+            .check_transition(BotStatus::Online, ack)
+            .unwrap_err();
+        assert_eq!(err.from, BotStatus::Provisioning);
+        assert_eq!(err.to, BotStatus::Online);
+
+        assert!(bot_with(BotStatus::Provisioning, Some(1))
+            .check_transition(BotStatus::Online, ack)
+            .is_ok());
+    }
+
+    #[test]
+    fn pending_bot_cannot_skip_provisioning() {
+        assert!(BotStatus::Pending
+            .transition_to(BotStatus::Online, TransitionReason::ConfigAcknowledged)
+            .is_err());
+    }
+
+    #[test]
+    fn reason_must_match_the_edge() {
+        use BotStatus::*;
+        assert!(Provisioning
+            .transition_to(Error, TransitionReason::RegistrationTimeout)
+            .is_ok());
+        assert!(Online
+            .transition_to(Error, TransitionReason::RegistrationTimeout)
+            .is_err());
     }
 }
@@ -0,0 +1,272 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Longest agent or owner name passed to the customizer.
+pub const MAX_CUSTOMIZER_NAME_LENGTH: usize = 64;
+/// Longest git ref (tag, branch or SHA) the customizer may check out.
+pub const MAX_CUSTOMIZER_REF_LENGTH: usize = 128;
+
+/// Workspace customizer (janebot-cli) settings for one bot.
+///
+/// Resolved when the bot is created and stored with it, so a redeploy builds the
+/// same workspace even if the service defaults change in between.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CustomizerSettings {
+    pub repo_url: String,
+    #[serde(rename = "ref")]
+    pub git_ref: String,
+    pub agent_name: String,
+    pub owner_name: String,
+    pub skip_qmd: bool,
+    pub skip_cron: bool,
+    pub skip_git: bool,
+    pub skip_heartbeat: bool,
+}
+
+/// Changes to [`CustomizerSettings`] requested for an account or a bot; unset
+/// fields inherit from the next level down (bot, then account, then service).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CustomizerOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo_url: Option<String>,
+    #[serde(default, rename = "ref", skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skip_qmd: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skip_cron: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skip_git: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skip_heartbeat: Option<bool>,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum CustomizerError {
+    #[error("Customizer repository {0} is not in the allow-list")]
+    RepoNotAllowed(String),
+    #[error("Customizer ref {0:?} is not a valid git ref")]
+    InvalidRef(String),
+    #[error("Customizer ref {0} is not in the allow-list")]
+    RefNotAllowed(String),
+    #[error("Customizer {field} must be 1-{max} characters without control characters")]
+    InvalidName { field: &'static str, max: usize },
+}
+
+/// Repository URLs and refs a bot or account may select.
+#[derive(Debug, Clone, Default)]
+pub struct CustomizerAllowList {
+    /// Allowed besides the service default repository.
+    pub repo_urls: Vec<String>,
+    /// When empty, any well-formed ref is allowed.
+    pub refs: Vec<String>,
+}
+
+impl CustomizerOverrides {
+    /// Fields set here win; the rest come from `fallback`.
+    pub fn or(self, fallback: &CustomizerOverrides) -> CustomizerOverrides {
+        CustomizerOverrides {
+            repo_url: self.repo_url.or_else(|| fallback.repo_url.clone()),
+            git_ref: self.git_ref.or_else(|| fallback.git_ref.clone()),
+            agent_name: self.agent_name.or_else(|| fallback.agent_name.clone()),
+            owner_name: self.owner_name.or_else(|| fallback.owner_name.clone()),
+            skip_qmd: self.skip_qmd.or(fallback.skip_qmd),
+            skip_cron: self.skip_cron.or(fallback.skip_cron),
+            skip_git: self.skip_git.or(fallback.skip_git),
+            skip_heartbeat: self.skip_heartbeat.or(fallback.skip_heartbeat),
+        }
+    }
+
+    pub fn apply(&self, base: &CustomizerSettings) -> CustomizerSettings {
+        CustomizerSettings {
+            repo_url: self
+                .repo_url
+                .clone()
+                .unwrap_or_else(|| base.repo_url.clone()),
+            git_ref: self.git_ref.clone().unwrap_or_else(|| base.git_ref.clone()),
+            agent_name: self
+                .agent_name
+                .clone()
+                .unwrap_or_else(|| base.agent_name.clone()),
+            owner_name: self
+                .owner_name
+                .clone()
+                .unwrap_or_else(|| base.owner_name.clone()),
+            skip_qmd: self.skip_qmd.unwrap_or(base.skip_qmd),
+            skip_cron: self.skip_cron.unwrap_or(base.skip_cron),
+            skip_git: self.skip_git.unwrap_or(base.skip_git),
+            skip_heartbeat: self.skip_heartbeat.unwrap_or(base.skip_heartbeat),
+        }
+    }
+
+    /// Checks the fields that are set. `default_repo_url` is always allowed.
+    pub fn validate(
+        &self,
+        default_repo_url: &str,
+        allow: &CustomizerAllowList,
+    ) -> Result<(), CustomizerError> {
+        if let Some(repo_url) = &self.repo_url {
+            if repo_url != default_repo_url && !allow.repo_urls.contains(repo_url) {
+                return Err(CustomizerError::RepoNotAllowed(repo_url.clone()));
+            }
+        }
+        if let Some(git_ref) = &self.git_ref {
+            if !is_valid_git_ref(git_ref) {
+                return Err(CustomizerError::InvalidRef(git_ref.clone()));
+            }
+            if !allow.refs.is_empty() && !allow.refs.contains(git_ref) {
+                return Err(CustomizerError::RefNotAllowed(git_ref.clone()));
+            }
+        }
+        for (field, name) in [
+            ("agent_name", &self.agent_name),
+            ("owner_name", &self.owner_name),
+        ] {
+            if let Some(name) = name {
+                let len = name.chars().count();
+                if len == 0
+                    || len > MAX_CUSTOMIZER_NAME_LENGTH
+                    || name.chars().any(char::is_control)
+                {
+                    return Err(CustomizerError::InvalidName {
+                        field,
+                        max: MAX_CUSTOMIZER_NAME_LENGTH,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A conservative subset of `git check-ref-format`: tag, branch or SHA names
+/// that can't be mistaken for an option or walk out of `refs/`.
+fn is_valid_git_ref(git_ref: &str) -> bool {
+    !git_ref.is_empty()
+        && git_ref.len() <= MAX_CUSTOMIZER_REF_LENGTH
+        && git_ref
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"._/-".contains(&b))
+        && !git_ref.starts_with(['-', '/', '.'])
+        && !git_ref.ends_with(['/', '.'])
+        && !git_ref.ends_with(".lock")
+        && !git_ref.contains("..")
+        && !git_ref.contains("//")
+        && !git_ref.contains("/.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> CustomizerSettings {
+        CustomizerSettings {
+            repo_url: "https://github.com/janebot2026/janebot-cli.git".to_string(),
+            git_ref: "main".to_string(),
+            agent_name: "Jane".to_string(),
+            owner_name: "Cedros".to_string(),
+            skip_qmd: true,
+            skip_cron: true,
+            skip_git: true,
+            skip_heartbeat: true,
+        }
+    }
+
+    #[test]
+    fn bot_overrides_win_over_account_and_service() {
+        let account = CustomizerOverrides {
+            agent_name: Some("Ada".to_string()),
+            owner_name: Some("Acme".to_string()),
+            skip_cron: Some(false),
+            ..Default::default()
+        };
+        let bot = CustomizerOverrides {
+            agent_name: Some("Bob".to_string()),
+            ..Default::default()
+        };
+
+        let settings = bot.or(&account).apply(&base());
+        assert_eq!(settings.agent_name, "Bob");
+        assert_eq!(settings.owner_name, "Acme");
+        assert!(!settings.skip_cron);
+        assert!(settings.skip_git);
+        assert_eq!(settings.git_ref, "main");
+    }
+
+    #[test]
+    fn repo_url_and_ref_are_allow_listed() {
+        let default_repo = base().repo_url;
+        let allow = CustomizerAllowList {
+            repo_urls: vec!["https://example.com/fork.git".to_string()],
+            refs: Vec::new(),
+        };
+        let with = |repo_url: &str, git_ref: &str| CustomizerOverrides {
+            repo_url: Some(repo_url.to_string()),
+            git_ref: Some(git_ref.to_string()),
+            ..Default::default()
+        };
+
+        assert!(with(&default_repo, "v1.2.3")
+            .validate(&default_repo, &allow)
+            .is_ok());
+        assert!(with("https://example.com/fork.git", "feature/x")
+            .validate(&default_repo, &allow)
+            .is_ok());
+        assert_eq!(
+            with("https://evil.example/x.git", "main").validate(&default_repo, &allow),
+            Err(CustomizerError::RepoNotAllowed(
+                "https://evil.example/x.git".to_string()
+            ))
+        );
+        for bad in [
+            "--upload-pack=x",
+            "a..b",
+            "main;rm",
+            "refs/../x",
+            "x.lock",
+            "",
+        ] {
+            assert!(
+                matches!(
+                    with(&default_repo, bad).validate(&default_repo, &allow),
+                    Err(CustomizerError::InvalidRef(_))
+                ),
+                "{bad:?} should be rejected"
+            );
+        }
+
+        let pinned = CustomizerAllowList {
+            refs: vec!["v1.2.3".to_string()],
+            ..allow
+        };
+        assert!(matches!(
+            with(&default_repo, "main").validate(&default_repo, &pinned),
+            Err(CustomizerError::RefNotAllowed(_))
+        ));
+    }
+
+    #[test]
+    fn names_must_be_short_and_printable() {
+        let default_repo = base().repo_url;
+        let allow = CustomizerAllowList::default();
+        let named = |name: &str| CustomizerOverrides {
+            owner_name: Some(name.to_string()),
+            ..Default::default()
+        };
+
+        assert!(named("O'Brien & Co")
+            .validate(&default_repo, &allow)
+            .is_ok());
+        assert!(named("").validate(&default_repo, &allow).is_err());
+        assert!(named("line\nbreak")
+            .validate(&default_repo, &allow)
+            .is_err());
+        assert!(named(&"x".repeat(MAX_CUSTOMIZER_NAME_LENGTH + 1))
+            .validate(&default_repo, &allow)
+            .is_err());
+    }
+}
@@ -13,6 +13,11 @@ pub struct Droplet {
     pub bot_id: Option<uuid::Uuid>,
     pub created_at: DateTime<Utc>,
     pub destroyed_at: Option<DateTime<Utc>>,
+    /// Toolchain profile the droplet's user data was rendered with.
+    pub toolchain_profile: Option<String>,
+    /// SHA-256 of the droplet's user data, rendered with the registration token
+    /// left out. A rebuild re-runs that user data, so it must still match.
+    pub user_data_digest: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -54,6 +59,8 @@ impl Droplet {
             bot_id: None,
             created_at: Utc::now(),
             destroyed_at: None,
+            toolchain_profile: None,
+            user_data_digest: None,
         }
     }
 }
@@ -69,6 +76,30 @@ impl DropletStatus {
     }
 }
 
+/// A droplet action (shutdown, power off, reboot, ...) as reported by DigitalOcean.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct DropletAction {
+    pub id: i64,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub status: DropletActionStatus,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum DropletActionStatus {
+    InProgress,
+    Completed,
+    Errored,
+}
+
+/// A snapshot image taken from a droplet. New droplets can boot from it by ID.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct DropletSnapshot {
+    pub id: i64,
+    pub name: String,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct DigitalOceanDropletResponse {
     pub id: i64,
@@ -98,6 +129,7 @@ pub struct Networks {
 #[derive(Debug, Deserialize)]
 pub struct NetworkV4 {
     pub ip_address: String,
+    #[serde(rename = "type")]
     pub type_: String,
 }
 
@@ -156,4 +188,20 @@ mod tests {
 
         assert!(droplet.ip_address.is_none());
     }
+
+    #[test]
+    fn droplet_action_parses_do_status() {
+        let action: DropletAction = serde_json::from_value(serde_json::json!({
+            "id": 36804636,
+            "status": "in-progress",
+            "type": "shutdown",
+            "started_at": "2014-11-14T16:29:21Z",
+            "resource_id": 3164444,
+        }))
+        .unwrap();
+
+        assert_eq!(action.id, 36804636);
+        assert_eq!(action.kind, "shutdown");
+        assert_eq!(action.status, DropletActionStatus::InProgress);
+    }
 }
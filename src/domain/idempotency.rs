@@ -0,0 +1,41 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// How long a key keeps replaying its response. Older keys are purged.
+pub const IDEMPOTENCY_KEY_TTL_HOURS: i64 = 24;
+
+/// A client-supplied `Idempotency-Key` and the response it produced.
+///
+/// `response_status` is `None` while the original request is still running.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IdempotencyRecord {
+    pub key: String,
+    /// Hash of method, path and body; a reused key must match it exactly.
+    pub request_fingerprint: String,
+    /// Identifies this reservation of the key; a retry that takes the key over
+    /// gets a new one.
+    pub reservation_id: Uuid,
+    pub response_status: Option<u16>,
+    pub response_body: Option<Vec<u8>>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl IdempotencyRecord {
+    pub fn new(key: String, request_fingerprint: String) -> Self {
+        Self {
+            key,
+            request_fingerprint,
+            reservation_id: Uuid::new_v4(),
+            response_status: None,
+            response_body: None,
+            created_at: Utc::now(),
+            completed_at: None,
+        }
+    }
+
+    pub fn is_completed(&self) -> bool {
+        self.response_status.is_some()
+    }
+}
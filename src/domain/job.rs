@@ -0,0 +1,78 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+use uuid::Uuid;
+
+/// A durable unit of provisioning work, executed by a background worker.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Job {
+    pub id: Uuid,
+    pub kind: JobKind,
+    pub bot_id: Uuid,
+    pub status: JobStatus,
+    /// Number of times a worker has claimed this job (including the current run).
+    pub attempts: i32,
+    pub max_attempts: i32,
+    /// Earliest time the job may be (re)claimed; pushed forward on retry.
+    pub run_at: DateTime<Utc>,
+    /// Lease held by the worker running the job. An expired lease on a `running`
+    /// job means the worker died and the job may be reclaimed.
+    pub locked_until: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Display, EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum JobKind {
+    SpawnBot,
+    DestroyBot,
+    RedeployBot,
+    /// Redeploy by rebuilding the droplet in place.
+    RebuildBot,
+    HibernateBot,
+    /// Provision a hibernated bot again.
+    WakeBot,
+    PauseBot,
+    /// Power a paused bot's droplet back on.
+    ResumeBot,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Display, EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+impl Job {
+    pub fn new(kind: JobKind, bot_id: Uuid, max_attempts: i32) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            kind,
+            bot_id,
+            status: JobStatus::Queued,
+            attempts: 0,
+            max_attempts: max_attempts.max(1),
+            run_at: now,
+            locked_until: None,
+            last_error: None,
+            created_at: now,
+            updated_at: now,
+            completed_at: None,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(self.status, JobStatus::Succeeded | JobStatus::Failed)
+    }
+
+    pub fn attempts_exhausted(&self) -> bool {
+        self.attempts >= self.max_attempts
+    }
+}
@@ -1,7 +1,19 @@
 pub mod account;
+pub mod bootstrap;
 pub mod bot;
+pub mod customizer;
 pub mod droplet;
+pub mod idempotency;
+pub mod job;
+pub mod provider_operation;
+pub mod toolchain;
 
 pub use account::*;
+pub use bootstrap::*;
 pub use bot::*;
+pub use customizer::*;
 pub use droplet::*;
+pub use idempotency::*;
+pub use job::*;
+pub use provider_operation::*;
+pub use toolchain::*;
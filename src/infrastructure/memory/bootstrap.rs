@@ -0,0 +1,86 @@
+use super::MemoryStore;
+use crate::domain::{BootstrapEvent, BootstrapLog};
+use crate::infrastructure::{BootstrapEventRepository, BootstrapLogRepository, RepositoryError};
+use async_trait::async_trait;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct InMemoryBootstrapEventRepository {
+    pub(super) store: MemoryStore,
+}
+
+#[async_trait]
+impl BootstrapEventRepository for InMemoryBootstrapEventRepository {
+    async fn record(&self, event: &BootstrapEvent) -> Result<(), RepositoryError> {
+        let mut tables = self.store.write();
+        tables.ensure_bot_exists(event.bot_id)?;
+        tables.bootstrap_events.push(event.clone());
+        Ok(())
+    }
+
+    async fn list_for_bot(
+        &self,
+        bot_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<BootstrapEvent>, RepositoryError> {
+        let mut events: Vec<BootstrapEvent> = self
+            .store
+            .lock()
+            .bootstrap_events
+            .iter()
+            .filter(|e| e.bot_id == bot_id)
+            .cloned()
+            .collect();
+        events.sort_by_key(|e| e.reported_at);
+        let skip = events.len().saturating_sub(limit.max(0) as usize);
+        Ok(events.split_off(skip))
+    }
+}
+
+#[derive(Clone)]
+pub struct InMemoryBootstrapLogRepository {
+    pub(super) store: MemoryStore,
+}
+
+#[async_trait]
+impl BootstrapLogRepository for InMemoryBootstrapLogRepository {
+    async fn record(&self, log: &BootstrapLog, keep: i64) -> Result<(), RepositoryError> {
+        let mut tables = self.store.write();
+        tables.ensure_bot_exists(log.bot_id)?;
+        tables.bootstrap_logs.push(log.clone());
+
+        let mut newest: Vec<_> = tables
+            .bootstrap_logs
+            .iter()
+            .filter(|l| l.bot_id == log.bot_id)
+            .map(|l| (l.uploaded_at, l.id))
+            .collect();
+        newest.sort_unstable_by(|a, b| b.cmp(a));
+        let pruned: HashSet<Uuid> = newest
+            .into_iter()
+            .skip(keep.max(0) as usize)
+            .map(|(_, id)| id)
+            .collect();
+        tables.bootstrap_logs.retain(|l| !pruned.contains(&l.id));
+        Ok(())
+    }
+
+    async fn list_for_bot(
+        &self,
+        bot_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<BootstrapLog>, RepositoryError> {
+        let mut logs: Vec<BootstrapLog> = self
+            .store
+            .lock()
+            .bootstrap_logs
+            .iter()
+            .filter(|l| l.bot_id == bot_id)
+            .cloned()
+            .collect();
+        logs.sort_by_key(|l| std::cmp::Reverse(l.uploaded_at));
+        logs.truncate(limit.max(0) as usize);
+        Ok(logs)
+    }
+}
@@ -0,0 +1,293 @@
+use super::{page, MemoryStore};
+use crate::domain::{Bot, BotStatus, TransitionReason};
+use crate::infrastructure::{
+    hash_registration_token, stale_status, BotFilter, BotRepository, RepositoryError,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct InMemoryBotRepository {
+    pub(super) store: MemoryStore,
+}
+
+impl InMemoryBotRepository {
+    fn update<T>(&self, id: Uuid, f: impl FnOnce(&mut Bot) -> T) -> Result<T, RepositoryError> {
+        let mut tables = self.store.write();
+        let bot = tables.bot_mut(id)?;
+        let result = f(bot);
+        bot.updated_at = Utc::now();
+        Ok(result)
+    }
+
+    fn select(&self, predicate: impl Fn(&Bot) -> bool) -> Vec<Bot> {
+        self.store
+            .lock()
+            .bots
+            .values()
+            .filter(|b| predicate(b))
+            .cloned()
+            .collect()
+    }
+}
+
+#[async_trait]
+impl BotRepository for InMemoryBotRepository {
+    async fn create(&self, bot: &Bot) -> Result<(), RepositoryError> {
+        let mut tables = self.store.write();
+        if !tables.accounts.contains_key(&bot.account_id) {
+            return Err(RepositoryError::InvalidData(format!(
+                "Account {} does not exist",
+                bot.account_id
+            )));
+        }
+        if tables.bots.contains_key(&bot.id) {
+            return Err(RepositoryError::Conflict(format!(
+                "Bot {} already exists",
+                bot.id
+            )));
+        }
+
+        tables.bots.insert(bot.id, bot.clone());
+        Ok(())
+    }
+
+    async fn get_by_id(&self, id: Uuid) -> Result<Bot, RepositoryError> {
+        self.store
+            .lock()
+            .bots
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| RepositoryError::NotFound(format!("Bot {}", id)))
+    }
+
+    async fn get_by_id_with_token(&self, id: Uuid, token: &str) -> Result<Bot, RepositoryError> {
+        // Tokens are stored hashed; rows created with a plaintext token still match.
+        let hashed_token = hash_registration_token(token);
+        self.store
+            .lock()
+            .bots
+            .get(&id)
+            .filter(|b| {
+                b.registration_token
+                    .as_deref()
+                    .is_some_and(|t| t == token || t == hashed_token)
+            })
+            .cloned()
+            .ok_or_else(|| RepositoryError::NotFound(format!("Bot {} with invalid token", id)))
+    }
+
+    async fn list_by_account(&self, account_id: Uuid) -> Result<Vec<Bot>, RepositoryError> {
+        let bots = self.select(|b| b.account_id == account_id);
+        Ok(page(bots, |b| b.created_at, i64::MAX, 0))
+    }
+
+    async fn list_by_account_paginated(
+        &self,
+        account_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Bot>, RepositoryError> {
+        let bots = self.select(|b| b.account_id == account_id);
+        Ok(page(bots, |b| b.created_at, limit, offset))
+    }
+
+    async fn count_by_account(&self, account_id: Uuid) -> Result<i64, RepositoryError> {
+        Ok(self.select(|b| b.account_id == account_id).len() as i64)
+    }
+
+    async fn list(
+        &self,
+        filter: &BotFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Bot>, RepositoryError> {
+        let bots = self.select(|b| {
+            filter.account_id.is_none_or(|id| b.account_id == id)
+                && filter.status.is_none_or(|s| b.status == s)
+        });
+        Ok(page(bots, |b| b.created_at, limit, offset))
+    }
+
+    async fn transition_status(
+        &self,
+        id: Uuid,
+        from: BotStatus,
+        to: BotStatus,
+        reason: TransitionReason,
+        detail: Option<&str>,
+    ) -> Result<(), RepositoryError> {
+        from.transition_to(to, reason)?;
+
+        let mut tables = self.store.write();
+        let bot = tables.bot_mut(id)?;
+        if bot.status != from {
+            return Err(stale_status(bot.status, from, to));
+        }
+
+        let now = Utc::now();
+        bot.status = to;
+        bot.status_reason = Some(reason);
+        bot.status_detail = detail.map(str::to_string);
+        bot.status_changed_at = now;
+        bot.updated_at = now;
+        Ok(())
+    }
+
+    async fn update_droplet(
+        &self,
+        bot_id: Uuid,
+        droplet_id: Option<i64>,
+    ) -> Result<(), RepositoryError> {
+        self.update(bot_id, |bot| bot.droplet_id = droplet_id)
+    }
+
+    async fn update_snapshot_on_hibernate(
+        &self,
+        bot_id: Uuid,
+        snapshot: Option<bool>,
+    ) -> Result<(), RepositoryError> {
+        self.update(bot_id, |bot| bot.snapshot_on_hibernate = snapshot)
+    }
+
+    async fn update_hibernation_snapshot(
+        &self,
+        bot_id: Uuid,
+        snapshot_id: Option<i64>,
+    ) -> Result<(), RepositoryError> {
+        self.update(bot_id, |bot| bot.hibernation_snapshot_id = snapshot_id)
+    }
+
+    async fn update_config_version(
+        &self,
+        bot_id: Uuid,
+        desired: Option<Uuid>,
+        applied: Option<Uuid>,
+    ) -> Result<(), RepositoryError> {
+        self.update(bot_id, |bot| {
+            bot.desired_config_version_id = desired;
+            bot.applied_config_version_id = applied;
+        })
+    }
+
+    async fn update_heartbeat(&self, bot_id: Uuid) -> Result<(), RepositoryError> {
+        self.update(bot_id, |bot| bot.last_heartbeat_at = Some(Utc::now()))
+    }
+
+    async fn update_registration_token(
+        &self,
+        bot_id: Uuid,
+        token: &str,
+    ) -> Result<(), RepositoryError> {
+        let hashed_token = hash_registration_token(token);
+        self.update(bot_id, |bot| {
+            bot.registration_token = Some(hashed_token);
+            bot.registered_at = None;
+            bot.registered_ip = None;
+            bot.agent_version = None;
+        })
+    }
+
+    async fn reset_registration(&self, bot_id: Uuid) -> Result<(), RepositoryError> {
+        self.update(bot_id, |bot| {
+            bot.registered_at = None;
+            bot.registered_ip = None;
+            bot.agent_version = None;
+        })
+    }
+
+    async fn record_registration(
+        &self,
+        id: Uuid,
+        ip: Option<&str>,
+        agent_version: Option<&str>,
+    ) -> Result<(), RepositoryError> {
+        self.update(id, |bot| {
+            bot.registered_at.get_or_insert_with(Utc::now);
+            if let Some(ip) = ip {
+                bot.registered_ip = Some(ip.to_string());
+            }
+            if let Some(version) = agent_version {
+                bot.agent_version = Some(version.to_string());
+            }
+            bot.registration_timeouts = 0;
+        })
+    }
+
+    async fn list_registration_overdue(
+        &self,
+        threshold: DateTime<Utc>,
+    ) -> Result<Vec<Bot>, RepositoryError> {
+        Ok(self.select(|b| {
+            b.status == BotStatus::Provisioning
+                && b.registered_at.is_none()
+                && b.status_changed_at < threshold
+        }))
+    }
+
+    async fn increment_registration_timeouts(&self, id: Uuid) -> Result<i32, RepositoryError> {
+        self.update(id, |bot| {
+            bot.registration_timeouts += 1;
+            bot.registration_timeouts
+        })
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<(), RepositoryError> {
+        self.update(id, |bot| {
+            bot.status = BotStatus::Destroyed;
+            bot.status_reason = Some(TransitionReason::Destroy);
+            bot.status_detail = None;
+            bot.status_changed_at = Utc::now();
+        })
+    }
+
+    async fn hard_delete(&self, id: Uuid) -> Result<(), RepositoryError> {
+        let mut tables = self.store.write();
+        if tables.bots.remove(&id).is_none() {
+            return Err(RepositoryError::NotFound(format!("Bot {}", id)));
+        }
+
+        // ON DELETE CASCADE for configs, jobs and bootstrap reports, ON DELETE
+        // SET NULL for droplets.
+        tables.configs.retain(|_, c| c.bot_id != id);
+        tables.jobs.retain(|_, j| j.bot_id != id);
+        tables.bootstrap_events.retain(|e| e.bot_id != id);
+        tables.bootstrap_logs.retain(|l| l.bot_id != id);
+        for droplet in tables.droplets.values_mut() {
+            if droplet.bot_id == Some(id) {
+                droplet.bot_id = None;
+            }
+        }
+        Ok(())
+    }
+
+    async fn increment_bot_counter(
+        &self,
+        account_id: Uuid,
+    ) -> Result<(bool, i32, i32), RepositoryError> {
+        let mut tables = self.store.write();
+        let counter = tables.counters.get_mut(&account_id).ok_or_else(|| {
+            RepositoryError::NotFound(format!("Account counter for {}", account_id))
+        })?;
+
+        if counter.current >= counter.max {
+            return Ok((false, counter.current, counter.max));
+        }
+        counter.current += 1;
+        Ok((true, counter.current, counter.max))
+    }
+
+    async fn decrement_bot_counter(&self, account_id: Uuid) -> Result<(), RepositoryError> {
+        if let Some(counter) = self.store.write().counters.get_mut(&account_id) {
+            counter.current = (counter.current - 1).max(0);
+        }
+        Ok(())
+    }
+
+    async fn list_stale_bots(&self, threshold: DateTime<Utc>) -> Result<Vec<Bot>, RepositoryError> {
+        Ok(self.select(|b| {
+            b.status == BotStatus::Online && b.last_heartbeat_at.is_none_or(|at| at < threshold)
+        }))
+    }
+}
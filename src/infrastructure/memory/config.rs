@@ -0,0 +1,83 @@
+use super::MemoryStore;
+use crate::domain::StoredBotConfig;
+use crate::infrastructure::{ConfigRepository, RepositoryError};
+use async_trait::async_trait;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct InMemoryConfigRepository {
+    pub(super) store: MemoryStore,
+}
+
+#[async_trait]
+impl ConfigRepository for InMemoryConfigRepository {
+    async fn create(&self, config: &StoredBotConfig) -> Result<(), RepositoryError> {
+        let mut tables = self.store.write();
+        tables.ensure_bot_exists(config.bot_id)?;
+        if tables.configs.contains_key(&config.id)
+            || tables
+                .configs
+                .values()
+                .any(|c| c.bot_id == config.bot_id && c.version == config.version)
+        {
+            return Err(RepositoryError::Conflict(format!(
+                "Config version {} for bot {} already exists",
+                config.version, config.bot_id
+            )));
+        }
+
+        tables.configs.insert(config.id, config.clone());
+        Ok(())
+    }
+
+    async fn get_by_id(&self, id: Uuid) -> Result<StoredBotConfig, RepositoryError> {
+        self.store
+            .lock()
+            .configs
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| RepositoryError::NotFound(format!("Config {}", id)))
+    }
+
+    async fn get_latest_for_bot(
+        &self,
+        bot_id: Uuid,
+    ) -> Result<Option<StoredBotConfig>, RepositoryError> {
+        Ok(self
+            .store
+            .lock()
+            .configs
+            .values()
+            .filter(|c| c.bot_id == bot_id)
+            .max_by_key(|c| c.version)
+            .cloned())
+    }
+
+    async fn list_by_bot(&self, bot_id: Uuid) -> Result<Vec<StoredBotConfig>, RepositoryError> {
+        let mut configs: Vec<StoredBotConfig> = self
+            .store
+            .lock()
+            .configs
+            .values()
+            .filter(|c| c.bot_id == bot_id)
+            .cloned()
+            .collect();
+        configs.sort_by_key(|c| c.version);
+        Ok(configs)
+    }
+
+    async fn get_next_version_atomic(&self, bot_id: Uuid) -> Result<i32, RepositoryError> {
+        // Like the SQL function: one past the highest stored version, so a
+        // version only counts as taken once its config is created.
+        Ok(self
+            .store
+            .lock()
+            .configs
+            .values()
+            .filter(|c| c.bot_id == bot_id)
+            .map(|c| c.version)
+            .max()
+            .unwrap_or(0)
+            + 1)
+    }
+}
@@ -0,0 +1,71 @@
+use super::MemoryStore;
+use crate::domain::{Droplet, DropletStatus};
+use crate::infrastructure::{string_to_droplet_status, DropletRepository, RepositoryError};
+use async_trait::async_trait;
+use chrono::Utc;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct InMemoryDropletRepository {
+    pub(super) store: MemoryStore,
+}
+
+#[async_trait]
+impl DropletRepository for InMemoryDropletRepository {
+    async fn create(&self, droplet: &Droplet) -> Result<(), RepositoryError> {
+        let mut tables = self.store.write();
+        if let Some(bot_id) = droplet.bot_id {
+            tables.ensure_bot_exists(bot_id)?;
+        }
+        if tables.droplets.contains_key(&droplet.id) {
+            return Err(RepositoryError::Conflict(format!(
+                "Droplet {} already exists",
+                droplet.id
+            )));
+        }
+
+        tables.droplets.insert(droplet.id, droplet.clone());
+        Ok(())
+    }
+
+    async fn get_by_id(&self, id: i64) -> Result<Droplet, RepositoryError> {
+        self.store
+            .lock()
+            .droplets
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| RepositoryError::NotFound(format!("Droplet {}", id)))
+    }
+
+    async fn update_bot_assignment(
+        &self,
+        droplet_id: i64,
+        bot_id: Option<Uuid>,
+    ) -> Result<(), RepositoryError> {
+        let mut tables = self.store.write();
+        if let Some(bot_id) = bot_id {
+            tables.ensure_bot_exists(bot_id)?;
+        }
+        tables.droplet_mut(droplet_id)?.bot_id = bot_id;
+        Ok(())
+    }
+
+    async fn update_status(&self, droplet_id: i64, status: &str) -> Result<(), RepositoryError> {
+        let status = string_to_droplet_status(status)?;
+        self.store.write().droplet_mut(droplet_id)?.status = status;
+        Ok(())
+    }
+
+    async fn update_ip(&self, droplet_id: i64, ip: Option<String>) -> Result<(), RepositoryError> {
+        self.store.write().droplet_mut(droplet_id)?.ip_address = ip;
+        Ok(())
+    }
+
+    async fn mark_destroyed(&self, droplet_id: i64) -> Result<(), RepositoryError> {
+        let mut tables = self.store.write();
+        let droplet = tables.droplet_mut(droplet_id)?;
+        droplet.status = DropletStatus::Destroyed;
+        droplet.destroyed_at = Some(Utc::now());
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+use super::MemoryStore;
+use crate::domain::IdempotencyRecord;
+use crate::infrastructure::{IdempotencyRepository, RepositoryError};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+#[derive(Clone)]
+pub struct InMemoryIdempotencyRepository {
+    pub(super) store: MemoryStore,
+}
+
+#[async_trait]
+impl IdempotencyRepository for InMemoryIdempotencyRepository {
+    async fn reserve(
+        &self,
+        record: &IdempotencyRecord,
+        expired_before: DateTime<Utc>,
+        abandoned_before: DateTime<Utc>,
+    ) -> Result<Option<IdempotencyRecord>, RepositoryError> {
+        let mut tables = self.store.write();
+        // Only expired or abandoned rows are taken over, as in the SQL upsert.
+        if let Some(existing) = tables.idempotency_keys.get(&record.key) {
+            let replaceable = existing.created_at < expired_before
+                || (existing.completed_at.is_none() && existing.created_at < abandoned_before);
+            if !replaceable {
+                return Ok(Some(existing.clone()));
+            }
+        }
+
+        tables
+            .idempotency_keys
+            .insert(record.key.clone(), record.clone());
+        Ok(None)
+    }
+
+    async fn complete(
+        &self,
+        reservation: &IdempotencyRecord,
+        status: u16,
+        body: &[u8],
+    ) -> Result<(), RepositoryError> {
+        let mut tables = self.store.write();
+        let record = tables
+            .idempotency_keys
+            .get_mut(&reservation.key)
+            .filter(|record| record.reservation_id == reservation.reservation_id)
+            .ok_or_else(|| {
+                RepositoryError::Conflict(format!(
+                    "Idempotency key {} is no longer held by this request",
+                    reservation.key
+                ))
+            })?;
+
+        record.response_status = Some(status);
+        record.response_body = Some(body.to_vec());
+        record.completed_at = Some(Utc::now());
+        Ok(())
+    }
+
+    async fn release(&self, reservation: &IdempotencyRecord) -> Result<(), RepositoryError> {
+        let mut tables = self.store.write();
+        if tables
+            .idempotency_keys
+            .get(&reservation.key)
+            .is_some_and(|record| {
+                record.reservation_id == reservation.reservation_id && record.completed_at.is_none()
+            })
+        {
+            tables.idempotency_keys.remove(&reservation.key);
+        }
+        Ok(())
+    }
+
+    async fn purge_expired(&self, created_before: DateTime<Utc>) -> Result<u64, RepositoryError> {
+        let mut tables = self.store.write();
+        let before = tables.idempotency_keys.len();
+        tables
+            .idempotency_keys
+            .retain(|_, record| record.created_at >= created_before);
+        Ok((before - tables.idempotency_keys.len()) as u64)
+    }
+}
@@ -0,0 +1,116 @@
+use super::MemoryStore;
+use crate::domain::{Job, JobStatus};
+use crate::infrastructure::{JobRepository, RepositoryError};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct InMemoryJobRepository {
+    pub(super) store: MemoryStore,
+}
+
+impl InMemoryJobRepository {
+    /// Apply `f` to the job if `job` still holds it, as the SQL `WHERE` clauses check.
+    fn update_held(&self, job: &Job, f: impl FnOnce(&mut Job)) -> Result<(), RepositoryError> {
+        let mut tables = self.store.write();
+        let stored = tables
+            .jobs
+            .get_mut(&job.id)
+            .filter(|stored| stored.status == JobStatus::Running && stored.attempts == job.attempts)
+            .ok_or_else(|| {
+                RepositoryError::Conflict(format!(
+                    "Job {} is no longer held by this worker (attempt {})",
+                    job.id, job.attempts
+                ))
+            })?;
+
+        f(stored);
+        stored.locked_until = None;
+        stored.updated_at = Utc::now();
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl JobRepository for InMemoryJobRepository {
+    async fn enqueue(&self, job: &Job) -> Result<(), RepositoryError> {
+        let mut tables = self.store.write();
+        tables.ensure_bot_exists(job.bot_id)?;
+        // idx_jobs_one_active_per_bot
+        if tables
+            .jobs
+            .values()
+            .any(|j| j.bot_id == job.bot_id && !j.is_finished())
+        {
+            return Err(RepositoryError::Conflict(format!(
+                "Bot {} already has a job in progress",
+                job.bot_id
+            )));
+        }
+
+        tables.jobs.insert(job.id, job.clone());
+        Ok(())
+    }
+
+    async fn get_by_id(&self, id: Uuid) -> Result<Job, RepositoryError> {
+        self.store
+            .lock()
+            .jobs
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| RepositoryError::NotFound(format!("Job {}", id)))
+    }
+
+    async fn claim_next(&self, lease: chrono::Duration) -> Result<Option<Job>, RepositoryError> {
+        let mut tables = self.store.write();
+        let now = Utc::now();
+        let Some(job) = tables
+            .jobs
+            .values_mut()
+            .filter(|j| match j.status {
+                JobStatus::Queued => j.run_at <= now,
+                JobStatus::Running => j.locked_until.is_some_and(|until| until < now),
+                _ => false,
+            })
+            .min_by_key(|j| j.run_at)
+        else {
+            return Ok(None);
+        };
+
+        job.status = JobStatus::Running;
+        job.attempts += 1;
+        job.locked_until = Some(now + lease);
+        job.updated_at = now;
+        Ok(Some(job.clone()))
+    }
+
+    async fn mark_succeeded(&self, job: &Job) -> Result<(), RepositoryError> {
+        self.update_held(job, |stored| {
+            stored.status = JobStatus::Succeeded;
+            stored.last_error = None;
+            stored.completed_at = Some(Utc::now());
+        })
+    }
+
+    async fn schedule_retry(
+        &self,
+        job: &Job,
+        error: &str,
+        run_at: DateTime<Utc>,
+    ) -> Result<(), RepositoryError> {
+        self.update_held(job, |stored| {
+            stored.status = JobStatus::Queued;
+            stored.last_error = Some(error.to_string());
+            stored.run_at = run_at;
+        })
+    }
+
+    async fn mark_failed(&self, job: &Job, error: &str) -> Result<(), RepositoryError> {
+        self.update_held(job, |stored| {
+            stored.status = JobStatus::Failed;
+            stored.last_error = Some(error.to_string());
+            stored.completed_at = Some(Utc::now());
+        })
+    }
+}
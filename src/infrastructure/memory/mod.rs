@@ -0,0 +1,231 @@
+//! In-memory repositories (`memory` feature) for tests, demos and embedders
+//! that run without Postgres.
+//!
+//! Repositories handed out by one [`MemoryStore`] share its tables, so they
+//! behave like the Postgres ones over a single database: accounts own bot
+//! counters, bots and configs reference existing rows, and hard-deleting a bot
+//! removes its configs, jobs and bootstrap reports and unassigns its droplets.
+//! The job queue, `provider_operations` outbox and idempotency keys live in the
+//! same tables.
+
+mod account;
+mod bootstrap;
+mod bot;
+mod config;
+mod droplet;
+mod idempotency;
+mod job;
+mod provider_operation;
+mod unit_of_work;
+
+pub use account::InMemoryAccountRepository;
+pub use bootstrap::{InMemoryBootstrapEventRepository, InMemoryBootstrapLogRepository};
+pub use bot::InMemoryBotRepository;
+pub use config::InMemoryConfigRepository;
+pub use droplet::InMemoryDropletRepository;
+pub use idempotency::InMemoryIdempotencyRepository;
+pub use job::InMemoryJobRepository;
+pub use provider_operation::InMemoryProviderOperationRepository;
+
+use crate::domain::{
+    Account, BootstrapEvent, BootstrapLog, Bot, Droplet, IdempotencyRecord, Job, ProviderOperation,
+    ProviderOperationStatus, StoredBotConfig,
+};
+use crate::infrastructure::{RepositoryError, StorageHealth};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use uuid::Uuid;
+
+/// Mirrors `account_bot_counters`.
+#[derive(Debug, Clone, Copy)]
+struct BotCounter {
+    current: i32,
+    max: i32,
+}
+
+#[derive(Clone, Default)]
+struct Tables {
+    /// Bumped by every write, so a unit of work can tell whether the tables
+    /// changed after it took its copy.
+    generation: u64,
+    accounts: HashMap<Uuid, Account>,
+    counters: HashMap<Uuid, BotCounter>,
+    bots: HashMap<Uuid, Bot>,
+    configs: HashMap<Uuid, StoredBotConfig>,
+    droplets: HashMap<i64, Droplet>,
+    provider_operations: HashMap<Uuid, ProviderOperation>,
+    jobs: HashMap<Uuid, Job>,
+    idempotency_keys: HashMap<String, IdempotencyRecord>,
+    bootstrap_events: Vec<BootstrapEvent>,
+    bootstrap_logs: Vec<BootstrapLog>,
+}
+
+impl Tables {
+    fn bot_mut(&mut self, id: Uuid) -> Result<&mut Bot, RepositoryError> {
+        self.bots
+            .get_mut(&id)
+            .ok_or_else(|| RepositoryError::NotFound(format!("Bot {}", id)))
+    }
+
+    fn droplet_mut(&mut self, id: i64) -> Result<&mut Droplet, RepositoryError> {
+        self.droplets
+            .get_mut(&id)
+            .ok_or_else(|| RepositoryError::NotFound(format!("Droplet {}", id)))
+    }
+
+    /// The operation if `operation` still holds it, as the SQL `WHERE` clauses check.
+    fn held_operation_mut(
+        &mut self,
+        operation: &ProviderOperation,
+    ) -> Result<&mut ProviderOperation, RepositoryError> {
+        self.provider_operations
+            .get_mut(&operation.id)
+            .filter(|stored| {
+                stored.status == ProviderOperationStatus::Pending
+                    && stored.attempts == operation.attempts
+            })
+            .ok_or_else(|| {
+                RepositoryError::Conflict(format!(
+                    "Provider operation {} is no longer held by this caller (attempt {})",
+                    operation.id, operation.attempts
+                ))
+            })
+    }
+
+    fn ensure_bot_exists(&self, id: Uuid) -> Result<(), RepositoryError> {
+        if !self.bots.contains_key(&id) {
+            return Err(RepositoryError::InvalidData(format!(
+                "Bot {} does not exist",
+                id
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Shared tables behind the in-memory repositories.
+#[derive(Clone, Default)]
+pub struct MemoryStore {
+    tables: Arc<Mutex<Tables>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn accounts(&self) -> InMemoryAccountRepository {
+        InMemoryAccountRepository {
+            store: self.clone(),
+        }
+    }
+
+    pub fn bots(&self) -> InMemoryBotRepository {
+        InMemoryBotRepository {
+            store: self.clone(),
+        }
+    }
+
+    pub fn configs(&self) -> InMemoryConfigRepository {
+        InMemoryConfigRepository {
+            store: self.clone(),
+        }
+    }
+
+    pub fn droplets(&self) -> InMemoryDropletRepository {
+        InMemoryDropletRepository {
+            store: self.clone(),
+        }
+    }
+
+    pub fn provider_operations(&self) -> InMemoryProviderOperationRepository {
+        InMemoryProviderOperationRepository {
+            store: self.clone(),
+        }
+    }
+
+    pub fn jobs(&self) -> InMemoryJobRepository {
+        InMemoryJobRepository {
+            store: self.clone(),
+        }
+    }
+
+    pub fn idempotency(&self) -> InMemoryIdempotencyRepository {
+        InMemoryIdempotencyRepository {
+            store: self.clone(),
+        }
+    }
+
+    pub fn bootstrap_events(&self) -> InMemoryBootstrapEventRepository {
+        InMemoryBootstrapEventRepository {
+            store: self.clone(),
+        }
+    }
+
+    pub fn bootstrap_logs(&self) -> InMemoryBootstrapLogRepository {
+        InMemoryBootstrapLogRepository {
+            store: self.clone(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Tables> {
+        self.tables.lock().expect("memory store lock poisoned")
+    }
+
+    fn write(&self) -> MutexGuard<'_, Tables> {
+        let mut tables = self.lock();
+        tables.generation += 1;
+        tables
+    }
+}
+
+#[async_trait]
+impl StorageHealth for MemoryStore {
+    async fn ping(&self) -> Result<(), RepositoryError> {
+        Ok(())
+    }
+}
+
+/// Newest first, then `offset`/`limit` as in the SQL queries.
+fn page<T>(
+    mut rows: Vec<T>,
+    created_at: fn(&T) -> DateTime<Utc>,
+    limit: i64,
+    offset: i64,
+) -> Vec<T> {
+    rows.sort_by_key(|row| std::cmp::Reverse(created_at(row)));
+    rows.into_iter()
+        .skip(offset.max(0) as usize)
+        .take(limit.max(0) as usize)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Persona, SubscriptionTier};
+    use crate::infrastructure::{AccountRepository, BotRepository, UnitOfWork};
+
+    // Shared behaviour is covered by `infrastructure::conformance`; this is the
+    // store's own optimistic check on commit.
+    #[tokio::test]
+    async fn commit_conflicts_with_writes_made_since_begin() {
+        let store = MemoryStore::new();
+        let account = Account::new("user".to_string(), SubscriptionTier::Basic);
+        store.accounts().create(&account).await.unwrap();
+        let bot = Bot::new(account.id, "Bot".to_string(), Persona::Beginner);
+        store.bots().create(&bot).await.unwrap();
+
+        let tx = store.begin().await.unwrap();
+        tx.bots().delete(bot.id).await.unwrap();
+        store.bots().update_heartbeat(bot.id).await.unwrap();
+        let err = tx.commit().await.unwrap_err();
+        assert!(matches!(err, RepositoryError::Conflict(_)));
+        assert_eq!(
+            store.bots().get_by_id(bot.id).await.unwrap().status,
+            bot.status
+        );
+    }
+}
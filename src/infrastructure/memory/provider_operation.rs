@@ -0,0 +1,85 @@
+use super::MemoryStore;
+use crate::domain::{ProviderOperation, ProviderOperationKind, ProviderOperationStatus};
+use crate::infrastructure::{ProviderOperationRepository, RepositoryError};
+use async_trait::async_trait;
+use chrono::Utc;
+
+#[derive(Clone)]
+pub struct InMemoryProviderOperationRepository {
+    pub(super) store: MemoryStore,
+}
+
+#[async_trait]
+impl ProviderOperationRepository for InMemoryProviderOperationRepository {
+    async fn create(&self, operation: &ProviderOperation) -> Result<(), RepositoryError> {
+        let mut tables = self.store.write();
+        let creates_pending = |op: &ProviderOperation| {
+            op.bot_id == operation.bot_id
+                && op.kind == ProviderOperationKind::CreateDroplet
+                && op.status == ProviderOperationStatus::Pending
+        };
+        if creates_pending(operation) && tables.provider_operations.values().any(creates_pending) {
+            return Err(RepositoryError::Conflict(format!(
+                "Bot {} already has a droplet create pending",
+                operation.bot_id
+            )));
+        }
+
+        tables
+            .provider_operations
+            .insert(operation.id, operation.clone());
+        Ok(())
+    }
+
+    async fn claim_expired(
+        &self,
+        lease: chrono::Duration,
+    ) -> Result<Option<ProviderOperation>, RepositoryError> {
+        let mut tables = self.store.write();
+        let now = Utc::now();
+        let Some(operation) = tables
+            .provider_operations
+            .values_mut()
+            .filter(|op| op.status == ProviderOperationStatus::Pending && op.locked_until < now)
+            .min_by_key(|op| op.locked_until)
+        else {
+            return Ok(None);
+        };
+
+        operation.attempts += 1;
+        operation.locked_until = now + lease;
+        operation.updated_at = now;
+        Ok(Some(operation.clone()))
+    }
+
+    async fn mark_completed(&self, operation: &ProviderOperation) -> Result<(), RepositoryError> {
+        let mut tables = self.store.write();
+        let stored = tables.held_operation_mut(operation)?;
+        stored.status = ProviderOperationStatus::Completed;
+        stored.last_error = None;
+        stored.completed_at = Some(Utc::now());
+        Ok(())
+    }
+
+    async fn mark_failed(
+        &self,
+        operation: &ProviderOperation,
+        error: &str,
+    ) -> Result<(), RepositoryError> {
+        let mut tables = self.store.write();
+        let stored = tables.held_operation_mut(operation)?;
+        stored.status = ProviderOperationStatus::Failed;
+        stored.last_error = Some(error.to_string());
+        stored.completed_at = Some(Utc::now());
+        Ok(())
+    }
+
+    async fn record_error(
+        &self,
+        operation: &ProviderOperation,
+        error: &str,
+    ) -> Result<(), RepositoryError> {
+        self.store.write().held_operation_mut(operation)?.last_error = Some(error.to_string());
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+use super::{
+    InMemoryBotRepository, InMemoryConfigRepository, InMemoryDropletRepository,
+    InMemoryJobRepository, InMemoryProviderOperationRepository, MemoryStore,
+};
+use crate::infrastructure::{
+    BotRepository, ConfigRepository, DropletRepository, JobRepository,
+    ProviderOperationRepository, RepositoryError, RepositoryTransaction, UnitOfWork,
+};
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+
+/// Transactions work on a private copy of the tables and swap it in on commit.
+/// A commit fails with [`RepositoryError::Conflict`] if anything else wrote to
+/// the store in the meantime, standing in for a serialization failure.
+#[async_trait]
+impl UnitOfWork for MemoryStore {
+    async fn begin(&self) -> Result<Box<dyn RepositoryTransaction>, RepositoryError> {
+        let tables = self.lock().clone();
+        let working = MemoryStore {
+            tables: Arc::new(Mutex::new(tables.clone())),
+        };
+        Ok(Box::new(MemoryTransaction {
+            origin: self.clone(),
+            generation: tables.generation,
+            bots: working.bots(),
+            configs: working.configs(),
+            droplets: working.droplets(),
+            jobs: working.jobs(),
+            provider_operations: working.provider_operations(),
+            working,
+        }))
+    }
+}
+
+struct MemoryTransaction {
+    origin: MemoryStore,
+    generation: u64,
+    working: MemoryStore,
+    bots: InMemoryBotRepository,
+    configs: InMemoryConfigRepository,
+    droplets: InMemoryDropletRepository,
+    jobs: InMemoryJobRepository,
+    provider_operations: InMemoryProviderOperationRepository,
+}
+
+#[async_trait]
+impl RepositoryTransaction for MemoryTransaction {
+    fn bots(&self) -> &dyn BotRepository {
+        &self.bots
+    }
+
+    fn configs(&self) -> &dyn ConfigRepository {
+        &self.configs
+    }
+
+    fn droplets(&self) -> &dyn DropletRepository {
+        &self.droplets
+    }
+
+    fn jobs(&self) -> &dyn JobRepository {
+        &self.jobs
+    }
+
+    fn provider_operations(&self) -> &dyn ProviderOperationRepository {
+        &self.provider_operations
+    }
+
+    async fn commit(self: Box<Self>) -> Result<(), RepositoryError> {
+        let mut tables = self.origin.lock();
+        if tables.generation != self.generation {
+            return Err(RepositoryError::Conflict(
+                "Memory store changed during the transaction".to_string(),
+            ));
+        }
+        *tables = self.working.lock().clone();
+        tables.generation = self.generation + 1;
+        Ok(())
+    }
+}
@@ -1,13 +1,37 @@
 pub mod config;
+#[cfg(test)]
+mod conformance;
 pub mod crypto;
 pub mod digital_ocean;
+pub mod digital_ocean_rate_limit;
+#[cfg(test)]
+pub(crate) mod digital_ocean_stub;
+#[cfg(any(test, feature = "memory"))]
+pub mod memory;
+pub mod postgres_bootstrap_repo;
 pub mod postgres_config_repo;
 pub mod postgres_droplet_repo;
+pub mod postgres_idempotency_repo;
+pub mod postgres_job_repo;
+pub mod postgres_provider_operation_repo;
+pub mod postgres_unit_of_work;
 pub mod repository;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
 
 pub use config::*;
 pub use crypto::*;
 pub use digital_ocean::*;
+pub use digital_ocean_rate_limit::RateLimitBudget;
+#[cfg(any(test, feature = "memory"))]
+pub use memory::*;
+pub use postgres_bootstrap_repo::*;
 pub use postgres_config_repo::*;
 pub use postgres_droplet_repo::*;
+pub use postgres_idempotency_repo::*;
+pub use postgres_job_repo::*;
+pub use postgres_provider_operation_repo::*;
+pub use postgres_unit_of_work::*;
 pub use repository::*;
+#[cfg(feature = "sqlite")]
+pub use sqlite::*;
@@ -0,0 +1,172 @@
+use crate::domain::{BootstrapEvent, BootstrapLog, BootstrapLogSource, BootstrapPhase};
+use crate::infrastructure::{BootstrapEventRepository, BootstrapLogRepository, RepositoryError};
+use async_trait::async_trait;
+use sqlx::{PgPool, Row};
+use std::str::FromStr;
+use tracing::instrument;
+use uuid::Uuid;
+
+pub struct PostgresBootstrapEventRepository {
+    pool: PgPool,
+}
+
+impl PostgresBootstrapEventRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl BootstrapEventRepository for PostgresBootstrapEventRepository {
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bootstrap_events"))]
+    async fn record(&self, event: &BootstrapEvent) -> Result<(), RepositoryError> {
+        sqlx::query(
+            r#"
+            INSERT INTO bootstrap_events (id, bot_id, phase, exit_code, message, reported_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(event.id)
+        .bind(event.bot_id)
+        .bind(event.phase.to_string())
+        .bind(event.exit_code)
+        .bind(&event.message)
+        .bind(event.reported_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bootstrap_events"))]
+    async fn list_for_bot(
+        &self,
+        bot_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<BootstrapEvent>, RepositoryError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, bot_id, phase, exit_code, message, reported_at
+            FROM (
+                SELECT id, bot_id, phase, exit_code, message, reported_at
+                FROM bootstrap_events
+                WHERE bot_id = $1
+                ORDER BY reported_at DESC
+                LIMIT $2
+            ) recent
+            ORDER BY reported_at ASC
+            "#,
+        )
+        .bind(bot_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(row_to_event).collect()
+    }
+}
+
+pub struct PostgresBootstrapLogRepository {
+    pool: PgPool,
+}
+
+impl PostgresBootstrapLogRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl BootstrapLogRepository for PostgresBootstrapLogRepository {
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bootstrap_logs"))]
+    async fn record(&self, log: &BootstrapLog, keep: i64) -> Result<(), RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            r#"
+            INSERT INTO bootstrap_logs (id, bot_id, droplet_id, source, content, uploaded_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(log.id)
+        .bind(log.bot_id)
+        .bind(log.droplet_id)
+        .bind(log.source.to_string())
+        .bind(&log.content)
+        .bind(log.uploaded_at)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM bootstrap_logs
+            WHERE bot_id = $1
+              AND id NOT IN (
+                  SELECT id FROM bootstrap_logs
+                  WHERE bot_id = $1
+                  ORDER BY uploaded_at DESC, id DESC
+                  LIMIT $2
+              )
+            "#,
+        )
+        .bind(log.bot_id)
+        .bind(keep)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bootstrap_logs"))]
+    async fn list_for_bot(
+        &self,
+        bot_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<BootstrapLog>, RepositoryError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, bot_id, droplet_id, source, content, uploaded_at
+            FROM bootstrap_logs
+            WHERE bot_id = $1
+            ORDER BY uploaded_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(bot_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(row_to_log).collect()
+    }
+}
+
+fn row_to_event(row: &sqlx::postgres::PgRow) -> Result<BootstrapEvent, RepositoryError> {
+    let phase_str: String = row.try_get("phase")?;
+
+    Ok(BootstrapEvent {
+        id: row.try_get("id")?,
+        bot_id: row.try_get("bot_id")?,
+        phase: BootstrapPhase::from_str(&phase_str).map_err(|_| {
+            RepositoryError::InvalidData(format!("Unknown bootstrap phase: {}", phase_str))
+        })?,
+        exit_code: row.try_get("exit_code")?,
+        message: row.try_get("message")?,
+        reported_at: row.try_get("reported_at")?,
+    })
+}
+
+fn row_to_log(row: &sqlx::postgres::PgRow) -> Result<BootstrapLog, RepositoryError> {
+    let source_str: String = row.try_get("source")?;
+
+    Ok(BootstrapLog {
+        id: row.try_get("id")?,
+        bot_id: row.try_get("bot_id")?,
+        droplet_id: row.try_get("droplet_id")?,
+        source: BootstrapLogSource::from_str(&source_str).map_err(|_| {
+            RepositoryError::InvalidData(format!("Unknown bootstrap log source: {}", source_str))
+        })?,
+        content: row.try_get("content")?,
+        uploaded_at: row.try_get("uploaded_at")?,
+    })
+}
@@ -1,21 +1,30 @@
 use crate::domain::{EncryptedBotSecrets, RiskConfig, StoredBotConfig, TradingConfig};
+use crate::infrastructure::postgres_unit_of_work::PgHandle;
 use crate::infrastructure::{ConfigRepository, RepositoryError};
 use async_trait::async_trait;
 use sqlx::{PgPool, Row};
+use tracing::instrument;
 use uuid::Uuid;
 
 pub struct PostgresConfigRepository {
-    pool: PgPool,
+    db: PgHandle,
 }
 
 impl PostgresConfigRepository {
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            db: PgHandle::Pool(pool),
+        }
+    }
+
+    pub(crate) fn with_handle(db: PgHandle) -> Self {
+        Self { db }
     }
 }
 
 #[async_trait]
 impl ConfigRepository for PostgresConfigRepository {
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bot_configs"))]
     async fn create(&self, config: &StoredBotConfig) -> Result<(), RepositoryError> {
         let trading_json = serde_json::to_value(&config.trading_config).map_err(|e| {
             RepositoryError::InvalidData(format!("Failed to serialize trading config: {}", e))
@@ -38,12 +47,13 @@ impl ConfigRepository for PostgresConfigRepository {
         .bind(&config.secrets.llm_api_key_encrypted)
         .bind(&config.secrets.llm_provider)
         .bind(config.created_at)
-        .execute(&self.pool)
+        .execute(&mut *self.db.acquire().await?)
         .await?;
 
         Ok(())
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bot_configs"))]
     async fn get_by_id(&self, id: Uuid) -> Result<StoredBotConfig, RepositoryError> {
         let row = sqlx::query(
             r#"
@@ -53,7 +63,7 @@ impl ConfigRepository for PostgresConfigRepository {
             "#,
         )
         .bind(id)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *self.db.acquire().await?)
         .await
         .map_err(|e| match e {
             sqlx::Error::RowNotFound => RepositoryError::NotFound(format!("Config {}", id)),
@@ -63,6 +73,7 @@ impl ConfigRepository for PostgresConfigRepository {
         Ok(row_to_config(&row)?)
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bot_configs"))]
     async fn get_latest_for_bot(
         &self,
         bot_id: Uuid,
@@ -77,7 +88,7 @@ impl ConfigRepository for PostgresConfigRepository {
             "#,
         )
         .bind(bot_id)
-        .fetch_optional(&self.pool)
+        .fetch_optional(&mut *self.db.acquire().await?)
         .await?;
 
         match row {
@@ -86,6 +97,7 @@ impl ConfigRepository for PostgresConfigRepository {
         }
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bot_configs"))]
     async fn list_by_bot(&self, bot_id: Uuid) -> Result<Vec<StoredBotConfig>, RepositoryError> {
         let rows = sqlx::query(
             r#"
@@ -96,12 +108,13 @@ impl ConfigRepository for PostgresConfigRepository {
             "#,
         )
         .bind(bot_id)
-        .fetch_all(&self.pool)
+        .fetch_all(&mut *self.db.acquire().await?)
         .await?;
 
         rows.iter().map(row_to_config).collect()
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bot_configs"))]
     async fn get_next_version_atomic(&self, bot_id: Uuid) -> Result<i32, RepositoryError> {
         let row = sqlx::query(
             r#"
@@ -109,7 +122,7 @@ impl ConfigRepository for PostgresConfigRepository {
             "#,
         )
         .bind(bot_id)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *self.db.acquire().await?)
         .await?;
 
         let version: i32 = row.try_get("version")?;
@@ -1,16 +1,24 @@
 use crate::domain::{Droplet, DropletStatus};
+use crate::infrastructure::postgres_unit_of_work::PgHandle;
 use crate::infrastructure::{DropletRepository, RepositoryError};
 use async_trait::async_trait;
 use sqlx::{postgres::PgQueryResult, PgPool, Row};
+use tracing::instrument;
 use uuid::Uuid;
 
 pub struct PostgresDropletRepository {
-    pool: PgPool,
+    db: PgHandle,
 }
 
 impl PostgresDropletRepository {
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            db: PgHandle::Pool(pool),
+        }
+    }
+
+    pub(crate) fn with_handle(db: PgHandle) -> Self {
+        Self { db }
     }
 }
 
@@ -27,13 +35,14 @@ fn ensure_single_row_affected(
 
 #[async_trait]
 impl DropletRepository for PostgresDropletRepository {
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "droplets"))]
     async fn create(&self, droplet: &Droplet) -> Result<(), RepositoryError> {
         let status_str = droplet_status_to_string(&droplet.status);
 
         sqlx::query(
             r#"
-            INSERT INTO droplets (id, name, region, size, image, status, ip_address, bot_id, created_at, destroyed_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            INSERT INTO droplets (id, name, region, size, image, status, ip_address, bot_id, created_at, destroyed_at, toolchain_profile, user_data_digest)
+            VALUES ($1, $2, $3, $4, $5, $6, $7::inet, $8, $9, $10, $11, $12)
             "#,
         )
         .bind(droplet.id)
@@ -46,22 +55,26 @@ impl DropletRepository for PostgresDropletRepository {
         .bind(droplet.bot_id)
         .bind(droplet.created_at)
         .bind(droplet.destroyed_at)
-        .execute(&self.pool)
+        .bind(&droplet.toolchain_profile)
+        .bind(&droplet.user_data_digest)
+        .execute(&mut *self.db.acquire().await?)
         .await?;
 
         Ok(())
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "droplets"))]
     async fn get_by_id(&self, id: i64) -> Result<Droplet, RepositoryError> {
         let row = sqlx::query(
             r#"
-            SELECT id, name, region, size, image, status, ip_address, bot_id, created_at, destroyed_at
+            SELECT id, name, region, size, image, status, host(ip_address) AS ip_address, bot_id,
+                   created_at, destroyed_at, toolchain_profile, user_data_digest
             FROM droplets
             WHERE id = $1
             "#,
         )
         .bind(id)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *self.db.acquire().await?)
         .await
         .map_err(|e| match e {
             sqlx::Error::RowNotFound => RepositoryError::NotFound(format!("Droplet {}", id)),
@@ -71,6 +84,7 @@ impl DropletRepository for PostgresDropletRepository {
         Ok(row_to_droplet(&row)?)
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "droplets"))]
     async fn update_bot_assignment(
         &self,
         droplet_id: i64,
@@ -85,13 +99,14 @@ impl DropletRepository for PostgresDropletRepository {
         )
         .bind(bot_id)
         .bind(droplet_id)
-        .execute(&self.pool)
+        .execute(&mut *self.db.acquire().await?)
         .await?;
 
         ensure_single_row_affected(result, "Droplet", droplet_id)?;
         Ok(())
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "droplets"))]
     async fn update_status(&self, droplet_id: i64, status: &str) -> Result<(), RepositoryError> {
         let result = sqlx::query(
             r#"
@@ -102,30 +117,32 @@ impl DropletRepository for PostgresDropletRepository {
         )
         .bind(status)
         .bind(droplet_id)
-        .execute(&self.pool)
+        .execute(&mut *self.db.acquire().await?)
         .await?;
 
         ensure_single_row_affected(result, "Droplet", droplet_id)?;
         Ok(())
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "droplets"))]
     async fn update_ip(&self, droplet_id: i64, ip: Option<String>) -> Result<(), RepositoryError> {
         let result = sqlx::query(
             r#"
             UPDATE droplets
-            SET ip_address = $1
+            SET ip_address = $1::inet
             WHERE id = $2
             "#,
         )
         .bind(ip)
         .bind(droplet_id)
-        .execute(&self.pool)
+        .execute(&mut *self.db.acquire().await?)
         .await?;
 
         ensure_single_row_affected(result, "Droplet", droplet_id)?;
         Ok(())
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "droplets"))]
     async fn mark_destroyed(&self, droplet_id: i64) -> Result<(), RepositoryError> {
         let result = sqlx::query(
             r#"
@@ -136,7 +153,7 @@ impl DropletRepository for PostgresDropletRepository {
         )
         .bind(chrono::Utc::now())
         .bind(droplet_id)
-        .execute(&self.pool)
+        .execute(&mut *self.db.acquire().await?)
         .await?;
 
         ensure_single_row_affected(result, "Droplet", droplet_id)?;
@@ -144,7 +161,7 @@ impl DropletRepository for PostgresDropletRepository {
     }
 }
 
-fn droplet_status_to_string(status: &DropletStatus) -> String {
+pub(crate) fn droplet_status_to_string(status: &DropletStatus) -> String {
     match status {
         DropletStatus::New => "new".to_string(),
         DropletStatus::Active => "active".to_string(),
@@ -154,7 +171,7 @@ fn droplet_status_to_string(status: &DropletStatus) -> String {
     }
 }
 
-fn string_to_droplet_status(status: &str) -> Result<DropletStatus, RepositoryError> {
+pub(crate) fn string_to_droplet_status(status: &str) -> Result<DropletStatus, RepositoryError> {
     match status {
         "new" => Ok(DropletStatus::New),
         "active" => Ok(DropletStatus::Active),
@@ -182,5 +199,7 @@ fn row_to_droplet(row: &sqlx::postgres::PgRow) -> Result<Droplet, RepositoryErro
         bot_id: row.try_get("bot_id")?,
         created_at: row.try_get("created_at")?,
         destroyed_at: row.try_get("destroyed_at")?,
+        toolchain_profile: row.try_get("toolchain_profile")?,
+        user_data_digest: row.try_get("user_data_digest")?,
     })
 }
@@ -0,0 +1,153 @@
+use crate::domain::IdempotencyRecord;
+use crate::infrastructure::{IdempotencyRepository, RepositoryError};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row};
+use tracing::instrument;
+
+pub struct PostgresIdempotencyRepository {
+    pool: PgPool,
+}
+
+impl PostgresIdempotencyRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl IdempotencyRepository for PostgresIdempotencyRepository {
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "idempotency_keys"))]
+    async fn reserve(
+        &self,
+        record: &IdempotencyRecord,
+        expired_before: DateTime<Utc>,
+        abandoned_before: DateTime<Utc>,
+    ) -> Result<Option<IdempotencyRecord>, RepositoryError> {
+        // The conditional upsert only takes over rows that are expired or abandoned,
+        // so exactly one of several concurrent callers gets a row back.
+        let reserved = sqlx::query(
+            r#"
+            INSERT INTO idempotency_keys (key, request_fingerprint, reservation_id, created_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (key) DO UPDATE
+            SET request_fingerprint = EXCLUDED.request_fingerprint,
+                reservation_id = EXCLUDED.reservation_id,
+                response_status = NULL,
+                response_body = NULL,
+                created_at = EXCLUDED.created_at,
+                completed_at = NULL
+            WHERE idempotency_keys.created_at < $5
+               OR (idempotency_keys.completed_at IS NULL AND idempotency_keys.created_at < $6)
+            RETURNING key
+            "#,
+        )
+        .bind(&record.key)
+        .bind(&record.request_fingerprint)
+        .bind(record.reservation_id)
+        .bind(record.created_at)
+        .bind(expired_before)
+        .bind(abandoned_before)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        if reserved.is_some() {
+            return Ok(None);
+        }
+
+        let row = sqlx::query(
+            r#"
+            SELECT key, request_fingerprint, reservation_id, response_status, response_body, created_at, completed_at
+            FROM idempotency_keys
+            WHERE key = $1
+            "#,
+        )
+        .bind(&record.key)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| match e {
+            // Released between our insert attempt and this read; the caller may retry.
+            sqlx::Error::RowNotFound => {
+                RepositoryError::Conflict(format!("Idempotency key {} changed hands", record.key))
+            }
+            _ => RepositoryError::DatabaseError(e),
+        })?;
+
+        Ok(Some(row_to_record(&row)?))
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "idempotency_keys"))]
+    async fn complete(
+        &self,
+        reservation: &IdempotencyRecord,
+        status: u16,
+        body: &[u8],
+    ) -> Result<(), RepositoryError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE idempotency_keys
+            SET response_status = $2, response_body = $3, completed_at = NOW()
+            WHERE key = $1 AND reservation_id = $4
+            "#,
+        )
+        .bind(&reservation.key)
+        .bind(i32::from(status))
+        .bind(body)
+        .bind(reservation.reservation_id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::Conflict(format!(
+                "Idempotency key {} is no longer held by this request",
+                reservation.key
+            )));
+        }
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "idempotency_keys"))]
+    async fn release(&self, reservation: &IdempotencyRecord) -> Result<(), RepositoryError> {
+        sqlx::query(
+            r#"
+            DELETE FROM idempotency_keys
+            WHERE key = $1 AND reservation_id = $2 AND completed_at IS NULL
+            "#,
+        )
+        .bind(&reservation.key)
+        .bind(reservation.reservation_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "idempotency_keys"))]
+    async fn purge_expired(&self, created_before: DateTime<Utc>) -> Result<u64, RepositoryError> {
+        let result = sqlx::query("DELETE FROM idempotency_keys WHERE created_at < $1")
+            .bind(created_before)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+fn row_to_record(row: &sqlx::postgres::PgRow) -> Result<IdempotencyRecord, RepositoryError> {
+    let status: Option<i32> = row.try_get("response_status")?;
+    let response_status = status
+        .map(|s| {
+            u16::try_from(s).map_err(|_| {
+                RepositoryError::InvalidData(format!("Invalid stored response status: {s}"))
+            })
+        })
+        .transpose()?;
+
+    Ok(IdempotencyRecord {
+        key: row.try_get("key")?,
+        request_fingerprint: row.try_get("request_fingerprint")?,
+        reservation_id: row.try_get("reservation_id")?,
+        response_status,
+        response_body: row.try_get("response_body")?,
+        created_at: row.try_get("created_at")?,
+        completed_at: row.try_get("completed_at")?,
+    })
+}
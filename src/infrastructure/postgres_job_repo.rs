@@ -0,0 +1,212 @@
+use crate::domain::{Job, JobKind, JobStatus};
+use crate::infrastructure::{JobRepository, PgHandle, RepositoryError};
+use async_trait::async_trait;
+use sqlx::{postgres::PgQueryResult, PgPool, Row};
+use std::str::FromStr;
+use tracing::instrument;
+use uuid::Uuid;
+
+/// Unique index enforcing one unfinished job per bot (see migration 009).
+const ONE_ACTIVE_PER_BOT_INDEX: &str = "idx_jobs_one_active_per_bot";
+
+const JOB_COLUMNS: &str = "id, kind, bot_id, status, attempts, max_attempts, run_at, \
+     locked_until, last_error, created_at, updated_at, completed_at";
+
+pub struct PostgresJobRepository {
+    db: PgHandle,
+}
+
+impl PostgresJobRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            db: PgHandle::Pool(pool),
+        }
+    }
+
+    pub(crate) fn with_handle(db: PgHandle) -> Self {
+        Self { db }
+    }
+}
+
+/// A zero-row update means another worker reclaimed the job after our lease expired.
+fn ensure_job_still_held(result: PgQueryResult, job: &Job) -> Result<(), RepositoryError> {
+    if result.rows_affected() == 0 {
+        return Err(RepositoryError::Conflict(format!(
+            "Job {} is no longer held by this worker (attempt {})",
+            job.id, job.attempts
+        )));
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl JobRepository for PostgresJobRepository {
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "jobs"))]
+    async fn enqueue(&self, job: &Job) -> Result<(), RepositoryError> {
+        sqlx::query(
+            r#"
+            INSERT INTO jobs (id, kind, bot_id, status, attempts, max_attempts, run_at, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#,
+        )
+        .bind(job.id)
+        .bind(job.kind.to_string())
+        .bind(job.bot_id)
+        .bind(job.status.to_string())
+        .bind(job.attempts)
+        .bind(job.max_attempts)
+        .bind(job.run_at)
+        .bind(job.created_at)
+        .bind(job.updated_at)
+        .execute(&mut *self.db.acquire().await?)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db)
+                if db.constraint() == Some(ONE_ACTIVE_PER_BOT_INDEX) =>
+            {
+                RepositoryError::Conflict(format!("Bot {} already has a job in progress", job.bot_id))
+            }
+            _ => RepositoryError::DatabaseError(e),
+        })?;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "jobs"))]
+    async fn get_by_id(&self, id: Uuid) -> Result<Job, RepositoryError> {
+        let row = sqlx::query(&format!("SELECT {JOB_COLUMNS} FROM jobs WHERE id = $1"))
+            .bind(id)
+            .fetch_one(&mut *self.db.acquire().await?)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => RepositoryError::NotFound(format!("Job {}", id)),
+                _ => RepositoryError::DatabaseError(e),
+            })?;
+
+        row_to_job(&row)
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "jobs"))]
+    async fn claim_next(&self, lease: chrono::Duration) -> Result<Option<Job>, RepositoryError> {
+        // SKIP LOCKED lets concurrent workers each take a different row without blocking.
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE jobs
+            SET status = 'running',
+                attempts = attempts + 1,
+                locked_until = NOW() + ($1 * INTERVAL '1 millisecond')
+            WHERE id = (
+                SELECT id FROM jobs
+                WHERE (status = 'queued' AND run_at <= NOW())
+                   OR (status = 'running' AND locked_until < NOW())
+                ORDER BY run_at
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING {JOB_COLUMNS}
+            "#
+        ))
+        .bind(lease.num_milliseconds() as f64)
+        .fetch_optional(&mut *self.db.acquire().await?)
+        .await?;
+
+        row.as_ref().map(row_to_job).transpose()
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "jobs"))]
+    async fn mark_succeeded(&self, job: &Job) -> Result<(), RepositoryError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE jobs
+            SET status = 'succeeded', locked_until = NULL, last_error = NULL, completed_at = NOW()
+            WHERE id = $1 AND status = 'running' AND attempts = $2
+            "#,
+        )
+        .bind(job.id)
+        .bind(job.attempts)
+        .execute(&mut *self.db.acquire().await?)
+        .await?;
+
+        ensure_job_still_held(result, job)
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "jobs"))]
+    async fn schedule_retry(
+        &self,
+        job: &Job,
+        error: &str,
+        run_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), RepositoryError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE jobs
+            SET status = 'queued', locked_until = NULL, last_error = $3, run_at = $4
+            WHERE id = $1 AND status = 'running' AND attempts = $2
+            "#,
+        )
+        .bind(job.id)
+        .bind(job.attempts)
+        .bind(error)
+        .bind(run_at)
+        .execute(&mut *self.db.acquire().await?)
+        .await?;
+
+        ensure_job_still_held(result, job)
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "jobs"))]
+    async fn mark_failed(&self, job: &Job, error: &str) -> Result<(), RepositoryError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE jobs
+            SET status = 'failed', locked_until = NULL, last_error = $3, completed_at = NOW()
+            WHERE id = $1 AND status = 'running' AND attempts = $2
+            "#,
+        )
+        .bind(job.id)
+        .bind(job.attempts)
+        .bind(error)
+        .execute(&mut *self.db.acquire().await?)
+        .await?;
+
+        ensure_job_still_held(result, job)
+    }
+}
+
+fn row_to_job(row: &sqlx::postgres::PgRow) -> Result<Job, RepositoryError> {
+    let kind_str: String = row.try_get("kind")?;
+    let status_str: String = row.try_get("status")?;
+
+    Ok(Job {
+        id: row.try_get("id")?,
+        kind: JobKind::from_str(&kind_str)
+            .map_err(|_| RepositoryError::InvalidData(format!("Unknown job kind: {}", kind_str)))?,
+        bot_id: row.try_get("bot_id")?,
+        status: JobStatus::from_str(&status_str).map_err(|_| {
+            RepositoryError::InvalidData(format!("Unknown job status: {}", status_str))
+        })?,
+        attempts: row.try_get("attempts")?,
+        max_attempts: row.try_get("max_attempts")?,
+        run_at: row.try_get("run_at")?,
+        locked_until: row.try_get("locked_until")?,
+        last_error: row.try_get("last_error")?,
+        created_at: row.try_get("created_at")?,
+        updated_at: row.try_get("updated_at")?,
+        completed_at: row.try_get("completed_at")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ensure_job_still_held, RepositoryError};
+    use crate::domain::{Job, JobKind};
+    use sqlx::postgres::PgQueryResult;
+    use uuid::Uuid;
+
+    #[test]
+    fn ensure_job_still_held_reports_conflict_when_lease_was_lost() {
+        let job = Job::new(JobKind::SpawnBot, Uuid::new_v4(), 3);
+        let err = ensure_job_still_held(PgQueryResult::default(), &job).unwrap_err();
+        assert!(matches!(err, RepositoryError::Conflict(_)));
+    }
+}
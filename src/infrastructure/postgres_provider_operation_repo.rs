@@ -0,0 +1,194 @@
+use crate::domain::{ProviderOperation, ProviderOperationKind, ProviderOperationStatus};
+use crate::infrastructure::{PgHandle, ProviderOperationRepository, RepositoryError};
+use async_trait::async_trait;
+use sqlx::{postgres::PgQueryResult, PgPool, Row};
+use std::str::FromStr;
+use tracing::instrument;
+
+/// Unique index allowing one pending droplet create per bot (see migration 017).
+const ONE_CREATE_PER_BOT_INDEX: &str = "idx_provider_operations_one_create_per_bot";
+
+const OPERATION_COLUMNS: &str = "id, kind, bot_id, droplet_id, status, attempts, locked_until, \
+     last_error, created_at, updated_at, completed_at";
+
+pub struct PostgresProviderOperationRepository {
+    db: PgHandle,
+}
+
+impl PostgresProviderOperationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            db: PgHandle::Pool(pool),
+        }
+    }
+
+    pub(crate) fn with_handle(db: PgHandle) -> Self {
+        Self { db }
+    }
+}
+
+/// A zero-row update means the executor took the operation over after our lease expired.
+fn ensure_operation_still_held(
+    result: PgQueryResult,
+    operation: &ProviderOperation,
+) -> Result<(), RepositoryError> {
+    if result.rows_affected() == 0 {
+        return Err(RepositoryError::Conflict(format!(
+            "Provider operation {} is no longer held by this caller (attempt {})",
+            operation.id, operation.attempts
+        )));
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl ProviderOperationRepository for PostgresProviderOperationRepository {
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "provider_operations"))]
+    async fn create(&self, operation: &ProviderOperation) -> Result<(), RepositoryError> {
+        sqlx::query(&format!(
+            r#"
+            INSERT INTO provider_operations ({OPERATION_COLUMNS})
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            "#
+        ))
+        .bind(operation.id)
+        .bind(operation.kind.to_string())
+        .bind(operation.bot_id)
+        .bind(operation.droplet_id)
+        .bind(operation.status.to_string())
+        .bind(operation.attempts)
+        .bind(operation.locked_until)
+        .bind(&operation.last_error)
+        .bind(operation.created_at)
+        .bind(operation.updated_at)
+        .bind(operation.completed_at)
+        .execute(&mut *self.db.acquire().await?)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.constraint() == Some(ONE_CREATE_PER_BOT_INDEX) => {
+                RepositoryError::Conflict(format!(
+                    "Bot {} already has a droplet create pending",
+                    operation.bot_id
+                ))
+            }
+            _ => RepositoryError::DatabaseError(e),
+        })?;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "provider_operations"))]
+    async fn claim_expired(
+        &self,
+        lease: chrono::Duration,
+    ) -> Result<Option<ProviderOperation>, RepositoryError> {
+        // SKIP LOCKED lets concurrent executors each take a different row without blocking.
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE provider_operations
+            SET attempts = attempts + 1,
+                locked_until = NOW() + ($1 * INTERVAL '1 millisecond')
+            WHERE id = (
+                SELECT id FROM provider_operations
+                WHERE status = 'pending' AND locked_until < NOW()
+                ORDER BY locked_until
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING {OPERATION_COLUMNS}
+            "#
+        ))
+        .bind(lease.num_milliseconds() as f64)
+        .fetch_optional(&mut *self.db.acquire().await?)
+        .await?;
+
+        row.as_ref().map(row_to_operation).transpose()
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "provider_operations"))]
+    async fn mark_completed(&self, operation: &ProviderOperation) -> Result<(), RepositoryError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE provider_operations
+            SET status = 'completed', last_error = NULL, completed_at = NOW()
+            WHERE id = $1 AND status = 'pending' AND attempts = $2
+            "#,
+        )
+        .bind(operation.id)
+        .bind(operation.attempts)
+        .execute(&mut *self.db.acquire().await?)
+        .await?;
+
+        ensure_operation_still_held(result, operation)
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "provider_operations"))]
+    async fn mark_failed(
+        &self,
+        operation: &ProviderOperation,
+        error: &str,
+    ) -> Result<(), RepositoryError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE provider_operations
+            SET status = 'failed', last_error = $3, completed_at = NOW()
+            WHERE id = $1 AND status = 'pending' AND attempts = $2
+            "#,
+        )
+        .bind(operation.id)
+        .bind(operation.attempts)
+        .bind(error)
+        .execute(&mut *self.db.acquire().await?)
+        .await?;
+
+        ensure_operation_still_held(result, operation)
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "provider_operations"))]
+    async fn record_error(
+        &self,
+        operation: &ProviderOperation,
+        error: &str,
+    ) -> Result<(), RepositoryError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE provider_operations
+            SET last_error = $3
+            WHERE id = $1 AND status = 'pending' AND attempts = $2
+            "#,
+        )
+        .bind(operation.id)
+        .bind(operation.attempts)
+        .bind(error)
+        .execute(&mut *self.db.acquire().await?)
+        .await?;
+
+        ensure_operation_still_held(result, operation)
+    }
+}
+
+fn row_to_operation(row: &sqlx::postgres::PgRow) -> Result<ProviderOperation, RepositoryError> {
+    let kind_str: String = row.try_get("kind")?;
+    let status_str: String = row.try_get("status")?;
+
+    Ok(ProviderOperation {
+        id: row.try_get("id")?,
+        kind: ProviderOperationKind::from_str(&kind_str).map_err(|_| {
+            RepositoryError::InvalidData(format!("Unknown provider operation kind: {}", kind_str))
+        })?,
+        bot_id: row.try_get("bot_id")?,
+        droplet_id: row.try_get("droplet_id")?,
+        status: ProviderOperationStatus::from_str(&status_str).map_err(|_| {
+            RepositoryError::InvalidData(format!(
+                "Unknown provider operation status: {}",
+                status_str
+            ))
+        })?,
+        attempts: row.try_get("attempts")?,
+        locked_until: row.try_get("locked_until")?,
+        last_error: row.try_get("last_error")?,
+        created_at: row.try_get("created_at")?,
+        updated_at: row.try_get("updated_at")?,
+        completed_at: row.try_get("completed_at")?,
+    })
+}
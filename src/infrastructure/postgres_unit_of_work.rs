@@ -0,0 +1,133 @@
+use crate::infrastructure::{
+    BotRepository, ConfigRepository, DropletRepository, JobRepository, PostgresBotRepository,
+    PostgresConfigRepository, PostgresDropletRepository, PostgresJobRepository,
+    PostgresProviderOperationRepository, ProviderOperationRepository, RepositoryError,
+    RepositoryTransaction, UnitOfWork,
+};
+use async_trait::async_trait;
+use sqlx::pool::PoolConnection;
+use sqlx::{PgConnection, PgPool, Postgres, Transaction};
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+use tokio::sync::{Mutex, MutexGuard};
+
+/// Where a Postgres repository runs its queries: the pool, or an open transaction
+/// shared by the repositories of one [`PostgresUnitOfWork::begin`].
+#[derive(Clone)]
+pub(crate) enum PgHandle {
+    Pool(PgPool),
+    Transaction(Arc<Mutex<Transaction<'static, Postgres>>>),
+}
+
+impl PgHandle {
+    /// A connection for the next query, held until the returned guard drops.
+    pub(crate) async fn acquire(&self) -> Result<PgConn<'_>, sqlx::Error> {
+        match self {
+            Self::Pool(pool) => Ok(PgConn::Pool(pool.acquire().await?)),
+            Self::Transaction(tx) => Ok(PgConn::Transaction(tx.lock().await)),
+        }
+    }
+}
+
+pub(crate) enum PgConn<'a> {
+    Pool(PoolConnection<Postgres>),
+    Transaction(MutexGuard<'a, Transaction<'static, Postgres>>),
+}
+
+impl Deref for PgConn<'_> {
+    type Target = PgConnection;
+
+    fn deref(&self) -> &PgConnection {
+        match self {
+            Self::Pool(conn) => conn,
+            Self::Transaction(tx) => tx,
+        }
+    }
+}
+
+impl DerefMut for PgConn<'_> {
+    fn deref_mut(&mut self) -> &mut PgConnection {
+        match self {
+            Self::Pool(conn) => conn,
+            Self::Transaction(tx) => tx,
+        }
+    }
+}
+
+pub struct PostgresUnitOfWork {
+    pool: PgPool,
+}
+
+impl PostgresUnitOfWork {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl UnitOfWork for PostgresUnitOfWork {
+    async fn begin(&self) -> Result<Box<dyn RepositoryTransaction>, RepositoryError> {
+        let tx = Arc::new(Mutex::new(self.pool.begin().await?));
+        let handle = PgHandle::Transaction(tx.clone());
+        Ok(Box::new(PostgresTransaction {
+            tx,
+            bots: PostgresBotRepository::with_handle(handle.clone()),
+            configs: PostgresConfigRepository::with_handle(handle.clone()),
+            droplets: PostgresDropletRepository::with_handle(handle.clone()),
+            jobs: PostgresJobRepository::with_handle(handle.clone()),
+            provider_operations: PostgresProviderOperationRepository::with_handle(handle),
+        }))
+    }
+}
+
+struct PostgresTransaction {
+    tx: Arc<Mutex<Transaction<'static, Postgres>>>,
+    bots: PostgresBotRepository,
+    configs: PostgresConfigRepository,
+    droplets: PostgresDropletRepository,
+    jobs: PostgresJobRepository,
+    provider_operations: PostgresProviderOperationRepository,
+}
+
+#[async_trait]
+impl RepositoryTransaction for PostgresTransaction {
+    fn bots(&self) -> &dyn BotRepository {
+        &self.bots
+    }
+
+    fn configs(&self) -> &dyn ConfigRepository {
+        &self.configs
+    }
+
+    fn droplets(&self) -> &dyn DropletRepository {
+        &self.droplets
+    }
+
+    fn jobs(&self) -> &dyn JobRepository {
+        &self.jobs
+    }
+
+    fn provider_operations(&self) -> &dyn ProviderOperationRepository {
+        &self.provider_operations
+    }
+
+    async fn commit(self: Box<Self>) -> Result<(), RepositoryError> {
+        let Self {
+            tx,
+            bots,
+            configs,
+            droplets,
+            jobs,
+            provider_operations,
+        } = *self;
+        // The repositories hold the other references to the transaction.
+        drop((bots, configs, droplets, jobs, provider_operations));
+        let tx = Arc::into_inner(tx).ok_or_else(|| {
+            RepositoryError::InvalidData(
+                "Transaction is still borrowed by a repository".to_string(),
+            )
+        })?;
+        tx.into_inner().commit().await?;
+        Ok(())
+    }
+}
@@ -1,10 +1,16 @@
-use crate::domain::{Account, Bot, BotStatus, Droplet, Persona, StoredBotConfig, SubscriptionTier};
+use crate::domain::{
+    Account, BootstrapEvent, BootstrapLog, Bot, BotStatus, Droplet, IdempotencyRecord,
+    InvalidTransition, Job, Persona, ProviderOperation, StoredBotConfig, SubscriptionTier,
+    TransitionReason,
+};
+use crate::infrastructure::postgres_unit_of_work::PgHandle;
 use async_trait::async_trait;
 use chrono::Utc;
 use sha2::{Digest, Sha256};
 use sqlx::{postgres::PgQueryResult, PgPool, Row};
 use std::str::FromStr;
 use thiserror::Error;
+use tracing::instrument;
 use uuid::Uuid;
 
 #[derive(Error, Debug)]
@@ -15,6 +21,10 @@ pub enum RepositoryError {
     NotFound(String),
     #[error("Invalid data: {0}")]
     InvalidData(String),
+    #[error("Conflict: {0}")]
+    Conflict(String),
+    #[error(transparent)]
+    InvalidTransition(#[from] InvalidTransition),
 }
 
 #[async_trait]
@@ -31,6 +41,21 @@ pub trait AccountRepository: Send + Sync {
         id: Uuid,
         tier: SubscriptionTier,
     ) -> Result<(), RepositoryError>;
+    /// Accounts newest first, optionally only those on `tier`.
+    #[must_use]
+    async fn list(
+        &self,
+        tier: Option<SubscriptionTier>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Account>, RepositoryError>;
+}
+
+/// Constraints for [`BotRepository::list`]; unset fields match every bot.
+#[derive(Debug, Clone, Default)]
+pub struct BotFilter {
+    pub account_id: Option<Uuid>,
+    pub status: Option<BotStatus>,
 }
 
 #[async_trait]
@@ -56,8 +81,27 @@ pub trait BotRepository: Send + Sync {
     /// Use SQL COUNT(*) instead of list_by_account().len()
     #[must_use]
     async fn count_by_account(&self, account_id: Uuid) -> Result<i64, RepositoryError>;
+    /// Bots across all accounts matching `filter`, newest first.
     #[must_use]
-    async fn update_status(&self, id: Uuid, status: BotStatus) -> Result<(), RepositoryError>;
+    async fn list(
+        &self,
+        filter: &BotFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Bot>, RepositoryError>;
+    /// Compare-and-set status change: applies only if the bot is still in `from` and
+    /// `(from, to, reason)` is an edge of the bot state machine. Otherwise returns
+    /// `InvalidTransition` carrying the status the bot is actually in.
+    /// Records `reason`, `detail` and the change time alongside the status.
+    #[must_use]
+    async fn transition_status(
+        &self,
+        id: Uuid,
+        from: BotStatus,
+        to: BotStatus,
+        reason: TransitionReason,
+        detail: Option<&str>,
+    ) -> Result<(), RepositoryError>;
     #[must_use]
     async fn update_droplet(
         &self,
@@ -65,6 +109,20 @@ pub trait BotRepository: Send + Sync {
         droplet_id: Option<i64>,
     ) -> Result<(), RepositoryError>;
     #[must_use]
+    /// Whether hibernating the bot snapshots its droplet; `None` follows the tier.
+    async fn update_snapshot_on_hibernate(
+        &self,
+        bot_id: Uuid,
+        snapshot: Option<bool>,
+    ) -> Result<(), RepositoryError>;
+
+    /// The snapshot the bot's next droplet boots from.
+    async fn update_hibernation_snapshot(
+        &self,
+        bot_id: Uuid,
+        snapshot_id: Option<i64>,
+    ) -> Result<(), RepositoryError>;
+
     async fn update_config_version(
         &self,
         bot_id: Uuid,
@@ -79,6 +137,30 @@ pub trait BotRepository: Send + Sync {
         bot_id: Uuid,
         token: &str,
     ) -> Result<(), RepositoryError>;
+    /// Forgets the current droplet's registration but keeps its token, for a
+    /// droplet that boots again with the same user data.
+    #[must_use]
+    async fn reset_registration(&self, bot_id: Uuid) -> Result<(), RepositoryError>;
+    /// Records a registration from the current droplet. Keeps the first `registered_at`,
+    /// updates IP and agent version, and clears the registration timeout count.
+    #[must_use]
+    async fn record_registration(
+        &self,
+        id: Uuid,
+        ip: Option<&str>,
+        agent_version: Option<&str>,
+    ) -> Result<(), RepositoryError>;
+    /// Bots still provisioning without a registration since before `threshold`.
+    #[must_use]
+    async fn list_registration_overdue(
+        &self,
+        threshold: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<Bot>, RepositoryError>;
+    /// Returns the new count of consecutive registration timeouts.
+    #[must_use]
+    async fn increment_registration_timeouts(&self, id: Uuid) -> Result<i32, RepositoryError>;
+    /// Mark the bot destroyed whatever its status. Lifecycle code destroys through
+    /// [`transition_status`](Self::transition_status) so racing destroys are caught.
     #[must_use]
     async fn delete(&self, id: Uuid) -> Result<(), RepositoryError>;
     #[must_use]
@@ -140,6 +222,168 @@ pub trait DropletRepository: Send + Sync {
     async fn mark_destroyed(&self, droplet_id: i64) -> Result<(), RepositoryError>;
 }
 
+/// Durable job queue. Every state change after `claim_next` is conditional on the
+/// job still being held by the caller (same `attempts` and `running`), so a worker
+/// whose lease expired cannot overwrite the outcome of the worker that reclaimed it.
+#[async_trait]
+pub trait JobRepository: Send + Sync {
+    /// Fails with `Conflict` when the bot already has a queued or running job.
+    #[must_use]
+    async fn enqueue(&self, job: &Job) -> Result<(), RepositoryError>;
+    #[must_use]
+    async fn get_by_id(&self, id: Uuid) -> Result<Job, RepositoryError>;
+    /// Claim the next due job (or a running job with an expired lease),
+    /// bumping `attempts` and holding it for `lease`.
+    #[must_use]
+    async fn claim_next(&self, lease: chrono::Duration) -> Result<Option<Job>, RepositoryError>;
+    #[must_use]
+    async fn mark_succeeded(&self, job: &Job) -> Result<(), RepositoryError>;
+    /// Release the job back to the queue, to be claimed again at `run_at`.
+    #[must_use]
+    async fn schedule_retry(
+        &self,
+        job: &Job,
+        error: &str,
+        run_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), RepositoryError>;
+    #[must_use]
+    async fn mark_failed(&self, job: &Job, error: &str) -> Result<(), RepositoryError>;
+}
+
+/// The `provider_operations` outbox. Like [`JobRepository`], updates are conditional
+/// on the operation still being `pending` with the caller's `attempts`, so a writer
+/// whose lease expired cannot overwrite the executor that took over.
+#[async_trait]
+pub trait ProviderOperationRepository: Send + Sync {
+    /// Fails with `Conflict` when the bot already has a pending droplet create.
+    #[must_use]
+    async fn create(&self, operation: &ProviderOperation) -> Result<(), RepositoryError>;
+    /// Claim a pending operation whose lease has expired, bumping `attempts`
+    /// and holding it for `lease`.
+    #[must_use]
+    async fn claim_expired(
+        &self,
+        lease: chrono::Duration,
+    ) -> Result<Option<ProviderOperation>, RepositoryError>;
+    #[must_use]
+    async fn mark_completed(&self, operation: &ProviderOperation) -> Result<(), RepositoryError>;
+    #[must_use]
+    async fn mark_failed(
+        &self,
+        operation: &ProviderOperation,
+        error: &str,
+    ) -> Result<(), RepositoryError>;
+    /// Note why an attempt failed; the operation stays pending until its lease expires.
+    #[must_use]
+    async fn record_error(
+        &self,
+        operation: &ProviderOperation,
+        error: &str,
+    ) -> Result<(), RepositoryError>;
+}
+
+/// Stored `Idempotency-Key`s and the responses they produced.
+#[async_trait]
+pub trait IdempotencyRepository: Send + Sync {
+    /// Atomically claim `record.key`. Returns `None` when the caller now owns the key,
+    /// otherwise the record already holding it. Records created before `expired_before`,
+    /// and unfinished ones created before `abandoned_before`, are replaced as if absent.
+    #[must_use]
+    async fn reserve(
+        &self,
+        record: &IdempotencyRecord,
+        expired_before: chrono::DateTime<chrono::Utc>,
+        abandoned_before: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<IdempotencyRecord>, RepositoryError>;
+    /// Store the response for `reservation`. Fails with `Conflict` if another
+    /// request has since taken the key over.
+    #[must_use]
+    async fn complete(
+        &self,
+        reservation: &IdempotencyRecord,
+        status: u16,
+        body: &[u8],
+    ) -> Result<(), RepositoryError>;
+    /// Drop `reservation` so the key can be retried. A key another request has
+    /// taken over is left alone.
+    #[must_use]
+    async fn release(&self, reservation: &IdempotencyRecord) -> Result<(), RepositoryError>;
+    /// Delete keys created before `created_before`, finished or not. Returns
+    /// how many were deleted.
+    #[must_use]
+    async fn purge_expired(
+        &self,
+        created_before: chrono::DateTime<chrono::Utc>,
+    ) -> Result<u64, RepositoryError>;
+}
+
+/// Bootstrap progress reported by droplets (`POST /bot/:id/bootstrap_progress`).
+#[async_trait]
+pub trait BootstrapEventRepository: Send + Sync {
+    #[must_use]
+    async fn record(&self, event: &BootstrapEvent) -> Result<(), RepositoryError>;
+    /// The bot's most recent `limit` events, oldest first.
+    #[must_use]
+    async fn list_for_bot(
+        &self,
+        bot_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<BootstrapEvent>, RepositoryError>;
+}
+
+/// Redacted log tails uploaded by droplets whose bootstrap failed.
+#[async_trait]
+pub trait BootstrapLogRepository: Send + Sync {
+    /// Store `log`, then delete the bot's uploads beyond the newest `keep`.
+    #[must_use]
+    async fn record(&self, log: &BootstrapLog, keep: i64) -> Result<(), RepositoryError>;
+    /// The bot's most recent `limit` uploads, newest first.
+    #[must_use]
+    async fn list_for_bot(
+        &self,
+        bot_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<BootstrapLog>, RepositoryError>;
+}
+
+/// Bot, config, droplet and outbox repositories bound to one open transaction.
+///
+/// Nothing they write is visible to other callers until [`commit`](Self::commit);
+/// dropping the transaction without committing rolls every change back.
+#[async_trait]
+pub trait RepositoryTransaction: Send + Sync {
+    fn bots(&self) -> &dyn BotRepository;
+    fn configs(&self) -> &dyn ConfigRepository;
+    fn droplets(&self) -> &dyn DropletRepository;
+    fn jobs(&self) -> &dyn JobRepository;
+    fn provider_operations(&self) -> &dyn ProviderOperationRepository;
+    #[must_use]
+    async fn commit(self: Box<Self>) -> Result<(), RepositoryError>;
+}
+
+/// Starts transactions spanning several repositories, so multi-row state
+/// changes commit or roll back together.
+#[async_trait]
+pub trait UnitOfWork: Send + Sync {
+    #[must_use]
+    async fn begin(&self) -> Result<Box<dyn RepositoryTransaction>, RepositoryError>;
+}
+
+/// Liveness probe for the storage behind the repositories (`GET /health`).
+#[async_trait]
+pub trait StorageHealth: Send + Sync {
+    #[must_use]
+    async fn ping(&self) -> Result<(), RepositoryError>;
+}
+
+#[async_trait]
+impl StorageHealth for PgPool {
+    async fn ping(&self) -> Result<(), RepositoryError> {
+        sqlx::query("SELECT 1").fetch_one(self).await?;
+        Ok(())
+    }
+}
+
 pub struct PostgresAccountRepository {
     pool: PgPool,
 }
@@ -152,6 +396,7 @@ impl PostgresAccountRepository {
 
 #[async_trait]
 impl AccountRepository for PostgresAccountRepository {
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "accounts"))]
     async fn create(&self, account: &Account) -> Result<(), RepositoryError> {
         let tier_str = match account.subscription_tier {
             SubscriptionTier::Free => "free",
@@ -159,16 +404,22 @@ impl AccountRepository for PostgresAccountRepository {
             SubscriptionTier::Pro => "pro",
         };
 
+        let customizer_json = serde_json::to_value(&account.customizer).map_err(|e| {
+            RepositoryError::InvalidData(format!("Failed to serialize customizer: {}", e))
+        })?;
+
         sqlx::query(
             r#"
-            INSERT INTO accounts (id, external_id, subscription_tier, max_bots, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6)
+            INSERT INTO accounts (id, external_id, subscription_tier, max_bots, customizer,
+                                  created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
             "#,
         )
         .bind(account.id)
         .bind(&account.external_id)
         .bind(tier_str)
         .bind(account.max_bots)
+        .bind(customizer_json)
         .bind(account.created_at)
         .bind(account.updated_at)
         .execute(&self.pool)
@@ -177,10 +428,11 @@ impl AccountRepository for PostgresAccountRepository {
         Ok(())
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "accounts"))]
     async fn get_by_id(&self, id: Uuid) -> Result<Account, RepositoryError> {
         let row = sqlx::query(
             r#"
-            SELECT id, external_id, subscription_tier, max_bots, created_at, updated_at
+            SELECT id, external_id, subscription_tier, max_bots, customizer, created_at, updated_at
             FROM accounts
             WHERE id = $1
             "#,
@@ -196,10 +448,11 @@ impl AccountRepository for PostgresAccountRepository {
         Ok(row_to_account(&row)?)
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "accounts"))]
     async fn get_by_external_id(&self, external_id: &str) -> Result<Account, RepositoryError> {
         let row = sqlx::query(
             r#"
-            SELECT id, external_id, subscription_tier, max_bots, created_at, updated_at
+            SELECT id, external_id, subscription_tier, max_bots, customizer, created_at, updated_at
             FROM accounts
             WHERE external_id = $1
             "#,
@@ -217,6 +470,7 @@ impl AccountRepository for PostgresAccountRepository {
         Ok(row_to_account(&row)?)
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "accounts"))]
     async fn update_subscription(
         &self,
         id: Uuid,
@@ -228,11 +482,7 @@ impl AccountRepository for PostgresAccountRepository {
             SubscriptionTier::Pro => "pro",
         };
 
-        let max_bots = match tier {
-            SubscriptionTier::Free => 0,
-            SubscriptionTier::Basic => 2,
-            SubscriptionTier::Pro => 4,
-        };
+        let max_bots = tier.max_bots();
 
         let result = sqlx::query(
             r#"
@@ -252,6 +502,31 @@ impl AccountRepository for PostgresAccountRepository {
 
         Ok(())
     }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "accounts"))]
+    async fn list(
+        &self,
+        tier: Option<SubscriptionTier>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Account>, RepositoryError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, external_id, subscription_tier, max_bots, customizer, created_at, updated_at
+            FROM accounts
+            WHERE ($1::text IS NULL OR subscription_tier = $1)
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(tier.map(|t| t.to_string()))
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(row_to_account).collect()
+    }
 }
 
 fn row_to_account(row: &sqlx::postgres::PgRow) -> Result<Account, RepositoryError> {
@@ -268,27 +543,39 @@ fn row_to_account(row: &sqlx::postgres::PgRow) -> Result<Account, RepositoryErro
         }
     };
 
+    let customizer_json: serde_json::Value = row.try_get("customizer")?;
+    let customizer = serde_json::from_value(customizer_json).map_err(|e| {
+        RepositoryError::InvalidData(format!("Failed to deserialize customizer: {}", e))
+    })?;
+
     Ok(Account {
         id: row.try_get("id")?,
         external_id: row.try_get("external_id")?,
         subscription_tier: tier,
         max_bots: row.try_get("max_bots")?,
+        customizer,
         created_at: row.try_get("created_at")?,
         updated_at: row.try_get("updated_at")?,
     })
 }
 
 pub struct PostgresBotRepository {
-    pool: PgPool,
+    db: PgHandle,
 }
 
 impl PostgresBotRepository {
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            db: PgHandle::Pool(pool),
+        }
+    }
+
+    pub(crate) fn with_handle(db: PgHandle) -> Self {
+        Self { db }
     }
 }
 
-fn hash_registration_token(token: &str) -> String {
+pub(crate) fn hash_registration_token(token: &str) -> String {
     let digest = Sha256::digest(token.as_bytes());
     format!("sha256:{:x}", digest)
 }
@@ -304,18 +591,45 @@ fn ensure_single_row_affected(
     Ok(())
 }
 
+/// Error for a compare-and-set that found the bot in `current` instead of `expected`.
+pub(crate) fn stale_status(
+    current: BotStatus,
+    expected: BotStatus,
+    to: BotStatus,
+) -> RepositoryError {
+    RepositoryError::InvalidTransition(InvalidTransition {
+        from: current,
+        to,
+        reason: format!("bot is no longer {expected}"),
+    })
+}
+
 #[async_trait]
 impl BotRepository for PostgresBotRepository {
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bots"))]
     async fn create(&self, bot: &Bot) -> Result<(), RepositoryError> {
         let status_str = bot.status.to_string();
         let persona_str = bot.persona.to_string();
+        let customizer_json = bot
+            .customizer
+            .as_ref()
+            .map(serde_json::to_value)
+            .transpose()
+            .map_err(|e| {
+                RepositoryError::InvalidData(format!("Failed to serialize customizer: {}", e))
+            })?;
 
         sqlx::query(
             r#"
             INSERT INTO bots (id, account_id, name, persona, status, droplet_id, 
                              desired_config_version_id, applied_config_version_id, 
-                             registration_token, created_at, updated_at, last_heartbeat_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+                             registration_token, created_at, updated_at, last_heartbeat_at,
+                             status_reason, status_detail, status_changed_at,
+                             registered_at, registered_ip, agent_version, registration_timeouts,
+                             customizer, toolchain_profile, snapshot_on_hibernate,
+                             hibernation_snapshot_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15,
+                    $16, $17, $18, $19, $20, $21, $22, $23)
             "#,
         )
         .bind(bot.id)
@@ -330,24 +644,40 @@ impl BotRepository for PostgresBotRepository {
         .bind(bot.created_at)
         .bind(bot.updated_at)
         .bind(bot.last_heartbeat_at)
-        .execute(&self.pool)
+        .bind(bot.status_reason.map(|r| r.to_string()))
+        .bind(&bot.status_detail)
+        .bind(bot.status_changed_at)
+        .bind(bot.registered_at)
+        .bind(&bot.registered_ip)
+        .bind(&bot.agent_version)
+        .bind(bot.registration_timeouts)
+        .bind(customizer_json)
+        .bind(&bot.toolchain_profile)
+        .bind(bot.snapshot_on_hibernate)
+        .bind(bot.hibernation_snapshot_id)
+        .execute(&mut *self.db.acquire().await?)
         .await?;
 
         Ok(())
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bots"))]
     async fn get_by_id(&self, id: Uuid) -> Result<Bot, RepositoryError> {
         let row = sqlx::query(
             r#"
             SELECT id, account_id, name, persona, status, droplet_id,
                    desired_config_version_id, applied_config_version_id,
-                   registration_token, created_at, updated_at, last_heartbeat_at
+                   registration_token, created_at, updated_at, last_heartbeat_at,
+                   status_reason, status_detail, status_changed_at,
+                   registered_at, registered_ip, agent_version, registration_timeouts,
+                   customizer, toolchain_profile, snapshot_on_hibernate,
+                   hibernation_snapshot_id
             FROM bots
             WHERE id = $1
             "#,
         )
         .bind(id)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *self.db.acquire().await?)
         .await
         .map_err(|e| match e {
             sqlx::Error::RowNotFound => RepositoryError::NotFound(format!("Bot {}", id)),
@@ -357,13 +687,18 @@ impl BotRepository for PostgresBotRepository {
         Ok(row_to_bot(&row)?)
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bots"))]
     async fn get_by_id_with_token(&self, id: Uuid, token: &str) -> Result<Bot, RepositoryError> {
         let hashed_token = hash_registration_token(token);
         let row = sqlx::query(
             r#"
             SELECT id, account_id, name, persona, status, droplet_id,
                    desired_config_version_id, applied_config_version_id,
-                   registration_token, created_at, updated_at, last_heartbeat_at
+                   registration_token, created_at, updated_at, last_heartbeat_at,
+                   status_reason, status_detail, status_changed_at,
+                   registered_at, registered_ip, agent_version, registration_timeouts,
+                   customizer, toolchain_profile, snapshot_on_hibernate,
+                   hibernation_snapshot_id
             FROM bots
             WHERE id = $1
               AND (registration_token = $2 OR registration_token = $3)
@@ -372,7 +707,7 @@ impl BotRepository for PostgresBotRepository {
         .bind(id)
         .bind(token)
         .bind(hashed_token)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *self.db.acquire().await?)
         .await
         .map_err(|e| match e {
             sqlx::Error::RowNotFound => {
@@ -384,24 +719,30 @@ impl BotRepository for PostgresBotRepository {
         Ok(row_to_bot(&row)?)
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bots"))]
     async fn list_by_account(&self, account_id: Uuid) -> Result<Vec<Bot>, RepositoryError> {
         let rows = sqlx::query(
             r#"
             SELECT id, account_id, name, persona, status, droplet_id,
                    desired_config_version_id, applied_config_version_id,
-                   registration_token, created_at, updated_at, last_heartbeat_at
+                   registration_token, created_at, updated_at, last_heartbeat_at,
+                   status_reason, status_detail, status_changed_at,
+                   registered_at, registered_ip, agent_version, registration_timeouts,
+                   customizer, toolchain_profile, snapshot_on_hibernate,
+                   hibernation_snapshot_id
             FROM bots
             WHERE account_id = $1
             ORDER BY created_at DESC
             "#,
         )
         .bind(account_id)
-        .fetch_all(&self.pool)
+        .fetch_all(&mut *self.db.acquire().await?)
         .await?;
 
         rows.iter().map(row_to_bot).collect()
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bots"))]
     async fn count_by_account(&self, account_id: Uuid) -> Result<i64, RepositoryError> {
         let count: i64 = sqlx::query_scalar(
             r#"
@@ -411,12 +752,46 @@ impl BotRepository for PostgresBotRepository {
             "#,
         )
         .bind(account_id)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *self.db.acquire().await?)
         .await?;
 
         Ok(count)
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bots"))]
+    async fn list(
+        &self,
+        filter: &BotFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Bot>, RepositoryError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, account_id, name, persona, status, droplet_id,
+                   desired_config_version_id, applied_config_version_id,
+                   registration_token, created_at, updated_at, last_heartbeat_at,
+                   status_reason, status_detail, status_changed_at,
+                   registered_at, registered_ip, agent_version, registration_timeouts,
+                   customizer, toolchain_profile, snapshot_on_hibernate,
+                   hibernation_snapshot_id
+            FROM bots
+            WHERE ($1::uuid IS NULL OR account_id = $1)
+              AND ($2::text IS NULL OR status = $2)
+            ORDER BY created_at DESC
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(filter.account_id)
+        .bind(filter.status.map(|s| s.to_string()))
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&mut *self.db.acquire().await?)
+        .await?;
+
+        rows.iter().map(row_to_bot).collect()
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bots"))]
     async fn list_by_account_paginated(
         &self,
         account_id: Uuid,
@@ -427,7 +802,11 @@ impl BotRepository for PostgresBotRepository {
             r#"
             SELECT id, account_id, name, persona, status, droplet_id,
                    desired_config_version_id, applied_config_version_id,
-                   registration_token, created_at, updated_at, last_heartbeat_at
+                   registration_token, created_at, updated_at, last_heartbeat_at,
+                   status_reason, status_detail, status_changed_at,
+                   registered_at, registered_ip, agent_version, registration_timeouts,
+                   customizer, toolchain_profile, snapshot_on_hibernate,
+                   hibernation_snapshot_id
             FROM bots
             WHERE account_id = $1
             ORDER BY created_at DESC
@@ -437,33 +816,50 @@ impl BotRepository for PostgresBotRepository {
         .bind(account_id)
         .bind(limit)
         .bind(offset)
-        .fetch_all(&self.pool)
+        .fetch_all(&mut *self.db.acquire().await?)
         .await?;
 
         rows.iter().map(row_to_bot).collect()
     }
 
-    async fn update_status(&self, id: Uuid, status: BotStatus) -> Result<(), RepositoryError> {
-        let status_str = status.to_string();
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bots"))]
+    async fn transition_status(
+        &self,
+        id: Uuid,
+        from: BotStatus,
+        to: BotStatus,
+        reason: TransitionReason,
+        detail: Option<&str>,
+    ) -> Result<(), RepositoryError> {
+        from.transition_to(to, reason)?;
 
         let result = sqlx::query(
             r#"
             UPDATE bots
-            SET status = $1, updated_at = $2
-            WHERE id = $3
+            SET status = $1, updated_at = $2, status_reason = $5, status_detail = $6,
+                status_changed_at = $2
+            WHERE id = $3 AND status = $4
             "#,
         )
-        .bind(status_str)
+        .bind(to.to_string())
         .bind(Utc::now())
         .bind(id)
-        .execute(&self.pool)
+        .bind(from.to_string())
+        .bind(reason.to_string())
+        .bind(detail)
+        .execute(&mut *self.db.acquire().await?)
         .await?;
 
-        ensure_single_row_affected(result, "Bot", id)?;
+        if result.rows_affected() == 1 {
+            return Ok(());
+        }
 
-        Ok(())
+        // Lost the compare-and-set: report what the bot is now, or that it is gone.
+        let current = self.get_by_id(id).await?;
+        Err(stale_status(current.status, from, to))
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bots"))]
     async fn update_droplet(
         &self,
         bot_id: Uuid,
@@ -479,7 +875,55 @@ impl BotRepository for PostgresBotRepository {
         .bind(droplet_id)
         .bind(Utc::now())
         .bind(bot_id)
-        .execute(&self.pool)
+        .execute(&mut *self.db.acquire().await?)
+        .await?;
+
+        ensure_single_row_affected(result, "Bot", bot_id)?;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bots"))]
+    async fn update_snapshot_on_hibernate(
+        &self,
+        bot_id: Uuid,
+        snapshot: Option<bool>,
+    ) -> Result<(), RepositoryError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE bots
+            SET snapshot_on_hibernate = $1, updated_at = $2
+            WHERE id = $3
+            "#,
+        )
+        .bind(snapshot)
+        .bind(Utc::now())
+        .bind(bot_id)
+        .execute(&mut *self.db.acquire().await?)
+        .await?;
+
+        ensure_single_row_affected(result, "Bot", bot_id)?;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bots"))]
+    async fn update_hibernation_snapshot(
+        &self,
+        bot_id: Uuid,
+        snapshot_id: Option<i64>,
+    ) -> Result<(), RepositoryError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE bots
+            SET hibernation_snapshot_id = $1, updated_at = $2
+            WHERE id = $3
+            "#,
+        )
+        .bind(snapshot_id)
+        .bind(Utc::now())
+        .bind(bot_id)
+        .execute(&mut *self.db.acquire().await?)
         .await?;
 
         ensure_single_row_affected(result, "Bot", bot_id)?;
@@ -487,6 +931,7 @@ impl BotRepository for PostgresBotRepository {
         Ok(())
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bots"))]
     async fn update_config_version(
         &self,
         bot_id: Uuid,
@@ -504,7 +949,7 @@ impl BotRepository for PostgresBotRepository {
         .bind(applied)
         .bind(Utc::now())
         .bind(bot_id)
-        .execute(&self.pool)
+        .execute(&mut *self.db.acquire().await?)
         .await?;
 
         ensure_single_row_affected(result, "Bot", bot_id)?;
@@ -512,6 +957,7 @@ impl BotRepository for PostgresBotRepository {
         Ok(())
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bots"))]
     async fn update_heartbeat(&self, bot_id: Uuid) -> Result<(), RepositoryError> {
         let result = sqlx::query(
             r#"
@@ -523,7 +969,7 @@ impl BotRepository for PostgresBotRepository {
         .bind(Utc::now())
         .bind(Utc::now())
         .bind(bot_id)
-        .execute(&self.pool)
+        .execute(&mut *self.db.acquire().await?)
         .await?;
 
         ensure_single_row_affected(result, "Bot", bot_id)?;
@@ -531,6 +977,7 @@ impl BotRepository for PostgresBotRepository {
         Ok(())
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bots"))]
     async fn update_registration_token(
         &self,
         bot_id: Uuid,
@@ -540,14 +987,35 @@ impl BotRepository for PostgresBotRepository {
         let result = sqlx::query(
             r#"
             UPDATE bots
-            SET registration_token = $1, updated_at = $2
+            SET registration_token = $1, updated_at = $2,
+                registered_at = NULL, registered_ip = NULL, agent_version = NULL
             WHERE id = $3
             "#,
         )
         .bind(hashed_token)
         .bind(Utc::now())
         .bind(bot_id)
-        .execute(&self.pool)
+        .execute(&mut *self.db.acquire().await?)
+        .await?;
+
+        ensure_single_row_affected(result, "Bot", bot_id)?;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bots"))]
+    async fn reset_registration(&self, bot_id: Uuid) -> Result<(), RepositoryError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE bots
+            SET registered_at = NULL, registered_ip = NULL, agent_version = NULL,
+                updated_at = $1
+            WHERE id = $2
+            "#,
+        )
+        .bind(Utc::now())
+        .bind(bot_id)
+        .execute(&mut *self.db.acquire().await?)
         .await?;
 
         ensure_single_row_affected(result, "Bot", bot_id)?;
@@ -555,17 +1023,97 @@ impl BotRepository for PostgresBotRepository {
         Ok(())
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bots"))]
+    async fn record_registration(
+        &self,
+        id: Uuid,
+        ip: Option<&str>,
+        agent_version: Option<&str>,
+    ) -> Result<(), RepositoryError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE bots
+            SET registered_at = COALESCE(registered_at, $1),
+                registered_ip = COALESCE($2, registered_ip),
+                agent_version = COALESCE($3, agent_version),
+                registration_timeouts = 0,
+                updated_at = $1
+            WHERE id = $4
+            "#,
+        )
+        .bind(Utc::now())
+        .bind(ip)
+        .bind(agent_version)
+        .bind(id)
+        .execute(&mut *self.db.acquire().await?)
+        .await?;
+
+        ensure_single_row_affected(result, "Bot", id)?;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bots"))]
+    async fn list_registration_overdue(
+        &self,
+        threshold: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<Bot>, RepositoryError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, account_id, name, persona, status, droplet_id,
+                   desired_config_version_id, applied_config_version_id,
+                   registration_token, created_at, updated_at, last_heartbeat_at,
+                   status_reason, status_detail, status_changed_at,
+                   registered_at, registered_ip, agent_version, registration_timeouts,
+                   customizer, toolchain_profile, snapshot_on_hibernate,
+                   hibernation_snapshot_id
+            FROM bots
+            WHERE status = 'provisioning'
+              AND registered_at IS NULL
+              AND status_changed_at < $1
+            "#,
+        )
+        .bind(threshold)
+        .fetch_all(&mut *self.db.acquire().await?)
+        .await?;
+
+        rows.iter().map(row_to_bot).collect()
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bots"))]
+    async fn increment_registration_timeouts(&self, id: Uuid) -> Result<i32, RepositoryError> {
+        let count: i32 = sqlx::query_scalar(
+            r#"
+            UPDATE bots
+            SET registration_timeouts = registration_timeouts + 1
+            WHERE id = $1
+            RETURNING registration_timeouts
+            "#,
+        )
+        .bind(id)
+        .fetch_one(&mut *self.db.acquire().await?)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => RepositoryError::NotFound(format!("Bot {}", id)),
+            _ => RepositoryError::DatabaseError(e),
+        })?;
+
+        Ok(count)
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bots"))]
     async fn delete(&self, id: Uuid) -> Result<(), RepositoryError> {
         let result = sqlx::query(
             r#"
             UPDATE bots
-            SET status = 'destroyed', updated_at = $1
+            SET status = 'destroyed', updated_at = $1, status_reason = 'destroy',
+                status_detail = NULL, status_changed_at = $1
             WHERE id = $2
             "#,
         )
         .bind(Utc::now())
         .bind(id)
-        .execute(&self.pool)
+        .execute(&mut *self.db.acquire().await?)
         .await?;
 
         ensure_single_row_affected(result, "Bot", id)?;
@@ -573,6 +1121,7 @@ impl BotRepository for PostgresBotRepository {
         Ok(())
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bots"))]
     async fn hard_delete(&self, id: Uuid) -> Result<(), RepositoryError> {
         let result = sqlx::query(
             r#"
@@ -581,7 +1130,7 @@ impl BotRepository for PostgresBotRepository {
             "#,
         )
         .bind(id)
-        .execute(&self.pool)
+        .execute(&mut *self.db.acquire().await?)
         .await?;
 
         ensure_single_row_affected(result, "Bot", id)?;
@@ -589,6 +1138,7 @@ impl BotRepository for PostgresBotRepository {
         Ok(())
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bots"))]
     async fn increment_bot_counter(
         &self,
         account_id: Uuid,
@@ -600,7 +1150,7 @@ impl BotRepository for PostgresBotRepository {
             "#,
         )
         .bind(account_id)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *self.db.acquire().await?)
         .await
         .map_err(|e| match e {
             sqlx::Error::RowNotFound => {
@@ -617,15 +1167,17 @@ impl BotRepository for PostgresBotRepository {
         Ok((success, current_count, max_count))
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bots"))]
     async fn decrement_bot_counter(&self, account_id: Uuid) -> Result<(), RepositoryError> {
         sqlx::query("SELECT decrement_bot_counter($1)")
             .bind(account_id)
-            .execute(&self.pool)
+            .execute(&mut *self.db.acquire().await?)
             .await?;
 
         Ok(())
     }
 
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "bots"))]
     async fn list_stale_bots(
         &self,
         threshold: chrono::DateTime<chrono::Utc>,
@@ -634,14 +1186,18 @@ impl BotRepository for PostgresBotRepository {
             r#"
             SELECT id, account_id, name, persona, status, droplet_id,
                    desired_config_version_id, applied_config_version_id,
-                   registration_token, created_at, updated_at, last_heartbeat_at
+                   registration_token, created_at, updated_at, last_heartbeat_at,
+                   status_reason, status_detail, status_changed_at,
+                   registered_at, registered_ip, agent_version, registration_timeouts,
+                   customizer, toolchain_profile, snapshot_on_hibernate,
+                   hibernation_snapshot_id
             FROM bots
             WHERE status = 'online'
               AND (last_heartbeat_at < $1 OR last_heartbeat_at IS NULL)
             "#,
         )
         .bind(threshold)
-        .fetch_all(&self.pool)
+        .fetch_all(&mut *self.db.acquire().await?)
         .await?;
 
         rows.iter().map(row_to_bot).collect()
@@ -655,6 +1211,20 @@ impl BotRepository for PostgresBotRepository {
 fn row_to_bot(row: &sqlx::postgres::PgRow) -> Result<Bot, RepositoryError> {
     let status_str: String = row.try_get("status")?;
     let persona_str: String = row.try_get("persona")?;
+    let reason_str: Option<String> = row.try_get("status_reason")?;
+    let status_reason = reason_str
+        .map(|r| {
+            TransitionReason::from_str(&r)
+                .map_err(|_| RepositoryError::InvalidData(format!("Unknown status reason: {}", r)))
+        })
+        .transpose()?;
+    let customizer_json: Option<serde_json::Value> = row.try_get("customizer")?;
+    let customizer = customizer_json
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| {
+            RepositoryError::InvalidData(format!("Failed to deserialize customizer: {}", e))
+        })?;
 
     Ok(Bot {
         id: row.try_get("id")?,
@@ -672,6 +1242,17 @@ fn row_to_bot(row: &sqlx::postgres::PgRow) -> Result<Bot, RepositoryError> {
         created_at: row.try_get("created_at")?,
         updated_at: row.try_get("updated_at")?,
         last_heartbeat_at: row.try_get("last_heartbeat_at")?,
+        status_reason,
+        status_detail: row.try_get("status_detail")?,
+        status_changed_at: row.try_get("status_changed_at")?,
+        registered_at: row.try_get("registered_at")?,
+        registered_ip: row.try_get("registered_ip")?,
+        agent_version: row.try_get("agent_version")?,
+        registration_timeouts: row.try_get("registration_timeouts")?,
+        customizer,
+        toolchain_profile: row.try_get("toolchain_profile")?,
+        snapshot_on_hibernate: row.try_get("snapshot_on_hibernate")?,
+        hibernation_snapshot_id: row.try_get("hibernation_snapshot_id")?,
     })
 }
 
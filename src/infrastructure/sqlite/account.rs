@@ -0,0 +1,153 @@
+use super::ensure_single_row_affected;
+use crate::domain::{Account, SubscriptionTier};
+use crate::infrastructure::{AccountRepository, RepositoryError};
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::{sqlite::SqliteRow, Row, SqlitePool};
+use std::str::FromStr;
+use tracing::instrument;
+use uuid::Uuid;
+
+const ACCOUNT_COLUMNS: &str =
+    "id, external_id, subscription_tier, max_bots, customizer, created_at, updated_at";
+
+pub struct SqliteAccountRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteAccountRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AccountRepository for SqliteAccountRepository {
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "accounts"))]
+    async fn create(&self, account: &Account) -> Result<(), RepositoryError> {
+        let customizer_json = serde_json::to_value(&account.customizer).map_err(|e| {
+            RepositoryError::InvalidData(format!("Failed to serialize customizer: {}", e))
+        })?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO accounts (id, external_id, subscription_tier, max_bots, customizer,
+                                  created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            "#,
+        )
+        .bind(account.id)
+        .bind(&account.external_id)
+        .bind(account.subscription_tier.to_string())
+        .bind(account.max_bots)
+        .bind(customizer_json)
+        .bind(account.created_at)
+        .bind(account.updated_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "accounts"))]
+    async fn get_by_id(&self, id: Uuid) -> Result<Account, RepositoryError> {
+        let row = sqlx::query(&format!(
+            "SELECT {ACCOUNT_COLUMNS} FROM accounts WHERE id = ?1"
+        ))
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => RepositoryError::NotFound(format!("Account {}", id)),
+            _ => RepositoryError::DatabaseError(e),
+        })?;
+
+        row_to_account(&row)
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "accounts"))]
+    async fn get_by_external_id(&self, external_id: &str) -> Result<Account, RepositoryError> {
+        let row = sqlx::query(&format!(
+            "SELECT {ACCOUNT_COLUMNS} FROM accounts WHERE external_id = ?1"
+        ))
+        .bind(external_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => {
+                RepositoryError::NotFound(format!("Account {}", external_id))
+            }
+            _ => RepositoryError::DatabaseError(e),
+        })?;
+
+        row_to_account(&row)
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "accounts"))]
+    async fn update_subscription(
+        &self,
+        id: Uuid,
+        tier: SubscriptionTier,
+    ) -> Result<(), RepositoryError> {
+        // The update_account_counter_max trigger resizes the bot counter.
+        let result = sqlx::query(
+            r#"
+            UPDATE accounts
+            SET subscription_tier = ?1, max_bots = ?2, updated_at = ?3
+            WHERE id = ?4
+            "#,
+        )
+        .bind(tier.to_string())
+        .bind(tier.max_bots())
+        .bind(Utc::now())
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        ensure_single_row_affected(result, "Account", id)
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "accounts"))]
+    async fn list(
+        &self,
+        tier: Option<SubscriptionTier>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Account>, RepositoryError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {ACCOUNT_COLUMNS}
+            FROM accounts
+            WHERE (?1 IS NULL OR subscription_tier = ?1)
+            ORDER BY created_at DESC
+            LIMIT ?2 OFFSET ?3
+            "#
+        ))
+        .bind(tier.map(|t| t.to_string()))
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(row_to_account).collect()
+    }
+}
+
+fn row_to_account(row: &SqliteRow) -> Result<Account, RepositoryError> {
+    let tier_str: String = row.try_get("subscription_tier")?;
+    let customizer_json: serde_json::Value = row.try_get("customizer")?;
+    let customizer = serde_json::from_value(customizer_json).map_err(|e| {
+        RepositoryError::InvalidData(format!("Failed to deserialize customizer: {}", e))
+    })?;
+
+    Ok(Account {
+        id: row.try_get("id")?,
+        external_id: row.try_get("external_id")?,
+        subscription_tier: SubscriptionTier::from_str(&tier_str)
+            .map_err(|_| RepositoryError::InvalidData(format!("Unknown tier: {}", tier_str)))?,
+        max_bots: row.try_get("max_bots")?,
+        customizer,
+        created_at: row.try_get("created_at")?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
@@ -0,0 +1,592 @@
+use super::{ensure_single_row_affected, SqliteHandle};
+use crate::domain::{Bot, BotStatus, Persona, TransitionReason};
+use crate::infrastructure::{
+    hash_registration_token, stale_status, BotFilter, BotRepository, RepositoryError,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{sqlite::SqliteRow, Row, SqlitePool};
+use std::str::FromStr;
+use tracing::instrument;
+use uuid::Uuid;
+
+const BOT_COLUMNS: &str = "id, account_id, name, persona, status, droplet_id, \
+     desired_config_version_id, applied_config_version_id, \
+     registration_token, created_at, updated_at, last_heartbeat_at, \
+     status_reason, status_detail, status_changed_at, \
+     registered_at, registered_ip, agent_version, registration_timeouts, \
+     customizer, toolchain_profile, snapshot_on_hibernate, hibernation_snapshot_id";
+
+pub struct SqliteBotRepository {
+    db: SqliteHandle,
+}
+
+impl SqliteBotRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            db: SqliteHandle::Pool(pool),
+        }
+    }
+
+    pub(crate) fn with_handle(db: SqliteHandle) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl BotRepository for SqliteBotRepository {
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "bots"))]
+    async fn create(&self, bot: &Bot) -> Result<(), RepositoryError> {
+        let customizer_json = bot
+            .customizer
+            .as_ref()
+            .map(serde_json::to_value)
+            .transpose()
+            .map_err(|e| {
+                RepositoryError::InvalidData(format!("Failed to serialize customizer: {}", e))
+            })?;
+
+        sqlx::query(&format!(
+            r#"
+            INSERT INTO bots ({BOT_COLUMNS})
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15,
+                    ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23)
+            "#
+        ))
+        .bind(bot.id)
+        .bind(bot.account_id)
+        .bind(&bot.name)
+        .bind(bot.persona.to_string())
+        .bind(bot.status.to_string())
+        .bind(bot.droplet_id)
+        .bind(bot.desired_config_version_id)
+        .bind(bot.applied_config_version_id)
+        .bind(&bot.registration_token)
+        .bind(bot.created_at)
+        .bind(bot.updated_at)
+        .bind(bot.last_heartbeat_at)
+        .bind(bot.status_reason.map(|r| r.to_string()))
+        .bind(&bot.status_detail)
+        .bind(bot.status_changed_at)
+        .bind(bot.registered_at)
+        .bind(&bot.registered_ip)
+        .bind(&bot.agent_version)
+        .bind(bot.registration_timeouts)
+        .bind(customizer_json)
+        .bind(&bot.toolchain_profile)
+        .bind(bot.snapshot_on_hibernate)
+        .bind(bot.hibernation_snapshot_id)
+        .execute(&mut *self.db.acquire().await?)
+        .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "bots"))]
+    async fn get_by_id(&self, id: Uuid) -> Result<Bot, RepositoryError> {
+        let row = sqlx::query(&format!("SELECT {BOT_COLUMNS} FROM bots WHERE id = ?1"))
+            .bind(id)
+            .fetch_one(&mut *self.db.acquire().await?)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => RepositoryError::NotFound(format!("Bot {}", id)),
+                _ => RepositoryError::DatabaseError(e),
+            })?;
+
+        row_to_bot(&row)
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "bots"))]
+    async fn get_by_id_with_token(&self, id: Uuid, token: &str) -> Result<Bot, RepositoryError> {
+        let hashed_token = hash_registration_token(token);
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {BOT_COLUMNS}
+            FROM bots
+            WHERE id = ?1
+              AND (registration_token = ?2 OR registration_token = ?3)
+            "#
+        ))
+        .bind(id)
+        .bind(token)
+        .bind(hashed_token)
+        .fetch_one(&mut *self.db.acquire().await?)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => {
+                RepositoryError::NotFound(format!("Bot {} with invalid token", id))
+            }
+            _ => RepositoryError::DatabaseError(e),
+        })?;
+
+        row_to_bot(&row)
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "bots"))]
+    async fn list_by_account(&self, account_id: Uuid) -> Result<Vec<Bot>, RepositoryError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {BOT_COLUMNS} FROM bots WHERE account_id = ?1 ORDER BY created_at DESC"
+        ))
+        .bind(account_id)
+        .fetch_all(&mut *self.db.acquire().await?)
+        .await?;
+
+        rows.iter().map(row_to_bot).collect()
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "bots"))]
+    async fn list_by_account_paginated(
+        &self,
+        account_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Bot>, RepositoryError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {BOT_COLUMNS}
+            FROM bots
+            WHERE account_id = ?1
+            ORDER BY created_at DESC
+            LIMIT ?2 OFFSET ?3
+            "#
+        ))
+        .bind(account_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&mut *self.db.acquire().await?)
+        .await?;
+
+        rows.iter().map(row_to_bot).collect()
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "bots"))]
+    async fn count_by_account(&self, account_id: Uuid) -> Result<i64, RepositoryError> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM bots WHERE account_id = ?1")
+            .bind(account_id)
+            .fetch_one(&mut *self.db.acquire().await?)
+            .await?;
+
+        Ok(count)
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "bots"))]
+    async fn list(
+        &self,
+        filter: &BotFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Bot>, RepositoryError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {BOT_COLUMNS}
+            FROM bots
+            WHERE (?1 IS NULL OR account_id = ?1)
+              AND (?2 IS NULL OR status = ?2)
+            ORDER BY created_at DESC
+            LIMIT ?3 OFFSET ?4
+            "#
+        ))
+        .bind(filter.account_id)
+        .bind(filter.status.map(|s| s.to_string()))
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&mut *self.db.acquire().await?)
+        .await?;
+
+        rows.iter().map(row_to_bot).collect()
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "bots"))]
+    async fn transition_status(
+        &self,
+        id: Uuid,
+        from: BotStatus,
+        to: BotStatus,
+        reason: TransitionReason,
+        detail: Option<&str>,
+    ) -> Result<(), RepositoryError> {
+        from.transition_to(to, reason)?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE bots
+            SET status = ?1, updated_at = ?2, status_reason = ?5, status_detail = ?6,
+                status_changed_at = ?2
+            WHERE id = ?3 AND status = ?4
+            "#,
+        )
+        .bind(to.to_string())
+        .bind(Utc::now())
+        .bind(id)
+        .bind(from.to_string())
+        .bind(reason.to_string())
+        .bind(detail)
+        .execute(&mut *self.db.acquire().await?)
+        .await?;
+
+        if result.rows_affected() == 1 {
+            return Ok(());
+        }
+
+        // Lost the compare-and-set: report what the bot is now, or that it is gone.
+        let current = self.get_by_id(id).await?;
+        Err(stale_status(current.status, from, to))
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "bots"))]
+    async fn update_droplet(
+        &self,
+        bot_id: Uuid,
+        droplet_id: Option<i64>,
+    ) -> Result<(), RepositoryError> {
+        let result = sqlx::query("UPDATE bots SET droplet_id = ?1, updated_at = ?2 WHERE id = ?3")
+            .bind(droplet_id)
+            .bind(Utc::now())
+            .bind(bot_id)
+            .execute(&mut *self.db.acquire().await?)
+            .await?;
+
+        ensure_single_row_affected(result, "Bot", bot_id)
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "bots"))]
+    async fn update_snapshot_on_hibernate(
+        &self,
+        bot_id: Uuid,
+        snapshot: Option<bool>,
+    ) -> Result<(), RepositoryError> {
+        let result = sqlx::query(
+            "UPDATE bots SET snapshot_on_hibernate = ?1, updated_at = ?2 WHERE id = ?3",
+        )
+        .bind(snapshot)
+        .bind(Utc::now())
+        .bind(bot_id)
+        .execute(&mut *self.db.acquire().await?)
+        .await?;
+
+        ensure_single_row_affected(result, "Bot", bot_id)
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "bots"))]
+    async fn update_hibernation_snapshot(
+        &self,
+        bot_id: Uuid,
+        snapshot_id: Option<i64>,
+    ) -> Result<(), RepositoryError> {
+        let result = sqlx::query(
+            "UPDATE bots SET hibernation_snapshot_id = ?1, updated_at = ?2 WHERE id = ?3",
+        )
+        .bind(snapshot_id)
+        .bind(Utc::now())
+        .bind(bot_id)
+        .execute(&mut *self.db.acquire().await?)
+        .await?;
+
+        ensure_single_row_affected(result, "Bot", bot_id)
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "bots"))]
+    async fn update_config_version(
+        &self,
+        bot_id: Uuid,
+        desired: Option<Uuid>,
+        applied: Option<Uuid>,
+    ) -> Result<(), RepositoryError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE bots
+            SET desired_config_version_id = ?1, applied_config_version_id = ?2, updated_at = ?3
+            WHERE id = ?4
+            "#,
+        )
+        .bind(desired)
+        .bind(applied)
+        .bind(Utc::now())
+        .bind(bot_id)
+        .execute(&mut *self.db.acquire().await?)
+        .await?;
+
+        ensure_single_row_affected(result, "Bot", bot_id)
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "bots"))]
+    async fn update_heartbeat(&self, bot_id: Uuid) -> Result<(), RepositoryError> {
+        let result =
+            sqlx::query("UPDATE bots SET last_heartbeat_at = ?1, updated_at = ?1 WHERE id = ?2")
+                .bind(Utc::now())
+                .bind(bot_id)
+                .execute(&mut *self.db.acquire().await?)
+                .await?;
+
+        ensure_single_row_affected(result, "Bot", bot_id)
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "bots"))]
+    async fn update_registration_token(
+        &self,
+        bot_id: Uuid,
+        token: &str,
+    ) -> Result<(), RepositoryError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE bots
+            SET registration_token = ?1, updated_at = ?2,
+                registered_at = NULL, registered_ip = NULL, agent_version = NULL
+            WHERE id = ?3
+            "#,
+        )
+        .bind(hash_registration_token(token))
+        .bind(Utc::now())
+        .bind(bot_id)
+        .execute(&mut *self.db.acquire().await?)
+        .await?;
+
+        ensure_single_row_affected(result, "Bot", bot_id)
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "bots"))]
+    async fn reset_registration(&self, bot_id: Uuid) -> Result<(), RepositoryError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE bots
+            SET registered_at = NULL, registered_ip = NULL, agent_version = NULL,
+                updated_at = ?1
+            WHERE id = ?2
+            "#,
+        )
+        .bind(Utc::now())
+        .bind(bot_id)
+        .execute(&mut *self.db.acquire().await?)
+        .await?;
+
+        ensure_single_row_affected(result, "Bot", bot_id)
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "bots"))]
+    async fn record_registration(
+        &self,
+        id: Uuid,
+        ip: Option<&str>,
+        agent_version: Option<&str>,
+    ) -> Result<(), RepositoryError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE bots
+            SET registered_at = COALESCE(registered_at, ?1),
+                registered_ip = COALESCE(?2, registered_ip),
+                agent_version = COALESCE(?3, agent_version),
+                registration_timeouts = 0,
+                updated_at = ?1
+            WHERE id = ?4
+            "#,
+        )
+        .bind(Utc::now())
+        .bind(ip)
+        .bind(agent_version)
+        .bind(id)
+        .execute(&mut *self.db.acquire().await?)
+        .await?;
+
+        ensure_single_row_affected(result, "Bot", id)
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "bots"))]
+    async fn list_registration_overdue(
+        &self,
+        threshold: DateTime<Utc>,
+    ) -> Result<Vec<Bot>, RepositoryError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {BOT_COLUMNS}
+            FROM bots
+            WHERE status = 'provisioning'
+              AND registered_at IS NULL
+              AND status_changed_at < ?1
+            "#
+        ))
+        .bind(threshold)
+        .fetch_all(&mut *self.db.acquire().await?)
+        .await?;
+
+        rows.iter().map(row_to_bot).collect()
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "bots"))]
+    async fn increment_registration_timeouts(&self, id: Uuid) -> Result<i32, RepositoryError> {
+        let count: i32 = sqlx::query_scalar(
+            r#"
+            UPDATE bots
+            SET registration_timeouts = registration_timeouts + 1, updated_at = ?1
+            WHERE id = ?2
+            RETURNING registration_timeouts
+            "#,
+        )
+        .bind(Utc::now())
+        .bind(id)
+        .fetch_one(&mut *self.db.acquire().await?)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => RepositoryError::NotFound(format!("Bot {}", id)),
+            _ => RepositoryError::DatabaseError(e),
+        })?;
+
+        Ok(count)
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "bots"))]
+    async fn delete(&self, id: Uuid) -> Result<(), RepositoryError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE bots
+            SET status = 'destroyed', updated_at = ?1, status_reason = 'destroy',
+                status_detail = NULL, status_changed_at = ?1
+            WHERE id = ?2
+            "#,
+        )
+        .bind(Utc::now())
+        .bind(id)
+        .execute(&mut *self.db.acquire().await?)
+        .await?;
+
+        ensure_single_row_affected(result, "Bot", id)
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "bots"))]
+    async fn hard_delete(&self, id: Uuid) -> Result<(), RepositoryError> {
+        let result = sqlx::query("DELETE FROM bots WHERE id = ?1")
+            .bind(id)
+            .execute(&mut *self.db.acquire().await?)
+            .await?;
+
+        ensure_single_row_affected(result, "Bot", id)
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "account_bot_counters"))]
+    async fn increment_bot_counter(
+        &self,
+        account_id: Uuid,
+    ) -> Result<(bool, i32, i32), RepositoryError> {
+        // Same steps as the increment_bot_counter plpgsql function. Inside a unit
+        // of work this begins a savepoint rather than a second transaction.
+        let mut conn = self.db.acquire().await?;
+        let mut tx = sqlx::Connection::begin(&mut *conn).await?;
+        let incremented: Option<(i32, i32)> = sqlx::query_as(
+            r#"
+            UPDATE account_bot_counters
+            SET current_count = current_count + 1
+            WHERE account_id = ?1 AND current_count < max_count
+            RETURNING current_count, max_count
+            "#,
+        )
+        .bind(account_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let result = match incremented {
+            Some((current_count, max_count)) => (true, current_count, max_count),
+            None => {
+                let (current_count, max_count): (i32, i32) = sqlx::query_as(
+                    r#"
+                    SELECT current_count, max_count
+                    FROM account_bot_counters
+                    WHERE account_id = ?1
+                    "#,
+                )
+                .bind(account_id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| match e {
+                    sqlx::Error::RowNotFound => {
+                        RepositoryError::NotFound(format!("Account counter for {}", account_id))
+                    }
+                    _ => RepositoryError::DatabaseError(e),
+                })?;
+                (false, current_count, max_count)
+            }
+        };
+        tx.commit().await?;
+
+        Ok(result)
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "account_bot_counters"))]
+    async fn decrement_bot_counter(&self, account_id: Uuid) -> Result<(), RepositoryError> {
+        sqlx::query(
+            r#"
+            UPDATE account_bot_counters
+            SET current_count = MAX(0, current_count - 1)
+            WHERE account_id = ?1
+            "#,
+        )
+        .bind(account_id)
+        .execute(&mut *self.db.acquire().await?)
+        .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "bots"))]
+    async fn list_stale_bots(&self, threshold: DateTime<Utc>) -> Result<Vec<Bot>, RepositoryError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {BOT_COLUMNS}
+            FROM bots
+            WHERE status = 'online'
+              AND (last_heartbeat_at < ?1 OR last_heartbeat_at IS NULL)
+            "#
+        ))
+        .bind(threshold)
+        .fetch_all(&mut *self.db.acquire().await?)
+        .await?;
+
+        rows.iter().map(row_to_bot).collect()
+    }
+}
+
+fn row_to_bot(row: &SqliteRow) -> Result<Bot, RepositoryError> {
+    let status_str: String = row.try_get("status")?;
+    let persona_str: String = row.try_get("persona")?;
+    let reason_str: Option<String> = row.try_get("status_reason")?;
+    let status_reason = reason_str
+        .map(|r| {
+            TransitionReason::from_str(&r)
+                .map_err(|_| RepositoryError::InvalidData(format!("Unknown status reason: {}", r)))
+        })
+        .transpose()?;
+    let customizer_json: Option<serde_json::Value> = row.try_get("customizer")?;
+    let customizer = customizer_json
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| {
+            RepositoryError::InvalidData(format!("Failed to deserialize customizer: {}", e))
+        })?;
+
+    Ok(Bot {
+        id: row.try_get("id")?,
+        account_id: row.try_get("account_id")?,
+        name: row.try_get("name")?,
+        persona: Persona::from_str(&persona_str).map_err(|_| {
+            RepositoryError::InvalidData(format!("Unknown persona: {}", persona_str))
+        })?,
+        status: BotStatus::from_str(&status_str)
+            .map_err(|_| RepositoryError::InvalidData(format!("Unknown status: {}", status_str)))?,
+        droplet_id: row.try_get("droplet_id")?,
+        desired_config_version_id: row.try_get("desired_config_version_id")?,
+        applied_config_version_id: row.try_get("applied_config_version_id")?,
+        registration_token: row.try_get("registration_token")?,
+        created_at: row.try_get("created_at")?,
+        updated_at: row.try_get("updated_at")?,
+        last_heartbeat_at: row.try_get("last_heartbeat_at")?,
+        status_reason,
+        status_detail: row.try_get("status_detail")?,
+        status_changed_at: row.try_get("status_changed_at")?,
+        registered_at: row.try_get("registered_at")?,
+        registered_ip: row.try_get("registered_ip")?,
+        agent_version: row.try_get("agent_version")?,
+        registration_timeouts: row.try_get("registration_timeouts")?,
+        customizer,
+        toolchain_profile: row.try_get("toolchain_profile")?,
+        snapshot_on_hibernate: row.try_get("snapshot_on_hibernate")?,
+        hibernation_snapshot_id: row.try_get("hibernation_snapshot_id")?,
+    })
+}
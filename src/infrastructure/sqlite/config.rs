@@ -0,0 +1,143 @@
+use super::SqliteHandle;
+use crate::domain::{EncryptedBotSecrets, RiskConfig, StoredBotConfig, TradingConfig};
+use crate::infrastructure::{ConfigRepository, RepositoryError};
+use async_trait::async_trait;
+use sqlx::{sqlite::SqliteRow, Row, SqlitePool};
+use tracing::instrument;
+use uuid::Uuid;
+
+const CONFIG_COLUMNS: &str = "id, bot_id, version, trading_config, risk_config, \
+     secrets_encrypted, llm_provider, created_at";
+
+pub struct SqliteConfigRepository {
+    db: SqliteHandle,
+}
+
+impl SqliteConfigRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            db: SqliteHandle::Pool(pool),
+        }
+    }
+
+    pub(crate) fn with_handle(db: SqliteHandle) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ConfigRepository for SqliteConfigRepository {
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "bot_configs"))]
+    async fn create(&self, config: &StoredBotConfig) -> Result<(), RepositoryError> {
+        let trading_json = serde_json::to_value(&config.trading_config).map_err(|e| {
+            RepositoryError::InvalidData(format!("Failed to serialize trading config: {}", e))
+        })?;
+        let risk_json = serde_json::to_value(&config.risk_config).map_err(|e| {
+            RepositoryError::InvalidData(format!("Failed to serialize risk config: {}", e))
+        })?;
+
+        sqlx::query(&format!(
+            "INSERT INTO bot_configs ({CONFIG_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"
+        ))
+        .bind(config.id)
+        .bind(config.bot_id)
+        .bind(config.version)
+        .bind(trading_json)
+        .bind(risk_json)
+        .bind(&config.secrets.llm_api_key_encrypted)
+        .bind(&config.secrets.llm_provider)
+        .bind(config.created_at)
+        .execute(&mut *self.db.acquire().await?)
+        .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "bot_configs"))]
+    async fn get_by_id(&self, id: Uuid) -> Result<StoredBotConfig, RepositoryError> {
+        let row = sqlx::query(&format!(
+            "SELECT {CONFIG_COLUMNS} FROM bot_configs WHERE id = ?1"
+        ))
+        .bind(id)
+        .fetch_one(&mut *self.db.acquire().await?)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => RepositoryError::NotFound(format!("Config {}", id)),
+            _ => RepositoryError::DatabaseError(e),
+        })?;
+
+        row_to_config(&row)
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "bot_configs"))]
+    async fn get_latest_for_bot(
+        &self,
+        bot_id: Uuid,
+    ) -> Result<Option<StoredBotConfig>, RepositoryError> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {CONFIG_COLUMNS}
+            FROM bot_configs
+            WHERE bot_id = ?1
+            ORDER BY version DESC
+            LIMIT 1
+            "#
+        ))
+        .bind(bot_id)
+        .fetch_optional(&mut *self.db.acquire().await?)
+        .await?;
+
+        row.as_ref().map(row_to_config).transpose()
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "bot_configs"))]
+    async fn list_by_bot(&self, bot_id: Uuid) -> Result<Vec<StoredBotConfig>, RepositoryError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {CONFIG_COLUMNS} FROM bot_configs WHERE bot_id = ?1 ORDER BY version ASC"
+        ))
+        .bind(bot_id)
+        .fetch_all(&mut *self.db.acquire().await?)
+        .await?;
+
+        rows.iter().map(row_to_config).collect()
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "bot_configs"))]
+    async fn get_next_version_atomic(&self, bot_id: Uuid) -> Result<i32, RepositoryError> {
+        // Stands in for get_next_config_version_atomic. Two callers can still read
+        // the same number; UNIQUE (bot_id, version) rejects the second insert.
+        let version: i32 = sqlx::query_scalar(
+            "SELECT COALESCE(MAX(version), 0) + 1 FROM bot_configs WHERE bot_id = ?1",
+        )
+        .bind(bot_id)
+        .fetch_one(&mut *self.db.acquire().await?)
+        .await?;
+
+        Ok(version)
+    }
+}
+
+fn row_to_config(row: &SqliteRow) -> Result<StoredBotConfig, RepositoryError> {
+    let trading_json: serde_json::Value = row.try_get("trading_config")?;
+    let risk_json: serde_json::Value = row.try_get("risk_config")?;
+
+    let trading_config: TradingConfig = serde_json::from_value(trading_json).map_err(|e| {
+        RepositoryError::InvalidData(format!("Failed to deserialize trading config: {}", e))
+    })?;
+    let risk_config: RiskConfig = serde_json::from_value(risk_json).map_err(|e| {
+        RepositoryError::InvalidData(format!("Failed to deserialize risk config: {}", e))
+    })?;
+
+    Ok(StoredBotConfig {
+        id: row.try_get("id")?,
+        bot_id: row.try_get("bot_id")?,
+        version: row.try_get("version")?,
+        trading_config,
+        risk_config,
+        secrets: EncryptedBotSecrets {
+            llm_provider: row.try_get("llm_provider")?,
+            llm_api_key_encrypted: row.try_get("secrets_encrypted")?,
+        },
+        created_at: row.try_get("created_at")?,
+    })
+}
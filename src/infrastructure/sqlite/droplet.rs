@@ -0,0 +1,142 @@
+use super::{ensure_single_row_affected, SqliteHandle};
+use crate::domain::Droplet;
+use crate::infrastructure::{
+    droplet_status_to_string, string_to_droplet_status, DropletRepository, RepositoryError,
+};
+use async_trait::async_trait;
+use sqlx::{sqlite::SqliteRow, Row, SqlitePool};
+use tracing::instrument;
+use uuid::Uuid;
+
+const DROPLET_COLUMNS: &str = "id, name, region, size, image, status, ip_address, bot_id, \
+     created_at, destroyed_at, toolchain_profile, user_data_digest";
+
+pub struct SqliteDropletRepository {
+    db: SqliteHandle,
+}
+
+impl SqliteDropletRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            db: SqliteHandle::Pool(pool),
+        }
+    }
+
+    pub(crate) fn with_handle(db: SqliteHandle) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl DropletRepository for SqliteDropletRepository {
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "droplets"))]
+    async fn create(&self, droplet: &Droplet) -> Result<(), RepositoryError> {
+        sqlx::query(&format!(
+            r#"
+            INSERT INTO droplets ({DROPLET_COLUMNS})
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+            "#
+        ))
+        .bind(droplet.id)
+        .bind(&droplet.name)
+        .bind(&droplet.region)
+        .bind(&droplet.size)
+        .bind(&droplet.image)
+        .bind(droplet_status_to_string(&droplet.status))
+        .bind(&droplet.ip_address)
+        .bind(droplet.bot_id)
+        .bind(droplet.created_at)
+        .bind(droplet.destroyed_at)
+        .bind(&droplet.toolchain_profile)
+        .bind(&droplet.user_data_digest)
+        .execute(&mut *self.db.acquire().await?)
+        .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "droplets"))]
+    async fn get_by_id(&self, id: i64) -> Result<Droplet, RepositoryError> {
+        let row = sqlx::query(&format!(
+            "SELECT {DROPLET_COLUMNS} FROM droplets WHERE id = ?1"
+        ))
+        .bind(id)
+        .fetch_one(&mut *self.db.acquire().await?)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => RepositoryError::NotFound(format!("Droplet {}", id)),
+            _ => RepositoryError::DatabaseError(e),
+        })?;
+
+        row_to_droplet(&row)
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "droplets"))]
+    async fn update_bot_assignment(
+        &self,
+        droplet_id: i64,
+        bot_id: Option<Uuid>,
+    ) -> Result<(), RepositoryError> {
+        let result = sqlx::query("UPDATE droplets SET bot_id = ?1 WHERE id = ?2")
+            .bind(bot_id)
+            .bind(droplet_id)
+            .execute(&mut *self.db.acquire().await?)
+            .await?;
+
+        ensure_single_row_affected(result, "Droplet", droplet_id)
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "droplets"))]
+    async fn update_status(&self, droplet_id: i64, status: &str) -> Result<(), RepositoryError> {
+        let result = sqlx::query("UPDATE droplets SET status = ?1 WHERE id = ?2")
+            .bind(status)
+            .bind(droplet_id)
+            .execute(&mut *self.db.acquire().await?)
+            .await?;
+
+        ensure_single_row_affected(result, "Droplet", droplet_id)
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "droplets"))]
+    async fn update_ip(&self, droplet_id: i64, ip: Option<String>) -> Result<(), RepositoryError> {
+        let result = sqlx::query("UPDATE droplets SET ip_address = ?1 WHERE id = ?2")
+            .bind(ip)
+            .bind(droplet_id)
+            .execute(&mut *self.db.acquire().await?)
+            .await?;
+
+        ensure_single_row_affected(result, "Droplet", droplet_id)
+    }
+
+    #[instrument(skip_all, fields(db.system = "sqlite", db.table = "droplets"))]
+    async fn mark_destroyed(&self, droplet_id: i64) -> Result<(), RepositoryError> {
+        let result = sqlx::query(
+            "UPDATE droplets SET status = 'destroyed', destroyed_at = ?1 WHERE id = ?2",
+        )
+        .bind(chrono::Utc::now())
+        .bind(droplet_id)
+        .execute(&mut *self.db.acquire().await?)
+        .await?;
+
+        ensure_single_row_affected(result, "Droplet", droplet_id)
+    }
+}
+
+fn row_to_droplet(row: &SqliteRow) -> Result<Droplet, RepositoryError> {
+    let status_str: String = row.try_get("status")?;
+
+    Ok(Droplet {
+        id: row.try_get("id")?,
+        name: row.try_get("name")?,
+        region: row.try_get("region")?,
+        size: row.try_get("size")?,
+        image: row.try_get("image")?,
+        status: string_to_droplet_status(&status_str)?,
+        ip_address: row.try_get("ip_address")?,
+        bot_id: row.try_get("bot_id")?,
+        created_at: row.try_get("created_at")?,
+        destroyed_at: row.try_get("destroyed_at")?,
+        toolchain_profile: row.try_get("toolchain_profile")?,
+        user_data_digest: row.try_get("user_data_digest")?,
+    })
+}
@@ -0,0 +1,55 @@
+//! SQLite storage backend (`sqlite` feature) for small self-hosted deployments
+//! and local development.
+//!
+//! Mirrors the Postgres repositories over the schema in `migrations/sqlite`.
+//! Timestamps are compared as RFC 3339 text, so every one is bound from Rust
+//! in UTC rather than taken from SQLite's clock.
+
+mod account;
+mod bootstrap;
+mod bot;
+mod config;
+mod droplet;
+mod idempotency;
+mod job;
+mod provider_operation;
+mod unit_of_work;
+
+pub use account::SqliteAccountRepository;
+pub use bootstrap::{SqliteBootstrapEventRepository, SqliteBootstrapLogRepository};
+pub use bot::SqliteBotRepository;
+pub use config::SqliteConfigRepository;
+pub use droplet::SqliteDropletRepository;
+pub use idempotency::SqliteIdempotencyRepository;
+pub use job::SqliteJobRepository;
+pub use provider_operation::SqliteProviderOperationRepository;
+pub(crate) use unit_of_work::SqliteHandle;
+pub use unit_of_work::SqliteUnitOfWork;
+
+use crate::infrastructure::{RepositoryError, StorageHealth};
+use async_trait::async_trait;
+use sqlx::migrate::Migrator;
+use sqlx::sqlite::SqliteQueryResult;
+use sqlx::SqlitePool;
+
+/// Migrations for this backend; the Postgres ones live one directory up.
+pub static SQLITE_MIGRATOR: Migrator = sqlx::migrate!("./migrations/sqlite");
+
+fn ensure_single_row_affected(
+    result: SqliteQueryResult,
+    resource: &str,
+    id: impl std::fmt::Display,
+) -> Result<(), RepositoryError> {
+    if result.rows_affected() == 0 {
+        return Err(RepositoryError::NotFound(format!("{resource} {id}")));
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl StorageHealth for SqlitePool {
+    async fn ping(&self) -> Result<(), RepositoryError> {
+        sqlx::query("SELECT 1").fetch_one(self).await?;
+        Ok(())
+    }
+}
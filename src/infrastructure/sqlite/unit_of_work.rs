@@ -0,0 +1,137 @@
+use super::{
+    SqliteBotRepository, SqliteConfigRepository, SqliteDropletRepository, SqliteJobRepository,
+    SqliteProviderOperationRepository,
+};
+use crate::infrastructure::{
+    BotRepository, ConfigRepository, DropletRepository, JobRepository, ProviderOperationRepository,
+    RepositoryError, RepositoryTransaction, UnitOfWork,
+};
+use async_trait::async_trait;
+use sqlx::pool::PoolConnection;
+use sqlx::{Sqlite, SqliteConnection, SqlitePool, Transaction};
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+use tokio::sync::{Mutex, MutexGuard};
+
+/// Where a SQLite repository runs its queries: the pool, or an open transaction
+/// shared by the repositories of one [`SqliteUnitOfWork::begin`].
+#[derive(Clone)]
+pub(crate) enum SqliteHandle {
+    Pool(SqlitePool),
+    Transaction(Arc<Mutex<Transaction<'static, Sqlite>>>),
+}
+
+impl SqliteHandle {
+    /// A connection for the next query, held until the returned guard drops.
+    pub(crate) async fn acquire(&self) -> Result<SqliteConn<'_>, sqlx::Error> {
+        match self {
+            Self::Pool(pool) => Ok(SqliteConn::Pool(pool.acquire().await?)),
+            Self::Transaction(tx) => Ok(SqliteConn::Transaction(tx.lock().await)),
+        }
+    }
+}
+
+pub(crate) enum SqliteConn<'a> {
+    Pool(PoolConnection<Sqlite>),
+    Transaction(MutexGuard<'a, Transaction<'static, Sqlite>>),
+}
+
+impl Deref for SqliteConn<'_> {
+    type Target = SqliteConnection;
+
+    fn deref(&self) -> &SqliteConnection {
+        match self {
+            Self::Pool(conn) => conn,
+            Self::Transaction(tx) => tx,
+        }
+    }
+}
+
+impl DerefMut for SqliteConn<'_> {
+    fn deref_mut(&mut self) -> &mut SqliteConnection {
+        match self {
+            Self::Pool(conn) => conn,
+            Self::Transaction(tx) => tx,
+        }
+    }
+}
+
+/// Holds a pool connection for the whole transaction, so a caller must not
+/// query through the pool-backed repositories until it commits or drops it.
+pub struct SqliteUnitOfWork {
+    pool: SqlitePool,
+}
+
+impl SqliteUnitOfWork {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl UnitOfWork for SqliteUnitOfWork {
+    async fn begin(&self) -> Result<Box<dyn RepositoryTransaction>, RepositoryError> {
+        let tx = Arc::new(Mutex::new(self.pool.begin().await?));
+        let handle = SqliteHandle::Transaction(tx.clone());
+        Ok(Box::new(SqliteTransaction {
+            tx,
+            bots: SqliteBotRepository::with_handle(handle.clone()),
+            configs: SqliteConfigRepository::with_handle(handle.clone()),
+            droplets: SqliteDropletRepository::with_handle(handle.clone()),
+            jobs: SqliteJobRepository::with_handle(handle.clone()),
+            provider_operations: SqliteProviderOperationRepository::with_handle(handle),
+        }))
+    }
+}
+
+struct SqliteTransaction {
+    tx: Arc<Mutex<Transaction<'static, Sqlite>>>,
+    bots: SqliteBotRepository,
+    configs: SqliteConfigRepository,
+    droplets: SqliteDropletRepository,
+    jobs: SqliteJobRepository,
+    provider_operations: SqliteProviderOperationRepository,
+}
+
+#[async_trait]
+impl RepositoryTransaction for SqliteTransaction {
+    fn bots(&self) -> &dyn BotRepository {
+        &self.bots
+    }
+
+    fn configs(&self) -> &dyn ConfigRepository {
+        &self.configs
+    }
+
+    fn droplets(&self) -> &dyn DropletRepository {
+        &self.droplets
+    }
+
+    fn jobs(&self) -> &dyn JobRepository {
+        &self.jobs
+    }
+
+    fn provider_operations(&self) -> &dyn ProviderOperationRepository {
+        &self.provider_operations
+    }
+
+    async fn commit(self: Box<Self>) -> Result<(), RepositoryError> {
+        let Self {
+            tx,
+            bots,
+            configs,
+            droplets,
+            jobs,
+            provider_operations,
+        } = *self;
+        // The repositories hold the other references to the transaction.
+        drop((bots, configs, droplets, jobs, provider_operations));
+        let tx = Arc::into_inner(tx).ok_or_else(|| {
+            RepositoryError::InvalidData(
+                "Transaction is still borrowed by a repository".to_string(),
+            )
+        })?;
+        tx.into_inner().commit().await?;
+        Ok(())
+    }
+}
@@ -15,12 +15,19 @@
 //! ```rust,ignore
 //! use axum::Router;
 //! use claw_spawn::infrastructure::AppConfig;
-//! use claw_spawn::server::{build_state_with_pool, router};
+//! use claw_spawn::application::JobWorkerSettings;
+//! use claw_spawn::server::{
+//!     build_state_with_pool, router, spawn_job_workers, spawn_outbox_executor, spawn_watchdog,
+//! };
 //! use sqlx::PgPool;
 //!
 //! let cfg = AppConfig::from_env()?;
 //! let pool = PgPool::connect(&cfg.database_url).await?;
+//! let job_settings = JobWorkerSettings::from_config(&cfg);
 //! let state = build_state_with_pool(cfg, pool, true).await?;
+//! spawn_job_workers(&state, job_settings);
+//! spawn_watchdog(&state);
+//! spawn_outbox_executor(&state);
 //! let app = Router::new().nest("/spawn", router(state));
 //! ```
 
@@ -33,6 +40,10 @@ pub mod infrastructure;
 #[cfg(feature = "server")]
 pub mod server;
 
+// Admin CLI (`claw-spawn-cli`), behind the `cli` feature.
+#[cfg(feature = "cli")]
+pub mod cli;
+
 pub use application::*;
 pub use domain::*;
 pub use infrastructure::*;
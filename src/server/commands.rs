@@ -0,0 +1,162 @@
+//! Subcommands of the `claw-spawn-server` binary besides `serve`.
+
+use super::state::{self, check_config, Database};
+use crate::infrastructure::{AppConfig, DigitalOceanClient};
+use anyhow::Context;
+use clap::{Parser, Subcommand};
+use std::collections::HashMap;
+use tracing_subscriber::EnvFilter;
+use uuid::Uuid;
+
+#[derive(Parser)]
+#[command(
+    name = "claw-spawn-server",
+    version,
+    about = "Claw Spawn control plane"
+)]
+pub(super) struct Cli {
+    /// Defaults to `serve`.
+    #[command(subcommand)]
+    pub(super) command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub(super) enum Command {
+    /// Run the HTTP server, job workers, watchdog and outbox executor.
+    Serve {
+        /// Skip migrations, e.g. when a separate `migrate up` step runs them
+        /// before several replicas roll out.
+        #[arg(long)]
+        no_migrate: bool,
+    },
+    /// Apply or inspect database migrations.
+    #[command(subcommand)]
+    Migrate(MigrateCommand),
+    /// Validate the config, check database and DigitalOcean connectivity, and
+    /// print the effective config with secrets masked.
+    CheckConfig,
+    /// Print the user data the bot's next droplet would get.
+    PrintUserData {
+        #[arg(long)]
+        bot_id: Uuid,
+    },
+}
+
+#[derive(Subcommand)]
+pub(super) enum MigrateCommand {
+    /// Apply pending migrations.
+    Up,
+    /// List migrations and whether each is applied.
+    Status,
+}
+
+/// Log to stderr, keeping stdout for the command's output, and load the config.
+pub(super) fn init() -> anyhow::Result<AppConfig> {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn")),
+        )
+        .with_writer(std::io::stderr)
+        .init();
+    AppConfig::from_env().context("load config")
+}
+
+pub(super) async fn migrate(config: &AppConfig, command: MigrateCommand) -> anyhow::Result<()> {
+    let database = Database::connect(config).await?;
+    match command {
+        MigrateCommand::Up => {
+            database.migrate().await?;
+            println!("Migrations are up to date");
+            Ok(())
+        }
+        MigrateCommand::Status => migration_status(&database).await,
+    }
+}
+
+async fn migration_status(database: &Database) -> anyhow::Result<()> {
+    let applied = applied_migrations(database)
+        .await
+        .context("list applied migrations")?;
+
+    let mut pending = 0;
+    for migration in database
+        .migrator()
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+    {
+        let status = match applied.get(&migration.version) {
+            Some(true) => "applied",
+            Some(false) => "failed",
+            None => {
+                pending += 1;
+                "pending"
+            }
+        };
+        println!(
+            "{:>4}  {:<8}  {}",
+            migration.version, status, migration.description
+        );
+    }
+    println!("{pending} pending");
+    Ok(())
+}
+
+/// `version -> success` from `_sqlx_migrations`, empty if it does not exist yet.
+async fn applied_migrations(database: &Database) -> Result<HashMap<i64, bool>, sqlx::Error> {
+    const APPLIED: &str = "SELECT version, success FROM _sqlx_migrations";
+    let rows = match database {
+        Database::Postgres(pool) => {
+            let has_table: bool =
+                sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+                    .fetch_one(pool)
+                    .await?;
+            if !has_table {
+                return Ok(HashMap::new());
+            }
+            sqlx::query_as(APPLIED).fetch_all(pool).await?
+        }
+        #[cfg(feature = "sqlite")]
+        Database::Sqlite(pool) => {
+            let has_table: bool = sqlx::query_scalar(
+                "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE name = '_sqlx_migrations')",
+            )
+            .fetch_one(pool)
+            .await?;
+            if !has_table {
+                return Ok(HashMap::new());
+            }
+            sqlx::query_as(APPLIED).fetch_all(pool).await?
+        }
+    };
+    Ok(rows.into_iter().collect())
+}
+
+pub(super) async fn check_config_and_connectivity(config: &AppConfig) -> anyhow::Result<()> {
+    check_config(config)?;
+    println!("{:#?}", config.redacted());
+
+    Database::connect(config)
+        .await?
+        .repositories()
+        .health
+        .ping()
+        .await
+        .context("query database")?;
+    println!("database: ok");
+
+    DigitalOceanClient::new(config.digitalocean_token.clone())
+        .context("init DigitalOcean client")?
+        .check_account()
+        .await
+        .context("reach DigitalOcean API")?;
+    println!("digitalocean: ok");
+    Ok(())
+}
+
+pub(super) async fn print_user_data(config: AppConfig, bot_id: Uuid) -> anyhow::Result<()> {
+    check_config(&config)?;
+    let state = state::connect_and_build_state(config, false).await?;
+    let user_data = state.provisioning.preview_user_data(bot_id).await?;
+    print!("{user_data}");
+    Ok(())
+}
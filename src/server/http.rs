@@ -2,56 +2,107 @@ use super::state::AppState;
 use super::{
     http_auth::{extract_bearer_token, is_admin_authorized},
     http_errors::{
-        map_account_read_error, map_ack_config_error, map_bot_action_error, map_bot_config_error,
-        map_bot_read_error, map_create_bot_error,
+        map_account_read_error, map_ack_config_error, map_bootstrap_log_error,
+        map_bootstrap_progress_error, map_bot_action_error, map_bot_config_error,
+        map_bot_read_error, map_create_bot_error, map_enqueue_job_error, map_job_read_error,
+        map_register_bot_error,
     },
+    http_idempotency::idempotency,
     http_parse::{
-        parse_algorithm, parse_asset_focus, parse_persona, parse_strictness, parse_subscription_tier,
+        parse_agent_version, parse_algorithm, parse_asset_focus, parse_bootstrap_log_source,
+        parse_bootstrap_message, parse_bootstrap_phase, parse_exit_code, parse_ip_address,
+        parse_persona, parse_strictness, parse_subscription_tier,
     },
+    http_trace::{make_request_span, record_response, REQUEST_ID_HEADER},
     http_types::{
-        AckConfigRequest, BotActionRequest, BotResponse, CreateAccountRequest, CreateBotRequest,
-        HealthResponse, PaginationParams, RegisterBotRequest,
+        AckConfigRequest, BootstrapEventResponse, BootstrapLogParams, BootstrapLogResponse,
+        BootstrapLogsResponse, BootstrapProgressRequest, BootstrapTimelineResponse,
+        BotActionRequest, BotResponse, CreateAccountRequest, CreateBotRequest, CustomizerRequest,
+        HealthResponse, JobAcceptedResponse, JobResponse, PaginationParams, RateLimitResponse,
+        RegisterBotRequest,
     },
 };
 use crate::application::ProvisioningError;
 use crate::domain::{
-    Account, BotConfig, BotSecrets, Persona, RiskConfig, SignalKnobs, StrictnessLevel, TradingConfig,
+    Account, BotConfig, BotSecrets, BotStatus, JobKind, RiskConfig, SignalKnobs, TradingConfig,
+    TransitionReason, MAX_BOOTSTRAP_LOG_BYTES,
 };
-use crate::infrastructure::AccountRepository;
 use axum::{
-    extract::{Path, Query, State},
+    body::Bytes,
+    extract::{DefaultBodyLimit, Path, Query, State},
     http::{header::HeaderMap, StatusCode},
-    response::IntoResponse,
+    middleware,
+    response::{IntoResponse, Response},
     routing::{get, post},
     Json, Router,
 };
-use tracing::{error, info};
+use tower_http::{
+    request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
+    trace::TraceLayer,
+};
+use tracing::{error, info, warn};
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 use uuid::Uuid;
 
 pub fn router(state: AppState) -> Router {
+    // Mutating admin routes that honour an `Idempotency-Key` header.
+    let idempotent = Router::new()
+        .route("/accounts", post(create_account))
+        .route("/bots", post(create_bot))
+        .route("/bots/:id/actions", post(bot_action))
+        .route_layer(middleware::from_fn_with_state(state.clone(), idempotency));
+
     Router::new()
         .route("/health", get(health_check))
-        .route("/accounts", post(create_account))
+        .merge(idempotent)
         .route("/accounts/:id", get(get_account))
         .route("/accounts/:id/bots", get(list_bots))
-        .route("/bots", post(create_bot))
         .route("/bots/:id", get(get_bot))
         .route("/bots/:id/config", get(get_bot_config))
-        .route("/bots/:id/actions", post(bot_action))
+        .route("/bots/:id/bootstrap", get(get_bot_bootstrap))
+        .route("/bots/:id/bootstrap/logs", get(get_bot_bootstrap_logs))
+        .route("/jobs/:id", get(get_job))
         .route("/bot/register", post(register_bot))
         .route("/bot/:id/config", get(get_desired_config))
+        .route("/bot/:id/user_data", get(get_bot_user_data))
         .route("/bot/:id/config_ack", post(acknowledge_config))
         .route("/bot/:id/heartbeat", post(record_heartbeat))
+        .route(
+            "/bot/:id/bootstrap_progress",
+            post(record_bootstrap_progress),
+        )
+        .route(
+            "/bot/:id/bootstrap_logs",
+            post(upload_bootstrap_log).layer(DefaultBodyLimit::max(MAX_BOOTSTRAP_LOG_BYTES)),
+        )
         .merge(SwaggerUi::new("/docs").url("/api-docs/openapi.json", ApiDoc::openapi()))
+        // Layers run outermost-last: assign/keep `x-request-id`, open the request span,
+        // then echo the ID back on the response.
+        .layer(PropagateRequestIdLayer::new(REQUEST_ID_HEADER))
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(make_request_span)
+                .on_response(record_response),
+        )
+        .layer(SetRequestIdLayer::new(REQUEST_ID_HEADER, MakeRequestUuid))
         .with_state(state)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use axum::http::{header, HeaderValue};
+    use crate::domain::{
+        Account, Bot, Droplet, DropletStatus, IdempotencyRecord, Persona, SubscriptionTier,
+    };
+    use crate::infrastructure::{
+        AccountRepository, BootstrapLogRepository, BotRepository, DropletRepository,
+        IdempotencyRepository, JobRepository, MemoryStore, RepositoryError,
+    };
+    use crate::server::http_idempotency::{
+        is_valid_key, request_fingerprint, IDEMPOTENT_REPLAYED_HEADER,
+    };
+    use axum::http::{header, HeaderValue, Method};
 
     #[test]
     fn extract_bearer_token_happy_path() {
@@ -83,6 +134,514 @@ mod tests {
         assert_eq!(extract_bearer_token(&headers), None);
     }
 
+    async fn test_state() -> AppState {
+        let config = crate::infrastructure::AppConfig {
+            api_bearer_token: "admin-token".to_string(),
+            ..crate::infrastructure::AppConfig::for_tests()
+        };
+        // Lazy pool: handlers exercised here reject before touching the database.
+        let pool = sqlx::PgPool::connect_lazy(&config.database_url).expect("lazy pool");
+        crate::server::build_state_with_pool(config, pool, false)
+            .await
+            .expect("test state")
+    }
+
+    /// State over `store`, for handlers that need working repositories.
+    async fn memory_state(store: &MemoryStore) -> AppState {
+        let config = crate::infrastructure::AppConfig {
+            api_bearer_token: "admin-token".to_string(),
+            ..crate::infrastructure::AppConfig::for_tests()
+        };
+        crate::server::build_state_with_repositories(
+            config,
+            crate::server::Repositories::memory(store.clone()),
+        )
+        .await
+        .expect("test state")
+    }
+
+    fn keyed_create_account(
+        key: &str,
+        body: &'static str,
+    ) -> axum::http::Request<axum::body::Body> {
+        axum::http::Request::post("/accounts")
+            .header("authorization", "Bearer admin-token")
+            .header("content-type", "application/json")
+            .header("idempotency-key", key)
+            .body(axum::body::Body::from(body))
+            .unwrap()
+    }
+
+    async fn body_bytes(response: axum::response::Response) -> axum::body::Bytes {
+        axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap()
+    }
+
+    async fn send(
+        app: Router,
+        request: axum::http::Request<axum::body::Body>,
+    ) -> axum::response::Response {
+        use tower::ServiceExt;
+        app.oneshot(request).await.expect("router is infallible")
+    }
+
+    #[tokio::test]
+    async fn request_id_is_generated_when_missing() {
+        let app = router(test_state().await);
+        let response = send(
+            app,
+            axum::http::Request::get(format!("/bots/{}", Uuid::new_v4()))
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let request_id = response
+            .headers()
+            .get("x-request-id")
+            .and_then(|v| v.to_str().ok())
+            .expect("x-request-id on response");
+        assert!(Uuid::parse_str(request_id).is_ok());
+    }
+
+    #[tokio::test]
+    async fn request_id_from_caller_is_propagated() {
+        let app = router(test_state().await);
+        let response = send(
+            app,
+            axum::http::Request::get(format!("/bots/{}", Uuid::new_v4()))
+                .header("x-request-id", "caller-supplied-id")
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+        .await;
+
+        assert_eq!(
+            response.headers().get("x-request-id").unwrap(),
+            "caller-supplied-id"
+        );
+    }
+
+    #[tokio::test]
+    async fn idempotency_key_is_ignored_for_unauthorized_requests() {
+        let app = router(test_state().await);
+        let response = send(
+            app,
+            axum::http::Request::post("/accounts")
+                .header("content-type", "application/json")
+                .header("idempotency-key", "retry-1")
+                .body(axum::body::Body::from(
+                    r#"{"external_id":"u1","tier":"free"}"#,
+                ))
+                .unwrap(),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn invalid_idempotency_key_is_rejected() {
+        let app = router(test_state().await);
+        let response = send(
+            app,
+            axum::http::Request::post("/accounts")
+                .header("authorization", "Bearer admin-token")
+                .header("content-type", "application/json")
+                .header("idempotency-key", "has spaces")
+                .body(axum::body::Body::from(
+                    r#"{"external_id":"u1","tier":"free"}"#,
+                ))
+                .unwrap(),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn idempotency_key_replays_the_first_response() {
+        let store = MemoryStore::new();
+        let app = router(memory_state(&store).await);
+        let body = r#"{"external_id":"u1","tier":"free"}"#;
+
+        let first = send(app.clone(), keyed_create_account("retry-1", body)).await;
+        assert_eq!(first.status(), StatusCode::CREATED);
+        assert!(first.headers().get(IDEMPOTENT_REPLAYED_HEADER).is_none());
+        let created = body_bytes(first).await;
+
+        let retry = send(app.clone(), keyed_create_account("retry-1", body)).await;
+        assert_eq!(retry.status(), StatusCode::CREATED);
+        assert_eq!(retry.headers()[IDEMPOTENT_REPLAYED_HEADER], "true");
+        assert_eq!(body_bytes(retry).await, created);
+        let accounts = store.accounts().list(None, 10, 0).await.unwrap();
+        assert_eq!(accounts.len(), 1);
+
+        let other_body = r#"{"external_id":"u2","tier":"free"}"#;
+        let reused = send(app, keyed_create_account("retry-1", other_body)).await;
+        assert_eq!(reused.status(), StatusCode::CONFLICT);
+        let accounts = store.accounts().list(None, 10, 0).await.unwrap();
+        assert_eq!(accounts.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn idempotency_key_in_flight_conflicts_until_abandoned() {
+        let store = MemoryStore::new();
+        let state = memory_state(&store).await;
+        let window = chrono::Duration::from_std(state.idempotency_abandon_after).unwrap();
+        let app = router(state);
+        let body = r#"{"external_id":"u1","tier":"free"}"#;
+        let fingerprint = request_fingerprint(&Method::POST, "/accounts", body.as_bytes());
+        let long_ago = chrono::Utc::now() - chrono::Duration::days(2);
+        let idempotency = store.idempotency();
+
+        let running = IdempotencyRecord::new("retry-1".to_string(), fingerprint.clone());
+        assert!(idempotency
+            .reserve(&running, long_ago, long_ago)
+            .await
+            .unwrap()
+            .is_none());
+        let response = send(app.clone(), keyed_create_account("retry-1", body)).await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        // Held for longer than the window, as by a request that died.
+        let mut abandoned = IdempotencyRecord::new("retry-2".to_string(), fingerprint);
+        abandoned.created_at = chrono::Utc::now() - window - chrono::Duration::seconds(1);
+        assert!(idempotency
+            .reserve(&abandoned, long_ago, long_ago)
+            .await
+            .unwrap()
+            .is_none());
+        let response = send(app, keyed_create_account("retry-2", body)).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert!(response.headers().get(IDEMPOTENT_REPLAYED_HEADER).is_none());
+
+        // Should the original request finish after all, its response is discarded.
+        let err = idempotency
+            .complete(&abandoned, 201, b"{}")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RepositoryError::Conflict(_)));
+    }
+
+    #[tokio::test]
+    async fn unknown_redeploy_strategy_is_rejected() {
+        let response = send(
+            router(test_state().await),
+            axum::http::Request::post(format!("/bots/{}/actions", Uuid::new_v4()))
+                .header("authorization", "Bearer admin-token")
+                .header("content-type", "application/json")
+                .body(axum::body::Body::from(
+                    r#"{"action":"redeploy","strategy":"in_place"}"#,
+                ))
+                .unwrap(),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    fn admin_post(path: &str, body: serde_json::Value) -> axum::http::Request<axum::body::Body> {
+        axum::http::Request::post(path)
+            .header("authorization", "Bearer admin-token")
+            .header("content-type", "application/json")
+            .body(axum::body::Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    async fn body_json(response: axum::response::Response) -> serde_json::Value {
+        serde_json::from_slice(&body_bytes(response).await).unwrap()
+    }
+
+    #[tokio::test]
+    async fn rebuild_is_queued_only_while_the_droplet_user_data_is_current() {
+        let store = MemoryStore::new();
+        let state = memory_state(&store).await;
+        let provisioning = state.provisioning.clone();
+        let app = router(state);
+        let account = send(
+            app.clone(),
+            admin_post(
+                "/accounts",
+                serde_json::json!({"external_id": "u1", "tier": "basic"}),
+            ),
+        )
+        .await;
+        let account_id = body_json(account).await["id"].clone();
+        let created = send(
+            app.clone(),
+            admin_post(
+                "/bots",
+                serde_json::json!({
+                    "account_id": account_id,
+                    "name": "steady",
+                    "persona": "beginner",
+                    "asset_focus": "majors",
+                    "algorithm": "trend",
+                    "strictness": "medium",
+                    "paper_mode": true,
+                    "max_position_size_pct": 10.0,
+                    "max_daily_loss_pct": 5.0,
+                    "max_drawdown_pct": 10.0,
+                    "max_trades_per_day": 10,
+                    "llm_provider": "test",
+                    "llm_api_key": "test-key",
+                }),
+            ),
+        )
+        .await;
+        assert_eq!(created.status(), StatusCode::ACCEPTED);
+        let bot_id: Uuid =
+            serde_json::from_value(body_json(created).await["bot"]["id"].clone()).unwrap();
+        let lease = chrono::Duration::minutes(10);
+        let spawn = store.jobs().claim_next(lease).await.unwrap().unwrap();
+        store.jobs().mark_succeeded(&spawn).await.unwrap();
+
+        let bot = store.bots().get_by_id(bot_id).await.unwrap();
+        let current = provisioning.boot_user_data_digest(&bot).await.unwrap();
+        let rebuild = || {
+            admin_post(
+                &format!("/bots/{}/actions", bot_id),
+                serde_json::json!({"action": "redeploy", "strategy": "rebuild"}),
+            )
+        };
+        for (droplet_id, digest) in [(41, None), (42, Some(current))] {
+            store
+                .droplets()
+                .create(&Droplet {
+                    id: droplet_id,
+                    name: "steady".to_string(),
+                    region: "nyc3".to_string(),
+                    size: "s-1vcpu-2gb".to_string(),
+                    image: "openclaw-image".to_string(),
+                    status: DropletStatus::Active,
+                    ip_address: None,
+                    bot_id: Some(bot_id),
+                    created_at: chrono::Utc::now(),
+                    destroyed_at: None,
+                    toolchain_profile: None,
+                    user_data_digest: digest,
+                })
+                .await
+                .unwrap();
+        }
+
+        // A droplet whose user data is unknown, or stale, would boot the old bootstrap.
+        store.bots().update_droplet(bot_id, Some(41)).await.unwrap();
+        let response = send(app.clone(), rebuild()).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(store.jobs().claim_next(lease).await.unwrap().is_none());
+
+        store.bots().update_droplet(bot_id, Some(42)).await.unwrap();
+        let response = send(app.clone(), rebuild()).await;
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        let job_id: Uuid =
+            serde_json::from_value(body_json(response).await["job_id"].clone()).unwrap();
+        let job = store.jobs().get_by_id(job_id).await.unwrap();
+        assert_eq!(job.kind, JobKind::RebuildBot);
+
+        // The rebuilt droplet's loader fetches the current bootstrap with the bot's token.
+        store
+            .bots()
+            .update_registration_token(bot_id, "registration-token")
+            .await
+            .unwrap();
+        let fetch = |token: &str| {
+            axum::http::Request::get(format!("/bot/{}/user_data", bot_id))
+                .header("authorization", format!("Bearer {token}"))
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+        let response = send(app.clone(), fetch("stale-token")).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = send(app, fetch("registration-token")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let user_data = String::from_utf8(body_bytes(response).await.to_vec()).unwrap();
+        assert!(user_data.contains("export REGISTRATION_TOKEN='registration-token'\n"));
+        assert!(user_data.contains("# Start of embedded bootstrap script"));
+    }
+
+    #[tokio::test]
+    async fn pause_and_resume_are_queued_when_the_bot_allows_them() {
+        let store = MemoryStore::new();
+        let app = router(memory_state(&store).await);
+        let account = Account::new("u1".to_string(), SubscriptionTier::Basic);
+        store.accounts().create(&account).await.unwrap();
+        let bot = Bot::new(account.id, "toggled".to_string(), Persona::Beginner);
+        store.bots().create(&bot).await.unwrap();
+        let action = |action: &str| {
+            admin_post(
+                &format!("/bots/{}/actions", bot.id),
+                serde_json::json!({ "action": action }),
+            )
+        };
+
+        // A pending bot has no droplet to power off.
+        let response = send(app.clone(), action("pause")).await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let lease = chrono::Duration::minutes(10);
+        assert!(store.jobs().claim_next(lease).await.unwrap().is_none());
+
+        store.bots().update_droplet(bot.id, Some(7)).await.unwrap();
+        for (from, to, reason) in [
+            (
+                BotStatus::Pending,
+                BotStatus::Provisioning,
+                TransitionReason::Provision,
+            ),
+            (
+                BotStatus::Provisioning,
+                BotStatus::Online,
+                TransitionReason::ConfigAcknowledged,
+            ),
+        ] {
+            store
+                .bots()
+                .transition_status(bot.id, from, to, reason, None)
+                .await
+                .unwrap();
+        }
+        let response = send(app.clone(), action("resume")).await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let response = send(app, action("pause")).await;
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        let job_id: Uuid =
+            serde_json::from_value(body_json(response).await["job_id"].clone()).unwrap();
+        let job = store.jobs().get_by_id(job_id).await.unwrap();
+        assert_eq!(job.kind, JobKind::PauseBot);
+        let stored = store.bots().get_by_id(bot.id).await.unwrap();
+        assert_eq!(stored.status, BotStatus::Online);
+    }
+
+    #[tokio::test]
+    async fn bootstrap_progress_rejects_unknown_phase_and_exit_code() {
+        for body in [
+            r#"{"phase":"reboot","exit_code":0}"#,
+            r#"{"phase":"customizer","exit_code":-1}"#,
+            r#"{"phase":"customizer","exit_code":256}"#,
+        ] {
+            let response = send(
+                router(test_state().await),
+                axum::http::Request::post(format!("/bot/{}/bootstrap_progress", Uuid::new_v4()))
+                    .header("authorization", "Bearer registration-token")
+                    .header("content-type", "application/json")
+                    .body(axum::body::Body::from(body))
+                    .unwrap(),
+            )
+            .await;
+
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{body}");
+        }
+    }
+
+    #[tokio::test]
+    async fn bootstrap_log_upload_enforces_size_cap_and_source() {
+        let upload = |source: &str, body: Vec<u8>| {
+            axum::http::Request::post(format!(
+                "/bot/{}/bootstrap_logs?source={source}",
+                Uuid::new_v4()
+            ))
+            .header("authorization", "Bearer registration-token")
+            .header("content-type", "text/plain")
+            .body(axum::body::Body::from(body))
+            .unwrap()
+        };
+
+        let too_large = vec![b'x'; crate::domain::MAX_BOOTSTRAP_LOG_BYTES + 1];
+        let response = send(router(test_state().await), upload("bootstrap", too_large)).await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let response = send(
+            router(test_state().await),
+            upload("syslog", b"tail".to_vec()),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn bootstrap_log_upload_drops_nul_bytes() {
+        let store = MemoryStore::new();
+        let app = router(memory_state(&store).await);
+        let account = Account::new("u1".to_string(), SubscriptionTier::Basic);
+        store.accounts().create(&account).await.unwrap();
+        let bot = Bot::new(account.id, "noisy".to_string(), Persona::Beginner);
+        store.bots().create(&bot).await.unwrap();
+        store
+            .bots()
+            .update_registration_token(bot.id, "registration-token")
+            .await
+            .unwrap();
+
+        let response = send(
+            app,
+            axum::http::Request::post(format!("/bot/{}/bootstrap_logs?source=bootstrap", bot.id))
+                .header("authorization", "Bearer registration-token")
+                .header("content-type", "text/plain")
+                .body(axum::body::Body::from(&b"apt\0 failed\xff"[..]))
+                .unwrap(),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let logs = store
+            .bootstrap_logs()
+            .list_for_bot(bot.id, 10)
+            .await
+            .unwrap();
+        assert_eq!(logs[0].content, "apt failed\u{fffd}");
+    }
+
+    #[tokio::test]
+    async fn shutdown_signal_cancels_state_and_drain_waits_for_tasks() {
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        let state = test_state().await.with_shutdown_signal(async {
+            let _ = rx.await;
+        });
+        let shutdown = state.shutdown.clone();
+        state.tasks.spawn(async move { shutdown.cancelled().await });
+
+        tx.send(()).unwrap();
+        assert!(state.drain(std::time::Duration::from_secs(5)).await);
+        assert!(state.shutdown.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn drain_reports_tasks_still_running_after_timeout() {
+        let state = test_state().await;
+        state.tasks.spawn(std::future::pending::<()>());
+        assert!(!state.drain(std::time::Duration::from_millis(20)).await);
+    }
+
+    #[test]
+    fn idempotency_key_validation() {
+        assert!(is_valid_key("3f2c9a4e-retry"));
+        assert!(!is_valid_key(""));
+        assert!(!is_valid_key("with space"));
+        assert!(!is_valid_key(&"k".repeat(256)));
+    }
+
+    #[test]
+    fn request_fingerprint_binds_method_path_and_body() {
+        let base = request_fingerprint(&Method::POST, "/bots", b"{\"a\":1}");
+        assert_eq!(
+            base,
+            request_fingerprint(&Method::POST, "/bots", b"{\"a\":1}")
+        );
+        assert_ne!(
+            base,
+            request_fingerprint(&Method::POST, "/bots", b"{\"a\":2}")
+        );
+        assert_ne!(
+            base,
+            request_fingerprint(&Method::POST, "/accounts", b"{\"a\":1}")
+        );
+        assert!(base.starts_with("sha256:"));
+    }
+
     #[test]
     fn parse_invalid_inputs_return_none() {
         assert!(parse_subscription_tier("nope").is_none());
@@ -92,6 +651,26 @@ mod tests {
         assert!(parse_strictness("nope").is_none());
     }
 
+    #[test]
+    fn parse_registration_fields() {
+        assert_eq!(
+            parse_ip_address(" 203.0.113.7 ").as_deref(),
+            Some("203.0.113.7")
+        );
+        assert_eq!(
+            parse_ip_address("2001:DB8::1").as_deref(),
+            Some("2001:db8::1")
+        );
+        assert!(parse_ip_address("not-an-ip").is_none());
+
+        assert_eq!(
+            parse_agent_version("bootstrap/1").as_deref(),
+            Some("bootstrap/1")
+        );
+        assert!(parse_agent_version("has space").is_none());
+        assert!(parse_agent_version(&"x".repeat(65)).is_none());
+    }
+
     #[test]
     fn is_admin_authorized_requires_exact_bearer_match() {
         let mut headers = HeaderMap::new();
@@ -121,6 +700,25 @@ mod tests {
                 crate::infrastructure::DigitalOceanError::RateLimited,
             ));
         assert_eq!(status_rate_limited, StatusCode::TOO_MANY_REQUESTS);
+
+        let (status_timed_out, _) = map_bot_action_error(&ProvisioningError::DigitalOcean(
+            crate::infrastructure::DigitalOceanError::ActionTimedOut {
+                id: 1,
+                kind: "power_off".to_string(),
+            },
+        ));
+        assert_eq!(status_timed_out, StatusCode::GATEWAY_TIMEOUT);
+
+        let (status_conflict, _) = map_bot_action_error(&ProvisioningError::Repository(
+            crate::infrastructure::RepositoryError::InvalidTransition(
+                crate::domain::InvalidTransition {
+                    from: crate::domain::BotStatus::Destroyed,
+                    to: crate::domain::BotStatus::Paused,
+                    reason: "transition not allowed".to_string(),
+                },
+            ),
+        ));
+        assert_eq!(status_conflict, StatusCode::CONFLICT);
     }
 
     #[test]
@@ -155,6 +753,24 @@ mod tests {
         assert_eq!(status_internal, StatusCode::INTERNAL_SERVER_ERROR);
     }
 
+    #[test]
+    fn map_job_errors_map_expected_status_codes() {
+        let (status_conflict, _) = map_enqueue_job_error(
+            &crate::infrastructure::RepositoryError::Conflict("busy".to_string()),
+        );
+        assert_eq!(status_conflict, StatusCode::CONFLICT);
+
+        let (status_not_found, _) = map_job_read_error(
+            &crate::infrastructure::RepositoryError::NotFound("missing".to_string()),
+        );
+        assert_eq!(status_not_found, StatusCode::NOT_FOUND);
+
+        let (status_internal, _) = map_enqueue_job_error(
+            &crate::infrastructure::RepositoryError::InvalidData("bad".to_string()),
+        );
+        assert_eq!(status_internal, StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
     #[test]
     fn map_account_read_error_maps_expected_status_codes() {
         let (status_not_found, _) = map_account_read_error(
@@ -217,21 +833,36 @@ mod tests {
         create_bot,
         get_bot,
         get_bot_config,
+        get_bot_bootstrap,
+        get_bot_bootstrap_logs,
         bot_action,
+        get_job,
         register_bot,
         get_desired_config,
+        get_bot_user_data,
         acknowledge_config,
         record_heartbeat,
+        record_bootstrap_progress,
+        upload_bootstrap_log,
     ),
     components(
         schemas(
             CreateAccountRequest,
             CreateBotRequest,
+            CustomizerRequest,
             BotActionRequest,
             RegisterBotRequest,
             AckConfigRequest,
+            BootstrapProgressRequest,
             BotResponse,
+            BootstrapEventResponse,
+            BootstrapTimelineResponse,
+            BootstrapLogResponse,
+            BootstrapLogsResponse,
+            JobAcceptedResponse,
+            JobResponse,
             HealthResponse,
+            RateLimitResponse,
         )
     ),
     tags(
@@ -239,6 +870,7 @@ mod tests {
         (name = "Accounts", description = "Account management endpoints"),
         (name = "Bots", description = "Bot management and lifecycle endpoints"),
         (name = "Configuration", description = "Bot configuration endpoints"),
+        (name = "Jobs", description = "Background provisioning job status"),
     ),
     info(
         title = "Claw Spawn API",
@@ -251,7 +883,8 @@ struct ApiDoc;
 
 /// Health check endpoint
 ///
-/// Verifies database connectivity and returns service health status.
+/// Verifies database connectivity and returns service health status, along
+/// with the DigitalOcean API budget left to the token.
 #[utoipa::path(
     get,
     path = "/health",
@@ -262,12 +895,14 @@ struct ApiDoc;
     )
 )]
 async fn health_check(State(state): State<AppState>) -> impl IntoResponse {
-    match sqlx::query("SELECT 1").fetch_one(&state.pool).await {
+    let digitalocean_rate_limit = state.digitalocean.rate_limit_budget().into();
+    match state.storage.ping().await {
         Ok(_) => (
             StatusCode::OK,
             Json(HealthResponse {
                 status: "healthy".to_string(),
                 error: None,
+                digitalocean_rate_limit,
             }),
         ),
         Err(e) => {
@@ -277,6 +912,7 @@ async fn health_check(State(state): State<AppState>) -> impl IntoResponse {
                 Json(HealthResponse {
                     status: "unhealthy".to_string(),
                     error: Some("Database connectivity failed".to_string()),
+                    digitalocean_rate_limit,
                 }),
             )
         }
@@ -288,10 +924,12 @@ async fn health_check(State(state): State<AppState>) -> impl IntoResponse {
     post,
     path = "/accounts",
     tag = "Accounts",
+    params(("Idempotency-Key" = Option<String>, Header, description = "Replays the first response for retries of the same request")),
     request_body = CreateAccountRequest,
     responses(
         (status = 201, description = "Account created successfully", body = Object),
-        (status = 400, description = "Invalid subscription tier", body = Object),
+        (status = 400, description = "Invalid subscription tier or customizer settings", body = Object),
+        (status = 409, description = "Idempotency-Key reused with a different request", body = Object),
         (status = 500, description = "Failed to create account", body = Object)
     )
 )]
@@ -320,7 +958,17 @@ async fn create_account(
         }
     };
 
-    let account = Account::new(req.external_id, tier);
+    let mut account = Account::new(req.external_id, tier);
+    if let Some(customizer) = req.customizer {
+        account.customizer = customizer.into();
+        if let Err(e) = state.provisioning.validate_customizer(&account.customizer) {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({"error": e.to_string()})),
+            );
+        }
+    }
+
     if let Err(e) = state.account_repo.create(&account).await {
         error!(error = %e, "Failed to create account");
         return (
@@ -418,11 +1066,13 @@ async fn list_bots(
     post,
     path = "/bots",
     tag = "Bots",
+    params(("Idempotency-Key" = Option<String>, Header, description = "Replays the first response for retries of the same request")),
     request_body = CreateBotRequest,
     responses(
-        (status = 201, description = "Bot created successfully", body = BotResponse),
-        (status = 400, description = "Invalid risk configuration", body = Object),
+        (status = 202, description = "Bot recorded; droplet provisioning queued", body = JobAcceptedResponse),
+        (status = 400, description = "Invalid risk configuration, customizer settings or toolchain profile", body = Object),
         (status = 403, description = "Account limit reached", body = Object),
+        (status = 409, description = "Idempotency-Key reused with a different request", body = Object),
         (status = 429, description = "Rate limited by DigitalOcean", body = Object),
         (status = 500, description = "Failed to create bot", body = Object)
     )
@@ -496,16 +1146,7 @@ async fn create_bot(
         algorithm,
         strictness,
         paper_mode: req.paper_mode,
-        signal_knobs: if matches!(persona, Persona::QuantLite) {
-            Some(SignalKnobs {
-                volume_confirmation: true,
-                volatility_brake: true,
-                liquidity_filter: StrictnessLevel::Medium,
-                correlation_brake: true,
-            })
-        } else {
-            None
-        },
+        signal_knobs: SignalKnobs::for_persona(&persona),
     };
 
     let risk_config = RiskConfig {
@@ -538,12 +1179,22 @@ async fn create_bot(
 
     match state
         .provisioning
-        .create_bot(req.account_id, req.name, persona, config)
+        .create_bot(
+            req.account_id,
+            req.name,
+            persona,
+            config,
+            req.customizer.map(Into::into).unwrap_or_default(),
+            req.toolchain_profile,
+        )
         .await
     {
-        Ok(bot) => (
-            StatusCode::CREATED,
-            Json(serde_json::json!(BotResponse::from(bot))),
+        Ok((bot, job)) => (
+            StatusCode::ACCEPTED,
+            Json(serde_json::json!(JobAcceptedResponse {
+                job_id: job.id,
+                bot: Some(BotResponse::from(bot)),
+            })),
         ),
         Err(e) => {
             error!(error = %e, "Failed to create bot");
@@ -623,15 +1274,94 @@ async fn get_bot_config(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/bots/{id}/bootstrap",
+    tag = "Bots",
+    params(("id" = Uuid, Path, description = "Bot ID")),
+    responses(
+        (status = 200, description = "Bootstrap progress reported by the droplet", body = BootstrapTimelineResponse),
+        (status = 404, description = "Bot not found", body = Object)
+    )
+)]
+async fn get_bot_bootstrap(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if !is_admin_authorized(&headers, &state.api_bearer_token) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({"error": "Missing or invalid admin authorization token"})),
+        );
+    }
+
+    match state.bootstrap.timeline(id).await {
+        Ok(events) => (
+            StatusCode::OK,
+            Json(serde_json::json!(BootstrapTimelineResponse {
+                bot_id: id,
+                events: events.into_iter().map(Into::into).collect(),
+            })),
+        ),
+        Err(e) => {
+            let (status, body) = map_bot_read_error(&e);
+            (status, Json(body))
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/bots/{id}/bootstrap/logs",
+    tag = "Bots",
+    params(("id" = Uuid, Path, description = "Bot ID")),
+    responses(
+        (status = 200, description = "Redacted log tails uploaded after failed bootstraps", body = BootstrapLogsResponse),
+        (status = 404, description = "Bot not found", body = Object)
+    )
+)]
+async fn get_bot_bootstrap_logs(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if !is_admin_authorized(&headers, &state.api_bearer_token) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({"error": "Missing or invalid admin authorization token"})),
+        );
+    }
+
+    match state.bootstrap.logs(id).await {
+        Ok(logs) => (
+            StatusCode::OK,
+            Json(serde_json::json!(BootstrapLogsResponse {
+                bot_id: id,
+                logs: logs.into_iter().map(Into::into).collect(),
+            })),
+        ),
+        Err(e) => {
+            let (status, body) = map_bot_read_error(&e);
+            (status, Json(body))
+        }
+    }
+}
+
 #[utoipa::path(
     post,
     path = "/bots/{id}/actions",
     tag = "Bots",
-    params(("id" = Uuid, Path, description = "Bot ID")),
+    params(
+        ("id" = Uuid, Path, description = "Bot ID"),
+        ("Idempotency-Key" = Option<String>, Header, description = "Replays the first response for retries of the same request")
+    ),
     request_body = BotActionRequest,
     responses(
-        (status = 200, description = "Action completed successfully", body = Object),
-        (status = 400, description = "Invalid action", body = Object),
+        (status = 202, description = "Action queued", body = JobAcceptedResponse),
+        (status = 400, description = "Invalid action or redeploy strategy, or a rebuild whose user data is out of date", body = Object),
+        (status = 404, description = "Bot not found", body = Object),
+        (status = 409, description = "Bot status does not allow the action, bot already has a job in progress, or Idempotency-Key reused with a different request", body = Object),
         (status = 500, description = "Action failed", body = Object)
     )
 )]
@@ -648,21 +1378,137 @@ async fn bot_action(
         );
     }
 
-    let result = match req.action.as_str() {
-        "pause" => state.provisioning.pause_bot(id).await,
-        "resume" => state.provisioning.resume_bot(id).await,
-        "redeploy" => state.provisioning.redeploy_bot(id).await,
-        "destroy" => state.provisioning.destroy_bot(id).await,
-        _ => Err(ProvisioningError::InvalidConfig(
-            "Unknown action".to_string(),
-        )),
+    // Every action waits on DigitalOcean, so all of them run on the job queue.
+    let kind = match req.action.as_str() {
+        "redeploy" => match req.strategy.as_deref() {
+            None | Some("recreate") => JobKind::RedeployBot,
+            Some("rebuild") => {
+                // Refuse up front rather than queueing a rebuild that can only fail.
+                if let Err(e) = state.provisioning.check_rebuild(id).await {
+                    let (status, body) = map_bot_action_error(&e);
+                    return (status, Json(body));
+                }
+                JobKind::RebuildBot
+            }
+            Some(_) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({
+                        "error": "Invalid redeploy strategy",
+                        "allowed": ["recreate", "rebuild"]
+                    })),
+                );
+            }
+        },
+        "destroy" => JobKind::DestroyBot,
+        "hibernate" => JobKind::HibernateBot,
+        "pause" | "resume" => {
+            let bot = match state.lifecycle.get_bot(id).await {
+                Ok(bot) => bot,
+                Err(e) => {
+                    let (status, body) = map_bot_read_error(&e);
+                    return (status, Json(body));
+                }
+            };
+            // A hibernated bot has no droplet to power on; it is provisioned again.
+            let (to, reason, kind) = match req.action.as_str() {
+                "pause" => (
+                    BotStatus::Paused,
+                    TransitionReason::Pause,
+                    JobKind::PauseBot,
+                ),
+                _ if bot.status == BotStatus::Hibernated => (
+                    BotStatus::Provisioning,
+                    TransitionReason::Resume,
+                    JobKind::WakeBot,
+                ),
+                _ => (
+                    BotStatus::Online,
+                    TransitionReason::Resume,
+                    JobKind::ResumeBot,
+                ),
+            };
+            // Refuse up front rather than queueing a power toggle that can only fail.
+            if let Err(e) = bot.check_transition(to, reason) {
+                let (status, body) = map_bot_action_error(&e.into());
+                return (status, Json(body));
+            }
+            kind
+        }
+        _ => {
+            let (status, body) = map_bot_action_error(&ProvisioningError::InvalidConfig(
+                "Unknown action".to_string(),
+            ));
+            return (status, Json(body));
+        }
     };
+    let snapshot = req.snapshot.filter(|_| kind == JobKind::HibernateBot);
+    enqueue_bot_job(&state, id, kind, snapshot).await
+}
 
-    match result {
-        Ok(_) => (StatusCode::OK, Json(serde_json::json!({"status": "ok"}))),
+async fn enqueue_bot_job(
+    state: &AppState,
+    bot_id: Uuid,
+    kind: JobKind,
+    snapshot_on_hibernate: Option<bool>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    // Reject unknown bots up front rather than queueing a job that can only fail.
+    if let Err(e) = state.lifecycle.get_bot(bot_id).await {
+        let (status, body) = map_bot_read_error(&e);
+        return (status, Json(body));
+    }
+
+    // The snapshot choice is saved with the job, so a rejected hibernate leaves it as it was.
+    let queued = match snapshot_on_hibernate {
+        Some(snapshot) => state.provisioning.queue_hibernate(bot_id, snapshot).await,
+        None => state.jobs.enqueue(kind, bot_id).await,
+    };
+    match queued {
+        Ok(job) => (
+            StatusCode::ACCEPTED,
+            Json(serde_json::json!(JobAcceptedResponse {
+                job_id: job.id,
+                bot: None,
+            })),
+        ),
         Err(e) => {
-            error!(error = %e, "Bot action failed");
-            let (status, body) = map_bot_action_error(&e);
+            warn!(bot_id = %bot_id, kind = %kind, error = %e, "Failed to enqueue bot job");
+            let (status, body) = map_enqueue_job_error(&e);
+            (status, Json(body))
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/jobs/{id}",
+    tag = "Jobs",
+    params(("id" = Uuid, Path, description = "Job ID")),
+    responses(
+        (status = 200, description = "Job found", body = JobResponse),
+        (status = 404, description = "Job not found", body = Object),
+        (status = 500, description = "Failed to get job", body = Object)
+    )
+)]
+async fn get_job(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if !is_admin_authorized(&headers, &state.api_bearer_token) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({"error": "Missing or invalid admin authorization token"})),
+        );
+    }
+
+    match state.jobs.get_job(id).await {
+        Ok(job) => (
+            StatusCode::OK,
+            Json(serde_json::json!(JobResponse::from(job))),
+        ),
+        Err(e) => {
+            let (status, body) = map_job_read_error(&e);
             (status, Json(body))
         }
     }
@@ -675,6 +1521,7 @@ async fn bot_action(
     request_body = RegisterBotRequest,
     responses(
         (status = 200, description = "Bot registered successfully", body = Object),
+        (status = 400, description = "Invalid ip_address or agent_version", body = Object),
         (status = 401, description = "Invalid or missing authorization token", body = Object)
     )
 )]
@@ -693,7 +1540,42 @@ async fn register_bot(
         }
     };
 
-    match state.lifecycle.get_bot_with_token(req.bot_id, token).await {
+    // Empty values mean the droplet could not determine them; store nothing.
+    let ip_address = match req.ip_address.as_deref().filter(|ip| !ip.is_empty()) {
+        Some(ip) => match parse_ip_address(ip) {
+            Some(ip) => Some(ip),
+            None => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({"error": "Invalid ip_address"})),
+                );
+            }
+        },
+        None => None,
+    };
+    let agent_version = match req.agent_version.as_deref().filter(|v| !v.is_empty()) {
+        Some(version) => match parse_agent_version(version) {
+            Some(version) => Some(version),
+            None => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({"error": "Invalid agent_version"})),
+                );
+            }
+        },
+        None => None,
+    };
+
+    match state
+        .lifecycle
+        .register_bot(
+            req.bot_id,
+            token,
+            ip_address.as_deref(),
+            agent_version.as_deref(),
+        )
+        .await
+    {
         Ok(bot) => {
             info!(bot_id = %bot.id, "Bot registered successfully");
             (
@@ -701,10 +1583,13 @@ async fn register_bot(
                 Json(serde_json::json!({"status": "registered"})),
             )
         }
-        Err(_) => (
-            StatusCode::UNAUTHORIZED,
-            Json(serde_json::json!({"error": "Invalid bot ID or registration token"})),
-        ),
+        Err(e) => {
+            let (status, body) = map_register_bot_error(&e);
+            if status.is_server_error() {
+                error!(bot_id = %req.bot_id, error = %e, "Failed to record bot registration");
+            }
+            (status, Json(body))
+        }
     }
 }
 
@@ -755,6 +1640,55 @@ async fn get_desired_config(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/bot/{id}/user_data",
+    tag = "Configuration",
+    params(("id" = Uuid, Path, description = "Bot ID")),
+    responses(
+        (status = 200, description = "The bot's current bootstrap, fetched by the droplet at boot", body = String, content_type = "text/x-shellscript"),
+        (status = 401, description = "Invalid or missing authorization token", body = Object),
+        (status = 500, description = "Failed to render user data", body = Object)
+    )
+)]
+async fn get_bot_user_data(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Response {
+    let token = match extract_bearer_token(&headers) {
+        Some(t) => t,
+        None => {
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(serde_json::json!({"error": "Missing or invalid authorization token"})),
+            )
+                .into_response();
+        }
+    };
+
+    if state.lifecycle.get_bot_with_token(id, token).await.is_err() {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({"error": "Invalid bot ID or registration token"})),
+        )
+            .into_response();
+    }
+
+    match state.provisioning.bootstrap_user_data(id, token).await {
+        Ok(user_data) => (
+            [(axum::http::header::CONTENT_TYPE, "text/x-shellscript")],
+            user_data,
+        )
+            .into_response(),
+        Err(e) => {
+            error!(bot_id = %id, error = %e, "Failed to render user data");
+            let (status, body) = map_bot_action_error(&e);
+            (status, Json(body)).into_response()
+        }
+    }
+}
+
 #[utoipa::path(
     post,
     path = "/bot/{id}/config_ack",
@@ -766,7 +1700,7 @@ async fn get_desired_config(
         (status = 401, description = "Invalid or missing authorization token", body = Object),
         (status = 400, description = "Invalid bot state for acknowledgment", body = Object),
         (status = 404, description = "Config not found", body = Object),
-        (status = 409, description = "Config version conflict", body = Object),
+        (status = 409, description = "Config version conflict, or bot cannot go online from its current status", body = Object),
         (status = 500, description = "Failed to acknowledge config", body = Object)
     )
 )]
@@ -846,3 +1780,145 @@ async fn record_heartbeat(
         ),
     }
 }
+
+#[utoipa::path(
+    post,
+    path = "/bot/{id}/bootstrap_progress",
+    tag = "Configuration",
+    params(("id" = Uuid, Path, description = "Bot ID")),
+    request_body = BootstrapProgressRequest,
+    responses(
+        (status = 200, description = "Progress recorded", body = Object),
+        (status = 400, description = "Unknown phase, exit code out of range, or message too long", body = Object),
+        (status = 401, description = "Invalid or missing authorization token", body = Object),
+        (status = 500, description = "Failed to record progress", body = Object)
+    )
+)]
+async fn record_bootstrap_progress(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(req): Json<BootstrapProgressRequest>,
+) -> impl IntoResponse {
+    let token = match extract_bearer_token(&headers) {
+        Some(t) => t,
+        None => {
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(serde_json::json!({"error": "Missing or invalid authorization token"})),
+            );
+        }
+    };
+
+    let phase = match parse_bootstrap_phase(&req.phase) {
+        Some(phase) => phase,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({"error": "Invalid phase"})),
+            );
+        }
+    };
+    let exit_code = match parse_exit_code(req.exit_code) {
+        Some(code) => code,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({"error": "Invalid exit_code"})),
+            );
+        }
+    };
+    let message = match req.message.as_deref().filter(|m| !m.trim().is_empty()) {
+        Some(message) => match parse_bootstrap_message(message) {
+            Some(message) => Some(message),
+            None => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({"error": "Message too long"})),
+                );
+            }
+        },
+        None => None,
+    };
+
+    match state
+        .bootstrap
+        .record_progress(id, token, phase, exit_code, message)
+        .await
+    {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(serde_json::json!({"status": "recorded"})),
+        ),
+        Err(e) => {
+            let (status, body) = map_bootstrap_progress_error(&e);
+            if status.is_server_error() {
+                error!(bot_id = %id, error = %e, "Failed to record bootstrap progress");
+            }
+            (status, Json(body))
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/bot/{id}/bootstrap_logs",
+    tag = "Configuration",
+    params(("id" = Uuid, Path, description = "Bot ID"), BootstrapLogParams),
+    request_body(content = String, content_type = "text/plain", description = "Tail of the log, at most 64 KiB"),
+    responses(
+        (status = 200, description = "Log stored", body = Object),
+        (status = 400, description = "Unknown log source", body = Object),
+        (status = 401, description = "Invalid or missing authorization token", body = Object),
+        (status = 413, description = "Log larger than 64 KiB"),
+        (status = 500, description = "Failed to store log", body = Object)
+    )
+)]
+async fn upload_bootstrap_log(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Query(params): Query<BootstrapLogParams>,
+    body: Bytes,
+) -> impl IntoResponse {
+    let token = match extract_bearer_token(&headers) {
+        Some(t) => t,
+        None => {
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(serde_json::json!({"error": "Missing or invalid authorization token"})),
+            );
+        }
+    };
+
+    let source = match parse_bootstrap_log_source(&params.source) {
+        Some(source) => source,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({"error": "Invalid source"})),
+            );
+        }
+    };
+
+    // Logs may be cut mid-character by `tail -c`; keep what decodes. Postgres
+    // can't store NUL bytes in text, so those are dropped too.
+    let content = String::from_utf8_lossy(&body).replace('\0', "");
+    match state
+        .bootstrap
+        .upload_log(id, token, source, &content)
+        .await
+    {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(serde_json::json!({"status": "stored"})),
+        ),
+        Err(e) => {
+            let (status, body) = map_bootstrap_log_error(&e);
+            if status.is_server_error() {
+                error!(bot_id = %id, error = %e, "Failed to store bootstrap log");
+            }
+            (status, Json(body))
+        }
+    }
+}
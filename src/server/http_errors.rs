@@ -10,6 +10,10 @@ pub(super) fn map_bot_action_error(err: &ProvisioningError) -> (StatusCode, serd
         ProvisioningError::Repository(RepositoryError::NotFound(_)) => {
             (StatusCode::NOT_FOUND, serde_json::json!({ "error": "Bot not found" }))
         }
+        ProvisioningError::InvalidTransition(t)
+        | ProvisioningError::Repository(RepositoryError::InvalidTransition(t)) => {
+            (StatusCode::CONFLICT, serde_json::json!({ "error": t.to_string() }))
+        }
         ProvisioningError::DigitalOcean(DigitalOceanError::RateLimited) => (
             StatusCode::TOO_MANY_REQUESTS,
             serde_json::json!({ "error": "Rate limited by DigitalOcean, please retry" }),
@@ -18,6 +22,10 @@ pub(super) fn map_bot_action_error(err: &ProvisioningError) -> (StatusCode, serd
             StatusCode::NOT_FOUND,
             serde_json::json!({ "error": "Associated droplet not found" }),
         ),
+        ProvisioningError::DigitalOcean(DigitalOceanError::ActionTimedOut { .. }) => (
+            StatusCode::GATEWAY_TIMEOUT,
+            serde_json::json!({ "error": "Droplet action timed out, please retry" }),
+        ),
         _ => (
             StatusCode::INTERNAL_SERVER_ERROR,
             serde_json::json!({ "error": "Action failed" }),
@@ -31,6 +39,14 @@ pub(super) fn map_create_bot_error(err: &ProvisioningError) -> (StatusCode, serd
             StatusCode::NOT_FOUND,
             serde_json::json!({ "error": "Account not found" }),
         ),
+        ProvisioningError::Toolchain(e) => (
+            StatusCode::BAD_REQUEST,
+            serde_json::json!({ "error": e.to_string() }),
+        ),
+        ProvisioningError::Customizer(e) => (
+            StatusCode::BAD_REQUEST,
+            serde_json::json!({ "error": e.to_string() }),
+        ),
         ProvisioningError::AccountLimitReached(max) => (
             StatusCode::FORBIDDEN,
             serde_json::json!({
@@ -87,6 +103,10 @@ pub(super) fn map_ack_config_error(err: &LifecycleError) -> (StatusCode, serde_j
             StatusCode::BAD_REQUEST,
             serde_json::json!({ "error": "Invalid bot state for config acknowledgment" }),
         ),
+        LifecycleError::InvalidTransition(t)
+        | LifecycleError::Repository(RepositoryError::InvalidTransition(t)) => {
+            (StatusCode::CONFLICT, serde_json::json!({ "error": t.to_string() }))
+        }
         _ => (
             StatusCode::INTERNAL_SERVER_ERROR,
             serde_json::json!({ "error": "Failed to acknowledge config" }),
@@ -94,6 +114,47 @@ pub(super) fn map_ack_config_error(err: &LifecycleError) -> (StatusCode, serde_j
     }
 }
 
+pub(super) fn map_register_bot_error(err: &LifecycleError) -> (StatusCode, serde_json::Value) {
+    match err {
+        LifecycleError::Repository(RepositoryError::NotFound(_)) => (
+            StatusCode::UNAUTHORIZED,
+            serde_json::json!({ "error": "Invalid bot ID or registration token" }),
+        ),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            serde_json::json!({ "error": "Failed to register bot" }),
+        ),
+    }
+}
+
+pub(super) fn map_bootstrap_progress_error(
+    err: &LifecycleError,
+) -> (StatusCode, serde_json::Value) {
+    match err {
+        LifecycleError::Repository(RepositoryError::NotFound(_)) => (
+            StatusCode::UNAUTHORIZED,
+            serde_json::json!({ "error": "Invalid bot ID or registration token" }),
+        ),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            serde_json::json!({ "error": "Failed to record bootstrap progress" }),
+        ),
+    }
+}
+
+pub(super) fn map_bootstrap_log_error(err: &LifecycleError) -> (StatusCode, serde_json::Value) {
+    match err {
+        LifecycleError::Repository(RepositoryError::NotFound(_)) => (
+            StatusCode::UNAUTHORIZED,
+            serde_json::json!({ "error": "Invalid bot ID or registration token" }),
+        ),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            serde_json::json!({ "error": "Failed to store bootstrap log" }),
+        ),
+    }
+}
+
 pub(super) fn map_account_read_error(err: &RepositoryError) -> (StatusCode, serde_json::Value) {
     match err {
         RepositoryError::NotFound(_) => {
@@ -105,3 +166,28 @@ pub(super) fn map_account_read_error(err: &RepositoryError) -> (StatusCode, serd
         ),
     }
 }
+
+pub(super) fn map_enqueue_job_error(err: &RepositoryError) -> (StatusCode, serde_json::Value) {
+    match err {
+        RepositoryError::Conflict(_) => (
+            StatusCode::CONFLICT,
+            serde_json::json!({ "error": "Bot already has a job in progress" }),
+        ),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            serde_json::json!({ "error": "Failed to enqueue job" }),
+        ),
+    }
+}
+
+pub(super) fn map_job_read_error(err: &RepositoryError) -> (StatusCode, serde_json::Value) {
+    match err {
+        RepositoryError::NotFound(_) => {
+            (StatusCode::NOT_FOUND, serde_json::json!({ "error": "Job not found" }))
+        }
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            serde_json::json!({ "error": "Failed to get job" }),
+        ),
+    }
+}
@@ -0,0 +1,171 @@
+use super::{http_auth::is_admin_authorized, state::AppState};
+use crate::domain::{IdempotencyRecord, IDEMPOTENCY_KEY_TTL_HOURS};
+use crate::infrastructure::RepositoryError;
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{header, HeaderName, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+use tracing::{error, info, warn};
+
+pub(super) const IDEMPOTENCY_KEY_HEADER: HeaderName = HeaderName::from_static("idempotency-key");
+/// Set on responses served from the idempotency store rather than the handler.
+pub(super) const IDEMPOTENT_REPLAYED_HEADER: HeaderName =
+    HeaderName::from_static("idempotent-replayed");
+
+const MAX_KEY_LENGTH: usize = 255;
+/// Matches axum's default JSON body limit, so buffering here rejects nothing new.
+const MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// Keys are opaque to us, but must be printable ASCII and fit the column.
+pub(super) fn is_valid_key(key: &str) -> bool {
+    !key.is_empty() && key.len() <= MAX_KEY_LENGTH && key.bytes().all(|b| b.is_ascii_graphic())
+}
+
+/// Binds a key to one exact request: the same key on another route or with another
+/// body is a client bug, not a retry.
+pub(super) fn request_fingerprint(method: &Method, path: &str, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_str().as_bytes());
+    hasher.update(b"\n");
+    hasher.update(path.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(body);
+    format!("sha256:{:x}", hasher.finalize())
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response {
+    (status, Json(serde_json::json!({ "error": message }))).into_response()
+}
+
+fn replay(record: &IdempotencyRecord, status: u16) -> Response {
+    let status = StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let body = record.response_body.clone().unwrap_or_default();
+    let mut response = (status, body).into_response();
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    headers.insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+    response
+}
+
+/// `Idempotency-Key` support for mutating admin routes.
+///
+/// The first request with a key runs normally and its response is stored; repeats
+/// with the same method, path and body get the stored response back. 5xx responses
+/// are not stored, so a retry after a server error runs the handler again.
+/// Requests without a key, or without valid admin auth, pass straight through.
+pub(super) async fn idempotency(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let key = match request.headers().get(&IDEMPOTENCY_KEY_HEADER) {
+        Some(value) => value.to_str().unwrap_or_default().to_string(),
+        None => return next.run(request).await,
+    };
+
+    // Unauthenticated callers must not be able to reserve (and poison) keys.
+    if !is_admin_authorized(request.headers(), &state.api_bearer_token) {
+        return next.run(request).await;
+    }
+
+    if !is_valid_key(&key) {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "Idempotency-Key must be 1-255 printable ASCII characters",
+        );
+    }
+
+    let (parts, body) = request.into_parts();
+    let body = match to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(_) => return error_response(StatusCode::PAYLOAD_TOO_LARGE, "Request body too large"),
+    };
+
+    let fingerprint = request_fingerprint(&parts.method, parts.uri.path(), &body);
+    let key_ttl = chrono::Duration::hours(IDEMPOTENCY_KEY_TTL_HOURS);
+    // An unfinished reservation this old belongs to a request that died mid-flight.
+    let abandoned_after = chrono::Duration::from_std(state.idempotency_abandon_after)
+        .map_or(key_ttl, |after| after.min(key_ttl));
+    let record = IdempotencyRecord::new(key, fingerprint.clone());
+    let now = Utc::now();
+    let reservation = state
+        .idempotency_repo
+        .reserve(&record, now - key_ttl, now - abandoned_after)
+        .await;
+
+    match reservation {
+        Ok(None) => {}
+        Ok(Some(existing)) if existing.request_fingerprint != fingerprint => {
+            warn!("Idempotency-Key reused with a different request");
+            return error_response(
+                StatusCode::CONFLICT,
+                "Idempotency-Key was already used with a different request",
+            );
+        }
+        Ok(Some(existing)) => {
+            return match existing.response_status {
+                Some(status) => {
+                    info!("Replaying stored response for Idempotency-Key");
+                    replay(&existing, status)
+                }
+                None => error_response(
+                    StatusCode::CONFLICT,
+                    "A request with this Idempotency-Key is still in progress",
+                ),
+            };
+        }
+        Err(RepositoryError::Conflict(_)) => {
+            return error_response(
+                StatusCode::CONFLICT,
+                "A request with this Idempotency-Key is still in progress",
+            );
+        }
+        Err(e) => {
+            error!(error = %e, "Failed to reserve idempotency key");
+            return error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to process Idempotency-Key",
+            );
+        }
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            error!(error = %e, "Failed to buffer response for idempotency store");
+            release(&state, &record).await;
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "Request failed");
+        }
+    };
+
+    if parts.status.is_server_error() {
+        release(&state, &record).await;
+    } else if let Err(e) = state
+        .idempotency_repo
+        .complete(&record, parts.status.as_u16(), &body)
+        .await
+    {
+        // The handler's work is done. Either a retry took the key over as
+        // abandoned, or the key will be reclaimable once abandoned.
+        error!(error = %e, "Failed to store response for idempotency key");
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+async fn release(state: &AppState, record: &IdempotencyRecord) {
+    if let Err(e) = state.idempotency_repo.release(record).await {
+        error!(error = %e, "Failed to release idempotency key");
+    }
+}
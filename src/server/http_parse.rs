@@ -1,6 +1,13 @@
-use crate::domain::{AlgorithmMode, AssetFocus, Persona, StrictnessLevel, SubscriptionTier};
+use crate::domain::{
+    AlgorithmMode, AssetFocus, BootstrapLogSource, BootstrapPhase, Persona, StrictnessLevel,
+    SubscriptionTier, MAX_BOOTSTRAP_MESSAGE_LENGTH,
+};
+use std::net::IpAddr;
+use std::str::FromStr;
 
-pub(super) fn parse_subscription_tier(tier: &str) -> Option<SubscriptionTier> {
+const MAX_AGENT_VERSION_LENGTH: usize = 64;
+
+pub(crate) fn parse_subscription_tier(tier: &str) -> Option<SubscriptionTier> {
     match tier {
         "free" => Some(SubscriptionTier::Free),
         "basic" => Some(SubscriptionTier::Basic),
@@ -9,7 +16,7 @@ pub(super) fn parse_subscription_tier(tier: &str) -> Option<SubscriptionTier> {
     }
 }
 
-pub(super) fn parse_persona(persona: &str) -> Option<Persona> {
+pub(crate) fn parse_persona(persona: &str) -> Option<Persona> {
     match persona {
         "beginner" => Some(Persona::Beginner),
         "tweaker" => Some(Persona::Tweaker),
@@ -18,7 +25,7 @@ pub(super) fn parse_persona(persona: &str) -> Option<Persona> {
     }
 }
 
-pub(super) fn parse_asset_focus(asset_focus: &str) -> Option<AssetFocus> {
+pub(crate) fn parse_asset_focus(asset_focus: &str) -> Option<AssetFocus> {
     match asset_focus {
         "majors" => Some(AssetFocus::Majors),
         "memes" => Some(AssetFocus::Memes),
@@ -26,7 +33,7 @@ pub(super) fn parse_asset_focus(asset_focus: &str) -> Option<AssetFocus> {
     }
 }
 
-pub(super) fn parse_algorithm(algorithm: &str) -> Option<AlgorithmMode> {
+pub(crate) fn parse_algorithm(algorithm: &str) -> Option<AlgorithmMode> {
     match algorithm {
         "trend" => Some(AlgorithmMode::Trend),
         "mean_reversion" => Some(AlgorithmMode::MeanReversion),
@@ -35,7 +42,7 @@ pub(super) fn parse_algorithm(algorithm: &str) -> Option<AlgorithmMode> {
     }
 }
 
-pub(super) fn parse_strictness(strictness: &str) -> Option<StrictnessLevel> {
+pub(crate) fn parse_strictness(strictness: &str) -> Option<StrictnessLevel> {
     match strictness {
         "low" => Some(StrictnessLevel::Low),
         "medium" => Some(StrictnessLevel::Medium),
@@ -43,3 +50,34 @@ pub(super) fn parse_strictness(strictness: &str) -> Option<StrictnessLevel> {
         _ => None,
     }
 }
+
+/// Canonical form of a droplet-reported IP address.
+pub(crate) fn parse_ip_address(ip: &str) -> Option<String> {
+    ip.trim().parse::<IpAddr>().ok().map(|ip| ip.to_string())
+}
+
+pub(crate) fn parse_agent_version(version: &str) -> Option<String> {
+    let version = version.trim();
+    let valid = !version.is_empty()
+        && version.len() <= MAX_AGENT_VERSION_LENGTH
+        && version.bytes().all(|b| b.is_ascii_graphic());
+    valid.then(|| version.to_string())
+}
+
+pub(crate) fn parse_bootstrap_phase(phase: &str) -> Option<BootstrapPhase> {
+    BootstrapPhase::from_str(phase).ok()
+}
+
+pub(crate) fn parse_bootstrap_log_source(source: &str) -> Option<BootstrapLogSource> {
+    BootstrapLogSource::from_str(source).ok()
+}
+
+/// Exit statuses a shell can report.
+pub(crate) fn parse_exit_code(code: i32) -> Option<i32> {
+    (0..=255).contains(&code).then_some(code)
+}
+
+pub(crate) fn parse_bootstrap_message(message: &str) -> Option<String> {
+    let message = message.trim();
+    (message.chars().count() <= MAX_BOOTSTRAP_MESSAGE_LENGTH).then(|| message.to_string())
+}
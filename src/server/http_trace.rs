@@ -0,0 +1,48 @@
+use axum::{
+    body::Body,
+    extract::MatchedPath,
+    http::{HeaderName, Request, Response},
+};
+use std::time::Duration;
+use tracing::{field::Empty, info, Span};
+
+/// Header used to correlate a request across logs, traces and the caller.
+pub(super) const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Root span for every HTTP request handled by the router.
+///
+/// The request ID is set (or preserved) by `SetRequestIdLayer` before this runs,
+/// so the span always carries the same value that is echoed back to the caller.
+pub(super) fn make_request_span(request: &Request<Body>) -> Span {
+    let request_id = request
+        .headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(MatchedPath::as_str)
+        .unwrap_or_else(|| request.uri().path());
+
+    tracing::info_span!(
+        "http_request",
+        otel.name = %format!("{} {}", request.method(), route),
+        otel.kind = "server",
+        http.method = %request.method(),
+        http.route = %route,
+        http.status_code = Empty,
+        request_id = %request_id,
+    )
+}
+
+/// Records the final status on the request span and logs one line per request.
+pub(super) fn record_response<B>(response: &Response<B>, latency: Duration, span: &Span) {
+    let status = response.status().as_u16();
+    span.record("http.status_code", status);
+    info!(
+        status,
+        latency_ms = latency.as_millis() as u64,
+        "request completed"
+    );
+}
@@ -1,4 +1,7 @@
-use crate::domain::Bot;
+use crate::domain::{
+    BootstrapEvent, BootstrapLog, Bot, CustomizerOverrides, CustomizerSettings, Job,
+};
+use crate::infrastructure::RateLimitBudget;
 use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
@@ -8,6 +11,31 @@ pub(super) struct HealthResponse {
     pub(super) status: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(super) error: Option<String>,
+    pub(super) digitalocean_rate_limit: RateLimitResponse,
+}
+
+/// DigitalOcean API budget as last reported, less requests sent since. Fields
+/// are null until DigitalOcean has reported them.
+#[derive(Serialize, ToSchema)]
+pub(super) struct RateLimitResponse {
+    #[schema(example = 5000)]
+    pub(super) limit: Option<u32>,
+    #[schema(example = 4870)]
+    pub(super) remaining: Option<u32>,
+    pub(super) reset_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Set while a `429` holds every DigitalOcean request back.
+    pub(super) throttled_until: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl From<RateLimitBudget> for RateLimitResponse {
+    fn from(budget: RateLimitBudget) -> Self {
+        Self {
+            limit: budget.limit,
+            remaining: budget.remaining,
+            reset_at: budget.reset_at,
+            throttled_until: budget.throttled_until,
+        }
+    }
 }
 
 #[derive(Deserialize, ToSchema)]
@@ -16,6 +44,51 @@ pub(super) struct CreateAccountRequest {
     pub(super) external_id: String,
     #[schema(example = "pro")]
     pub(super) tier: String,
+    /// Customizer defaults for the account's bots.
+    #[serde(default)]
+    pub(super) customizer: Option<CustomizerRequest>,
+}
+
+/// Workspace customizer (janebot-cli) overrides. Omitted fields inherit from the
+/// account, then from the service defaults.
+#[derive(Deserialize, ToSchema)]
+pub(super) struct CustomizerRequest {
+    /// Must be the default repository or on the operator's allow-list.
+    #[serde(default)]
+    pub(super) repo_url: Option<String>,
+    /// Tag, branch or commit SHA to check out.
+    #[serde(default, rename = "ref")]
+    #[schema(example = "v1.4.0")]
+    pub(super) git_ref: Option<String>,
+    #[serde(default)]
+    #[schema(example = "Jane")]
+    pub(super) agent_name: Option<String>,
+    #[serde(default)]
+    #[schema(example = "Cedros")]
+    pub(super) owner_name: Option<String>,
+    #[serde(default)]
+    pub(super) skip_qmd: Option<bool>,
+    #[serde(default)]
+    pub(super) skip_cron: Option<bool>,
+    #[serde(default)]
+    pub(super) skip_git: Option<bool>,
+    #[serde(default)]
+    pub(super) skip_heartbeat: Option<bool>,
+}
+
+impl From<CustomizerRequest> for CustomizerOverrides {
+    fn from(req: CustomizerRequest) -> Self {
+        Self {
+            repo_url: req.repo_url,
+            git_ref: req.git_ref,
+            agent_name: req.agent_name,
+            owner_name: req.owner_name,
+            skip_qmd: req.skip_qmd,
+            skip_cron: req.skip_cron,
+            skip_git: req.skip_git,
+            skip_heartbeat: req.skip_heartbeat,
+        }
+    }
 }
 
 #[derive(Deserialize, Debug, IntoParams, ToSchema)]
@@ -47,16 +120,41 @@ pub(super) struct CreateBotRequest {
     pub(super) max_trades_per_day: i32,
     pub(super) llm_provider: String,
     pub(super) llm_api_key: String,
+    /// Overrides the account's customizer settings for this bot.
+    #[serde(default)]
+    pub(super) customizer: Option<CustomizerRequest>,
+    /// Named toolchain profile, e.g. `minimal`, `node-only` or `full-rust`;
+    /// omitted uses the service default.
+    #[serde(default)]
+    #[schema(example = "node-only")]
+    pub(super) toolchain_profile: Option<String>,
 }
 
 #[derive(Deserialize, ToSchema)]
 pub(super) struct BotActionRequest {
     pub(super) action: String,
+    /// For `hibernate`: whether to snapshot the droplet first. Remembered for
+    /// the bot's later hibernations; omitted keeps its choice, or the tier default.
+    #[serde(default)]
+    pub(super) snapshot: Option<bool>,
+    /// For `redeploy`: `recreate` (the default) replaces the droplet, giving the
+    /// bot a new droplet ID and IP; `rebuild` reinstalls it in place, keeping both.
+    /// A rebuild re-runs the user data the droplet was created with, so it is
+    /// refused with `400` once that differs from what the bot would get now
+    /// (after a toolchain, customizer or template change).
+    #[serde(default)]
+    #[schema(example = "rebuild")]
+    pub(super) strategy: Option<String>,
 }
 
 #[derive(Deserialize, ToSchema)]
 pub(super) struct RegisterBotRequest {
     pub(super) bot_id: Uuid,
+    /// Droplet public IPv4/IPv6 address as seen by the droplet.
+    #[serde(default)]
+    pub(super) ip_address: Option<String>,
+    #[serde(default)]
+    pub(super) agent_version: Option<String>,
 }
 
 #[derive(Deserialize, ToSchema)]
@@ -64,8 +162,24 @@ pub(super) struct AckConfigRequest {
     pub(super) config_id: Uuid,
 }
 
+#[derive(Deserialize, ToSchema)]
+pub(super) struct BootstrapProgressRequest {
+    #[schema(example = "customizer")]
+    pub(super) phase: String,
+    /// Exit status of the phase (0-255); 0 means it succeeded.
+    pub(super) exit_code: i32,
+    #[serde(default)]
+    pub(super) message: Option<String>,
+}
+
+#[derive(Deserialize, Debug, IntoParams)]
+pub(super) struct BootstrapLogParams {
+    /// `bootstrap` (the script's cloud-init output) or `customizer`.
+    pub(super) source: String,
+}
+
 #[derive(Serialize, ToSchema)]
-pub(super) struct BotResponse {
+pub(crate) struct BotResponse {
     pub(super) id: Uuid,
     pub(super) account_id: Uuid,
     pub(super) name: String,
@@ -78,6 +192,26 @@ pub(super) struct BotResponse {
     pub(super) updated_at: chrono::DateTime<chrono::Utc>,
     #[schema(format = "date-time")]
     pub(super) last_heartbeat_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Why the bot entered its current status, e.g. `provision_failed` or `heartbeat_timeout`.
+    pub(super) status_reason: Option<String>,
+    /// What went wrong, when the status change came from a failure.
+    pub(super) status_detail: Option<String>,
+    pub(super) status_changed_at: chrono::DateTime<chrono::Utc>,
+    #[schema(format = "date-time")]
+    pub(super) registered_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub(super) registered_ip: Option<String>,
+    pub(super) agent_version: Option<String>,
+    /// Customizer settings the bot was created with; `null` for older bots, which
+    /// use the service defaults.
+    #[schema(value_type = Option<Object>)]
+    pub(super) customizer: Option<CustomizerSettings>,
+    /// Toolchain profile the bot was created with; `null` for older bots, which
+    /// use the default profile.
+    pub(super) toolchain_profile: Option<String>,
+    /// Whether hibernating snapshots the droplet; `null` follows the account tier.
+    pub(super) snapshot_on_hibernate: Option<bool>,
+    /// Snapshot a hibernated bot's next droplet boots from.
+    pub(super) hibernation_snapshot_id: Option<i64>,
 }
 
 impl From<Bot> for BotResponse {
@@ -94,6 +228,117 @@ impl From<Bot> for BotResponse {
             created_at: bot.created_at,
             updated_at: bot.updated_at,
             last_heartbeat_at: bot.last_heartbeat_at,
+            status_reason: bot.status_reason.map(|r| r.to_string()),
+            status_detail: bot.status_detail,
+            status_changed_at: bot.status_changed_at,
+            registered_at: bot.registered_at,
+            registered_ip: bot.registered_ip,
+            agent_version: bot.agent_version,
+            customizer: bot.customizer,
+            toolchain_profile: bot.toolchain_profile,
+            snapshot_on_hibernate: bot.snapshot_on_hibernate,
+            hibernation_snapshot_id: bot.hibernation_snapshot_id,
+        }
+    }
+}
+
+/// Returned with `202 Accepted` when work is handed to the job queue.
+#[derive(Serialize, ToSchema)]
+pub(crate) struct JobAcceptedResponse {
+    pub(crate) job_id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) bot: Option<BotResponse>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(super) struct JobResponse {
+    pub(super) id: Uuid,
+    #[schema(example = "spawn_bot")]
+    pub(super) kind: String,
+    pub(super) bot_id: Uuid,
+    #[schema(example = "running")]
+    pub(super) status: String,
+    pub(super) attempts: i32,
+    pub(super) max_attempts: i32,
+    /// When the job is next due (the retry time for a job waiting on backoff).
+    pub(super) run_at: chrono::DateTime<chrono::Utc>,
+    pub(super) last_error: Option<String>,
+    pub(super) created_at: chrono::DateTime<chrono::Utc>,
+    pub(super) updated_at: chrono::DateTime<chrono::Utc>,
+    #[schema(format = "date-time")]
+    pub(super) completed_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl From<Job> for JobResponse {
+    fn from(job: Job) -> Self {
+        Self {
+            id: job.id,
+            kind: job.kind.to_string(),
+            bot_id: job.bot_id,
+            status: job.status.to_string(),
+            attempts: job.attempts,
+            max_attempts: job.max_attempts,
+            run_at: job.run_at,
+            last_error: job.last_error,
+            created_at: job.created_at,
+            updated_at: job.updated_at,
+            completed_at: job.completed_at,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct BootstrapEventResponse {
+    #[schema(example = "customizer")]
+    pub(super) phase: String,
+    pub(super) exit_code: i32,
+    pub(super) message: Option<String>,
+    pub(super) reported_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<BootstrapEvent> for BootstrapEventResponse {
+    fn from(event: BootstrapEvent) -> Self {
+        Self {
+            phase: event.phase.to_string(),
+            exit_code: event.exit_code,
+            message: event.message,
+            reported_at: event.reported_at,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub(super) struct BootstrapTimelineResponse {
+    pub(super) bot_id: Uuid,
+    /// Progress reports, oldest first.
+    pub(super) events: Vec<BootstrapEventResponse>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(super) struct BootstrapLogResponse {
+    /// Droplet the log came from; one per provisioning attempt.
+    pub(super) droplet_id: Option<i64>,
+    #[schema(example = "customizer")]
+    pub(super) source: String,
+    /// Redacted tail of the log.
+    pub(super) content: String,
+    pub(super) uploaded_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<BootstrapLog> for BootstrapLogResponse {
+    fn from(log: BootstrapLog) -> Self {
+        Self {
+            droplet_id: log.droplet_id,
+            source: log.source.to_string(),
+            content: log.content,
+            uploaded_at: log.uploaded_at,
         }
     }
 }
+
+#[derive(Serialize, ToSchema)]
+pub(super) struct BootstrapLogsResponse {
+    pub(super) bot_id: Uuid,
+    /// Uploaded logs, newest first.
+    pub(super) logs: Vec<BootstrapLogResponse>,
+}
@@ -1,32 +1,79 @@
 //! HTTP server support (standalone + embeddable).
 //!
 //! This module mirrors the pattern used by other Cedros services:
-//! - **Standalone**: `claw-spawn-server` binary calls `run()`
+//! - **Standalone**: `claw-spawn-server` binary calls `run()`, which also handles
+//!   the `migrate`, `check-config` and `print-user-data` subcommands
 //! - **Embedded**: host Axum app calls `router(state)` (and may nest it)
 
+mod commands;
 mod http;
 mod http_auth;
 mod http_errors;
-mod http_parse;
-mod http_types;
+mod http_idempotency;
+pub(crate) mod http_parse;
+mod http_trace;
+pub(crate) mod http_types;
+mod shutdown;
 mod state;
+mod telemetry;
 
 pub use http::router;
-pub use state::{build_state_from_env, build_state_with_pool, AppState};
+pub use shutdown::shutdown_signal;
+#[cfg(feature = "sqlite")]
+pub use state::build_state_with_sqlite_pool;
+#[cfg(feature = "cli")]
+pub(crate) use state::connect_and_build_state;
+pub use state::{
+    build_state_from_env, build_state_with_pool, build_state_with_repositories, check_config,
+    spawn_job_workers, spawn_outbox_executor, spawn_watchdog, AppState, Repositories,
+};
+pub use telemetry::{init_tracing, TelemetryGuard};
 
+use crate::application::JobWorkerSettings;
 use crate::infrastructure::AppConfig;
 use anyhow::Context;
+use clap::Parser;
+use commands::{Cli, Command};
+use std::future::IntoFuture;
 use std::net::SocketAddr;
+use std::time::Duration;
 use tokio::net::TcpListener;
-use tracing::info;
+use tracing::{info, warn};
 
 /// Standalone entrypoint for the `claw-spawn-server` binary.
+///
+/// Without a subcommand it serves, running migrations first.
 pub async fn run() -> anyhow::Result<()> {
-    tracing_subscriber::fmt::init();
     dotenvy::dotenv().ok();
+    let cli = Cli::parse();
 
-    let config = AppConfig::from_env().context("load config")?;
-    let state = build_state_from_env(config.clone()).await?;
+    match cli.command.unwrap_or(Command::Serve { no_migrate: false }) {
+        Command::Serve { no_migrate } => {
+            let _telemetry = init_tracing().context("init tracing")?;
+            let config = AppConfig::from_env().context("load config")?;
+            serve(config, !no_migrate).await
+        }
+        Command::Migrate(migrate) => {
+            let config = commands::init()?;
+            commands::migrate(&config, migrate).await
+        }
+        Command::CheckConfig => {
+            let config = commands::init()?;
+            commands::check_config_and_connectivity(&config).await
+        }
+        Command::PrintUserData { bot_id } => {
+            let config = commands::init()?;
+            commands::print_user_data(config, bot_id).await
+        }
+    }
+}
+
+async fn serve(config: AppConfig, run_migrations: bool) -> anyhow::Result<()> {
+    // Report every config problem before touching the database.
+    check_config(&config)?;
+    let state = state::connect_and_build_state(config.clone(), run_migrations)
+        .await?
+        .with_shutdown_signal(shutdown_signal());
 
     let addr: SocketAddr = format!("{}:{}", config.server_host, config.server_port)
         .parse()
@@ -43,7 +90,44 @@ pub async fn run() -> anyhow::Result<()> {
         "API docs"
     );
 
-    let app = router(state);
-    axum::serve(listener, app).await.context("serve")?;
-    Ok(())
+    let workers = spawn_job_workers(&state, JobWorkerSettings::from_config(&config));
+    info!(workers = workers.len(), "Job workers started");
+    spawn_watchdog(&state);
+    spawn_outbox_executor(&state);
+
+    // On shutdown: stop accepting connections, let in-flight requests and the job
+    // a worker is running finish, and give up on both after one shared drain period.
+    let shutdown = state.shutdown.clone();
+    let drain = Duration::from_secs(config.shutdown_drain_secs);
+    let serve = axum::serve(listener, router(state.clone()))
+        .with_graceful_shutdown(shutdown.clone().cancelled_owned())
+        .into_future();
+
+    let http = async {
+        tokio::select! {
+            res = serve => {
+                // Also stops the workers if the server exits on its own (e.g. an I/O error).
+                shutdown.cancel();
+                res.context("serve")
+            }
+            _ = async {
+                shutdown.cancelled().await;
+                tokio::time::sleep(drain).await;
+            } => {
+                warn!(drain_secs = drain.as_secs(), "Drain period elapsed with requests still in flight");
+                Ok(())
+            }
+        }
+    };
+    let background = async {
+        shutdown.cancelled().await;
+        state.drain(drain).await
+    };
+
+    let (served, drained) = tokio::join!(http, background);
+    if !drained {
+        warn!(drain_secs = drain.as_secs(), "Drain period elapsed with jobs still running");
+    }
+    info!("Shutdown complete");
+    served
 }
@@ -1,108 +1,464 @@
-use crate::application::{BotLifecycleService, ProvisioningService};
+use crate::application::{
+    BootstrapService, BotLifecycleService, JobService, JobWorker, JobWorkerSettings,
+    OutboxExecutor, OutboxSettings, ProvisioningService, ProvisioningSettings,
+    RegistrationWatchdog, UserDataFormat, UserDataOverlays, WatchdogSettings,
+};
+#[cfg(any(test, feature = "memory"))]
+use crate::infrastructure::MemoryStore;
+use crate::infrastructure::{
+    AccountRepository, AppConfig, BootstrapEventRepository, BootstrapLogRepository, BotRepository,
+    ConfigRepository, DigitalOceanClient, DropletRepository, IdempotencyRepository, JobRepository,
+    PostgresAccountRepository, PostgresBootstrapEventRepository, PostgresBootstrapLogRepository,
+    PostgresBotRepository, PostgresConfigRepository, PostgresDropletRepository,
+    PostgresIdempotencyRepository, PostgresJobRepository, PostgresProviderOperationRepository,
+    PostgresUnitOfWork, ProviderOperationRepository, SecretsEncryption, StorageHealth, UnitOfWork,
+};
+#[cfg(feature = "sqlite")]
 use crate::infrastructure::{
-    AppConfig, DigitalOceanClient, PostgresAccountRepository, PostgresBotRepository,
-    PostgresConfigRepository, PostgresDropletRepository, SecretsEncryption,
+    SqliteAccountRepository, SqliteBootstrapEventRepository, SqliteBootstrapLogRepository,
+    SqliteBotRepository, SqliteConfigRepository, SqliteDropletRepository,
+    SqliteIdempotencyRepository, SqliteJobRepository, SqliteProviderOperationRepository,
+    SqliteUnitOfWork, SQLITE_MIGRATOR,
 };
 use anyhow::Context;
+use sqlx::migrate::Migrator;
+#[cfg(feature = "sqlite")]
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePool};
 use sqlx::PgPool;
+use std::future::Future;
+#[cfg(feature = "sqlite")]
+use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
+use tracing::warn;
+
+/// The crate's embedded database migrations.
+pub(super) static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
 
 pub type ProvisioningServiceType = ProvisioningService<
-    PostgresAccountRepository,
-    PostgresBotRepository,
-    PostgresConfigRepository,
-    PostgresDropletRepository,
+    dyn AccountRepository,
+    dyn BotRepository,
+    dyn ConfigRepository,
+    dyn DropletRepository,
 >;
 
-pub type BotLifecycleServiceType =
-    BotLifecycleService<PostgresBotRepository, PostgresConfigRepository>;
+pub type BotLifecycleServiceType = BotLifecycleService<dyn BotRepository, dyn ConfigRepository>;
+
+pub type JobServiceType = JobService<dyn JobRepository>;
+
+pub type BootstrapServiceType =
+    BootstrapService<dyn BotRepository, dyn BootstrapEventRepository, dyn BootstrapLogRepository>;
+
+/// One implementation of every repository the services need, sharing a store.
+///
+/// Build it with [`Repositories::postgres`], `Repositories::sqlite` (`sqlite`
+/// feature), `Repositories::memory` (`memory` feature) or your own
+/// implementations, and pass it to [`build_state_with_repositories`].
+#[derive(Clone)]
+pub struct Repositories {
+    pub accounts: Arc<dyn AccountRepository>,
+    pub bots: Arc<dyn BotRepository>,
+    pub configs: Arc<dyn ConfigRepository>,
+    pub droplets: Arc<dyn DropletRepository>,
+    pub jobs: Arc<dyn JobRepository>,
+    pub idempotency: Arc<dyn IdempotencyRepository>,
+    pub bootstrap_events: Arc<dyn BootstrapEventRepository>,
+    pub bootstrap_logs: Arc<dyn BootstrapLogRepository>,
+    /// The outbox of DigitalOcean calls, read by the outbox executor.
+    pub provider_operations: Arc<dyn ProviderOperationRepository>,
+    /// Starts transactions over the same store as `bots`, `configs` and `droplets`.
+    pub unit_of_work: Arc<dyn UnitOfWork>,
+    /// Probed by `GET /health`.
+    pub health: Arc<dyn StorageHealth>,
+}
+
+impl Repositories {
+    pub fn postgres(pool: PgPool) -> Self {
+        Self {
+            accounts: Arc::new(PostgresAccountRepository::new(pool.clone())),
+            bots: Arc::new(PostgresBotRepository::new(pool.clone())),
+            configs: Arc::new(PostgresConfigRepository::new(pool.clone())),
+            droplets: Arc::new(PostgresDropletRepository::new(pool.clone())),
+            jobs: Arc::new(PostgresJobRepository::new(pool.clone())),
+            idempotency: Arc::new(PostgresIdempotencyRepository::new(pool.clone())),
+            bootstrap_events: Arc::new(PostgresBootstrapEventRepository::new(pool.clone())),
+            bootstrap_logs: Arc::new(PostgresBootstrapLogRepository::new(pool.clone())),
+            provider_operations: Arc::new(PostgresProviderOperationRepository::new(pool.clone())),
+            unit_of_work: Arc::new(PostgresUnitOfWork::new(pool.clone())),
+            health: Arc::new(pool),
+        }
+    }
+
+    /// Requires the `sqlite` feature; the schema comes from `migrations/sqlite`.
+    #[cfg(feature = "sqlite")]
+    pub fn sqlite(pool: SqlitePool) -> Self {
+        Self {
+            accounts: Arc::new(SqliteAccountRepository::new(pool.clone())),
+            bots: Arc::new(SqliteBotRepository::new(pool.clone())),
+            configs: Arc::new(SqliteConfigRepository::new(pool.clone())),
+            droplets: Arc::new(SqliteDropletRepository::new(pool.clone())),
+            jobs: Arc::new(SqliteJobRepository::new(pool.clone())),
+            idempotency: Arc::new(SqliteIdempotencyRepository::new(pool.clone())),
+            bootstrap_events: Arc::new(SqliteBootstrapEventRepository::new(pool.clone())),
+            bootstrap_logs: Arc::new(SqliteBootstrapLogRepository::new(pool.clone())),
+            provider_operations: Arc::new(SqliteProviderOperationRepository::new(pool.clone())),
+            unit_of_work: Arc::new(SqliteUnitOfWork::new(pool.clone())),
+            health: Arc::new(pool),
+        }
+    }
+
+    /// Requires the `memory` feature; nothing outlives the process.
+    #[cfg(any(test, feature = "memory"))]
+    pub fn memory(store: MemoryStore) -> Self {
+        Self {
+            accounts: Arc::new(store.accounts()),
+            bots: Arc::new(store.bots()),
+            configs: Arc::new(store.configs()),
+            droplets: Arc::new(store.droplets()),
+            jobs: Arc::new(store.jobs()),
+            idempotency: Arc::new(store.idempotency()),
+            bootstrap_events: Arc::new(store.bootstrap_events()),
+            bootstrap_logs: Arc::new(store.bootstrap_logs()),
+            provider_operations: Arc::new(store.provider_operations()),
+            unit_of_work: Arc::new(store.clone()),
+            health: Arc::new(store),
+        }
+    }
+}
+
+/// A pool for `database_url`, whichever backend it names.
+pub(crate) enum Database {
+    Postgres(PgPool),
+    #[cfg(feature = "sqlite")]
+    Sqlite(SqlitePool),
+}
+
+impl Database {
+    pub(crate) async fn connect(config: &AppConfig) -> anyhow::Result<Self> {
+        #[cfg(feature = "sqlite")]
+        if config.database_url.starts_with("sqlite:") {
+            // WAL lets readers proceed while the single writer holds the lock.
+            let options = SqliteConnectOptions::from_str(&config.database_url)
+                .context("parse database URL")?
+                .create_if_missing(true)
+                .journal_mode(SqliteJournalMode::Wal);
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .context("connect database")?;
+            return Ok(Self::Sqlite(pool));
+        }
+
+        let pool = PgPool::connect(&config.database_url)
+            .await
+            .context("connect database")?;
+        Ok(Self::Postgres(pool))
+    }
+
+    pub(crate) fn migrator(&self) -> &'static Migrator {
+        match self {
+            Self::Postgres(_) => &MIGRATOR,
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(_) => &SQLITE_MIGRATOR,
+        }
+    }
+
+    pub(crate) async fn migrate(&self) -> anyhow::Result<()> {
+        match self {
+            Self::Postgres(pool) => MIGRATOR.run(pool).await,
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(pool) => SQLITE_MIGRATOR.run(pool).await,
+        }
+        .context("run migrations")
+    }
+
+    pub(crate) fn repositories(&self) -> Repositories {
+        match self {
+            Self::Postgres(pool) => Repositories::postgres(pool.clone()),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(pool) => Repositories::sqlite(pool.clone()),
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct AppState {
-    pub pool: PgPool,
+    pub storage: Arc<dyn StorageHealth>,
+    /// Shared with the provisioning service; `GET /health` reports its rate-limit budget.
+    pub digitalocean: Arc<DigitalOceanClient>,
     pub api_bearer_token: String,
-    pub account_repo: Arc<PostgresAccountRepository>,
+    pub account_repo: Arc<dyn AccountRepository>,
     pub provisioning: Arc<ProvisioningServiceType>,
     pub lifecycle: Arc<BotLifecycleServiceType>,
+    pub bootstrap: Arc<BootstrapServiceType>,
+    pub job_repo: Arc<dyn JobRepository>,
+    pub jobs: Arc<JobServiceType>,
+    pub job_workers: usize,
+    pub watchdog: WatchdogSettings,
+    pub provider_operations: Arc<dyn ProviderOperationRepository>,
+    pub outbox: OutboxSettings,
+    pub idempotency_repo: Arc<dyn IdempotencyRepository>,
+    /// How long an unfinished `Idempotency-Key` request holds its key.
+    pub idempotency_abandon_after: Duration,
+    /// Cancelled when the service should stop taking on work.
+    pub shutdown: CancellationToken,
+    /// Background tasks (job workers, watchdog, outbox executor) that shutdown waits for.
+    pub tasks: TaskTracker,
+}
+
+impl AppState {
+    /// Trigger `shutdown` when `signal` resolves.
+    ///
+    /// Embedders pass the same future they give their own server, so the job
+    /// workers stop taking new work when the host app shuts down.
+    pub fn with_shutdown_signal<F>(self, signal: F) -> Self
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let shutdown = self.shutdown.clone();
+        tokio::spawn(async move {
+            signal.await;
+            shutdown.cancel();
+        });
+        self
+    }
+
+    /// Wait up to `timeout` for background tasks to finish after shutdown.
+    /// Returns `false` if some were still running.
+    pub async fn drain(&self, timeout: Duration) -> bool {
+        self.tasks.close();
+        tokio::time::timeout(timeout, self.tasks.wait())
+            .await
+            .is_ok()
+    }
+}
+
+/// Run [`AppConfig::validate`], logging its warnings; the error lists every problem.
+pub fn check_config(config: &AppConfig) -> anyhow::Result<()> {
+    let warnings = config.validate()?;
+    for warning in warnings {
+        warn!(environment = %config.environment, problem = %warning, "Configuration warning");
+    }
+    Ok(())
 }
 
 /// Build full state from config + an existing pool.
 ///
 /// Intended for embedding into a larger service that already manages a `PgPool`.
+/// Fails if the config does not pass [`check_config`].
 pub async fn build_state_with_pool(
     config: AppConfig,
     pool: PgPool,
     run_migrations: bool,
 ) -> anyhow::Result<AppState> {
+    check_config(&config)?;
+    let database = Database::Postgres(pool);
     if run_migrations {
-        sqlx::migrate!("./migrations")
-            .run(&pool)
-            .await
-            .context("run migrations")?;
+        database.migrate().await?;
+    }
+    build_state(config, database.repositories()).await
+}
+
+/// [`build_state_with_pool`] for a `SqlitePool` (`sqlite` feature).
+#[cfg(feature = "sqlite")]
+pub async fn build_state_with_sqlite_pool(
+    config: AppConfig,
+    pool: SqlitePool,
+    run_migrations: bool,
+) -> anyhow::Result<AppState> {
+    check_config(&config)?;
+    let database = Database::Sqlite(pool);
+    if run_migrations {
+        database.migrate().await?;
     }
+    build_state(config, database.repositories()).await
+}
+
+/// Build full state from config + repositories on any backend.
+///
+/// The backend-agnostic form of [`build_state_with_pool`]; migrating the
+/// store is up to the caller. Fails if the config does not pass [`check_config`].
+pub async fn build_state_with_repositories(
+    config: AppConfig,
+    repositories: Repositories,
+) -> anyhow::Result<AppState> {
+    check_config(&config)?;
+    build_state(config, repositories).await
+}
 
+async fn build_state(config: AppConfig, repositories: Repositories) -> anyhow::Result<AppState> {
     let encryption =
         Arc::new(SecretsEncryption::new(&config.encryption_key).context("init encryption")?);
 
+    let settings =
+        ProvisioningSettings::from_config(&config).context("invalid provisioning settings")?;
+
     let do_client = Arc::new(
-        DigitalOceanClient::new(config.digitalocean_token).context("init DigitalOcean client")?,
+        DigitalOceanClient::new(config.digitalocean_token)
+            .context("init DigitalOcean client")?
+            .with_max_rate_limit_wait(Duration::from_secs(
+                config.digitalocean_max_rate_limit_wait_secs,
+            )),
     );
 
-    let account_repo = Arc::new(PostgresAccountRepository::new(pool.clone()));
-    let bot_repo = Arc::new(PostgresBotRepository::new(pool.clone()));
-    let config_repo = Arc::new(PostgresConfigRepository::new(pool.clone()));
-    let droplet_repo = Arc::new(PostgresDropletRepository::new(pool.clone()));
+    let Repositories {
+        accounts: account_repo,
+        bots: bot_repo,
+        configs: config_repo,
+        droplets: droplet_repo,
+        jobs: job_repo,
+        idempotency: idempotency_repo,
+        bootstrap_events,
+        bootstrap_logs,
+        provider_operations,
+        unit_of_work,
+        health: storage,
+    } = repositories;
 
     let api_bearer_token = config.api_bearer_token.clone();
 
-    let provisioning = Arc::new(ProvisioningService::new(
-        do_client,
-        account_repo.clone(),
+    let overlays = if config.user_data_overlay_dir.is_empty() {
+        UserDataOverlays::default()
+    } else {
+        UserDataOverlays::load(&config.user_data_overlay_dir).context("load user-data overlays")?
+    };
+    let user_data_format: UserDataFormat = config
+        .user_data_format
+        .parse()
+        .with_context(|| format!("invalid user_data_format {:?}", config.user_data_format))?;
+
+    let provisioning = Arc::new(
+        ProvisioningService::new(
+            do_client.clone(),
+            account_repo.clone(),
+            bot_repo.clone(),
+            config_repo.clone(),
+            droplet_repo.clone(),
+            unit_of_work,
+            encryption,
+            settings,
+        )
+        .with_user_data_overlays(overlays)
+        .with_user_data_format(user_data_format),
+    );
+
+    let lifecycle = Arc::new(BotLifecycleService::new(
         bot_repo.clone(),
         config_repo.clone(),
-        droplet_repo.clone(),
-        encryption,
-        config.openclaw_image,
-        config.control_plane_url,
-        config.customizer_repo_url,
-        config.customizer_ref,
-        config.customizer_workspace_dir,
-        config.customizer_agent_name,
-        config.customizer_owner_name,
-        config.customizer_skip_qmd,
-        config.customizer_skip_cron,
-        config.customizer_skip_git,
-        config.customizer_skip_heartbeat,
-        config.toolchain_node_major,
-        config.toolchain_install_pnpm,
-        config.toolchain_pnpm_version,
-        config.toolchain_install_rust,
-        config.toolchain_rust_toolchain,
-        config.toolchain_extra_apt_packages,
-        config.toolchain_global_npm_packages,
-        config.toolchain_cargo_crates,
     ));
 
-    let lifecycle = Arc::new(BotLifecycleService::new(
+    let bootstrap = Arc::new(BootstrapService::new(
         bot_repo.clone(),
-        config_repo.clone(),
+        bootstrap_events,
+        bootstrap_logs,
     ));
 
+    let jobs = Arc::new(JobService::new(job_repo.clone(), config.job_max_attempts));
+
     Ok(AppState {
-        pool,
+        storage,
+        digitalocean: do_client,
         api_bearer_token,
         account_repo,
         provisioning,
         lifecycle,
+        bootstrap,
+        job_repo,
+        jobs,
+        job_workers: config.job_workers,
+        watchdog: WatchdogSettings {
+            interval: Duration::from_secs(config.watchdog_interval_secs),
+            registration_timeout: Duration::from_secs(config.registration_timeout_secs),
+            registration_redeploys: config.registration_timeout_redeploys,
+        },
+        provider_operations,
+        outbox: OutboxSettings {
+            interval: Duration::from_secs(config.outbox_interval_secs),
+            lease: Duration::from_secs(config.outbox_lease_secs),
+            max_attempts: config.outbox_max_attempts,
+        },
+        idempotency_repo,
+        idempotency_abandon_after: Duration::from_secs(config.idempotency_abandon_after_secs),
+        shutdown: CancellationToken::new(),
+        tasks: TaskTracker::new(),
     })
 }
 
+/// Start `state.job_workers` background workers draining the job queue.
+///
+/// Embedders must call this (once per process) for queued spawn/redeploy/destroy
+/// jobs to run; any number of processes may share one queue. Workers stop when
+/// `state.shutdown` is cancelled and are tracked by `state.tasks`.
+pub fn spawn_job_workers(state: &AppState, settings: JobWorkerSettings) -> Vec<JoinHandle<()>> {
+    (0..state.job_workers)
+        .map(|_| {
+            let worker = JobWorker::new(
+                state.job_repo.clone(),
+                state.provisioning.clone(),
+                settings.clone(),
+            );
+            let shutdown = state.shutdown.clone();
+            state.tasks.spawn(async move { worker.run(shutdown).await })
+        })
+        .collect()
+}
+
+/// Start the registration watchdog, which times out bots whose droplet never
+/// registers (see `state.watchdog`) and purges expired idempotency keys.
+///
+/// Like [`spawn_job_workers`], embedders call this once; it stops when
+/// `state.shutdown` is cancelled. Running it in several processes is harmless:
+/// status changes are compare-and-set, so each bot is timed out once.
+pub fn spawn_watchdog(state: &AppState) -> JoinHandle<()> {
+    let watchdog = RegistrationWatchdog::new(
+        state.lifecycle.clone(),
+        state.jobs.clone(),
+        state.watchdog.clone(),
+    )
+    .with_idempotency_keys(state.idempotency_repo.clone());
+    let shutdown = state.shutdown.clone();
+    state
+        .tasks
+        .spawn(async move { watchdog.run(shutdown).await })
+}
+
+/// Start the outbox executor, which finishes droplet creates and destroys
+/// whose caller died or failed before completing them (see `state.outbox`).
+///
+/// Like [`spawn_watchdog`], embedders call this once; it stops when
+/// `state.shutdown` is cancelled. Several processes may run it: each claim
+/// locks the operation for `state.outbox.lease`.
+pub fn spawn_outbox_executor(state: &AppState) -> JoinHandle<()> {
+    let executor = OutboxExecutor::new(
+        state.provider_operations.clone(),
+        state.provisioning.clone(),
+        state.outbox.clone(),
+    );
+    let shutdown = state.shutdown.clone();
+    state
+        .tasks
+        .spawn(async move { executor.run(shutdown).await })
+}
+
 /// Build state for the standalone server.
 ///
-/// Creates the `PgPool`, runs migrations, and wires repositories/services.
+/// Checks the config before connecting, then opens the database named by
+/// `database_url` (Postgres, or SQLite with the `sqlite` feature), runs
+/// migrations, and wires repositories/services.
 pub async fn build_state_from_env(config: AppConfig) -> anyhow::Result<AppState> {
-    let pool = PgPool::connect(&config.database_url)
-        .await
-        .context("connect database")?;
-    build_state_with_pool(config, pool, true).await
+    check_config(&config)?;
+    connect_and_build_state(config, true).await
+}
+
+/// [`build_state_from_env`] for callers that already ran [`check_config`].
+pub(crate) async fn connect_and_build_state(
+    config: AppConfig,
+    run_migrations: bool,
+) -> anyhow::Result<AppState> {
+    let database = Database::connect(&config).await?;
+    if run_migrations {
+        database.migrate().await?;
+    }
+    build_state(config, database.repositories()).await
 }
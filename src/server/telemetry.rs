@@ -0,0 +1,97 @@
+//! Tracing subscriber setup for the standalone server.
+//!
+//! Always installs a `fmt` layer filtered by `RUST_LOG` (default `info`).
+//! With the `otel` feature enabled and `OTEL_EXPORTER_OTLP_ENDPOINT` set, spans are
+//! additionally exported over OTLP/gRPC so HTTP requests, repository calls and
+//! DigitalOcean API calls show up as a single trace.
+
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+
+/// Flushes pending spans on drop. Keep it alive for the lifetime of the process.
+#[must_use]
+pub struct TelemetryGuard {
+    #[cfg(feature = "otel")]
+    provider: Option<opentelemetry_sdk::trace::TracerProvider>,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        #[cfg(feature = "otel")]
+        if let Some(provider) = self.provider.take() {
+            if let Err(e) = provider.shutdown() {
+                eprintln!("Failed to flush OpenTelemetry spans: {e}");
+            }
+        }
+    }
+}
+
+/// Install the global tracing subscriber.
+///
+/// Must be called from within a Tokio runtime when the `otel` feature is enabled
+/// (the batch span processor spawns onto it).
+pub fn init_tracing() -> anyhow::Result<TelemetryGuard> {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let registry = tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer());
+
+    #[cfg(feature = "otel")]
+    {
+        if let Some(provider) = otel::build_provider()? {
+            use opentelemetry::trace::TracerProvider as _;
+
+            let tracer = provider.tracer("claw-spawn");
+            registry
+                .with(tracing_opentelemetry::layer().with_tracer(tracer))
+                .try_init()?;
+            return Ok(TelemetryGuard {
+                provider: Some(provider),
+            });
+        }
+    }
+
+    registry.try_init()?;
+    Ok(TelemetryGuard {
+        #[cfg(feature = "otel")]
+        provider: None,
+    })
+}
+
+#[cfg(feature = "otel")]
+mod otel {
+    use anyhow::Context;
+    use opentelemetry::KeyValue;
+    use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+    use opentelemetry_sdk::{runtime, trace::TracerProvider, Resource};
+
+    const DEFAULT_SERVICE_NAME: &str = "claw-spawn";
+
+    /// Returns `None` when no collector endpoint is configured.
+    pub(super) fn build_provider() -> anyhow::Result<Option<TracerProvider>> {
+        let endpoint = match std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT") {
+            Ok(v) if !v.trim().is_empty() => v,
+            _ => return Ok(None),
+        };
+        let service_name = std::env::var("OTEL_SERVICE_NAME")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_SERVICE_NAME.to_string());
+
+        let exporter = SpanExporter::builder()
+            .with_tonic()
+            .with_endpoint(endpoint)
+            .build()
+            .context("build OTLP span exporter")?;
+
+        let provider = TracerProvider::builder()
+            .with_batch_exporter(exporter, runtime::Tokio)
+            .with_resource(Resource::new(vec![
+                KeyValue::new("service.name", service_name),
+                KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
+            ]))
+            .build();
+
+        opentelemetry::global::set_tracer_provider(provider.clone());
+        Ok(Some(provider))
+    }
+}
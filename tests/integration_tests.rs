@@ -2,364 +2,21 @@
 //! CLEAN-003: Comprehensive test suite covering account creation, bot lifecycle,
 //! config versioning, and authentication.
 
-use async_trait::async_trait;
-use chrono::{DateTime, Utc};
+use chrono::Utc;
 use claw_spawn::{
-    application::BotLifecycleService,
+    application::{BootstrapService, BotLifecycleService, LifecycleError},
     domain::{
-        Account, AlgorithmMode, AssetFocus, Bot, BotStatus, EncryptedBotSecrets, Persona,
-        RiskConfig, StoredBotConfig, StrictnessLevel, SubscriptionTier, TradingConfig,
+        Account, AlgorithmMode, AssetFocus, BootstrapLogSource, BootstrapPhase, Bot, BotStatus,
+        EncryptedBotSecrets, Persona, RiskConfig, StoredBotConfig, StrictnessLevel,
+        SubscriptionTier, TradingConfig, TransitionReason,
+    },
+    infrastructure::{
+        AccountRepository, BotFilter, BotRepository, ConfigRepository, MemoryStore, RepositoryError,
     },
-    infrastructure::{AccountRepository, BotRepository, ConfigRepository, RepositoryError},
 };
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 use uuid::Uuid;
 
-// ============================================================================
-// Mock Repositories for Testing
-// ============================================================================
-
-/// In-memory mock implementation of AccountRepository
-#[derive(Clone, Default)]
-struct MockAccountRepository {
-    accounts: Arc<Mutex<HashMap<Uuid, Account>>>,
-    external_ids: Arc<Mutex<HashMap<String, Uuid>>>,
-}
-
-#[async_trait]
-impl AccountRepository for MockAccountRepository {
-    async fn create(&self, account: &Account) -> Result<(), RepositoryError> {
-        let mut accounts = self.accounts.lock().unwrap();
-        let mut external_ids = self.external_ids.lock().unwrap();
-
-        if accounts.contains_key(&account.id) {
-            return Err(RepositoryError::InvalidData(
-                "Account already exists".to_string(),
-            ));
-        }
-
-        accounts.insert(account.id, account.clone());
-        external_ids.insert(account.external_id.clone(), account.id);
-        Ok(())
-    }
-
-    async fn get_by_id(&self, id: Uuid) -> Result<Account, RepositoryError> {
-        let accounts = self.accounts.lock().unwrap();
-        accounts
-            .get(&id)
-            .cloned()
-            .ok_or_else(|| RepositoryError::NotFound(format!("Account {}", id)))
-    }
-
-    async fn get_by_external_id(&self, external_id: &str) -> Result<Account, RepositoryError> {
-        let external_ids = self.external_ids.lock().unwrap();
-        let accounts = self.accounts.lock().unwrap();
-
-        external_ids
-            .get(external_id)
-            .and_then(|id| accounts.get(id).cloned())
-            .ok_or_else(|| RepositoryError::NotFound(format!("Account {}", external_id)))
-    }
-
-    async fn update_subscription(
-        &self,
-        id: Uuid,
-        tier: SubscriptionTier,
-    ) -> Result<(), RepositoryError> {
-        let mut accounts = self.accounts.lock().unwrap();
-        let account = accounts
-            .get_mut(&id)
-            .ok_or_else(|| RepositoryError::NotFound(format!("Account {}", id)))?;
-
-        account.max_bots = match tier {
-            SubscriptionTier::Free => 0,
-            SubscriptionTier::Basic => 2,
-            SubscriptionTier::Pro => 4,
-        };
-        account.subscription_tier = tier;
-        account.updated_at = Utc::now();
-        Ok(())
-    }
-}
-
-/// In-memory mock implementation of BotRepository
-#[derive(Clone, Default)]
-struct MockBotRepository {
-    bots: Arc<Mutex<HashMap<Uuid, Bot>>>,
-    account_bots: Arc<Mutex<HashMap<Uuid, Vec<Uuid>>>>,
-    counter: Arc<Mutex<HashMap<Uuid, i32>>>,
-}
-
-#[async_trait]
-impl BotRepository for MockBotRepository {
-    async fn create(&self, bot: &Bot) -> Result<(), RepositoryError> {
-        let mut bots = self.bots.lock().unwrap();
-        let mut account_bots = self.account_bots.lock().unwrap();
-
-        if bots.contains_key(&bot.id) {
-            return Err(RepositoryError::InvalidData(
-                "Bot already exists".to_string(),
-            ));
-        }
-
-        bots.insert(bot.id, bot.clone());
-        account_bots.entry(bot.account_id).or_default().push(bot.id);
-
-        Ok(())
-    }
-
-    async fn get_by_id(&self, id: Uuid) -> Result<Bot, RepositoryError> {
-        let bots = self.bots.lock().unwrap();
-        bots.get(&id)
-            .cloned()
-            .ok_or_else(|| RepositoryError::NotFound(format!("Bot {}", id)))
-    }
-
-    async fn get_by_id_with_token(&self, id: Uuid, token: &str) -> Result<Bot, RepositoryError> {
-        let bots = self.bots.lock().unwrap();
-        bots.get(&id)
-            .filter(|b| b.registration_token.as_ref() == Some(&token.to_string()))
-            .cloned()
-            .ok_or_else(|| RepositoryError::NotFound(format!("Bot {} with invalid token", id)))
-    }
-
-    async fn list_by_account(&self, account_id: Uuid) -> Result<Vec<Bot>, RepositoryError> {
-        let bots = self.bots.lock().unwrap();
-        let account_bots = self.account_bots.lock().unwrap();
-
-        let bot_ids = account_bots.get(&account_id).cloned().unwrap_or_default();
-        let result: Vec<Bot> = bot_ids
-            .iter()
-            .filter_map(|id| bots.get(id).cloned())
-            .collect();
-
-        Ok(result)
-    }
-
-    async fn list_by_account_paginated(
-        &self,
-        account_id: Uuid,
-        limit: i64,
-        offset: i64,
-    ) -> Result<Vec<Bot>, RepositoryError> {
-        let all = self.list_by_account(account_id).await?;
-        let offset = offset.max(0) as usize;
-        let limit = limit.max(0) as usize;
-        Ok(all.into_iter().skip(offset).take(limit).collect())
-    }
-
-    async fn update_status(&self, id: Uuid, status: BotStatus) -> Result<(), RepositoryError> {
-        let mut bots = self.bots.lock().unwrap();
-        let bot = bots
-            .get_mut(&id)
-            .ok_or_else(|| RepositoryError::NotFound(format!("Bot {}", id)))?;
-
-        bot.status = status;
-        bot.updated_at = Utc::now();
-        Ok(())
-    }
-
-    async fn update_droplet(
-        &self,
-        bot_id: Uuid,
-        droplet_id: Option<i64>,
-    ) -> Result<(), RepositoryError> {
-        let mut bots = self.bots.lock().unwrap();
-        let bot = bots
-            .get_mut(&bot_id)
-            .ok_or_else(|| RepositoryError::NotFound(format!("Bot {}", bot_id)))?;
-
-        bot.droplet_id = droplet_id;
-        bot.updated_at = Utc::now();
-        Ok(())
-    }
-
-    async fn update_config_version(
-        &self,
-        bot_id: Uuid,
-        desired: Option<Uuid>,
-        applied: Option<Uuid>,
-    ) -> Result<(), RepositoryError> {
-        let mut bots = self.bots.lock().unwrap();
-        let bot = bots
-            .get_mut(&bot_id)
-            .ok_or_else(|| RepositoryError::NotFound(format!("Bot {}", bot_id)))?;
-
-        bot.desired_config_version_id = desired;
-        bot.applied_config_version_id = applied;
-        bot.updated_at = Utc::now();
-        Ok(())
-    }
-
-    async fn update_heartbeat(&self, bot_id: Uuid) -> Result<(), RepositoryError> {
-        let mut bots = self.bots.lock().unwrap();
-        let bot = bots
-            .get_mut(&bot_id)
-            .ok_or_else(|| RepositoryError::NotFound(format!("Bot {}", bot_id)))?;
-
-        bot.last_heartbeat_at = Some(Utc::now());
-        bot.updated_at = Utc::now();
-        Ok(())
-    }
-
-    async fn update_registration_token(
-        &self,
-        bot_id: Uuid,
-        token: &str,
-    ) -> Result<(), RepositoryError> {
-        let mut bots = self.bots.lock().unwrap();
-        let bot = bots
-            .get_mut(&bot_id)
-            .ok_or_else(|| RepositoryError::NotFound(format!("Bot {}", bot_id)))?;
-
-        bot.registration_token = Some(token.to_string());
-        bot.updated_at = Utc::now();
-        Ok(())
-    }
-
-    async fn delete(&self, id: Uuid) -> Result<(), RepositoryError> {
-        let mut bots = self.bots.lock().unwrap();
-        bots.get_mut(&id)
-            .ok_or_else(|| RepositoryError::NotFound(format!("Bot {}", id)))?
-            .status = BotStatus::Destroyed;
-        Ok(())
-    }
-
-    async fn hard_delete(&self, id: Uuid) -> Result<(), RepositoryError> {
-        let mut bots = self.bots.lock().unwrap();
-        let mut account_bots = self.account_bots.lock().unwrap();
-        let bot = bots
-            .remove(&id)
-            .ok_or_else(|| RepositoryError::NotFound(format!("Bot {}", id)))?;
-
-        if let Some(ids) = account_bots.get_mut(&bot.account_id) {
-            ids.retain(|existing| *existing != id);
-        }
-        Ok(())
-    }
-
-    async fn increment_bot_counter(
-        &self,
-        account_id: Uuid,
-    ) -> Result<(bool, i32, i32), RepositoryError> {
-        let mut counter = self.counter.lock().unwrap();
-        let current = counter.get(&account_id).cloned().unwrap_or(0);
-
-        // Mock account with Basic tier (2 bots max)
-        let max_bots = 2;
-
-        if current >= max_bots {
-            return Ok((false, current, max_bots));
-        }
-
-        counter.insert(account_id, current + 1);
-        Ok((true, current + 1, max_bots))
-    }
-
-    async fn decrement_bot_counter(&self, account_id: Uuid) -> Result<(), RepositoryError> {
-        let mut counter = self.counter.lock().unwrap();
-        let current = counter.get(&account_id).cloned().unwrap_or(0);
-
-        if current > 0 {
-            counter.insert(account_id, current - 1);
-        }
-
-        Ok(())
-    }
-
-    async fn count_by_account(&self, account_id: Uuid) -> Result<i64, RepositoryError> {
-        let bots = self.bots.lock().unwrap();
-        let count = bots
-            .values()
-            .filter(|b| b.account_id == account_id && b.status != BotStatus::Destroyed)
-            .count() as i64;
-        Ok(count)
-    }
-
-    async fn list_stale_bots(&self, threshold: DateTime<Utc>) -> Result<Vec<Bot>, RepositoryError> {
-        let bots = self.bots.lock().unwrap();
-        let stale: Vec<Bot> = bots
-            .values()
-            .filter(|b| {
-                b.status == BotStatus::Online
-                    && (b.last_heartbeat_at.is_none() || b.last_heartbeat_at.unwrap() < threshold)
-            })
-            .cloned()
-            .collect();
-        Ok(stale)
-    }
-}
-
-/// In-memory mock implementation of ConfigRepository
-#[derive(Clone, Default)]
-struct MockConfigRepository {
-    configs: Arc<Mutex<HashMap<Uuid, StoredBotConfig>>>,
-    bot_configs: Arc<Mutex<HashMap<Uuid, Vec<Uuid>>>>,
-    version_counter: Arc<Mutex<HashMap<Uuid, i32>>>,
-}
-
-#[async_trait]
-impl ConfigRepository for MockConfigRepository {
-    async fn create(&self, config: &StoredBotConfig) -> Result<(), RepositoryError> {
-        let mut configs = self.configs.lock().unwrap();
-        let mut bot_configs = self.bot_configs.lock().unwrap();
-
-        configs.insert(config.id, config.clone());
-        bot_configs
-            .entry(config.bot_id)
-            .or_default()
-            .push(config.id);
-
-        Ok(())
-    }
-
-    async fn get_by_id(&self, id: Uuid) -> Result<StoredBotConfig, RepositoryError> {
-        let configs = self.configs.lock().unwrap();
-        configs
-            .get(&id)
-            .cloned()
-            .ok_or_else(|| RepositoryError::NotFound(format!("Config {}", id)))
-    }
-
-    async fn get_latest_for_bot(
-        &self,
-        bot_id: Uuid,
-    ) -> Result<Option<StoredBotConfig>, RepositoryError> {
-        let configs = self.configs.lock().unwrap();
-        let bot_configs = self.bot_configs.lock().unwrap();
-
-        let config_ids = bot_configs.get(&bot_id).cloned().unwrap_or_default();
-        let latest = config_ids
-            .iter()
-            .filter_map(|id| configs.get(id))
-            .max_by_key(|c| c.version)
-            .cloned();
-
-        Ok(latest)
-    }
-
-    async fn list_by_bot(&self, bot_id: Uuid) -> Result<Vec<StoredBotConfig>, RepositoryError> {
-        let configs = self.configs.lock().unwrap();
-        let bot_configs = self.bot_configs.lock().unwrap();
-
-        let config_ids = bot_configs.get(&bot_id).cloned().unwrap_or_default();
-        let result: Vec<StoredBotConfig> = config_ids
-            .iter()
-            .filter_map(|id| configs.get(id).cloned())
-            .collect();
-
-        Ok(result)
-    }
-
-    async fn get_next_version_atomic(&self, bot_id: Uuid) -> Result<i32, RepositoryError> {
-        let mut counter = self.version_counter.lock().unwrap();
-        let next = counter.get(&bot_id).cloned().unwrap_or(0) + 1;
-        counter.insert(bot_id, next);
-        Ok(next)
-    }
-}
-
 // ============================================================================
 // Test Helpers
 // ============================================================================
@@ -383,6 +40,26 @@ fn create_test_trading_config() -> TradingConfig {
     }
 }
 
+async fn create_test_account(store: &MemoryStore, tier: SubscriptionTier) -> Account {
+    let account = Account::new(Uuid::new_v4().to_string(), tier);
+    store
+        .accounts()
+        .create(&account)
+        .await
+        .expect("Failed to create account");
+    account
+}
+
+async fn create_test_bot(store: &MemoryStore, account_id: Uuid, name: &str) -> Bot {
+    let bot = Bot::new(account_id, name.to_string(), Persona::Beginner);
+    store
+        .bots()
+        .create(&bot)
+        .await
+        .expect("Failed to create bot");
+    bot
+}
+
 fn create_test_stored_config(bot_id: Uuid, version: i32) -> StoredBotConfig {
     StoredBotConfig {
         id: Uuid::new_v4(),
@@ -404,7 +81,8 @@ fn create_test_stored_config(bot_id: Uuid, version: i32) -> StoredBotConfig {
 
 #[tokio::test]
 async fn test_account_creation() {
-    let account_repo = Arc::new(MockAccountRepository::default());
+    let store = MemoryStore::new();
+    let account_repo = Arc::new(store.accounts());
 
     // Test creating an account
     let account = Account::new("test-external-id".to_string(), SubscriptionTier::Basic);
@@ -447,8 +125,9 @@ async fn test_account_creation() {
 
 #[tokio::test]
 async fn test_bot_lifecycle() {
-    let account_repo = Arc::new(MockAccountRepository::default());
-    let bot_repo = Arc::new(MockBotRepository::default());
+    let store = MemoryStore::new();
+    let account_repo = Arc::new(store.accounts());
+    let bot_repo = Arc::new(store.bots());
 
     // Create account first
     let account = Account::new("lifecycle-test".to_string(), SubscriptionTier::Basic);
@@ -472,7 +151,13 @@ async fn test_bot_lifecycle() {
 
     // Update status through lifecycle
     bot_repo
-        .update_status(bot_id, BotStatus::Provisioning)
+        .transition_status(
+            bot_id,
+            BotStatus::Pending,
+            BotStatus::Provisioning,
+            TransitionReason::Provision,
+            None,
+        )
         .await
         .expect("Failed to update status");
 
@@ -481,7 +166,13 @@ async fn test_bot_lifecycle() {
 
     // Simulate bot coming online
     bot_repo
-        .update_status(bot_id, BotStatus::Online)
+        .transition_status(
+            bot_id,
+            BotStatus::Provisioning,
+            BotStatus::Online,
+            TransitionReason::ConfigAcknowledged,
+            None,
+        )
         .await
         .expect("Failed to set online");
 
@@ -495,7 +186,13 @@ async fn test_bot_lifecycle() {
 
     // Pause bot
     bot_repo
-        .update_status(bot_id, BotStatus::Paused)
+        .transition_status(
+            bot_id,
+            BotStatus::Online,
+            BotStatus::Paused,
+            TransitionReason::Pause,
+            None,
+        )
         .await
         .expect("Failed to pause");
     let paused = bot_repo.get_by_id(bot_id).await.expect("Failed to get bot");
@@ -503,7 +200,13 @@ async fn test_bot_lifecycle() {
 
     // Resume bot
     bot_repo
-        .update_status(bot_id, BotStatus::Online)
+        .transition_status(
+            bot_id,
+            BotStatus::Paused,
+            BotStatus::Online,
+            TransitionReason::Resume,
+            None,
+        )
         .await
         .expect("Failed to resume");
 
@@ -515,18 +218,14 @@ async fn test_bot_lifecycle() {
 
 #[tokio::test]
 async fn test_config_versioning() {
-    let config_repo = Arc::new(MockConfigRepository::default());
-    let bot_repo = Arc::new(MockBotRepository::default());
-
-    let bot_id = Uuid::new_v4();
+    let store = MemoryStore::new();
+    let config_repo = Arc::new(store.configs());
 
     // Create a bot for the config
-    let bot = Bot::new(
-        Uuid::new_v4(),
-        "Config Test Bot".to_string(),
-        Persona::Beginner,
-    );
-    bot_repo.create(&bot).await.expect("Failed to create bot");
+    let account = create_test_account(&store, SubscriptionTier::Basic).await;
+    let bot_id = create_test_bot(&store, account.id, "Config Test Bot")
+        .await
+        .id;
 
     // Create initial config (version 1)
     let config1 = create_test_stored_config(bot_id, 1);
@@ -564,32 +263,32 @@ async fn test_config_versioning() {
         .expect("Failed to list configs");
     assert_eq!(all_configs.len(), 2);
 
-    // Test atomic version generation
+    // Test atomic version generation: one past the highest stored version
     let next_version = config_repo
         .get_next_version_atomic(bot_id)
         .await
         .expect("Failed to get next version");
-    assert_eq!(next_version, 1); // First call for this bot
+    assert_eq!(next_version, 3);
 
-    let next_version2 = config_repo
-        .get_next_version_atomic(bot_id)
+    // Configs from another bot do not share the sequence
+    let other_bot = create_test_bot(&store, account.id, "Other Bot").await;
+    let other_version = config_repo
+        .get_next_version_atomic(other_bot.id)
         .await
-        .expect("Failed to get next version 2");
-    assert_eq!(next_version2, 2); // Second call increments
+        .expect("Failed to get next version for other bot");
+    assert_eq!(other_version, 1);
 }
 
 #[tokio::test]
 async fn test_authentication_registration_token() {
-    let bot_repo = Arc::new(MockBotRepository::default());
+    let store = MemoryStore::new();
+    let bot_repo = Arc::new(store.bots());
 
     // Create bot without token
-    let bot = Bot::new(
-        Uuid::new_v4(),
-        "Auth Test Bot".to_string(),
-        Persona::Beginner,
-    );
-    let bot_id = bot.id;
-    bot_repo.create(&bot).await.expect("Failed to create bot");
+    let account = create_test_account(&store, SubscriptionTier::Basic).await;
+    let bot_id = create_test_bot(&store, account.id, "Auth Test Bot")
+        .await
+        .id;
 
     // Initially no token set
     let initial = bot_repo.get_by_id(bot_id).await.expect("Failed to get bot");
@@ -602,9 +301,10 @@ async fn test_authentication_registration_token() {
         .await
         .expect("Failed to set token");
 
-    // Verify token was set
+    // Verify token was set, and stored hashed rather than in plaintext
     let with_token = bot_repo.get_by_id(bot_id).await.expect("Failed to get bot");
-    assert_eq!(with_token.registration_token, Some(token.to_string()));
+    let stored_token = with_token.registration_token.expect("Token not set");
+    assert_ne!(stored_token, token);
 
     // Test get_by_id_with_token with correct token
     let authenticated = bot_repo
@@ -666,10 +366,12 @@ async fn test_risk_config_validation() {
 
 #[tokio::test]
 async fn test_account_limit_enforcement() {
-    let bot_repo = Arc::new(MockBotRepository::default());
-    let account_id = Uuid::new_v4();
-
-    // Mock account has Basic tier with 2 bot limit
+    let store = MemoryStore::new();
+    let bot_repo = Arc::new(store.bots());
+    // Basic tier has a 2 bot limit
+    let account_id = create_test_account(&store, SubscriptionTier::Basic)
+        .await
+        .id;
 
     // First bot - should succeed
     let (success1, count1, max1) = bot_repo
@@ -715,18 +417,16 @@ async fn test_account_limit_enforcement() {
 
 #[tokio::test]
 async fn test_stale_bot_detection() {
-    let bot_repo = Arc::new(MockBotRepository::default());
-    let lifecycle =
-        BotLifecycleService::new(bot_repo.clone(), Arc::new(MockConfigRepository::default()));
+    let store = MemoryStore::new();
+    let account = create_test_account(&store, SubscriptionTier::Pro).await;
+    let bot_repo = Arc::new(store.bots());
+    let lifecycle = BotLifecycleService::new(bot_repo.clone(), Arc::new(store.configs()));
 
     // Create bot and set it online
-    let bot = Bot::new(Uuid::new_v4(), "Stale Bot".to_string(), Persona::Beginner);
+    let bot = Bot::new(account.id, "Stale Bot".to_string(), Persona::Beginner);
     let bot_id = bot.id;
     bot_repo.create(&bot).await.expect("Failed to create bot");
-    bot_repo
-        .update_status(bot_id, BotStatus::Online)
-        .await
-        .expect("Failed to set online");
+    set_online(&store, bot_id).await;
 
     // Initially not stale (just set online with no heartbeat)
     let threshold = Utc::now() - chrono::Duration::minutes(5);
@@ -757,10 +457,39 @@ async fn test_stale_bot_detection() {
     assert!(!stale_via_service.iter().any(|b| b.id == bot_id));
 }
 
+#[tokio::test]
+async fn test_heartbeat_timeout_records_status_reason() {
+    let store = MemoryStore::new();
+    let account = create_test_account(&store, SubscriptionTier::Pro).await;
+    let bot_repo = Arc::new(store.bots());
+    let lifecycle = BotLifecycleService::new(bot_repo.clone(), Arc::new(store.configs()));
+
+    let bot = Bot::new(account.id, "Silent Bot".to_string(), Persona::Beginner);
+    let bot_id = bot.id;
+    bot_repo.create(&bot).await.expect("Failed to create bot");
+    set_online(&store, bot_id).await;
+
+    let marked = lifecycle
+        .check_stale_bots(chrono::Duration::minutes(5))
+        .await
+        .expect("Failed to check stale");
+    assert!(marked.iter().any(|b| b.id == bot_id));
+
+    let errored = bot_repo.get_by_id(bot_id).await.expect("Failed to get bot");
+    assert_eq!(errored.status, BotStatus::Error);
+    assert_eq!(
+        errored.status_reason,
+        Some(TransitionReason::HeartbeatTimeout)
+    );
+    assert!(errored.status_detail.is_some());
+    assert!(errored.status_changed_at >= bot.status_changed_at);
+}
+
 #[tokio::test]
 async fn test_pagination() {
-    let bot_repo = Arc::new(MockBotRepository::default());
-    let account_id = Uuid::new_v4();
+    let store = MemoryStore::new();
+    let bot_repo = Arc::new(store.bots());
+    let account_id = create_test_account(&store, SubscriptionTier::Pro).await.id;
 
     // Create 5 bots
     for i in 0..5 {
@@ -796,13 +525,56 @@ async fn test_pagination() {
     assert_eq!(page2.len(), 2);
 }
 
+#[tokio::test]
+async fn test_list_bots_filters_by_account_and_status() {
+    let store = MemoryStore::new();
+    let bot_repo = Arc::new(store.bots());
+    let lifecycle = BotLifecycleService::new(bot_repo.clone(), Arc::new(store.configs()));
+    let (account_a, account_b) = (
+        create_test_account(&store, SubscriptionTier::Pro).await.id,
+        create_test_account(&store, SubscriptionTier::Pro).await.id,
+    );
+
+    for account_id in [account_a, account_a, account_b] {
+        let bot = Bot::new(account_id, "Bot".to_string(), Persona::Beginner);
+        bot_repo.create(&bot).await.expect("Failed to create bot");
+    }
+    let provisioning = bot_repo.list_by_account(account_a).await.unwrap()[0].id;
+    bot_repo
+        .transition_status(
+            provisioning,
+            BotStatus::Pending,
+            BotStatus::Provisioning,
+            TransitionReason::Provision,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let all = lifecycle
+        .list_bots(&BotFilter::default(), 100, 0)
+        .await
+        .unwrap();
+    assert_eq!(all.len(), 3);
+
+    let filter = BotFilter {
+        account_id: Some(account_a),
+        status: Some(BotStatus::Pending),
+    };
+    let pending = lifecycle.list_bots(&filter, 100, 0).await.unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_ne!(pending[0].id, provisioning);
+    assert_eq!(pending[0].account_id, account_a);
+}
+
 #[tokio::test]
 async fn test_config_version_conflict_detection() {
-    let config_repo = Arc::new(MockConfigRepository::default());
-    let bot_repo = Arc::new(MockBotRepository::default());
+    let store = MemoryStore::new();
+    let config_repo = Arc::new(store.configs());
+    let bot_repo = Arc::new(store.bots());
     let lifecycle = BotLifecycleService::new(bot_repo.clone(), config_repo.clone());
 
-    let account_id = Uuid::new_v4();
+    let account_id = create_test_account(&store, SubscriptionTier::Pro).await.id;
     let bot = Bot::new(account_id, "Conflict Test".to_string(), Persona::Beginner);
     let bot_id = bot.id;
     bot_repo.create(&bot).await.expect("Failed to create bot");
@@ -839,3 +611,356 @@ async fn test_config_version_conflict_detection() {
     let result2 = lifecycle.acknowledge_config(bot_id, config1.id).await;
     assert!(result2.is_err());
 }
+
+async fn set_online(store: &MemoryStore, bot_id: Uuid) {
+    let bot_repo = store.bots();
+    bot_repo
+        .transition_status(
+            bot_id,
+            BotStatus::Pending,
+            BotStatus::Provisioning,
+            TransitionReason::Provision,
+            None,
+        )
+        .await
+        .expect("Failed to start provisioning");
+    bot_repo
+        .transition_status(
+            bot_id,
+            BotStatus::Provisioning,
+            BotStatus::Online,
+            TransitionReason::ConfigAcknowledged,
+            None,
+        )
+        .await
+        .expect("Failed to set online");
+}
+
+#[tokio::test]
+async fn test_transition_status_rejects_disallowed_edges() {
+    let store = MemoryStore::new();
+    let account = create_test_account(&store, SubscriptionTier::Pro).await;
+    let bot_repo = store.bots();
+    let bot = Bot::new(account.id, "Edge Bot".to_string(), Persona::Beginner);
+    bot_repo.create(&bot).await.expect("Failed to create bot");
+
+    let err = bot_repo
+        .transition_status(
+            bot.id,
+            BotStatus::Pending,
+            BotStatus::Paused,
+            TransitionReason::Pause,
+            None,
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(err, RepositoryError::InvalidTransition(_)));
+
+    let unchanged = bot_repo.get_by_id(bot.id).await.expect("Failed to get bot");
+    assert_eq!(unchanged.status, BotStatus::Pending);
+}
+
+#[tokio::test]
+async fn test_pause_loses_race_with_destroy() {
+    let store = MemoryStore::new();
+    let account = create_test_account(&store, SubscriptionTier::Pro).await;
+    let bot_repo = store.bots();
+    let bot = Bot::new(account.id, "Race Bot".to_string(), Persona::Beginner);
+    let bot_id = bot.id;
+    bot_repo.create(&bot).await.expect("Failed to create bot");
+    set_online(&store, bot_id).await;
+
+    // Both calls read the bot while online; destroy commits first.
+    bot_repo.delete(bot_id).await.expect("Failed to delete bot");
+    let err = bot_repo
+        .transition_status(
+            bot_id,
+            BotStatus::Online,
+            BotStatus::Paused,
+            TransitionReason::Pause,
+            None,
+        )
+        .await
+        .unwrap_err();
+
+    match err {
+        RepositoryError::InvalidTransition(t) => {
+            assert_eq!(t.from, BotStatus::Destroyed);
+            assert_eq!(t.to, BotStatus::Paused);
+        }
+        other => panic!("expected InvalidTransition, got {other:?}"),
+    }
+    let destroyed = bot_repo.get_by_id(bot_id).await.expect("Failed to get bot");
+    assert_eq!(destroyed.status, BotStatus::Destroyed);
+}
+
+#[tokio::test]
+async fn test_acknowledge_config_requires_droplet_to_go_online() {
+    let store = MemoryStore::new();
+    let account = create_test_account(&store, SubscriptionTier::Pro).await;
+    let config_repo = Arc::new(store.configs());
+    let bot_repo = Arc::new(store.bots());
+    let lifecycle = BotLifecycleService::new(bot_repo.clone(), config_repo.clone());
+
+    let bot = Bot::new(account.id, "Ack Bot".to_string(), Persona::Beginner);
+    let bot_id = bot.id;
+    bot_repo.create(&bot).await.expect("Failed to create bot");
+    bot_repo
+        .transition_status(
+            bot_id,
+            BotStatus::Pending,
+            BotStatus::Provisioning,
+            TransitionReason::Provision,
+            None,
+        )
+        .await
+        .expect("Failed to start provisioning");
+
+    let config = create_test_stored_config(bot_id, 1);
+    config_repo
+        .create(&config)
+        .await
+        .expect("Failed to create config");
+    bot_repo
+        .update_config_version(bot_id, Some(config.id), None)
+        .await
+        .expect("Failed to set desired");
+
+    let err = lifecycle
+        .acknowledge_config(bot_id, config.id)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, LifecycleError::InvalidTransition(_)));
+
+    bot_repo
+        .update_droplet(bot_id, Some(42))
+        .await
+        .expect("Failed to set droplet");
+    lifecycle
+        .acknowledge_config(bot_id, config.id)
+        .await
+        .expect("Failed to acknowledge");
+
+    let online = bot_repo.get_by_id(bot_id).await.expect("Failed to get bot");
+    assert_eq!(online.status, BotStatus::Online);
+    assert_eq!(online.applied_config_version_id, Some(config.id));
+}
+
+#[tokio::test]
+async fn test_registration_timeout_marks_unregistered_bots() {
+    let store = MemoryStore::new();
+    let account = create_test_account(&store, SubscriptionTier::Pro).await;
+    let bot_repo = Arc::new(store.bots());
+    let lifecycle = BotLifecycleService::new(bot_repo.clone(), Arc::new(store.configs()));
+
+    let silent = Bot::new(account.id, "Silent".to_string(), Persona::Beginner);
+    let registered = Bot::new(account.id, "Registered".to_string(), Persona::Beginner);
+    for bot in [&silent, &registered] {
+        bot_repo.create(bot).await.expect("Failed to create bot");
+        bot_repo
+            .update_registration_token(bot.id, "token")
+            .await
+            .expect("Failed to set token");
+        bot_repo
+            .transition_status(
+                bot.id,
+                BotStatus::Pending,
+                BotStatus::Provisioning,
+                TransitionReason::Provision,
+                None,
+            )
+            .await
+            .expect("Failed to start provisioning");
+    }
+
+    let reg = lifecycle
+        .register_bot(
+            registered.id,
+            "token",
+            Some("203.0.113.7"),
+            Some("bootstrap/1"),
+        )
+        .await
+        .expect("Failed to register");
+    assert_eq!(reg.id, registered.id);
+
+    // A zero deadline makes every unregistered provisioning bot overdue.
+    let timed_out = lifecycle
+        .check_registration_timeouts(chrono::Duration::zero())
+        .await
+        .expect("Failed to check registration timeouts");
+    assert_eq!(timed_out.len(), 1);
+    assert_eq!(timed_out[0].id, silent.id);
+    assert_eq!(timed_out[0].registration_timeouts, 1);
+
+    let silent = bot_repo
+        .get_by_id(silent.id)
+        .await
+        .expect("Failed to get bot");
+    assert_eq!(silent.status, BotStatus::Error);
+    assert_eq!(
+        silent.status_reason,
+        Some(TransitionReason::RegistrationTimeout)
+    );
+
+    let registered = bot_repo
+        .get_by_id(registered.id)
+        .await
+        .expect("Failed to get bot");
+    assert_eq!(registered.status, BotStatus::Provisioning);
+    assert!(registered.registered_at.is_some());
+    assert_eq!(registered.registered_ip.as_deref(), Some("203.0.113.7"));
+    assert_eq!(registered.agent_version.as_deref(), Some("bootstrap/1"));
+}
+
+#[tokio::test]
+async fn test_register_bot_rejects_wrong_token() {
+    let store = MemoryStore::new();
+    let account = create_test_account(&store, SubscriptionTier::Pro).await;
+    let bot_repo = Arc::new(store.bots());
+    let lifecycle = BotLifecycleService::new(bot_repo.clone(), Arc::new(store.configs()));
+
+    let bot = Bot::new(account.id, "Bot".to_string(), Persona::Beginner);
+    bot_repo.create(&bot).await.expect("Failed to create bot");
+    bot_repo
+        .update_registration_token(bot.id, "token")
+        .await
+        .expect("Failed to set token");
+
+    let err = lifecycle
+        .register_bot(bot.id, "wrong", None, None)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        LifecycleError::Repository(RepositoryError::NotFound(_))
+    ));
+    let unchanged = bot_repo.get_by_id(bot.id).await.expect("Failed to get bot");
+    assert!(unchanged.registered_at.is_none());
+}
+
+#[tokio::test]
+async fn test_bootstrap_progress_timeline() {
+    let store = MemoryStore::new();
+    let account = create_test_account(&store, SubscriptionTier::Pro).await;
+    let bot_repo = Arc::new(store.bots());
+    let event_repo = Arc::new(store.bootstrap_events());
+    let bootstrap = BootstrapService::new(
+        bot_repo.clone(),
+        event_repo.clone(),
+        Arc::new(store.bootstrap_logs()),
+    );
+
+    let bot = Bot::new(account.id, "Bot".to_string(), Persona::Beginner);
+    bot_repo.create(&bot).await.expect("Failed to create bot");
+    bot_repo
+        .update_registration_token(bot.id, "token")
+        .await
+        .expect("Failed to set token");
+
+    bootstrap
+        .record_progress(bot.id, "token", BootstrapPhase::AptUpdate, 0, None)
+        .await
+        .expect("Failed to record apt_update");
+    bootstrap
+        .record_progress(
+            bot.id,
+            "token",
+            BootstrapPhase::Customizer,
+            2,
+            Some("janebot-cli init failed".to_string()),
+        )
+        .await
+        .expect("Failed to record customizer");
+
+    // Reports need the bot's registration token.
+    let err = bootstrap
+        .record_progress(bot.id, "wrong", BootstrapPhase::Register, 0, None)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        LifecycleError::Repository(RepositoryError::NotFound(_))
+    ));
+
+    let timeline = bootstrap
+        .timeline(bot.id)
+        .await
+        .expect("Failed to get timeline");
+    let phases: Vec<BootstrapPhase> = timeline.iter().map(|e| e.phase).collect();
+    assert_eq!(
+        phases,
+        vec![BootstrapPhase::AptUpdate, BootstrapPhase::Customizer]
+    );
+    assert!(timeline[0].succeeded());
+    assert!(!timeline[1].succeeded());
+    assert_eq!(
+        timeline[1].message.as_deref(),
+        Some("janebot-cli init failed")
+    );
+
+    let err = bootstrap.timeline(Uuid::new_v4()).await.unwrap_err();
+    assert!(matches!(
+        err,
+        LifecycleError::Repository(RepositoryError::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_bootstrap_log_upload_is_redacted_and_filed_by_droplet() {
+    let store = MemoryStore::new();
+    let account = create_test_account(&store, SubscriptionTier::Pro).await;
+    let bot_repo = Arc::new(store.bots());
+    let bootstrap = BootstrapService::new(
+        bot_repo.clone(),
+        Arc::new(store.bootstrap_events()),
+        Arc::new(store.bootstrap_logs()),
+    );
+
+    let bot = Bot::new(account.id, "Bot".to_string(), Persona::Beginner);
+    bot_repo.create(&bot).await.expect("Failed to create bot");
+    bot_repo
+        .update_registration_token(bot.id, "reg-token-secret")
+        .await
+        .expect("Failed to set token");
+    bot_repo
+        .update_droplet(bot.id, Some(4242))
+        .await
+        .expect("Failed to set droplet");
+
+    bootstrap
+        .upload_log(
+            bot.id,
+            "reg-token-secret",
+            BootstrapLogSource::Bootstrap,
+            "curl -H 'Authorization: Bearer reg-token-secret'\nE: Unable to locate package nodejs",
+        )
+        .await
+        .expect("Failed to upload bootstrap log");
+    bootstrap
+        .upload_log(
+            bot.id,
+            "reg-token-secret",
+            BootstrapLogSource::Customizer,
+            "npm ERR! code E401",
+        )
+        .await
+        .expect("Failed to upload customizer log");
+
+    let err = bootstrap
+        .upload_log(bot.id, "wrong", BootstrapLogSource::Bootstrap, "log")
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        LifecycleError::Repository(RepositoryError::NotFound(_))
+    ));
+
+    let logs = bootstrap.logs(bot.id).await.expect("Failed to get logs");
+    assert_eq!(logs.len(), 2);
+    assert_eq!(logs[0].source, BootstrapLogSource::Customizer);
+    assert!(logs.iter().all(|l| l.droplet_id == Some(4242)));
+    let bootstrap_log = &logs[1].content;
+    assert!(!bootstrap_log.contains("reg-token-secret"));
+    assert!(bootstrap_log.contains("Unable to locate package nodejs"));
+}